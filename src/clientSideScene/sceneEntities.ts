@@ -59,7 +59,11 @@ import {
   codeManager,
   editorManager,
 } from 'lib/singletons'
-import { getNodeFromPath, getNodePathFromSourceRange } from 'lang/queryAst'
+import {
+  getBindingNames,
+  getNodeFromPath,
+  getNodePathFromSourceRange,
+} from 'lang/queryAst'
 import { executeAst, useStore } from 'useStore'
 import {
   createArcGeometry,
@@ -1610,13 +1614,14 @@ function prepareTruncatedMemoryAndAst(
     if (node.type !== 'VariableDeclaration') {
       continue
     }
-    const name = node.declarations[0].id.name
-    // const memoryItem = kclManager.programMemory.root[name]
-    const memoryItem = programMemory.root[name]
-    if (!memoryItem) {
-      continue
+    for (const name of getBindingNames(node.declarations[0].id)) {
+      // const memoryItem = kclManager.programMemory.root[name]
+      const memoryItem = programMemory.root[name]
+      if (!memoryItem) {
+        continue
+      }
+      programMemoryOverride.root[name] = JSON.parse(JSON.stringify(memoryItem))
     }
-    programMemoryOverride.root[name] = JSON.parse(JSON.stringify(memoryItem))
   }
   return {
     truncatedAst,
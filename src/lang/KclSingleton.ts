@@ -190,8 +190,12 @@ export class KclManager {
     sceneInfra.modelingSend({ type: 'code edit during sketch' })
     enterEditMode(programMemory, this.engineCommandManager)
     this.isExecuting = false
-    // Check the cancellation token for this execution before applying side effects
-    if (this._cancelTokens.get(currentExecutionId)) {
+    // Check the cancellation token for this execution before applying side effects.
+    // An interrupted run was replaced by a newer one, which applies its own.
+    if (
+      this._cancelTokens.get(currentExecutionId) ||
+      errors.some((error) => error.kind === 'interrupted')
+    ) {
       this._cancelTokens.delete(currentExecutionId)
       return
     }
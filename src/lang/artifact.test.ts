@@ -119,6 +119,7 @@ const mySketch001 = startSketchOn('XY')
         },
       ],
       height: 2,
+      helixIds: [],
       position: [0, 0, 0],
       rotation: [0, 0, 0, 1],
       xAxis: { x: 1, y: 0, z: 0 },
@@ -221,6 +222,7 @@ const sk2 = startSketchOn('XY')
           },
         ],
         height: 2,
+        helixIds: [],
         position: [0, 0, 0],
         rotation: [0, 0, 0, 1],
         xAxis: { x: 1, y: 0, z: 0 },
@@ -295,6 +297,7 @@ const sk2 = startSketchOn('XY')
           },
         ],
         height: 2,
+        helixIds: [],
         position: [0, 0, 0],
         rotation: [0, 0, 0, 1],
         xAxis: { x: 1, y: 0, z: 0 },
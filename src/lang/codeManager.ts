@@ -48,6 +48,10 @@ export default class CodeManager {
     this.#updateState = setCode
   }
 
+  get currentFilePath(): string | null {
+    return this._currentFilePath
+  }
+
   updateCurrentFilePath(path: string) {
     this._currentFilePath = path
   }
@@ -77,7 +77,7 @@ describe('Testing createVariableDeclaration', () => {
     expect(result.type).toBe('VariableDeclaration')
     expect(result.declarations[0].type).toBe('VariableDeclarator')
     expect(result.declarations[0].id.type).toBe('Identifier')
-    expect(result.declarations[0].id.name).toBe('myVar')
+    expect((result.declarations[0].id as Identifier).name).toBe('myVar')
     expect(result.declarations[0].init.type).toBe('Literal')
     expect((result.declarations[0].init as any).value).toBe(5)
   })
@@ -18,6 +18,7 @@ import {
 } from './wasm'
 import {
   findAllPreviousVariables,
+  getDeclaratorName,
   getNodeFromPath,
   getNodePathFromSourceRange,
   isNodeSafeToReplace,
@@ -262,7 +263,7 @@ export function extrudeSketch(
     distance,
     shouldPipe
       ? createPipeSubstitution()
-      : createIdentifier(variableDeclarator.id.name),
+      : createIdentifier(getDeclaratorName(variableDeclarator)),
   ])
 
   if (shouldPipe) {
@@ -321,7 +322,7 @@ export function sketchOnExtrudedFace(
     'VariableDeclarator',
     true
   )
-  const oldSketchName = oldSketchNode.id.name
+  const oldSketchName = getDeclaratorName(oldSketchNode)
   const { node: expression } = getNodeFromPath<CallExpression>(
     _node,
     pathToNode,
@@ -15,6 +15,8 @@ import {
   SketchGroup,
   SourceRange,
   PipeExpression,
+  VariableDeclarator,
+  BindingPattern,
 } from './wasm'
 import { createIdentifier, splitPathAtLastIndex } from './modifyAst'
 import { getSketchSegmentFromSourceRange } from './std/sketchConstraints'
@@ -323,12 +325,13 @@ export function findAllPreviousVariables(
   const variables: PrevVariable<any>[] = []
   bodyItems?.forEach?.((item) => {
     if (item.type !== 'VariableDeclaration' || item.end > sourceRange[0]) return
-    const varName = item.declarations[0].id.name
-    const varValue = programMemory?.root[varName]
-    if (typeof varValue?.value !== type) return
-    variables.push({
-      key: varName,
-      value: varValue.value,
+    getBindingNames(item.declarations[0].id).forEach((varName) => {
+      const varValue = programMemory?.root[varName]
+      if (typeof varValue?.value !== type) return
+      variables.push({
+        key: varName,
+        value: varValue.value,
+      })
     })
   })
 
@@ -554,7 +557,9 @@ export function hasExtrudeSketchGroup({
     'VariableDeclaration'
   ).node
   if (varDec.type !== 'VariableDeclaration') return false
-  const varName = varDec.declarations[0].id.name
+  const { id } = varDec.declarations[0]
+  if (id.type !== 'Identifier') return false
+  const varName = id.name
   const varValue = programMemory?.root[varName]
   return varValue?.type === 'ExtrudeGroup' || varValue?.type === 'SketchGroup'
 }
@@ -579,3 +584,17 @@ export function isSingleCursorInPipe(
   if (nodeTypes.includes('PipeExpression')) return true
   return false
 }
+
+/** Every name a declaration binds, e.g. `['x', 'y']` for `const [x, y] = ...` */
+export function getBindingNames(id: BindingPattern): string[] {
+  if (id.type === 'Identifier') return [id.name]
+  if (id.type === 'ArrayPattern') return id.elements.map(({ name }) => name)
+  return id.properties.map(({ name }) => name)
+}
+
+/** The name of a declarator which can't be destructured, like a sketch's. */
+export function getDeclaratorName(varDec: VariableDeclarator): string {
+  if (varDec.id.type !== 'Identifier')
+    throw new Error('expected a variable name, not a destructuring pattern')
+  return varDec.id.name
+}
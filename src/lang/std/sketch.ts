@@ -13,6 +13,7 @@ import {
   VariableDeclaration,
 } from '../wasm'
 import {
+  getDeclaratorName,
   getNodeFromPath,
   getNodeFromPathCurry,
   getNodePathFromSourceRange,
@@ -625,7 +626,7 @@ export const angledLineOfXLength: SketchLineHelper = {
       pathToNode,
       'VariableDeclarator'
     )
-    const variableName = varDec.id.name
+    const variableName = getDeclaratorName(varDec)
     const sketch = previousProgramMemory?.root?.[variableName]
     if (sketch.type !== 'SketchGroup') throw new Error('not a SketchGroup')
     const angle = createLiteral(roundOff(getAngle(from, to), 0))
@@ -698,7 +699,7 @@ export const angledLineOfYLength: SketchLineHelper = {
       pathToNode,
       'VariableDeclarator'
     )
-    const variableName = varDec.id.name
+    const variableName = getDeclaratorName(varDec)
     const sketch = previousProgramMemory?.root?.[variableName]
     if (sketch.type !== 'SketchGroup') throw new Error('not a SketchGroup')
 
@@ -954,7 +955,7 @@ export const angledLineThatIntersects: SketchLineHelper = {
       'VariableDeclaration'
     )
 
-    const varName = varDec.declarations[0].id.name
+    const varName = getDeclaratorName(varDec.declarations[0])
     const sketchGroup = previousProgramMemory.root[varName] as SketchGroup
     const intersectPath = sketchGroup.value.find(
       ({ name }: Path) => name === intersectTagName
@@ -11,6 +11,7 @@ import {
   ProgramMemory,
 } from '../wasm'
 import {
+  getDeclaratorName,
   getNodeFromPath,
   getNodeFromPathCurry,
   getNodePathFromSourceRange,
@@ -1436,7 +1437,7 @@ export function transformAstSketchLines({
       ''
     const [varValA, varValB] = Array.isArray(val) ? val : [val, val]
 
-    const varName = varDec.id.name
+    const varName = getDeclaratorName(varDec)
     const sketchGroup = programMemory.root?.[varName]
     if (!sketchGroup || sketchGroup.type !== 'SketchGroup')
       throw new Error('not a sketch group')
@@ -18,6 +18,7 @@ import init, {
 } from '../wasm-lib/pkg/wasm_lib'
 import { KCLError } from './errors'
 import { KclError as RustKclError } from '../wasm-lib/kcl/bindings/KclError'
+import { KclWarning } from '../wasm-lib/kcl/bindings/KclWarning'
 import { EngineCommandManager } from './std/engineConnection'
 import { ProgramReturn } from '../wasm-lib/kcl/bindings/ProgramReturn'
 import { MemoryItem } from '../wasm-lib/kcl/bindings/MemoryItem'
@@ -30,6 +31,7 @@ import { CoreDumpManager } from 'lib/coredump'
 import openWindow from 'lib/openWindow'
 import { DefaultPlanes } from 'wasm-lib/kcl/bindings/DefaultPlanes'
 import { TEST } from 'env'
+import { isTauri } from 'lib/isTauri'
 import { Configuration } from 'wasm-lib/kcl/bindings/Configuration'
 import { ProjectConfiguration } from 'wasm-lib/kcl/bindings/ProjectConfiguration'
 import { ProjectRoute } from 'wasm-lib/kcl/bindings/ProjectRoute'
@@ -49,6 +51,7 @@ export type { ReturnStatement } from '../wasm-lib/kcl/bindings/ReturnStatement'
 export type { ExpressionStatement } from '../wasm-lib/kcl/bindings/ExpressionStatement'
 export type { CallExpression } from '../wasm-lib/kcl/bindings/CallExpression'
 export type { VariableDeclarator } from '../wasm-lib/kcl/bindings/VariableDeclarator'
+export type { BindingPattern } from '../wasm-lib/kcl/bindings/BindingPattern'
 export type { BinaryPart } from '../wasm-lib/kcl/bindings/BinaryPart'
 export type { Literal } from '../wasm-lib/kcl/bindings/Literal'
 export type { ArrayExpression } from '../wasm-lib/kcl/bindings/ArrayExpression'
@@ -145,6 +148,8 @@ interface Memory {
 export interface ProgramMemory {
   root: Memory
   return: ProgramReturn | null
+  // Problems found by the run which didn't stop it.
+  warnings?: KclWarning[]
 }
 
 export const executor = async (
@@ -166,20 +171,41 @@ export const executor = async (
   return _programMemory
 }
 
+// The directory of the file being executed, relative to the project directory the file
+// system manager reads from. Imports in the file are relative to it.
+const currentFileDirectory = async (): Promise<string | undefined> => {
+  const { codeManager } = await import('lib/singletons')
+  const filePath = codeManager.currentFilePath
+  if (!isTauri() || !filePath) return undefined
+  const relativePath = filePath
+    .replace(fileSystemManager.dir, '')
+    .replace(/^[\\/]+/, '')
+  return relativePath.split(/[\\/]/).slice(0, -1).join('/')
+}
+
+// The runs in progress, one real and one mock, so a new run can stop the one it replaces.
+const runningExecutions = new Map<boolean, AbortController>()
+
 export const _executor = async (
   node: Program,
   programMemory: ProgramMemory = { root: {}, return: null },
   engineCommandManager: EngineCommandManager,
   isMock: boolean
 ): Promise<ProgramMemory> => {
+  // The previous run's result would be thrown away, so stop it rather than let it finish.
+  runningExecutions.get(isMock)?.abort()
+  const controller = new AbortController()
+  runningExecutions.set(isMock, controller)
   try {
     let baseUnit = 'mm'
+    let projectDirectory: string | undefined
     if (!TEST) {
       const getSettingsState = import('components/SettingsAuthProvider').then(
         (module) => module.getSettingsState
       )
       baseUnit =
         (await getSettingsState)()?.modeling.defaultUnit.current || 'mm'
+      projectDirectory = await currentFileDirectory()
     }
     const memory: ProgramMemory = await execute_wasm(
       JSON.stringify(node),
@@ -187,7 +213,9 @@ export const _executor = async (
       baseUnit,
       engineCommandManager,
       fileSystemManager,
-      isMock
+      isMock,
+      projectDirectory,
+      controller.signal
     )
     return memory
   } catch (e: any) {
@@ -201,6 +229,10 @@ export const _executor = async (
 
     console.log(kclError)
     throw kclError
+  } finally {
+    if (runningExecutions.get(isMock) === controller) {
+      runningExecutions.delete(isMock)
+    }
   }
 }
 
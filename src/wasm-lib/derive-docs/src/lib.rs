@@ -789,8 +789,9 @@ fn generate_code_block_test(
                     uuid::Uuid::new_v4(),
                     crate::executor::SourceRange::default(),
                     kittycad::types::ModelingCmd::ZoomToFit {
+                        animated: false,
                         object_ids: Default::default(),
-                        padding: 0.1,
+                        padding: Some(0.1),
                     },
                 )
                 .await.unwrap();
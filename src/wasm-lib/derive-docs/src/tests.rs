@@ -241,7 +241,7 @@ fn test_stdlib_option_input_format() {
             ///     import
             fn inner_import(
                 /// The args to do shit to.
-                args: Option<kittycad::types::InputFormat>
+                args: Option<kittycad::types::InputFormat3D>
             ) -> Result<Box<f64>> {
                 args
             }
@@ -268,7 +268,7 @@ fn test_stdlib_return_vec_sketch_group() {
             ///     import
             fn inner_import(
                 /// The args to do shit to.
-                args: Option<kittycad::types::InputFormat>
+                args: Option<kittycad::types::InputFormat3D>
             ) -> Result<Vec<SketchGroup>> {
                 args
             }
@@ -295,7 +295,7 @@ fn test_stdlib_return_vec_box_sketch_group() {
             ///     import
             fn inner_import(
                 /// The args to do shit to.
-                args: Option<kittycad::types::InputFormat>
+                args: Option<kittycad::types::InputFormat3D>
             ) -> Result<Vec<Box<SketchGroup>>> {
                 args
             }
@@ -328,7 +328,7 @@ fn test_stdlib_doc_comment_with_code() {
             /// ```
             fn inner_my_func(
                 /// The args to do shit to.
-                args: Option<kittycad::types::InputFormat>
+                args: Option<kittycad::types::InputFormat3D>
             ) -> Result<Vec<Box<SketchGroup>>> {
                 args
             }
@@ -362,7 +362,7 @@ fn test_stdlib_doc_comment_with_code_on_ignored_function() {
             /// ```
             fn inner_import(
                 /// The args to do shit to.
-                args: Option<kittycad::types::InputFormat>
+                args: Option<kittycad::types::InputFormat3D>
             ) -> Result<Vec<Box<SketchGroup>>> {
                 args
             }
@@ -396,7 +396,7 @@ fn test_stdlib_fail_non_camel_case() {
             /// ```
             fn inner_import_thing(
                 /// The args to do shit to.
-                args: Option<kittycad::types::InputFormat>
+                args: Option<kittycad::types::InputFormat3D>
             ) -> Result<Vec<Box<SketchGroup>>> {
                 args
             }
@@ -420,7 +420,7 @@ fn test_stdlib_fail_no_code_block() {
         quote! {
             fn inner_import(
                 /// The args to do shit to.
-                args: Option<kittycad::types::InputFormat>
+                args: Option<kittycad::types::InputFormat3D>
             ) -> Result<Vec<Box<SketchGroup>>> {
                 args
             }
@@ -454,7 +454,7 @@ fn test_stdlib_fail_name_not_in_code_block() {
             /// ```
             fn inner_import(
                 /// The args to do shit to.
-                args: Option<kittycad::types::InputFormat>
+                args: Option<kittycad::types::InputFormat3D>
             ) -> Result<Vec<Box<SketchGroup>>> {
                 args
             }
@@ -43,6 +43,25 @@ pub enum CompileError {
     MultipleReturns,
     #[error("A KCL function must end with a return statement, but your function doesn't have one.")]
     NoReturnStmt,
+    #[error("Execution plans cannot branch, so the condition of an if expression must be known at compile time, e.g. true or 1 < 2")]
+    NonConstantCondition,
+    #[error("Execution plans cannot compare values, so comparisons (like <) and logic (like &&) must only use literals, e.g. 1 < 2")]
+    NonConstantComparison,
+    #[error(
+        "Execution plans cannot loop, so a for loop must iterate over a literal range (like 0..10) or a literal array"
+    )]
+    NonConstantLoop,
+    #[error(
+        "This for loop would run {iterations} times, but loops can run at most {} times",
+        kcl_lib::executor::MAX_LOOP_ITERATIONS
+    )]
+    TooManyIterations { iterations: u64 },
+    #[error("Execution plans cannot read other files, so KCL modules cannot be imported yet")]
+    ImportNotSupported,
+    #[error("Execution plans don't know the scene's units, so numbers can't have units of measure (like 10mm) yet")]
+    UnitsNotSupported,
+    #[error("A block (e.g. a branch of an if expression) must end with an expression, which becomes its value")]
+    NoBlockValue,
     #[error("You used the %, which means \"substitute this argument for the value to the left in this |> pipeline\". But there is no such value, because you're not calling a pipeline.")]
     NotInPipeline,
     #[error("The function '{fn_name}' expects a parameter of type {expected} as argument number {arg_number} but you supplied {actual}")]
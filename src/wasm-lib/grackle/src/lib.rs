@@ -9,7 +9,11 @@ use std::collections::HashMap;
 
 use kcl_lib::{
     ast,
-    ast::types::{BodyItem, FunctionExpressionParts, KclNone, LiteralValue, Program},
+    ast::types::{
+        BinaryExpression, BinaryPart, BindingPattern, BodyItem, ForIterable, ForStatement, FunctionExpressionParts,
+        KclNone, Literal, LiteralIdentifier, LiteralValue, Program, Value,
+    },
+    executor::MAX_LOOP_ITERATIONS,
 };
 use kcl_value_group::into_single_value;
 use kittycad_execution_plan::{
@@ -77,6 +81,8 @@ impl Planner {
                         retval = Some(binding);
                         instructions
                     }
+                    BodyItem::ForStatement(node) => self.plan_to_unroll(*node)?,
+                    BodyItem::ImportStatement(_) => return Err(CompileError::ImportNotSupported),
                 };
                 instructions.extend(instructions_for_this_node);
                 Ok((instructions, retval))
@@ -118,6 +124,9 @@ impl Planner {
                 })
             }
             SingleValue::Literal(expr) => {
+                if expr.suffix.is_some() {
+                    return Err(CompileError::UnitsNotSupported);
+                }
                 let kcep_val = kcl_literal_to_kcep_literal(expr.value);
                 // KCEP primitives always have size of 1, because each address holds 1 primitive.
                 let size = 1;
@@ -197,6 +206,37 @@ impl Planner {
                 })
             }
             SingleValue::BinaryExpression(expr) => {
+                let operation = match expr.operator {
+                    ast::types::BinaryOperator::Add => ep::BinaryOperation::Add,
+                    ast::types::BinaryOperator::Sub => ep::BinaryOperation::Sub,
+                    ast::types::BinaryOperator::Mul => ep::BinaryOperation::Mul,
+                    ast::types::BinaryOperator::Div => ep::BinaryOperation::Div,
+                    ast::types::BinaryOperator::Mod => ep::BinaryOperation::Mod,
+                    ast::types::BinaryOperator::Pow => ep::BinaryOperation::Pow,
+                    // Execution plans can't compare values or do logic, so these operators can only
+                    // be compiled if their result is known at compile time.
+                    ast::types::BinaryOperator::Eq
+                    | ast::types::BinaryOperator::Neq
+                    | ast::types::BinaryOperator::Lt
+                    | ast::types::BinaryOperator::Lte
+                    | ast::types::BinaryOperator::Gt
+                    | ast::types::BinaryOperator::Gte
+                    | ast::types::BinaryOperator::And
+                    | ast::types::BinaryOperator::Or => {
+                        let b = fold_boolean_expression(&expr)?.ok_or(CompileError::NonConstantComparison)?;
+                        let address = self.next_addr.offset_by(1);
+                        return Ok(EvalPlan {
+                            instructions: vec![Instruction::from_range(
+                                InstructionKind::SetPrimitive {
+                                    address,
+                                    value: ept::Primitive::Bool(b),
+                                },
+                                KcvmSourceRange([expr.start, expr.end]),
+                            )],
+                            binding: EpBinding::Single(address),
+                        });
+                    }
+                };
                 let l = self.plan_to_compute_single(ctx, into_single_value(expr.left))?;
                 let r = self.plan_to_compute_single(ctx, into_single_value(expr.right))?;
                 let EpBinding::Single(l_binding) = l.binding else {
@@ -216,14 +256,7 @@ impl Planner {
                 plan.push(Instruction::from_range(
                     InstructionKind::BinaryArithmetic {
                         arithmetic: ep::BinaryArithmetic {
-                            operation: match expr.operator {
-                                ast::types::BinaryOperator::Add => ep::BinaryOperation::Add,
-                                ast::types::BinaryOperator::Sub => ep::BinaryOperation::Sub,
-                                ast::types::BinaryOperator::Mul => ep::BinaryOperation::Mul,
-                                ast::types::BinaryOperator::Div => ep::BinaryOperation::Div,
-                                ast::types::BinaryOperator::Mod => ep::BinaryOperation::Mod,
-                                ast::types::BinaryOperator::Pow => ep::BinaryOperation::Pow,
-                            },
+                            operation,
                             operand0: ep::Operand::Reference(l_binding),
                             operand1: ep::Operand::Reference(r_binding),
                         },
@@ -625,7 +658,106 @@ impl Planner {
                 };
                 Ok(EvalPlan { instructions, binding })
             }
+            SingleValue::IfExpression(expr) => {
+                // Execution plans can't branch, so we can only compile if expressions whose
+                // conditions are known at compile time. Pick the branch that will be taken.
+                let ast::types::IfExpression {
+                    cond,
+                    then_val,
+                    else_ifs,
+                    final_else,
+                    ..
+                } = *expr;
+                let branches = std::iter::once((*cond, then_val))
+                    .chain(else_ifs.into_iter().map(|else_if| (else_if.cond, else_if.then_val)));
+                let mut taken = final_else;
+                for (cond, then_val) in branches {
+                    match literal_bool(&cond)? {
+                        Some(true) => {
+                            taken = then_val;
+                            break;
+                        }
+                        Some(false) => continue,
+                        None => return Err(CompileError::NonConstantCondition),
+                    }
+                }
+                self.plan_to_compute_block(ctx, *taken)
+            }
+        }
+    }
+
+    /// Emits instructions which, when run, compute the value of a block (e.g. a branch of an if expression).
+    /// The block's value is its last expression. Anything bound inside the block is scoped to it.
+    fn plan_to_compute_block(&mut self, ctx: &mut Context, mut block: Program) -> Result<EvalPlan, CompileError> {
+        let Some(BodyItem::ExpressionStatement(last)) = block.body.pop() else {
+            return Err(CompileError::NoBlockValue);
+        };
+        self.binding_scope.add_scope();
+        let (mut instructions, _retval) = self.build_plan(block)?;
+        let EvalPlan {
+            instructions: instructions_for_value,
+            binding,
+        } = self.plan_to_compute_single(ctx, last.expression)?;
+        self.binding_scope.remove_scope();
+        instructions.extend(instructions_for_value);
+        Ok(EvalPlan { instructions, binding })
+    }
+
+    /// Execution plans can't loop, so a for loop is unrolled into one copy of its body per item.
+    /// That means the items must be known at compile time, i.e. a literal range or array.
+    fn plan_to_unroll(&mut self, for_statement: ForStatement) -> Result<Vec<Instruction>, CompileError> {
+        let ForStatement {
+            variable,
+            iterable,
+            body,
+            ..
+        } = for_statement;
+        let items: Vec<Value> = match iterable {
+            ForIterable::Range(range) => {
+                let (Some(from), Some(to)) = (literal_integer(&range.from), literal_integer(&range.to)) else {
+                    return Err(CompileError::NonConstantLoop);
+                };
+                let iterations = to.saturating_sub(from).saturating_add(1).max(0) as u64;
+                if iterations > MAX_LOOP_ITERATIONS {
+                    return Err(CompileError::TooManyIterations { iterations });
+                }
+                (from..=to)
+                    .map(|n| {
+                        Value::Literal(Box::new(Literal {
+                            start: range.start,
+                            end: range.end,
+                            value: n.into(),
+                            raw: n.to_string(),
+                            suffix: None,
+                        }))
+                    })
+                    .collect()
+            }
+            ForIterable::Value(Value::ArrayExpression(array)) => {
+                let iterations = array.elements.len() as u64;
+                if iterations > MAX_LOOP_ITERATIONS {
+                    return Err(CompileError::TooManyIterations { iterations });
+                }
+                array.elements
+            }
+            ForIterable::Value(_) => return Err(CompileError::NonConstantLoop),
+        };
+
+        let mut ctx = Context::default();
+        let mut instructions = Vec::new();
+        for item in items {
+            let EvalPlan {
+                instructions: instructions_for_item,
+                binding,
+            } = self.plan_to_compute_single(&mut ctx, SingleValue::from(item))?;
+            instructions.extend(instructions_for_item);
+            self.binding_scope.add_scope();
+            self.binding_scope.bind(variable.name.clone(), binding);
+            let (instructions_for_body, _retval) = self.build_plan((*body).clone())?;
+            self.binding_scope.remove_scope();
+            instructions.extend(instructions_for_body);
         }
+        Ok(instructions)
     }
 
     /// Emits instructions which, when run, compute a given KCL value and store it in memory.
@@ -642,8 +774,27 @@ impl Planner {
             .try_fold(Vec::new(), |mut acc, declaration| {
                 let EvalPlan { instructions, binding } =
                     self.plan_to_compute_single(&mut ctx, SingleValue::from(declaration.init))?;
-                self.binding_scope.bind(declaration.id.name, binding);
                 acc.extend(instructions);
+                match declaration.id {
+                    BindingPattern::Identifier(id) => self.binding_scope.bind(id.name, binding),
+                    BindingPattern::ArrayPattern(pattern) => {
+                        for (i, id) in pattern.elements.into_iter().enumerate() {
+                            let index =
+                                LiteralIdentifier::Literal(Box::new(Literal::new(LiteralValue::IInteger(i as i64))));
+                            let element = binding.property_of(index)?.clone();
+                            self.binding_scope.bind(id.name, element);
+                        }
+                    }
+                    BindingPattern::ObjectPattern(pattern) => {
+                        for id in pattern.properties {
+                            let key = LiteralIdentifier::Literal(Box::new(Literal::new(LiteralValue::String(
+                                id.name.clone(),
+                            ))));
+                            let property = binding.property_of(key)?.clone();
+                            self.binding_scope.bind(id.name, property);
+                        }
+                    }
+                }
                 Ok(acc)
             })
     }
@@ -660,6 +811,77 @@ fn kcl_literal_to_kcep_literal(expr: LiteralValue) -> ept::Primitive {
     }
 }
 
+/// If the given value is a literal `true` or `false`, or a comparison which can be evaluated
+/// at compile time (e.g. `1 < 2`), return it.
+fn literal_bool(value: &SingleValue) -> Result<Option<bool>, CompileError> {
+    match value {
+        SingleValue::Literal(literal) => match literal.value {
+            LiteralValue::Bool(b) => Ok(Some(b)),
+            _ => Ok(None),
+        },
+        SingleValue::BinaryExpression(expr) => fold_boolean_expression(expr),
+        _ => Ok(None),
+    }
+}
+
+/// Evaluate a comparison or logical expression (e.g. `1 < 2` or `true && false`) at compile time.
+/// Returns None if its operands aren't known at compile time.
+fn fold_boolean_expression(expr: &BinaryExpression) -> Result<Option<bool>, CompileError> {
+    Ok(match fold_constant(expr)? {
+        Some(LiteralValue::Bool(b)) => Some(b),
+        _ => None,
+    })
+}
+
+/// Evaluate an expression made only of literals (e.g. `1 + 1 < 3`) at compile time.
+/// Returns None if its operands aren't known at compile time.
+fn fold_constant(expr: &BinaryExpression) -> Result<Option<LiteralValue>, CompileError> {
+    let (Some(left), Some(right)) = (literal_operand(&expr.left)?, literal_operand(&expr.right)?) else {
+        return Ok(None);
+    };
+    let result = expr
+        .operator
+        .apply(
+            &left.into(),
+            &right.into(),
+            expr.left.clone().into(),
+            expr.right.clone().into(),
+        )
+        .map_err(|_| {
+            CompileError::InvalidOperand(
+                "the operands have the wrong type for this operator, e.g. < needs numbers and && needs booleans",
+            )
+        })?;
+    Ok(if let Some(b) = result.as_bool() {
+        Some(LiteralValue::Bool(b))
+    } else if let Some(x) = result.as_f64() {
+        Some(LiteralValue::Fractional(x))
+    } else {
+        result.as_str().map(|s| LiteralValue::String(s.to_owned()))
+    })
+}
+
+/// If the given operand is known at compile time, return its value.
+fn literal_operand(part: &BinaryPart) -> Result<Option<LiteralValue>, CompileError> {
+    match part {
+        BinaryPart::Literal(literal) if literal.suffix.is_some() => Err(CompileError::UnitsNotSupported),
+        BinaryPart::Literal(literal) => Ok(Some(literal.value.clone())),
+        BinaryPart::BinaryExpression(expr) => fold_constant(expr),
+        _ => Ok(None),
+    }
+}
+
+/// If the given value is a literal integer, return it.
+fn literal_integer(value: &Value) -> Option<i64> {
+    match value {
+        Value::Literal(literal) => match literal.value {
+            LiteralValue::IInteger(x) => Some(x),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 /// Instructions that can compute some value.
 struct EvalPlan {
     /// The instructions which will compute the value.
@@ -657,6 +657,25 @@ fn member_expressions_array() {
     }
 }
 
+#[test]
+fn destructuring() {
+    let program = r#"
+    let [first, second] = ["a", "b"]
+    "#;
+    let (_plan, scope, _) = must_plan(program);
+    assert_eq!(scope.get("first").unwrap(), &EpBinding::Single(Address::ZERO + 2));
+    assert_eq!(scope.get("second").unwrap(), &EpBinding::Single(Address::ZERO + 4));
+
+    let program = r#"
+    let { y } = {x: 1, y: 2}
+    "#;
+    let (_plan, scope, _) = must_plan(program);
+    assert_eq!(scope.get("y").unwrap(), &EpBinding::Single(Address::ZERO + 4));
+
+    let err = should_not_compile("let [a, b, c] = [1, 2]");
+    assert_eq!(err, CompileError::IndexOutOfBounds { i: 2, len: 2 });
+}
+
 #[test]
 fn compile_flipped_sign() {
     let program = "let x = 3
@@ -1572,6 +1591,7 @@ async fn test_client() -> Session {
         video_res_width: Some(1280),
         buffer_reqs: None,
         await_response_timeout: None,
+        show_grid: None,
     };
     match Session::start(session_builder).await {
         Err(e) => match e {
@@ -1829,3 +1849,136 @@ async fn cos_sin_pi() {
     // Constants don't live in memory.
     assert_eq!(*z, constants::PI);
 }
+
+#[test]
+fn if_expression_with_literal_condition() {
+    let program = "
+        let x = if false {
+            1
+        } else if true {
+            let y = 2
+            y
+        } else {
+            3
+        }";
+    let (plan, scope, _) = must_plan(program);
+    // Only the branch which is taken should be planned.
+    assert_eq!(
+        plan,
+        vec![Instruction::from_range(
+            InstructionKind::SetPrimitive {
+                address: Address::ZERO,
+                value: 2i64.into(),
+            },
+            SourceRange([87, 88])
+        )]
+    );
+    assert_eq!(scope.get("x"), Some(&EpBinding::Single(Address::ZERO)));
+    // Bindings inside the branch aren't visible outside of it.
+    assert!(scope.get("y").is_none());
+}
+
+#[test]
+fn if_expression_with_non_literal_condition() {
+    let program = "
+        let cond = true
+        let x = if cond { 1 } else { 2 }";
+    let err = should_not_compile(program);
+    assert_eq!(err, CompileError::NonConstantCondition);
+}
+
+#[test]
+fn for_loop_over_literal_range_is_unrolled() {
+    let program = "
+        for i in 0..2 {
+            let y = i
+        }";
+    let (plan, scope, _) = must_plan(program);
+    // The body is planned once per item, with the loop variable bound to that item.
+    let expected: Vec<_> = (0..=2)
+        .map(|i| {
+            Instruction::from_range(
+                InstructionKind::SetPrimitive {
+                    address: Address::ZERO + i,
+                    value: (i as i64).into(),
+                },
+                SourceRange([18, 22]),
+            )
+        })
+        .collect();
+    assert_eq!(plan, expected);
+    // Neither the loop variable nor bindings inside the body are visible outside of it.
+    assert!(scope.get("i").is_none());
+    assert!(scope.get("y").is_none());
+}
+
+#[test]
+fn for_loop_over_non_literal_array() {
+    let program = "
+        let points = [1, 2, 3]
+        for p in points {
+            let y = p
+        }";
+    let err = should_not_compile(program);
+    assert_eq!(err, CompileError::NonConstantLoop);
+}
+
+#[test]
+fn numbers_with_units() {
+    let err = should_not_compile("let width = 10mm");
+    assert_eq!(err, CompileError::UnitsNotSupported);
+    let err = should_not_compile("let y = if 1in < 2mm { 1 } else { 2 }");
+    assert_eq!(err, CompileError::UnitsNotSupported);
+}
+
+#[test]
+fn for_loop_with_too_many_iterations() {
+    let program = "
+        for i in 0..100000 {
+            let y = i
+        }";
+    let err = should_not_compile(program);
+    assert_eq!(err, CompileError::TooManyIterations { iterations: 100001 });
+}
+
+#[test]
+fn comparisons_of_literals_are_folded() {
+    let program = "let x = 1 + 1 < 3 && 2 != 2.5";
+    let (plan, scope, _) = must_plan(program);
+    assert_eq!(
+        plan,
+        vec![Instruction::from_range(
+            InstructionKind::SetPrimitive {
+                address: Address::ZERO,
+                value: true.into(),
+            },
+            SourceRange([8, 29])
+        )]
+    );
+    assert_eq!(scope.get("x"), Some(&EpBinding::Single(Address::ZERO)));
+}
+
+#[test]
+fn if_expression_with_literal_comparison() {
+    let program = "let x = if 1 > 2 { 1 } else { 2 }";
+    let (plan, _scope, _) = must_plan(program);
+    assert_eq!(
+        plan,
+        vec![Instruction::from_range(
+            InstructionKind::SetPrimitive {
+                address: Address::ZERO,
+                value: 2i64.into(),
+            },
+            SourceRange([30, 31])
+        )]
+    );
+}
+
+#[test]
+fn comparison_with_non_literal_operand() {
+    let program = "
+        let a = 1
+        let x = a < 2";
+    let err = should_not_compile(program);
+    assert_eq!(err, CompileError::NonConstantComparison);
+}
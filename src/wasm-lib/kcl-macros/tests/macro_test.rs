@@ -1,7 +1,7 @@
 extern crate alloc;
 use kcl_lib::ast::types::{
-    BodyItem, Identifier, Literal, LiteralValue, NonCodeMeta, Program, Value, VariableDeclaration, VariableDeclarator,
-    VariableKind,
+    BindingPattern, BodyItem, Identifier, ItemVisibility, Literal, LiteralValue, NonCodeMeta, Program, Value,
+    VariableDeclaration, VariableDeclarator, VariableKind,
 };
 use kcl_macros::parse;
 use pretty_assertions::assert_eq;
@@ -18,19 +18,21 @@ fn basic() {
             declarations: vec![VariableDeclarator {
                 start: 6,
                 end: 11,
-                id: Identifier {
+                id: BindingPattern::Identifier(Box::new(Identifier {
                     start: 6,
                     end: 7,
                     name: "y".to_owned(),
-                },
+                })),
                 init: Value::Literal(Box::new(Literal {
                     start: 10,
                     end: 11,
                     value: LiteralValue::IInteger(4),
                     raw: "4".to_owned(),
+                    suffix: None,
                 })),
             }],
             kind: VariableKind::Const,
+            visibility: ItemVisibility::Default,
         })],
         non_code_meta: NonCodeMeta::default(),
     };
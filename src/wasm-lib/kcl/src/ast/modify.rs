@@ -56,6 +56,7 @@ pub async fn modify_ast_for_sketch(
                         sketch_name, constraint_level
                     ),
                     source_ranges: levels.get_all_partial_or_full_source_ranges(),
+                    ..Default::default()
                 }));
             }
             ConstraintLevel::Full { source_ranges } => {
@@ -65,6 +66,7 @@ pub async fn modify_ast_for_sketch(
                         sketch_name, constraint_level
                     ),
                     source_ranges: source_ranges.clone(),
+                    ..Default::default()
                 }));
             }
         }
@@ -88,6 +90,7 @@ pub async fn modify_ast_for_sketch(
         return Err(KclError::Engine(KclErrorDetails {
             message: format!("Get path info response was not as expected: {:?}", resp),
             source_ranges: vec![SourceRange::default()],
+            ..Default::default()
         }));
     };
 
@@ -111,6 +114,7 @@ pub async fn modify_ast_for_sketch(
                 return Err(KclError::Engine(KclErrorDetails {
                     message: format!("Curve get control points response was not as expected: {:?}", resp),
                     source_ranges: vec![SourceRange::default()],
+                    ..Default::default()
                 }));
             };
 
@@ -126,6 +130,7 @@ pub async fn modify_ast_for_sketch(
         return Err(KclError::Engine(KclErrorDetails {
             message: format!("No control points found for sketch {}", sketch_name),
             source_ranges: vec![SourceRange::default()],
+            ..Default::default()
         }));
     }
 
@@ -133,6 +138,7 @@ pub async fn modify_ast_for_sketch(
         KclError::Engine(KclErrorDetails {
             message: format!("No control points found for sketch {}", sketch_name),
             source_ranges: vec![SourceRange::default()],
+            ..Default::default()
         })
     })?;
 
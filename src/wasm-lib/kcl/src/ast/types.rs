@@ -12,11 +12,17 @@ use tower_lsp::lsp_types::{
     CompletionItem, CompletionItemKind, DocumentSymbol, FoldingRange, FoldingRangeKind, Range as LspRange, SymbolKind,
 };
 
-pub use crate::ast::types::{literal_value::LiteralValue, none::KclNone};
+pub use crate::ast::types::{
+    literal_value::{Dimension, LiteralValue, NumericSuffix},
+    none::KclNone,
+};
 use crate::{
     docs::StdLibFn,
-    errors::{KclError, KclErrorDetails},
-    executor::{BodyType, ExecutorContext, MemoryItem, Metadata, PipeInfo, ProgramMemory, SourceRange, UserVal},
+    errors::{KclError, KclErrorDetails, KclWarning},
+    executor::{
+        order_named_args, BodyType, ExecutorContext, MemoryItem, Metadata, PipeInfo, ProgramMemory, SourceRange,
+        UserVal, MAX_LOOP_ITERATIONS,
+    },
     parser::PIPE_OPERATOR,
     std::{kcl_stdlib::KclStdLibFn, FunctionKind},
 };
@@ -65,9 +71,13 @@ impl Program {
             .iter()
             .map(|statement| match statement.clone() {
                 BodyItem::ExpressionStatement(expression_statement) => {
-                    expression_statement
-                        .expression
-                        .recast(options, indentation_level, false)
+                    format!(
+                        "{}{}",
+                        indentation,
+                        expression_statement
+                            .expression
+                            .recast(options, indentation_level, false)
+                    )
                 }
                 BodyItem::VariableDeclaration(variable_declaration) => {
                     variable_declaration.recast(options, indentation_level)
@@ -76,9 +86,15 @@ impl Program {
                     format!(
                         "{}return {}",
                         indentation,
-                        return_statement.argument.recast(options, 0, false)
+                        return_statement.argument.recast(options, indentation_level, false)
                     )
                 }
+                BodyItem::ForStatement(for_statement) => {
+                    format!("{}{}", indentation, for_statement.recast(options, indentation_level))
+                }
+                BodyItem::ImportStatement(import_statement) => {
+                    format!("{}{}", indentation, import_statement.recast())
+                }
             })
             .enumerate()
             .fold(String::new(), |mut output, (index, recast_str)| {
@@ -179,6 +195,8 @@ impl Program {
             BodyItem::ExpressionStatement(expression_statement) => Some(&expression_statement.expression),
             BodyItem::VariableDeclaration(variable_declaration) => variable_declaration.get_value_for_position(pos),
             BodyItem::ReturnStatement(return_statement) => Some(&return_statement.argument),
+            BodyItem::ForStatement(for_statement) => for_statement.get_value_for_position(pos),
+            BodyItem::ImportStatement(_import_statement) => None,
         }
     }
 
@@ -195,6 +213,8 @@ impl Program {
             BodyItem::ExpressionStatement(expression_statement) => Some(&expression_statement.expression),
             BodyItem::VariableDeclaration(variable_declaration) => variable_declaration.get_value_for_position(pos),
             BodyItem::ReturnStatement(return_statement) => Some(&return_statement.argument),
+            BodyItem::ForStatement(for_statement) => for_statement.get_value_for_position(pos),
+            BodyItem::ImportStatement(_import_statement) => None,
         };
 
         // Check if the value's non code meta contains the position.
@@ -221,6 +241,8 @@ impl Program {
                     symbols.extend(variable_declaration.get_lsp_symbols(code))
                 }
                 BodyItem::ReturnStatement(_return_statement) => continue,
+                BodyItem::ForStatement(_for_statement) => continue,
+                BodyItem::ImportStatement(_import_statement) => continue,
             }
         }
 
@@ -244,6 +266,8 @@ impl Program {
                     }
                 }
                 BodyItem::ReturnStatement(_return_statement) => continue,
+                BodyItem::ForStatement(_for_statement) => continue,
+                BodyItem::ImportStatement(_import_statement) => continue,
             }
         }
 
@@ -266,6 +290,8 @@ impl Program {
                     }
                 }
                 BodyItem::ReturnStatement(_return_statement) => continue,
+                BodyItem::ForStatement(_for_statement) => continue,
+                BodyItem::ImportStatement(_import_statement) => continue,
             }
         }
 
@@ -289,6 +315,8 @@ impl Program {
                     variable_declaration.get_mut_value_for_position(pos)
                 }
                 BodyItem::ReturnStatement(ref mut return_statement) => Some(&mut return_statement.argument),
+                BodyItem::ForStatement(ref mut for_statement) => for_statement.get_mut_value_for_position(pos),
+                BodyItem::ImportStatement(_import_statement) => None,
             };
 
             // Check if we have a function expression.
@@ -322,6 +350,12 @@ impl Program {
                 BodyItem::ReturnStatement(ref mut return_statement) => {
                     return_statement.argument.rename_identifiers(old_name, new_name);
                 }
+                BodyItem::ForStatement(ref mut for_statement) => {
+                    for_statement.rename_identifiers(old_name, new_name);
+                }
+                BodyItem::ImportStatement(ref mut import_statement) => {
+                    import_statement.rename_identifiers(old_name, new_name);
+                }
             }
         }
     }
@@ -335,13 +369,15 @@ impl Program {
                 }
                 BodyItem::VariableDeclaration(ref mut variable_declaration) => {
                     for declaration in &mut variable_declaration.declarations {
-                        if declaration.id.name == name {
+                        if declaration.declares(name) {
                             *declaration = declarator;
                             return;
                         }
                     }
                 }
                 BodyItem::ReturnStatement(_return_statement) => continue,
+                BodyItem::ForStatement(_for_statement) => continue,
+                BodyItem::ImportStatement(_import_statement) => continue,
             }
         }
     }
@@ -359,6 +395,10 @@ impl Program {
                 BodyItem::ReturnStatement(ref mut return_statement) => {
                     return_statement.argument.replace_value(source_range, new_value.clone())
                 }
+                BodyItem::ForStatement(ref mut for_statement) => {
+                    for_statement.replace_value(source_range, new_value.clone())
+                }
+                BodyItem::ImportStatement(_import_statement) => {}
             }
         }
     }
@@ -372,12 +412,14 @@ impl Program {
                 }
                 BodyItem::VariableDeclaration(variable_declaration) => {
                     for declaration in &variable_declaration.declarations {
-                        if declaration.id.name == name {
+                        if declaration.declares(name) {
                             return Some(declaration);
                         }
                     }
                 }
                 BodyItem::ReturnStatement(_return_statement) => continue,
+                BodyItem::ForStatement(_for_statement) => continue,
+                BodyItem::ImportStatement(_import_statement) => continue,
             }
         }
 
@@ -433,6 +475,8 @@ pub enum BodyItem {
     ExpressionStatement(ExpressionStatement),
     VariableDeclaration(VariableDeclaration),
     ReturnStatement(ReturnStatement),
+    ForStatement(Box<ForStatement>),
+    ImportStatement(Box<ImportStatement>),
 }
 
 impl BodyItem {
@@ -441,6 +485,8 @@ impl BodyItem {
             BodyItem::ExpressionStatement(expression_statement) => expression_statement.start(),
             BodyItem::VariableDeclaration(variable_declaration) => variable_declaration.start(),
             BodyItem::ReturnStatement(return_statement) => return_statement.start(),
+            BodyItem::ForStatement(for_statement) => for_statement.start(),
+            BodyItem::ImportStatement(import_statement) => import_statement.start(),
         }
     }
 
@@ -449,6 +495,8 @@ impl BodyItem {
             BodyItem::ExpressionStatement(expression_statement) => expression_statement.end(),
             BodyItem::VariableDeclaration(variable_declaration) => variable_declaration.end(),
             BodyItem::ReturnStatement(return_statement) => return_statement.end(),
+            BodyItem::ForStatement(for_statement) => for_statement.end(),
+            BodyItem::ImportStatement(import_statement) => import_statement.end(),
         }
     }
 }
@@ -481,6 +529,7 @@ pub enum Value {
     ObjectExpression(Box<ObjectExpression>),
     MemberExpression(Box<MemberExpression>),
     UnaryExpression(Box<UnaryExpression>),
+    IfExpression(Box<IfExpression>),
     None(KclNone),
 }
 
@@ -497,6 +546,7 @@ impl Value {
             Value::Identifier(ident) => ident.name.to_string(),
             Value::PipeExpression(pipe_exp) => pipe_exp.recast(options, indentation_level),
             Value::UnaryExpression(unary_exp) => unary_exp.recast(options),
+            Value::IfExpression(if_exp) => if_exp.recast(options, indentation_level),
             Value::PipeSubstitution(_) => crate::parser::PIPE_SUBSTITUTION_OPERATOR.to_string(),
             Value::None(_) => {
                 unimplemented!("there is no literal None, see https://github.com/KittyCAD/modeling-app/issues/1115")
@@ -537,6 +587,7 @@ impl Value {
             Value::Identifier(_ident) => None,
             Value::PipeExpression(pipe_exp) => Some(&pipe_exp.non_code_meta),
             Value::UnaryExpression(_unary_exp) => None,
+            Value::IfExpression(_if_exp) => None,
             Value::PipeSubstitution(_pipe_substitution) => None,
             Value::None(_none) => None,
         }
@@ -559,6 +610,7 @@ impl Value {
             Value::Identifier(_) => {}
             Value::PipeExpression(ref mut pipe_exp) => pipe_exp.replace_value(source_range, new_value),
             Value::UnaryExpression(ref mut unary_exp) => unary_exp.replace_value(source_range, new_value),
+            Value::IfExpression(ref mut if_exp) => if_exp.replace_value(source_range, new_value),
             Value::PipeSubstitution(_) => {}
             Value::None(_) => {}
         }
@@ -577,6 +629,7 @@ impl Value {
             Value::ObjectExpression(object_expression) => object_expression.start(),
             Value::MemberExpression(member_expression) => member_expression.start(),
             Value::UnaryExpression(unary_expression) => unary_expression.start(),
+            Value::IfExpression(if_expression) => if_expression.start(),
             Value::None(none) => none.start,
         }
     }
@@ -594,6 +647,7 @@ impl Value {
             Value::ObjectExpression(object_expression) => object_expression.end(),
             Value::MemberExpression(member_expression) => member_expression.end(),
             Value::UnaryExpression(unary_expression) => unary_expression.end(),
+            Value::IfExpression(if_expression) => if_expression.end(),
             Value::None(none) => none.end,
         }
     }
@@ -612,6 +666,7 @@ impl Value {
             Value::ObjectExpression(object_expression) => object_expression.get_hover_value_for_position(pos, code),
             Value::MemberExpression(member_expression) => member_expression.get_hover_value_for_position(pos, code),
             Value::UnaryExpression(unary_expression) => unary_expression.get_hover_value_for_position(pos, code),
+            Value::IfExpression(if_expression) => if_expression.get_hover_value_for_position(pos, code),
             // TODO: LSP hover information for values/types. https://github.com/KittyCAD/modeling-app/issues/1126
             Value::None(_) => None,
             Value::Literal(_) => None,
@@ -641,6 +696,7 @@ impl Value {
                 member_expression.rename_identifiers(old_name, new_name)
             }
             Value::UnaryExpression(ref mut unary_expression) => unary_expression.rename_identifiers(old_name, new_name),
+            Value::IfExpression(ref mut if_expression) => if_expression.rename_identifiers(old_name, new_name),
             Value::None(_) => {}
         }
     }
@@ -662,6 +718,7 @@ impl Value {
             Value::ObjectExpression(object_expression) => object_expression.get_constraint_level(),
             Value::MemberExpression(member_expression) => member_expression.get_constraint_level(),
             Value::UnaryExpression(unary_expression) => unary_expression.get_constraint_level(),
+            Value::IfExpression(if_expression) => if_expression.get_constraint_level(),
             Value::None(none) => none.get_constraint_level(),
         }
     }
@@ -775,7 +832,7 @@ impl BinaryPart {
         ctx: &ExecutorContext,
     ) -> Result<MemoryItem, KclError> {
         match self {
-            BinaryPart::Literal(literal) => Ok(literal.into()),
+            BinaryPart::Literal(literal) => Ok(literal.get_result(ctx)),
             BinaryPart::Identifier(identifier) => {
                 let value = memory.get(&identifier.name, identifier.into())?;
                 Ok(value.clone())
@@ -1027,17 +1084,44 @@ impl_value_meta!(ExpressionStatement);
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema, Bake)]
 #[databake(path = kcl_lib::ast::types)]
 #[ts(export)]
-#[serde(tag = "type")]
+#[serde(rename_all = "camelCase", tag = "type")]
 pub struct CallExpression {
     pub start: usize,
     pub end: usize,
     pub callee: Identifier,
     pub arguments: Vec<Value>,
+    /// Arguments passed by name, e.g. `tag = 'seg01'`. These always come after the positional arguments.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub named_arguments: Vec<NamedArgument>,
     pub optional: bool,
 }
 
 impl_value_meta!(CallExpression);
 
+/// An argument passed to a function by the name of its parameter, e.g. `radius = 2`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema, Bake)]
+#[databake(path = kcl_lib::ast::types)]
+#[ts(export)]
+#[serde(tag = "type")]
+pub struct NamedArgument {
+    pub start: usize,
+    pub end: usize,
+    pub name: Identifier,
+    pub value: Value,
+}
+
+impl_value_meta!(NamedArgument);
+
+impl NamedArgument {
+    fn recast(&self, options: &FormatOptions, indentation_level: usize, is_in_pipe: bool) -> String {
+        format!(
+            "{} = {}",
+            self.name.name,
+            self.value.recast(options, indentation_level, is_in_pipe)
+        )
+    }
+}
+
 impl From<CallExpression> for Value {
     fn from(call_expression: CallExpression) -> Self {
         Value::CallExpression(Box::new(call_expression))
@@ -1051,6 +1135,7 @@ impl CallExpression {
             end: 0,
             callee: Identifier::new(name),
             arguments,
+            named_arguments: Vec::new(),
             optional: false,
         })
     }
@@ -1070,6 +1155,9 @@ impl CallExpression {
         for arg in &mut self.arguments {
             arg.replace_value(source_range, new_value.clone());
         }
+        for arg in &mut self.named_arguments {
+            arg.value.replace_value(source_range, new_value.clone());
+        }
     }
 
     fn recast(&self, options: &FormatOptions, indentation_level: usize, is_in_pipe: bool) -> String {
@@ -1079,6 +1167,11 @@ impl CallExpression {
             self.arguments
                 .iter()
                 .map(|arg| arg.recast(options, indentation_level, is_in_pipe))
+                .chain(
+                    self.named_arguments
+                        .iter()
+                        .map(|arg| arg.recast(options, indentation_level, is_in_pipe))
+                )
                 .collect::<Vec<String>>()
                 .join(", ")
         )
@@ -1098,7 +1191,7 @@ impl CallExpression {
         for arg in &self.arguments {
             let result: MemoryItem = match arg {
                 Value::None(none) => none.into(),
-                Value::Literal(literal) => literal.into(),
+                Value::Literal(literal) => literal.get_result(ctx),
                 Value::Identifier(identifier) => {
                     let value = memory.get(&identifier.name, identifier.into())?;
                     value.clone()
@@ -1108,12 +1201,14 @@ impl CallExpression {
                 }
                 Value::CallExpression(call_expression) => call_expression.execute(memory, pipe_info, ctx).await?,
                 Value::UnaryExpression(unary_expression) => unary_expression.get_result(memory, pipe_info, ctx).await?,
+                Value::IfExpression(if_expression) => if_expression.get_result(memory, pipe_info, ctx).await?,
                 Value::ObjectExpression(object_expression) => object_expression.execute(memory, pipe_info, ctx).await?,
                 Value::ArrayExpression(array_expression) => array_expression.execute(memory, pipe_info, ctx).await?,
                 Value::PipeExpression(pipe_expression) => {
                     return Err(KclError::Semantic(KclErrorDetails {
                         message: format!("PipeExpression not implemented here: {:?}", pipe_expression),
                         source_ranges: vec![pipe_expression.into()],
+                        ..Default::default()
                     }));
                 }
                 Value::PipeSubstitution(pipe_substitution) => pipe_info
@@ -1123,6 +1218,7 @@ impl CallExpression {
                         KclError::Semantic(KclErrorDetails {
                             message: format!("PipeSubstitution index out of bounds: {:?}", pipe_info),
                             source_ranges: vec![pipe_substitution.into()],
+                            ..Default::default()
                         })
                     })?
                     .clone(),
@@ -1131,6 +1227,7 @@ impl CallExpression {
                     return Err(KclError::Semantic(KclErrorDetails {
                         message: format!("FunctionExpression not implemented here: {:?}", function_expression),
                         source_ranges: vec![function_expression.into()],
+                        ..Default::default()
                     }));
                 }
             };
@@ -1138,8 +1235,22 @@ impl CallExpression {
             fn_args.push(result);
         }
 
+        let mut named_args = Vec::with_capacity(self.named_arguments.len());
+        for arg in &self.named_arguments {
+            let result = ctx.execute_value(&arg.value, memory, pipe_info).await?;
+            named_args.push((arg.name.clone(), result));
+        }
+
         match ctx.stdlib.get_either(&self.callee.name) {
             FunctionKind::Core(func) => {
+                if !named_args.is_empty() {
+                    let params: Vec<Parameter> = func.args().iter().map(Parameter::from).collect();
+                    fn_args = order_named_args(&params, fn_args, named_args, self.into())?;
+                }
+                if func.deprecated() {
+                    ctx.warnings
+                        .add(KclWarning::new(self.into(), format!("`{}` is deprecated", func.name())));
+                }
                 // Attempt to call the function.
                 let args = crate::std::Args::new(fn_args, self.into(), ctx.clone());
                 let result = func.std_lib_fn()(args).await?;
@@ -1147,10 +1258,12 @@ impl CallExpression {
             }
             FunctionKind::Std(func) => {
                 let function_expression = func.function();
+                let fn_args = order_named_args(&function_expression.params, fn_args, named_args, self.into())?;
                 let parts = function_expression.clone().into_parts().map_err(|e| {
                     KclError::Semantic(KclErrorDetails {
                         message: format!("Error getting parts of function: {}", e),
                         source_ranges: vec![self.into()],
+                        ..Default::default()
                     })
                 })?;
                 if fn_args.len() < parts.params_required.len() || fn_args.len() > function_expression.params.len() {
@@ -1161,11 +1274,13 @@ impl CallExpression {
                             fn_args.len(),
                         ),
                         source_ranges: vec![self.into()],
+                        ..Default::default()
                     }));
                 }
 
                 // Add the arguments to the memory.
-                let mut fn_memory = memory.clone();
+                // Stdlib functions only see their own arguments, not the caller's variables.
+                let mut fn_memory = ProgramMemory::new();
                 for (index, param) in parts.params_required.iter().enumerate() {
                     fn_memory.add(
                         &param.identifier.name,
@@ -1183,6 +1298,7 @@ impl CallExpression {
                             MemoryItem::UserVal(UserVal {
                                 value: serde_json::value::Value::Null,
                                 meta: Default::default(),
+                                ..Default::default()
                             }),
                             param.identifier.clone().into(),
                         )?;
@@ -1204,6 +1320,7 @@ impl CallExpression {
                     KclError::UndefinedValue(KclErrorDetails {
                         message: format!("Result of stdlib function {} is undefined", fn_name),
                         source_ranges: vec![self.into()],
+                        ..Default::default()
                     })
                 })?;
                 let result = result.get_value()?;
@@ -1213,12 +1330,14 @@ impl CallExpression {
             FunctionKind::UserDefined => {
                 let func = memory.get(&fn_name, self.into())?;
                 let result = func
-                    .call_fn(fn_args, memory.clone(), ctx.clone())
-                    .await?
+                    .call_fn_with_named_args(fn_args, named_args, ctx.clone())
+                    .await
+                    .map_err(|e| e.add_stack_frame(&fn_name, self.into()))?
                     .ok_or_else(|| {
                         KclError::UndefinedValue(KclErrorDetails {
                             message: format!("Result of user-defined function {} is undefined", fn_name),
                             source_ranges: vec![self.into()],
+                            ..Default::default()
                         })
                     })?;
 
@@ -1250,6 +1369,17 @@ impl CallExpression {
             }
         }
 
+        for arg in &self.named_arguments {
+            let source_range: SourceRange = arg.into();
+            if source_range.contains(pos) {
+                return Some(Hover::NamedArgument {
+                    name: self.callee.name.clone(),
+                    argument: arg.name.name.clone(),
+                    range: source_range.to_lsp_range(code),
+                });
+            }
+        }
+
         None
     }
 
@@ -1260,11 +1390,14 @@ impl CallExpression {
         for arg in &mut self.arguments {
             arg.rename_identifiers(old_name, new_name);
         }
+        for arg in &mut self.named_arguments {
+            arg.value.rename_identifiers(old_name, new_name);
+        }
     }
 
     /// Return the constraint level for this call expression.
     pub fn get_constraint_level(&self) -> ConstraintLevel {
-        if self.arguments.is_empty() {
+        if self.arguments.is_empty() && self.named_arguments.is_empty() {
             return ConstraintLevel::Ignore {
                 source_ranges: vec![self.into()],
             };
@@ -1272,7 +1405,11 @@ impl CallExpression {
 
         // Iterate over the arguments and get the constraint level for each one.
         let mut constraint_levels = ConstraintLevels::new();
-        for arg in &self.arguments {
+        for arg in self
+            .arguments
+            .iter()
+            .chain(self.named_arguments.iter().map(|arg| &arg.value))
+        {
             constraint_levels.push(arg.get_constraint_level());
         }
 
@@ -1319,6 +1456,8 @@ pub struct VariableDeclaration {
     pub end: usize,
     pub declarations: Vec<VariableDeclarator>,
     pub kind: VariableKind, // Change to enum if there are specific values
+    #[serde(default, skip_serializing_if = "ItemVisibility::is_default")]
+    pub visibility: ItemVisibility,
 }
 
 impl_value_meta!(VariableDeclaration);
@@ -1330,6 +1469,7 @@ impl VariableDeclaration {
             end: 0,
             declarations,
             kind,
+            visibility: ItemVisibility::Default,
         }
     }
 
@@ -1358,10 +1498,11 @@ impl VariableDeclaration {
         self.declarations.iter().fold(String::new(), |mut output, declaration| {
             let _ = write!(
                 output,
-                "{}{} {} = {}",
+                "{}{}{} {} = {}",
                 indentation,
+                self.visibility.recast(),
                 self.kind,
-                declaration.id.name,
+                declaration.id.recast(),
                 declaration.init.recast(options, indentation_level, false)
             );
             output
@@ -1408,11 +1549,13 @@ impl VariableDeclaration {
         }
 
         for declaration in &mut self.declarations {
-            let declaration_source_range: SourceRange = declaration.id.clone().into();
-            if declaration_source_range.contains(pos) {
-                let old_name = declaration.id.name.clone();
-                declaration.id.name = new_name.to_string();
-                return Some(old_name);
+            for id in declaration.id.names_mut() {
+                let declaration_source_range: SourceRange = (&*id).into();
+                if declaration_source_range.contains(pos) {
+                    let old_name = id.name.clone();
+                    id.name = new_name.to_string();
+                    return Some(old_name);
+                }
             }
         }
 
@@ -1422,7 +1565,7 @@ impl VariableDeclaration {
     pub fn rename_identifiers(&mut self, old_name: &str, new_name: &str) {
         for declaration in &mut self.declarations {
             // Skip the init for the variable with the new name since it is the one we are renaming.
-            if declaration.id.name == new_name {
+            if declaration.declares(new_name) {
                 continue;
             }
 
@@ -1435,7 +1578,6 @@ impl VariableDeclaration {
 
         for declaration in &self.declarations {
             let source_range: SourceRange = declaration.into();
-            let inner_source_range: SourceRange = declaration.id.clone().into();
 
             let mut symbol_kind = match self.kind {
                 VariableKind::Fn => SymbolKind::FUNCTION,
@@ -1479,9 +1621,32 @@ impl VariableDeclaration {
                 _ => vec![],
             };
 
+            let BindingPattern::Identifier(id) = &declaration.id else {
+                // Each destructured name is its own symbol, but the value's contents belong to none of them.
+                for id in declaration.id.names() {
+                    let inner_source_range: SourceRange = id.into();
+                    #[allow(deprecated)]
+                    symbols.push(DocumentSymbol {
+                        name: id.name.clone(),
+                        detail: Some(self.kind.to_string()),
+                        kind: match self.kind {
+                            VariableKind::Const => SymbolKind::CONSTANT,
+                            _ => SymbolKind::VARIABLE,
+                        },
+                        range: source_range.to_lsp_range(code),
+                        selection_range: inner_source_range.to_lsp_range(code),
+                        children: Some(vec![]),
+                        tags: None,
+                        deprecated: None,
+                    });
+                }
+                continue;
+            };
+            let inner_source_range: SourceRange = id.into();
+
             #[allow(deprecated)]
             symbols.push(DocumentSymbol {
-                name: declaration.id.name.clone(),
+                name: id.name.clone(),
                 detail: Some(self.kind.to_string()),
                 kind: symbol_kind,
                 range: source_range.to_lsp_range(code),
@@ -1496,6 +1661,32 @@ impl VariableDeclaration {
     }
 }
 
+/// Whether a declaration can be imported by other KCL files.
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, ts_rs::TS, JsonSchema, Bake)]
+#[databake(path = kcl_lib::ast::types)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemVisibility {
+    /// Only visible inside the file which declares it.
+    #[default]
+    Default,
+    /// Marked with `export`, so other files can import it.
+    Export,
+}
+
+impl ItemVisibility {
+    pub fn is_default(&self) -> bool {
+        *self == ItemVisibility::Default
+    }
+
+    fn recast(&self) -> &'static str {
+        match self {
+            ItemVisibility::Default => "",
+            ItemVisibility::Export => "export ",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema, FromStr, Display, Bake)]
 #[databake(path = kcl_lib::ast::types)]
 #[ts(export)]
@@ -1548,8 +1739,8 @@ impl VariableKind {
 pub struct VariableDeclarator {
     pub start: usize,
     pub end: usize,
-    /// The identifier of the variable.
-    pub id: Identifier,
+    /// The identifier of the variable, or the names the value is destructured into.
+    pub id: BindingPattern,
     /// The value of the variable.
     pub init: Value,
 }
@@ -1561,16 +1752,118 @@ impl VariableDeclarator {
         Self {
             start: 0,
             end: 0,
-            id: Identifier::new(name),
+            id: BindingPattern::Identifier(Box::new(Identifier::new(name))),
             init,
         }
     }
 
+    /// Does this declare a variable with the given name?
+    pub fn declares(&self, name: &str) -> bool {
+        self.id.names().iter().any(|id| id.name == name)
+    }
+
     pub fn get_constraint_level(&self) -> ConstraintLevel {
         self.init.get_constraint_level()
     }
 }
 
+/// The names a variable declaration binds its value to.
+/// Each pattern has its own `type` tag, so an identifier serializes the same as it always has.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema, Bake)]
+#[databake(path = kcl_lib::ast::types)]
+#[ts(export)]
+#[serde(untagged)]
+pub enum BindingPattern {
+    /// The whole value gets one name, e.g. `const x = ...`.
+    Identifier(Box<Identifier>),
+    /// Each item of an array gets a name, e.g. `const [x, y] = ...`.
+    ArrayPattern(Box<ArrayPattern>),
+    /// Some properties of an object get their own names, e.g. `const { width, height } = ...`.
+    ObjectPattern(Box<ObjectPattern>),
+}
+
+impl BindingPattern {
+    pub fn start(&self) -> usize {
+        match self {
+            BindingPattern::Identifier(identifier) => identifier.start(),
+            BindingPattern::ArrayPattern(array_pattern) => array_pattern.start(),
+            BindingPattern::ObjectPattern(object_pattern) => object_pattern.start(),
+        }
+    }
+
+    pub fn end(&self) -> usize {
+        match self {
+            BindingPattern::Identifier(identifier) => identifier.end(),
+            BindingPattern::ArrayPattern(array_pattern) => array_pattern.end(),
+            BindingPattern::ObjectPattern(object_pattern) => object_pattern.end(),
+        }
+    }
+
+    /// Every name this pattern binds, in the order they're written.
+    pub fn names(&self) -> Vec<&Identifier> {
+        match self {
+            BindingPattern::Identifier(identifier) => vec![identifier],
+            BindingPattern::ArrayPattern(array_pattern) => array_pattern.elements.iter().collect(),
+            BindingPattern::ObjectPattern(object_pattern) => object_pattern.properties.iter().collect(),
+        }
+    }
+
+    fn names_mut(&mut self) -> Vec<&mut Identifier> {
+        match self {
+            BindingPattern::Identifier(identifier) => vec![identifier],
+            BindingPattern::ArrayPattern(array_pattern) => array_pattern.elements.iter_mut().collect(),
+            BindingPattern::ObjectPattern(object_pattern) => object_pattern.properties.iter_mut().collect(),
+        }
+    }
+
+    fn recast(&self) -> String {
+        let names = || {
+            self.names()
+                .iter()
+                .map(|id| id.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match self {
+            BindingPattern::Identifier(identifier) => identifier.name.clone(),
+            BindingPattern::ArrayPattern(_) => format!("[{}]", names()),
+            BindingPattern::ObjectPattern(_) => format!("{{ {} }}", names()),
+        }
+    }
+}
+
+impl From<&BindingPattern> for SourceRange {
+    fn from(pattern: &BindingPattern) -> Self {
+        Self([pattern.start(), pattern.end()])
+    }
+}
+
+/// Destructures an array into names for its items, e.g. `[x, y]`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema, Bake)]
+#[databake(path = kcl_lib::ast::types)]
+#[ts(export)]
+#[serde(tag = "type")]
+pub struct ArrayPattern {
+    pub start: usize,
+    pub end: usize,
+    pub elements: Vec<Identifier>,
+}
+
+impl_value_meta!(ArrayPattern);
+
+/// Destructures an object into variables named after its properties, e.g. `{ width, height }`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema, Bake)]
+#[databake(path = kcl_lib::ast::types)]
+#[ts(export)]
+#[serde(tag = "type")]
+pub struct ObjectPattern {
+    pub start: usize,
+    pub end: usize,
+    pub properties: Vec<Identifier>,
+}
+
+impl_value_meta!(ObjectPattern);
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema, Bake)]
 #[databake(path = kcl_lib::ast::types)]
 #[ts(export)]
@@ -1580,6 +1873,9 @@ pub struct Literal {
     pub end: usize,
     pub value: LiteralValue,
     pub raw: String,
+    /// The unit of measure written after a number, e.g. the `mm` in `10mm`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<NumericSuffix>,
 }
 
 impl_value_meta!(Literal);
@@ -1591,9 +1887,26 @@ impl Literal {
             end: 0,
             raw: JValue::from(value.clone()).to_string(),
             value,
+            suffix: None,
         }
     }
 
+    /// The value of the literal, with any unit of measure converted to the scene's units.
+    pub fn get_result(&self, ctx: &ExecutorContext) -> MemoryItem {
+        let value = match (&self.value, self.suffix) {
+            (LiteralValue::IInteger(x), Some(suffix)) => suffix.convert(*x as f64, &ctx.settings.units).into(),
+            (LiteralValue::Fractional(x), Some(suffix)) => suffix.convert(*x, &ctx.settings.units).into(),
+            (value, _) => JValue::from(value.clone()),
+        };
+        MemoryItem::UserVal(UserVal {
+            value,
+            meta: vec![Metadata {
+                source_range: self.into(),
+            }],
+            dimension: self.suffix.map(|suffix| suffix.dimension()),
+        })
+    }
+
     /// Get the constraint level for this literal.
     /// Literals are always not constrained.
     pub fn get_constraint_level(&self) -> ConstraintLevel {
@@ -1606,7 +1919,8 @@ impl Literal {
         match self.value {
             LiteralValue::Fractional(x) => {
                 if x.fract() == 0.0 {
-                    format!("{x:?}")
+                    let suffix = self.suffix.map(|suffix| suffix.to_string()).unwrap_or_default();
+                    format!("{x:?}{suffix}")
                 } else {
                     self.raw.clone()
                 }
@@ -1628,6 +1942,7 @@ impl From<Literal> for MemoryItem {
             meta: vec![Metadata {
                 source_range: literal.into(),
             }],
+            ..Default::default()
         })
     }
 }
@@ -1639,6 +1954,7 @@ impl From<&Box<Literal>> for MemoryItem {
             meta: vec![Metadata {
                 source_range: literal.into(),
             }],
+            ..Default::default()
         })
     }
 }
@@ -1815,7 +2131,7 @@ impl ArrayExpression {
 
         for element in &self.elements {
             let result = match element {
-                Value::Literal(literal) => literal.into(),
+                Value::Literal(literal) => literal.get_result(ctx),
                 Value::None(none) => none.into(),
                 Value::Identifier(identifier) => {
                     let value = memory.get(&identifier.name, identifier.into())?;
@@ -1826,6 +2142,7 @@ impl ArrayExpression {
                 }
                 Value::CallExpression(call_expression) => call_expression.execute(memory, pipe_info, ctx).await?,
                 Value::UnaryExpression(unary_expression) => unary_expression.get_result(memory, pipe_info, ctx).await?,
+                Value::IfExpression(if_expression) => if_expression.get_result(memory, pipe_info, ctx).await?,
                 Value::ObjectExpression(object_expression) => object_expression.execute(memory, pipe_info, ctx).await?,
                 Value::ArrayExpression(array_expression) => array_expression.execute(memory, pipe_info, ctx).await?,
                 Value::PipeExpression(pipe_expression) => pipe_expression.get_result(memory, pipe_info, ctx).await?,
@@ -1833,6 +2150,7 @@ impl ArrayExpression {
                     return Err(KclError::Semantic(KclErrorDetails {
                         message: format!("PipeSubstitution not implemented here: {:?}", pipe_substitution),
                         source_ranges: vec![pipe_substitution.into()],
+                        ..Default::default()
                     }));
                 }
                 Value::MemberExpression(member_expression) => member_expression.get_result(memory)?,
@@ -1840,6 +2158,7 @@ impl ArrayExpression {
                     return Err(KclError::Semantic(KclErrorDetails {
                         message: format!("FunctionExpression not implemented here: {:?}", function_expression),
                         source_ranges: vec![function_expression.into()],
+                        ..Default::default()
                     }));
                 }
             }
@@ -1853,6 +2172,7 @@ impl ArrayExpression {
             meta: vec![Metadata {
                 source_range: self.into(),
             }],
+            ..Default::default()
         }))
     }
 
@@ -1973,7 +2293,7 @@ impl ObjectExpression {
         let mut object = Map::new();
         for property in &self.properties {
             let result = match &property.value {
-                Value::Literal(literal) => literal.into(),
+                Value::Literal(literal) => literal.get_result(ctx),
                 Value::None(none) => none.into(),
                 Value::Identifier(identifier) => {
                     let value = memory.get(&identifier.name, identifier.into())?;
@@ -1984,6 +2304,7 @@ impl ObjectExpression {
                 }
                 Value::CallExpression(call_expression) => call_expression.execute(memory, pipe_info, ctx).await?,
                 Value::UnaryExpression(unary_expression) => unary_expression.get_result(memory, pipe_info, ctx).await?,
+                Value::IfExpression(if_expression) => if_expression.get_result(memory, pipe_info, ctx).await?,
                 Value::ObjectExpression(object_expression) => object_expression.execute(memory, pipe_info, ctx).await?,
                 Value::ArrayExpression(array_expression) => array_expression.execute(memory, pipe_info, ctx).await?,
                 Value::PipeExpression(pipe_expression) => pipe_expression.get_result(memory, pipe_info, ctx).await?,
@@ -1992,12 +2313,14 @@ impl ObjectExpression {
                     return Err(KclError::Semantic(KclErrorDetails {
                         message: format!("PipeSubstitution not implemented here: {:?}", pipe_substitution),
                         source_ranges: vec![pipe_substitution.into()],
+                        ..Default::default()
                     }));
                 }
                 Value::FunctionExpression(function_expression) => {
                     return Err(KclError::Semantic(KclErrorDetails {
                         message: format!("FunctionExpression not implemented here: {:?}", function_expression),
                         source_ranges: vec![function_expression.into()],
+                        ..Default::default()
                     }));
                 }
             };
@@ -2010,6 +2333,7 @@ impl ObjectExpression {
             meta: vec![Metadata {
                 source_range: self.into(),
             }],
+            ..Default::default()
         }))
     }
 
@@ -2219,17 +2543,20 @@ impl MemberExpression {
                     meta: vec![Metadata {
                         source_range: self.into(),
                     }],
+                    ..Default::default()
                 }))
             } else {
                 Err(KclError::UndefinedValue(KclErrorDetails {
                     message: format!("index {} not found in array", index),
                     source_ranges: vec![self.clone().into()],
+                    ..Default::default()
                 }))
             }
         } else {
             Err(KclError::Semantic(KclErrorDetails {
                 message: format!("MemberExpression array is not an array: {:?}", array),
                 source_ranges: vec![self.clone().into()],
+                ..Default::default()
             }))
         }
     }
@@ -2245,12 +2572,14 @@ impl MemberExpression {
                         return Err(KclError::Syntax(KclErrorDetails {
                             source_ranges: vec![self.into()],
                             message: format!("invalid index: {x}"),
+                            ..Default::default()
                         }))
                     }
                     LiteralValue::Fractional(x) => {
                         return Err(KclError::Syntax(KclErrorDetails {
                             source_ranges: vec![self.into()],
                             message: format!("invalid index: {x}"),
+                            ..Default::default()
                         }))
                     }
                     LiteralValue::String(s) => s,
@@ -2276,17 +2605,20 @@ impl MemberExpression {
                     meta: vec![Metadata {
                         source_range: self.into(),
                     }],
+                    ..Default::default()
                 }))
             } else {
                 Err(KclError::UndefinedValue(KclErrorDetails {
                     message: format!("Property {} not found in object", property_name),
                     source_ranges: vec![self.clone().into()],
+                    ..Default::default()
                 }))
             }
         } else {
             Err(KclError::Semantic(KclErrorDetails {
                 message: format!("MemberExpression object is not an object: {:?}", object),
                 source_ranges: vec![self.clone().into()],
+                ..Default::default()
             }))
         }
     }
@@ -2373,12 +2705,27 @@ impl BinaryExpression {
                 self.precedence() > bin_exp.precedence()
                     || self.operator == BinaryOperator::Sub
                     || self.operator == BinaryOperator::Div
+                    // Comparisons aren't associative, e.g. `a == (b == c)` is not `(a == b) == c`.
+                    || (self.precedence() == bin_exp.precedence()
+                        && matches!(
+                            self.operator,
+                            BinaryOperator::Eq
+                                | BinaryOperator::Neq
+                                | BinaryOperator::Lt
+                                | BinaryOperator::Lte
+                                | BinaryOperator::Gt
+                                | BinaryOperator::Gte
+                        ))
             }
             _ => false,
         };
 
         let should_wrap_left = match &self.left {
-            BinaryPart::BinaryExpression(bin_exp) => self.precedence() > bin_exp.precedence(),
+            BinaryPart::BinaryExpression(bin_exp) => {
+                self.precedence() > bin_exp.precedence()
+                    // `^` is right-associative, e.g. `(a ^ b) ^ c` is not `a ^ b ^ c`.
+                    || (self.precedence() == bin_exp.precedence() && !self.operator.associativity().is_left())
+            }
             _ => false,
         };
 
@@ -2413,35 +2760,36 @@ impl BinaryExpression {
         pipe_info: &PipeInfo,
         ctx: &ExecutorContext,
     ) -> Result<MemoryItem, KclError> {
-        let left_json_value = self.left.get_result(memory, pipe_info, ctx).await?.get_json_value()?;
-        let right_json_value = self.right.get_result(memory, pipe_info, ctx).await?.get_json_value()?;
-
-        // First check if we are doing string concatenation.
-        if self.operator == BinaryOperator::Add {
-            if let (Some(left), Some(right)) = (
-                parse_json_value_as_string(&left_json_value),
-                parse_json_value_as_string(&right_json_value),
-            ) {
-                let value = serde_json::Value::String(format!("{}{}", left, right));
+        let left = self.left.get_result(memory, pipe_info, ctx).await?;
+        let left_json_value = left.get_json_value()?;
+
+        // `&&` and `||` short-circuit, so only evaluate the right-hand side if it's needed.
+        if matches!(self.operator, BinaryOperator::And | BinaryOperator::Or) {
+            let left = parse_json_value_as_bool(&left_json_value, self.left.clone().into())?;
+            if left == (self.operator == BinaryOperator::Or) {
                 return Ok(MemoryItem::UserVal(UserVal {
-                    value,
+                    value: left.into(),
                     meta: vec![Metadata {
                         source_range: self.into(),
                     }],
+                    ..Default::default()
                 }));
             }
         }
 
-        let left = parse_json_number_as_f64(&left_json_value, self.left.clone().into())?;
-        let right = parse_json_number_as_f64(&right_json_value, self.right.clone().into())?;
-
-        let value: serde_json::Value = match self.operator {
-            BinaryOperator::Add => (left + right).into(),
-            BinaryOperator::Sub => (left - right).into(),
-            BinaryOperator::Mul => (left * right).into(),
-            BinaryOperator::Div => (left / right).into(),
-            BinaryOperator::Mod => (left % right).into(),
-            BinaryOperator::Pow => (left.powf(right)).into(),
+        let right = self.right.get_result(memory, pipe_info, ctx).await?;
+        let right_json_value = right.get_json_value()?;
+        let value = self.operator.apply(
+            &left_json_value,
+            &right_json_value,
+            self.left.clone().into(),
+            self.right.clone().into(),
+        )?;
+        let dimension = if value.is_number() {
+            self.operator
+                .apply_dimension(left.dimension(), right.dimension(), self.right.clone().into())?
+        } else {
+            None
         };
 
         Ok(MemoryItem::UserVal(UserVal {
@@ -2449,6 +2797,7 @@ impl BinaryExpression {
             meta: vec![Metadata {
                 source_range: self.into(),
             }],
+            dimension,
         }))
     }
 
@@ -2465,12 +2814,26 @@ pub fn parse_json_number_as_f64(j: &serde_json::Value, source_range: SourceRange
             KclError::Syntax(KclErrorDetails {
                 source_ranges: vec![source_range],
                 message: format!("Invalid number: {}", j),
+                ..Default::default()
             })
         })
     } else {
         Err(KclError::Syntax(KclErrorDetails {
             source_ranges: vec![source_range],
             message: format!("Invalid number: {}", j),
+            ..Default::default()
+        }))
+    }
+}
+
+pub fn parse_json_value_as_bool(j: &serde_json::Value, source_range: SourceRange) -> Result<bool, KclError> {
+    if let serde_json::Value::Bool(b) = &j {
+        Ok(*b)
+    } else {
+        Err(KclError::Type(KclErrorDetails {
+            source_ranges: vec![source_range],
+            message: format!("Expected a boolean, found: {}", j),
+            ..Default::default()
         }))
     }
 }
@@ -2513,6 +2876,38 @@ pub enum BinaryOperator {
     #[serde(rename = "^")]
     #[display("^")]
     Pow,
+    /// Are two values equal?
+    #[serde(rename = "==")]
+    #[display("==")]
+    Eq,
+    /// Are two values not equal?
+    #[serde(rename = "!=")]
+    #[display("!=")]
+    Neq,
+    /// Is the left number less than the right number?
+    #[serde(rename = "<")]
+    #[display("<")]
+    Lt,
+    /// Is the left number less than or equal to the right number?
+    #[serde(rename = "<=")]
+    #[display("<=")]
+    Lte,
+    /// Is the left number greater than the right number?
+    #[serde(rename = ">")]
+    #[display(">")]
+    Gt,
+    /// Is the left number greater than or equal to the right number?
+    #[serde(rename = ">=")]
+    #[display(">=")]
+    Gte,
+    /// Are both booleans true?
+    #[serde(rename = "&&")]
+    #[display("&&")]
+    And,
+    /// Is either boolean true?
+    #[serde(rename = "||")]
+    #[display("||")]
+    Or,
 }
 
 /// Mathematical associativity.
@@ -2535,11 +2930,18 @@ impl Associativity {
 impl BinaryOperator {
     /// Follow JS definitions of each operator.
     /// Taken from <https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Operators/Operator_precedence#table>
+    ///
+    /// Unlike JS, `^` binds looser than the other arithmetic operators, so `2 * 3 ^ 2` is
+    /// `(2 * 3) ^ 2`. Comparison and logical operators bind looser than all of them.
     pub fn precedence(&self) -> u8 {
         match &self {
             BinaryOperator::Add | BinaryOperator::Sub => 11,
             BinaryOperator::Mul | BinaryOperator::Div | BinaryOperator::Mod => 12,
             BinaryOperator::Pow => 6,
+            BinaryOperator::Lt | BinaryOperator::Lte | BinaryOperator::Gt | BinaryOperator::Gte => 5,
+            BinaryOperator::Eq | BinaryOperator::Neq => 4,
+            BinaryOperator::And => 3,
+            BinaryOperator::Or => 2,
         }
     }
 
@@ -2547,10 +2949,113 @@ impl BinaryOperator {
     /// Taken from <https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Operators/Operator_precedence#table>
     pub fn associativity(&self) -> Associativity {
         match self {
-            Self::Add | Self::Sub | Self::Mul | Self::Div | Self::Mod => Associativity::Left,
+            Self::Add
+            | Self::Sub
+            | Self::Mul
+            | Self::Div
+            | Self::Mod
+            | Self::Eq
+            | Self::Neq
+            | Self::Lt
+            | Self::Lte
+            | Self::Gt
+            | Self::Gte
+            | Self::And
+            | Self::Or => Associativity::Left,
             Self::Pow => Associativity::Right,
         }
     }
+
+    /// Apply this operator to two values.
+    /// The source ranges are used to report which operand had the wrong type.
+    pub fn apply(
+        &self,
+        left: &serde_json::Value,
+        right: &serde_json::Value,
+        left_range: SourceRange,
+        right_range: SourceRange,
+    ) -> Result<serde_json::Value, KclError> {
+        let numbers = || -> Result<(f64, f64), KclError> {
+            Ok((
+                parse_json_number_as_f64(left, left_range)?,
+                parse_json_number_as_f64(right, right_range)?,
+            ))
+        };
+        let booleans = || -> Result<(bool, bool), KclError> {
+            Ok((
+                parse_json_value_as_bool(left, left_range)?,
+                parse_json_value_as_bool(right, right_range)?,
+            ))
+        };
+
+        Ok(match self {
+            BinaryOperator::Add => match (parse_json_value_as_string(left), parse_json_value_as_string(right)) {
+                // String concatenation.
+                (Some(left), Some(right)) => format!("{}{}", left, right).into(),
+                _ => numbers().map(|(left, right)| left + right)?.into(),
+            },
+            BinaryOperator::Sub => numbers().map(|(left, right)| left - right)?.into(),
+            BinaryOperator::Mul => numbers().map(|(left, right)| left * right)?.into(),
+            BinaryOperator::Div => numbers().map(|(left, right)| left / right)?.into(),
+            BinaryOperator::Mod => numbers().map(|(left, right)| left % right)?.into(),
+            BinaryOperator::Pow => numbers().map(|(left, right)| left.powf(right))?.into(),
+            BinaryOperator::Eq | BinaryOperator::Neq => {
+                // Numbers are compared by value, so that e.g. 1 == 1.0.
+                let equal = match (left.as_f64(), right.as_f64()) {
+                    (Some(left), Some(right)) => left == right,
+                    _ => left == right,
+                };
+                (equal == (*self == BinaryOperator::Eq)).into()
+            }
+            BinaryOperator::Lt => numbers().map(|(left, right)| left < right)?.into(),
+            BinaryOperator::Lte => numbers().map(|(left, right)| left <= right)?.into(),
+            BinaryOperator::Gt => numbers().map(|(left, right)| left > right)?.into(),
+            BinaryOperator::Gte => numbers().map(|(left, right)| left >= right)?.into(),
+            BinaryOperator::And => booleans().map(|(left, right)| left && right)?.into(),
+            BinaryOperator::Or => booleans().map(|(left, right)| left || right)?.into(),
+        })
+    }
+
+    /// What the result of this operator on two numbers measures, given what they measure.
+    /// Numbers with units of measure can only be added to, subtracted from or compared with
+    /// numbers of the same dimension.
+    pub fn apply_dimension(
+        &self,
+        left: Option<Dimension>,
+        right: Option<Dimension>,
+        right_range: SourceRange,
+    ) -> Result<Option<Dimension>, KclError> {
+        match self {
+            BinaryOperator::Add
+            | BinaryOperator::Sub
+            | BinaryOperator::Mod
+            | BinaryOperator::Lt
+            | BinaryOperator::Lte
+            | BinaryOperator::Gt
+            | BinaryOperator::Gte => match (left, right) {
+                (Some(left), Some(right)) if left != right => Err(KclError::Type(KclErrorDetails {
+                    source_ranges: vec![right_range],
+                    message: format!("Expected {}, but this is {}", left.describe(), right.describe()),
+                    ..Default::default()
+                })),
+                (left, right) => Ok(left.or(right)),
+            },
+            // Scaling a quantity keeps its dimension, but e.g. an area isn't a length any more.
+            BinaryOperator::Mul => match (left, right) {
+                (Some(dimension), None) | (None, Some(dimension)) => Ok(Some(dimension)),
+                _ => Ok(None),
+            },
+            BinaryOperator::Div => match (left, right) {
+                (Some(dimension), None) => Ok(Some(dimension)),
+                _ => Ok(None),
+            },
+            BinaryOperator::Pow
+            | BinaryOperator::Eq
+            | BinaryOperator::Neq
+            | BinaryOperator::And
+            | BinaryOperator::Or => Ok(None),
+        }
+    }
 }
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema, Bake)]
 #[databake(path = kcl_lib::ast::types)]
@@ -2603,19 +3108,19 @@ impl UnaryExpression {
         pipe_info: &PipeInfo,
         ctx: &ExecutorContext,
     ) -> Result<MemoryItem, KclError> {
-        let num = parse_json_number_as_f64(
-            &self
-                .argument
-                .get_result(memory, pipe_info, ctx)
-                .await?
-                .get_json_value()?,
-            self.into(),
-        )?;
+        let argument = self.argument.get_result(memory, pipe_info, ctx).await?;
+        let dimension = argument.dimension();
+        let argument = argument.get_json_value()?;
+        let value: serde_json::Value = match self.operator {
+            UnaryOperator::Neg => (-parse_json_number_as_f64(&argument, self.into())?).into(),
+            UnaryOperator::Not => (!parse_json_value_as_bool(&argument, self.into())?).into(),
+        };
         Ok(MemoryItem::UserVal(UserVal {
-            value: (-(num)).into(),
+            value,
             meta: vec![Metadata {
                 source_range: self.into(),
             }],
+            dimension,
         }))
     }
 
@@ -2781,6 +3286,7 @@ async fn execute_pipe_body(
         KclError::Semantic(KclErrorDetails {
             message: "Pipe expressions cannot be empty".to_owned(),
             source_ranges: vec![source_range],
+            ..Default::default()
         })
     })?;
     // Evaluate the first element in the pipeline.
@@ -2791,11 +3297,13 @@ async fn execute_pipe_body(
         Value::BinaryExpression(binary_expression) => binary_expression.get_result(memory, pipe_info, ctx).await?,
         Value::CallExpression(call_expression) => call_expression.execute(memory, pipe_info, ctx).await?,
         Value::Identifier(identifier) => memory.get(&identifier.name, identifier.into())?.clone(),
+        Value::IfExpression(if_expression) => if_expression.get_result(memory, pipe_info, ctx).await?,
         _ => {
             // Return an error this should not happen.
             return Err(KclError::Semantic(KclErrorDetails {
                 message: format!("PipeExpression not implemented here: {:?}", first),
                 source_ranges: vec![first.into()],
+                ..Default::default()
             }));
         }
     };
@@ -2817,6 +3325,7 @@ async fn execute_pipe_body(
                 return Err(KclError::Semantic(KclErrorDetails {
                     message: format!("PipeExpression not implemented here: {:?}", expression),
                     source_ranges: vec![expression.into()],
+                    ..Default::default()
                 }));
             }
         };
@@ -2827,108 +3336,311 @@ async fn execute_pipe_body(
     Ok(final_output)
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, Bake, FromStr, Display)]
+/// An `if cond { ... } else { ... }` expression.
+/// Each branch is a block, whose last expression is the value of the whole `if` expression.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema, Bake)]
 #[databake(path = kcl_lib::ast::types)]
-#[serde(tag = "type")]
-#[display(style = "snake_case")]
-pub enum FnArgPrimitive {
-    /// A string type.
-    String,
-    /// A number type.
-    Number,
-    /// A boolean type.
-    #[display("bool")]
-    #[serde(rename = "bool")]
-    Boolean,
-    /// A sketch group type.
-    SketchGroup,
-    /// A sketch surface type.
-    SketchSurface,
-    /// An extrude group type.
-    ExtrudeGroup,
+#[ts(export)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub struct IfExpression {
+    pub start: usize,
+    pub end: usize,
+    pub cond: Box<Value>,
+    pub then_val: Box<Program>,
+    pub else_ifs: Vec<ElseIf>,
+    pub final_else: Box<Program>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, Bake)]
-#[databake(path = kcl_lib::ast::types)]
-#[serde(tag = "type")]
-pub enum FnArgType {
-    /// A primitive type.
-    Primitive(FnArgPrimitive),
-    // An array of a primitive type.
-    Array(FnArgPrimitive),
-    // An object type.
-    Object {
-        properties: Vec<Parameter>,
-    },
-}
+impl_value_meta!(IfExpression);
 
-/// Parameter of a KCL function.
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, ts_rs::TS, JsonSchema, Bake)]
-#[databake(path = kcl_lib::ast::types)]
-#[ts(export)]
-#[serde(tag = "type")]
-pub struct Parameter {
-    /// The parameter's label or name.
-    pub identifier: Identifier,
-    /// The type of the parameter.
-    /// This is optional if the user defines a type.
-    #[serde(skip)]
-    pub type_: Option<FnArgType>,
-    /// Is the parameter optional?
-    pub optional: bool,
+impl From<IfExpression> for Value {
+    fn from(if_expression: IfExpression) -> Self {
+        Value::IfExpression(Box::new(if_expression))
+    }
 }
 
+/// An `else if cond { ... }` branch of an `if` expression.
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema, Bake)]
 #[databake(path = kcl_lib::ast::types)]
 #[ts(export)]
-#[serde(tag = "type")]
-pub struct FunctionExpression {
-    pub start: usize,
-    pub end: usize,
-    pub params: Vec<Parameter>,
-    pub body: Program,
-    #[serde(skip)]
-    pub return_type: Option<FnArgType>,
-}
-
-impl_value_meta!(FunctionExpression);
-
-pub struct FunctionExpressionParts {
+#[serde(rename_all = "camelCase", tag = "type")]
+pub struct ElseIf {
     pub start: usize,
     pub end: usize,
-    pub params_required: Vec<Parameter>,
-    pub params_optional: Vec<Parameter>,
-    pub body: Program,
+    pub cond: Value,
+    pub then_val: Box<Program>,
 }
 
-#[derive(Debug, PartialEq, Clone)]
-pub struct RequiredParamAfterOptionalParam(pub Parameter);
+impl_value_meta!(ElseIf);
 
-impl std::fmt::Display for RequiredParamAfterOptionalParam {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "KCL functions must declare any optional parameters after all the required parameters. But your required parameter {} is _after_ an optional parameter. You must move it to before the optional parameters instead.", self.0.identifier.name)
+impl IfExpression {
+    pub fn replace_value(&mut self, source_range: SourceRange, new_value: Value) {
+        self.cond.replace_value(source_range, new_value.clone());
+        self.then_val.replace_value(source_range, new_value.clone());
+        for else_if in &mut self.else_ifs {
+            else_if.cond.replace_value(source_range, new_value.clone());
+            else_if.then_val.replace_value(source_range, new_value.clone());
+        }
+        self.final_else.replace_value(source_range, new_value);
     }
-}
 
-impl FunctionExpression {
-    /// Function expressions don't really apply.
+    /// The value of an if expression depends on which branch is taken, so we
+    /// consider it fully constrained.
     pub fn get_constraint_level(&self) -> ConstraintLevel {
-        ConstraintLevel::Ignore {
+        ConstraintLevel::Full {
             source_ranges: vec![self.into()],
         }
     }
 
-    pub fn into_parts(self) -> Result<FunctionExpressionParts, RequiredParamAfterOptionalParam> {
-        let Self {
-            start,
-            end,
-            params,
-            body,
-            return_type: _,
-        } = self;
-        let mut params_required = Vec::with_capacity(params.len());
-        let mut params_optional = Vec::with_capacity(params.len());
-        for param in params {
+    fn recast(&self, options: &FormatOptions, indentation_level: usize) -> String {
+        // We don't want to end with a new line inside the blocks.
+        let mut new_options = options.clone();
+        new_options.insert_final_newline = false;
+        let recast_block = |block: &Program| {
+            format!(
+                "{{\n{}{}\n{}}}",
+                options.get_indentation(indentation_level + 1),
+                block.recast(&new_options, indentation_level + 1),
+                options.get_indentation(indentation_level)
+            )
+        };
+
+        let mut output = format!(
+            "if {} {}",
+            self.cond.recast(options, indentation_level, false),
+            recast_block(&self.then_val)
+        );
+        for else_if in &self.else_ifs {
+            let _ = write!(
+                output,
+                " else if {} {}",
+                else_if.cond.recast(options, indentation_level, false),
+                recast_block(&else_if.then_val)
+            );
+        }
+        let _ = write!(output, " else {}", recast_block(&self.final_else));
+        output
+    }
+
+    /// Returns a hover value that includes the given character position.
+    pub fn get_hover_value_for_position(&self, pos: usize, code: &str) -> Option<Hover> {
+        let cond_source_range: SourceRange = self.cond.as_ref().into();
+        if cond_source_range.contains(pos) {
+            return self.cond.get_hover_value_for_position(pos, code);
+        }
+
+        for else_if in &self.else_ifs {
+            let cond_source_range: SourceRange = (&else_if.cond).into();
+            if cond_source_range.contains(pos) {
+                return else_if.cond.get_hover_value_for_position(pos, code);
+            }
+        }
+
+        let blocks = std::iter::once(&self.then_val)
+            .chain(self.else_ifs.iter().map(|else_if| &else_if.then_val))
+            .chain(std::iter::once(&self.final_else));
+        for block in blocks {
+            if let Some(value) = block.get_value_for_position(pos) {
+                return value.get_hover_value_for_position(pos, code);
+            }
+        }
+
+        None
+    }
+
+    #[async_recursion::async_recursion]
+    pub async fn get_result(
+        &self,
+        memory: &mut ProgramMemory,
+        pipe_info: &PipeInfo,
+        ctx: &ExecutorContext,
+    ) -> Result<MemoryItem, KclError> {
+        let cond = ctx
+            .execute_value(&self.cond, memory, pipe_info)
+            .await?
+            .get_json_value()?;
+        if parse_json_value_as_bool(&cond, self.cond.as_ref().into())? {
+            return execute_block(&self.then_val, memory, pipe_info, ctx).await;
+        }
+
+        for else_if in &self.else_ifs {
+            let cond = ctx
+                .execute_value(&else_if.cond, memory, pipe_info)
+                .await?
+                .get_json_value()?;
+            if parse_json_value_as_bool(&cond, (&else_if.cond).into())? {
+                return execute_block(&else_if.then_val, memory, pipe_info, ctx).await;
+            }
+        }
+
+        execute_block(&self.final_else, memory, pipe_info, ctx).await
+    }
+
+    /// Rename all identifiers that have the old name to the new given name.
+    fn rename_identifiers(&mut self, old_name: &str, new_name: &str) {
+        self.cond.rename_identifiers(old_name, new_name);
+        self.then_val.rename_identifiers(old_name, new_name);
+        for else_if in &mut self.else_ifs {
+            else_if.cond.rename_identifiers(old_name, new_name);
+            else_if.then_val.rename_identifiers(old_name, new_name);
+        }
+        self.final_else.rename_identifiers(old_name, new_name);
+    }
+}
+
+/// Execute a block (e.g. a branch of an if expression) in its own scope, and
+/// return the value of its last expression.
+async fn execute_block(
+    block: &Program,
+    memory: &mut ProgramMemory,
+    pipe_info: &PipeInfo,
+    ctx: &ExecutorContext,
+) -> Result<MemoryItem, KclError> {
+    let source_range = SourceRange([block.start, block.end]);
+    let Some((BodyItem::ExpressionStatement(last), rest)) = block.body.split_last() else {
+        return Err(KclError::Semantic(KclErrorDetails {
+            message: "Blocks must end with an expression, which becomes the value of the block".to_owned(),
+            source_ranges: vec![source_range],
+            ..Default::default()
+        }));
+    };
+
+    // Anything declared inside the block is not visible outside of it.
+    let mut block_memory = ProgramMemory::new_scope(memory.share());
+    let statements = Program {
+        start: block.start,
+        end: last.start,
+        body: rest.to_vec(),
+        non_code_meta: Default::default(),
+    };
+    ctx.inner_execute(statements, &mut block_memory, BodyType::Block)
+        .await?;
+
+    ctx.execute_value(&last.expression, &mut block_memory, pipe_info).await
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, Bake, FromStr, Display)]
+#[databake(path = kcl_lib::ast::types)]
+#[serde(tag = "type")]
+#[display(style = "snake_case")]
+pub enum FnArgPrimitive {
+    /// A string type.
+    String,
+    /// A number type.
+    Number,
+    /// A boolean type.
+    #[display("bool")]
+    #[serde(rename = "bool")]
+    Boolean,
+    /// A sketch group type.
+    SketchGroup,
+    /// A sketch surface type.
+    SketchSurface,
+    /// An extrude group type.
+    ExtrudeGroup,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, Bake)]
+#[databake(path = kcl_lib::ast::types)]
+#[serde(tag = "type")]
+pub enum FnArgType {
+    /// A primitive type.
+    Primitive(FnArgPrimitive),
+    // An array of a primitive type.
+    Array(FnArgPrimitive),
+    // An object type.
+    Object {
+        properties: Vec<Parameter>,
+    },
+}
+
+/// Parameter of a KCL function.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema, Bake)]
+#[databake(path = kcl_lib::ast::types)]
+#[ts(export)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub struct Parameter {
+    /// The parameter's label or name.
+    pub identifier: Identifier,
+    /// The type of the parameter.
+    /// This is optional if the user defines a type.
+    #[serde(skip)]
+    pub type_: Option<FnArgType>,
+    /// Is the parameter optional?
+    /// Parameters with a default value are always optional.
+    pub optional: bool,
+    /// The value the parameter takes when the caller doesn't pass it, e.g. the `2` in `(x, y = 2)`.
+    /// It's evaluated each time the function is called, after the parameters before it are bound.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_value: Option<Value>,
+}
+
+impl Parameter {
+    /// Recast the parameter the way it was declared, leaving out its type.
+    fn recast(&self, options: &FormatOptions, indentation_level: usize) -> String {
+        match &self.default_value {
+            Some(default_value) => format!(
+                "{} = {}",
+                self.identifier.name,
+                default_value.recast(options, indentation_level, false)
+            ),
+            None if self.optional => format!("{}?", self.identifier.name),
+            None => self.identifier.name.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema, Bake)]
+#[databake(path = kcl_lib::ast::types)]
+#[ts(export)]
+#[serde(tag = "type")]
+pub struct FunctionExpression {
+    pub start: usize,
+    pub end: usize,
+    pub params: Vec<Parameter>,
+    pub body: Program,
+    #[serde(skip)]
+    pub return_type: Option<FnArgType>,
+}
+
+impl_value_meta!(FunctionExpression);
+
+pub struct FunctionExpressionParts {
+    pub start: usize,
+    pub end: usize,
+    pub params_required: Vec<Parameter>,
+    pub params_optional: Vec<Parameter>,
+    pub body: Program,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct RequiredParamAfterOptionalParam(pub Parameter);
+
+impl std::fmt::Display for RequiredParamAfterOptionalParam {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "KCL functions must declare any optional parameters after all the required parameters. But your required parameter {} is _after_ an optional parameter. You must move it to before the optional parameters instead.", self.0.identifier.name)
+    }
+}
+
+impl FunctionExpression {
+    /// Function expressions don't really apply.
+    pub fn get_constraint_level(&self) -> ConstraintLevel {
+        ConstraintLevel::Ignore {
+            source_ranges: vec![self.into()],
+        }
+    }
+
+    pub fn into_parts(self) -> Result<FunctionExpressionParts, RequiredParamAfterOptionalParam> {
+        let Self {
+            start,
+            end,
+            params,
+            body,
+            return_type: _,
+        } = self;
+        let mut params_required = Vec::with_capacity(params.len());
+        let mut params_optional = Vec::with_capacity(params.len());
+        for param in params {
             if param.optional {
                 params_optional.push(param);
             } else {
@@ -2978,7 +3690,7 @@ impl FunctionExpression {
             "({}) => {{\n{}{}\n}}",
             self.params
                 .iter()
-                .map(|param| param.identifier.name.clone())
+                .map(|param| param.recast(options, indentation_level))
                 .collect::<Vec<String>>()
                 .join(", "),
             options.get_indentation(indentation_level + 1),
@@ -3008,6 +3720,301 @@ pub struct ReturnStatement {
 
 impl_value_meta!(ReturnStatement);
 
+/// A `for x in 0..n { ... }` loop, which runs its body once for every item it iterates over.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema, Bake)]
+#[databake(path = kcl_lib::ast::types)]
+#[ts(export)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub struct ForStatement {
+    pub start: usize,
+    pub end: usize,
+    /// The name each item is bound to inside the body.
+    pub variable: Identifier,
+    pub iterable: ForIterable,
+    pub body: Box<Program>,
+}
+
+impl_value_meta!(ForStatement);
+
+/// The thing a `for` loop iterates over.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema, Bake)]
+#[databake(path = kcl_lib::ast::types)]
+#[ts(export)]
+#[serde(untagged)]
+pub enum ForIterable {
+    /// An inclusive range of integers, e.g. `0..n`.
+    Range(Box<RangeExpression>),
+    /// Any value which evaluates to an array, e.g. `[1, 2, 3]` or `points`.
+    Value(Value),
+}
+
+/// An inclusive range of integers, e.g. `0..n`. Like array ranges (`[0..10]`), both ends are included.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema, Bake)]
+#[databake(path = kcl_lib::ast::types)]
+#[ts(export)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub struct RangeExpression {
+    pub start: usize,
+    pub end: usize,
+    pub from: Value,
+    pub to: Value,
+}
+
+impl_value_meta!(RangeExpression);
+
+impl ForIterable {
+    fn values(&self) -> Vec<&Value> {
+        match self {
+            ForIterable::Range(range) => vec![&range.from, &range.to],
+            ForIterable::Value(value) => vec![value],
+        }
+    }
+
+    fn values_mut(&mut self) -> Vec<&mut Value> {
+        match self {
+            ForIterable::Range(range) => vec![&mut range.from, &mut range.to],
+            ForIterable::Value(value) => vec![value],
+        }
+    }
+
+    fn recast(&self, options: &FormatOptions, indentation_level: usize) -> String {
+        match self {
+            ForIterable::Range(range) => format!(
+                "{}..{}",
+                range.from.recast(options, indentation_level, false),
+                range.to.recast(options, indentation_level, false)
+            ),
+            ForIterable::Value(value) => value.recast(options, indentation_level, false),
+        }
+    }
+}
+
+impl ForStatement {
+    pub fn recast(&self, options: &FormatOptions, indentation_level: usize) -> String {
+        // We don't want to end with a new line inside the body.
+        let mut new_options = options.clone();
+        new_options.insert_final_newline = false;
+        format!(
+            "for {} in {} {{\n{}{}\n{}}}",
+            self.variable.name,
+            self.iterable.recast(options, indentation_level),
+            options.get_indentation(indentation_level + 1),
+            self.body.recast(&new_options, indentation_level + 1),
+            options.get_indentation(indentation_level)
+        )
+    }
+
+    pub fn replace_value(&mut self, source_range: SourceRange, new_value: Value) {
+        for value in self.iterable.values_mut() {
+            value.replace_value(source_range, new_value.clone());
+        }
+        self.body.replace_value(source_range, new_value);
+    }
+
+    /// Returns a value that includes the given character position.
+    pub fn get_value_for_position(&self, pos: usize) -> Option<&Value> {
+        for value in self.iterable.values() {
+            let source_range: SourceRange = value.into();
+            if source_range.contains(pos) {
+                return Some(value);
+            }
+        }
+
+        self.body.get_value_for_position(pos)
+    }
+
+    /// Returns a value that includes the given character position.
+    pub fn get_mut_value_for_position(&mut self, pos: usize) -> Option<&mut Value> {
+        for value in self.iterable.values_mut() {
+            let source_range: SourceRange = (&*value).into();
+            if source_range.contains(pos) {
+                return Some(value);
+            }
+        }
+
+        match self.body.get_mut_body_item_for_position(pos)? {
+            BodyItem::ExpressionStatement(ref mut expression_statement) => Some(&mut expression_statement.expression),
+            BodyItem::VariableDeclaration(ref mut variable_declaration) => {
+                variable_declaration.get_mut_value_for_position(pos)
+            }
+            BodyItem::ReturnStatement(ref mut return_statement) => Some(&mut return_statement.argument),
+            BodyItem::ForStatement(ref mut for_statement) => for_statement.get_mut_value_for_position(pos),
+            BodyItem::ImportStatement(_import_statement) => None,
+        }
+    }
+
+    /// Rename all identifiers that have the old name to the new given name.
+    fn rename_identifiers(&mut self, old_name: &str, new_name: &str) {
+        for value in self.iterable.values_mut() {
+            value.rename_identifiers(old_name, new_name);
+        }
+        self.variable.rename(old_name, new_name);
+        self.body.rename_identifiers(old_name, new_name);
+    }
+
+    /// Run the body once for each item, binding the item to the loop variable.
+    /// Anything declared inside the body is not visible outside of it.
+    #[async_recursion::async_recursion]
+    pub async fn execute(
+        &self,
+        memory: &mut ProgramMemory,
+        pipe_info: &PipeInfo,
+        ctx: &ExecutorContext,
+    ) -> Result<(), KclError> {
+        let items = match &self.iterable {
+            ForIterable::Range(range) => {
+                let from = range_bound(&range.from, memory, pipe_info, ctx).await?;
+                let to = range_bound(&range.to, memory, pipe_info, ctx).await?;
+                let iterations = to.saturating_sub(from).saturating_add(1).max(0) as u64;
+                check_loop_iterations(iterations, range.as_ref().into())?;
+                (from..=to).map(JValue::from).collect()
+            }
+            ForIterable::Value(value) => {
+                let source_range: SourceRange = value.into();
+                let json = ctx.execute_value(value, memory, pipe_info).await?.get_json_value()?;
+                let JValue::Array(items) = json else {
+                    return Err(KclError::Type(KclErrorDetails {
+                        message: format!("A for loop can only iterate over a range or an array, found: {}", json),
+                        source_ranges: vec![source_range],
+                        ..Default::default()
+                    }));
+                };
+                check_loop_iterations(items.len() as u64, source_range)?;
+                items
+            }
+        };
+
+        let variable_range: SourceRange = (&self.variable).into();
+        // Each iteration gets its own scope, so names declared in the body don't clash.
+        let scope = memory.share();
+        for item in items {
+            ctx.check_interrupted(SourceRange([self.start, self.end]))?;
+            let mut body_memory = ProgramMemory::new_scope(scope.clone());
+            body_memory.add(
+                &self.variable.name,
+                MemoryItem::UserVal(UserVal {
+                    value: item,
+                    meta: vec![Metadata {
+                        source_range: variable_range,
+                    }],
+                    ..Default::default()
+                }),
+                variable_range,
+            )?;
+            ctx.inner_execute(*self.body.clone(), &mut body_memory, BodyType::Block)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Evaluate one end of a `for` loop's range, which must be an integer.
+async fn range_bound(
+    value: &Value,
+    memory: &mut ProgramMemory,
+    pipe_info: &PipeInfo,
+    ctx: &ExecutorContext,
+) -> Result<i64, KclError> {
+    let source_range: SourceRange = value.into();
+    let json = ctx.execute_value(value, memory, pipe_info).await?.get_json_value()?;
+    json.as_i64().ok_or_else(|| {
+        KclError::Type(KclErrorDetails {
+            message: format!("The ends of a range must be integers, found: {}", json),
+            source_ranges: vec![source_range],
+            ..Default::default()
+        })
+    })
+}
+
+/// Guard against runaway loops, e.g. a typo like `0..10000000` which would otherwise hang the app.
+fn check_loop_iterations(iterations: u64, source_range: SourceRange) -> Result<(), KclError> {
+    if iterations > MAX_LOOP_ITERATIONS {
+        return Err(KclError::Semantic(KclErrorDetails {
+            message: format!(
+                "This for loop would run {} times, but loops can run at most {} times",
+                iterations, MAX_LOOP_ITERATIONS
+            ),
+            source_ranges: vec![source_range],
+            ..Default::default()
+        }));
+    }
+    Ok(())
+}
+
+/// An `import a, b as c from "lib.kcl"` statement, which binds names exported by another KCL file.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema, Bake)]
+#[databake(path = kcl_lib::ast::types)]
+#[ts(export)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub struct ImportStatement {
+    pub start: usize,
+    pub end: usize,
+    pub items: Vec<ImportItem>,
+    /// The file to import from, relative to the importing file.
+    pub path: String,
+}
+
+impl_value_meta!(ImportStatement);
+
+/// One name imported by an `import` statement, e.g. `b as c`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema, Bake)]
+#[databake(path = kcl_lib::ast::types)]
+#[ts(export)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub struct ImportItem {
+    pub start: usize,
+    pub end: usize,
+    /// The name the other file exported.
+    pub name: Identifier,
+    /// The name to bind it to in this file, if different.
+    pub alias: Option<Identifier>,
+}
+
+impl_value_meta!(ImportItem);
+
+impl ImportItem {
+    /// The name this item is bound to in the importing file.
+    pub fn local_name(&self) -> &Identifier {
+        self.alias.as_ref().unwrap_or(&self.name)
+    }
+}
+
+impl ImportStatement {
+    pub fn recast(&self) -> String {
+        let items = self
+            .items
+            .iter()
+            .map(|item| match &item.alias {
+                Some(alias) => format!("{} as {}", item.name.name, alias.name),
+                None => item.name.name.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("import {} from \"{}\"", items, self.path)
+    }
+
+    /// Rename all identifiers that have the old name to the new given name.
+    /// Only the local names change, the exported names must still match the other file.
+    fn rename_identifiers(&mut self, old_name: &str, new_name: &str) {
+        for item in &mut self.items {
+            if item.local_name().name != old_name {
+                continue;
+            }
+            match item.alias {
+                Some(ref mut alias) => alias.rename(old_name, new_name),
+                None => {
+                    item.alias = Some(Identifier {
+                        start: item.name.start,
+                        end: item.name.end,
+                        name: new_name.to_string(),
+                    })
+                }
+            }
+        }
+    }
+}
+
 /// Describes information about a hover.
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -3021,6 +4028,11 @@ pub enum Hover {
         parameter_index: u32,
         range: LspRange,
     },
+    NamedArgument {
+        name: String,
+        argument: String,
+        range: LspRange,
+    },
     Comment {
         value: String,
         range: LspRange,
@@ -3435,6 +4447,235 @@ const outsideRevolve = startSketchOn('XZ')
         );
     }
 
+    #[test]
+    fn test_recast_if_else() {
+        let some_program_string = r#"const thickness = if   heavyDuty {
+    2
+}   else if medium { 1.5 } else {
+ const x = 1
+  x
+}
+fn f = (big) => {
+  return if big {
+    10
+  } else {
+    1
+  }
+}
+if big {
+  startSketchOn('XY')
+} else {
+  startSketchOn('XZ')
+}"#;
+        let tokens = crate::token::lexer(some_program_string).unwrap();
+        let parser = crate::parser::Parser::new(tokens);
+        let program = parser.ast().unwrap();
+
+        let recasted = program.recast(&Default::default(), 0);
+        assert_eq!(
+            recasted,
+            r#"const thickness = if heavyDuty {
+  2
+} else if medium {
+  1.5
+} else {
+  const x = 1
+  x
+}
+fn f = (big) => {
+  return if big {
+    10
+  } else {
+    1
+  }
+}
+if big {
+  startSketchOn('XY')
+} else {
+  startSketchOn('XZ')
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_recast_comparisons() {
+        let some_program_string = r#"const a = x+1<y*2&&!z||(b==c)==d
+const g = b==(c==d)
+const h = (b<c)<d"#;
+        let tokens = crate::token::lexer(some_program_string).unwrap();
+        let parser = crate::parser::Parser::new(tokens);
+        let program = parser.ast().unwrap();
+
+        let recasted = program.recast(&Default::default(), 0);
+        assert_eq!(
+            recasted,
+            r#"const a = x + 1 < y * 2 && !z || b == c == d
+const g = b == (c == d)
+const h = b < c < d
+"#
+        );
+    }
+
+    #[test]
+    fn test_recast_pow() {
+        let some_program_string = r#"const a = (2*3)^2
+const b = 2*3^2
+const c = 2^3^2
+const d = (2^3)^2"#;
+        let tokens = crate::token::lexer(some_program_string).unwrap();
+        let parser = crate::parser::Parser::new(tokens);
+        let program = parser.ast().unwrap();
+
+        let recasted = program.recast(&Default::default(), 0);
+        assert_eq!(
+            recasted,
+            r#"const a = 2 * 3 ^ 2
+const b = 2 * 3 ^ 2
+const c = 2 ^ 3 ^ 2
+const d = (2 ^ 3) ^ 2
+"#
+        );
+    }
+
+    #[test]
+    fn test_recast_import_and_export() {
+        let some_program_string = r#"import   double,TWO  as  two from "lib.kcl"
+export   fn triple = (x) => { return x * two }"#;
+        let tokens = crate::token::lexer(some_program_string).unwrap();
+        let parser = crate::parser::Parser::new(tokens);
+        let program = parser.ast().unwrap();
+
+        let recasted = program.recast(&Default::default(), 0);
+        assert_eq!(
+            recasted,
+            r#"import double, TWO as two from "lib.kcl"
+export fn triple = (x) => {
+  return x * two
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_rename_imported_name() {
+        let some_program_string = r#"import double from "lib.kcl"
+const x = double(2)"#;
+        let tokens = crate::token::lexer(some_program_string).unwrap();
+        let parser = crate::parser::Parser::new(tokens);
+        let mut program = parser.ast().unwrap();
+        program.rename_identifiers("double", "twice");
+
+        let recasted = program.recast(&Default::default(), 0);
+        assert_eq!(
+            recasted,
+            r#"import double as twice from "lib.kcl"
+const x = twice(2)
+"#
+        );
+    }
+
+    #[test]
+    fn test_recast_for_loop() {
+        let some_program_string = r#"for i in   0..n {
+const x = i * 2
+}
+fn f = (points) => {
+  for p in points { startSketchOn('XY') }
+  return 0
+}"#;
+        let tokens = crate::token::lexer(some_program_string).unwrap();
+        let parser = crate::parser::Parser::new(tokens);
+        let program = parser.ast().unwrap();
+
+        let recasted = program.recast(&Default::default(), 0);
+        assert_eq!(
+            recasted,
+            r#"for i in 0..n {
+  const x = i * 2
+}
+fn f = (points) => {
+  for p in points {
+    startSketchOn('XY')
+  }
+  return 0
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_recast_named_arguments() {
+        let some_program_string = r#"fn box = (width, height=width, tag?) => {
+  return startSketchOn('XY')
+    |> startProfileAt([0, 0], %)
+    |> line([width,height], %, tag=tag)
+}
+const b = box(2,   tag = 'edge')"#;
+        let tokens = crate::token::lexer(some_program_string).unwrap();
+        let parser = crate::parser::Parser::new(tokens);
+        let program = parser.ast().unwrap();
+
+        let recasted = program.recast(&Default::default(), 0);
+        assert_eq!(
+            recasted,
+            r#"fn box = (width, height = width, tag?) => {
+  return startSketchOn('XY')
+    |> startProfileAt([0, 0], %)
+    |> line([width, height], %, tag = tag)
+}
+const b = box(2, tag = 'edge')
+"#
+        );
+    }
+
+    #[test]
+    fn test_recast_destructuring() {
+        let some_program_string = r#"const [x,y] = [1, 2]
+let {   width, height} = size
+const area = width * height"#;
+        let tokens = crate::token::lexer(some_program_string).unwrap();
+        let parser = crate::parser::Parser::new(tokens);
+        let mut program = parser.ast().unwrap();
+
+        let symbols = program.get_lsp_symbols(some_program_string);
+        let names: Vec<_> = symbols.iter().map(|symbol| symbol.name.as_str()).collect();
+        assert_eq!(names, vec!["x", "y", "width", "height", "area"]);
+
+        program.rename_symbol("w", 30);
+        let recasted = program.recast(&Default::default(), 0);
+        assert_eq!(
+            recasted,
+            r#"const [x, y] = [1, 2]
+let { w, height } = size
+const area = w * height
+"#
+        );
+    }
+
+    #[test]
+    fn test_destructuring_json_roundtrip() {
+        let some_program_string = r#"const x = 1
+const [a, b] = [1, 2]
+const { width, height } = size"#;
+        let tokens = crate::token::lexer(some_program_string).unwrap();
+        let parser = crate::parser::Parser::new(tokens);
+        let program = parser.ast().unwrap();
+
+        let json = serde_json::to_value(&program).unwrap();
+        let ids: Vec<_> = json["body"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["declarations"][0]["id"]["type"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, vec!["Identifier", "ArrayPattern", "ObjectPattern"]);
+        assert_eq!(json["body"][0]["declarations"][0]["id"]["name"], "x");
+
+        let deserialized: Program = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized, program);
+    }
+
     #[test]
     fn test_recast_empty_file() {
         let some_program_string = r#""#;
@@ -3471,7 +4712,7 @@ const outsideRevolve = startSketchOn('XZ')
         assert!(result.is_err());
         assert_eq!(
             result.unwrap_err().to_string(),
-            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([21, 24])], message: "Unexpected end of file. The compiler expected a function body items (functions are made up of variable declarations, expressions, and return statements, each of those is a possible body item" }"#
+            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([21, 24])], message: "Unexpected end of file. The compiler expected a function body items (functions are made up of variable declarations, expressions, and return statements, each of those is a possible body item", call_stack: [] }"#
         );
     }
 
@@ -4398,6 +5639,25 @@ const cylinder = startSketchOn('-XZ')
         assert!(value.is_some());
     }
 
+    #[test]
+    fn test_recast_units() {
+        let some_program_string = r#"const a = 10mm+2.50in
+const b = -45deg
+const c = 3.0rad"#;
+        let tokens = crate::token::lexer(some_program_string).unwrap();
+        let parser = crate::parser::Parser::new(tokens);
+        let program = parser.ast().unwrap();
+
+        let recasted = program.recast(&Default::default(), 0);
+        assert_eq!(
+            recasted,
+            r#"const a = 10mm + 2.50in
+const b = -45deg
+const c = 3.0rad
+"#
+        );
+    }
+
     #[test]
     fn test_recast_negative_var() {
         let some_program_string = r#"const w = 20
@@ -4566,7 +5826,8 @@ const firstExtrude = startSketchOn('XY')
                             name: "thing".to_owned()
                         },
                         type_: Some(FnArgType::Primitive(FnArgPrimitive::Number)),
-                        optional: false
+                        optional: false,
+                        default_value: None,
                     },
                     Parameter {
                         identifier: Identifier {
@@ -4575,7 +5836,8 @@ const firstExtrude = startSketchOn('XY')
                             name: "things".to_owned()
                         },
                         type_: Some(FnArgType::Array(FnArgPrimitive::String)),
-                        optional: false
+                        optional: false,
+                        default_value: None,
                     },
                     Parameter {
                         identifier: Identifier {
@@ -4584,7 +5846,8 @@ const firstExtrude = startSketchOn('XY')
                             name: "more".to_owned()
                         },
                         type_: Some(FnArgType::Primitive(FnArgPrimitive::String)),
-                        optional: true
+                        optional: true,
+                        default_value: None,
                     }
                 ]
             })
@@ -4622,7 +5885,8 @@ const firstExtrude = startSketchOn('XY')
                             name: "thing".to_owned()
                         },
                         type_: Some(FnArgType::Primitive(FnArgPrimitive::Number)),
-                        optional: false
+                        optional: false,
+                        default_value: None,
                     },
                     Parameter {
                         identifier: Identifier {
@@ -4631,7 +5895,8 @@ const firstExtrude = startSketchOn('XY')
                             name: "things".to_owned()
                         },
                         type_: Some(FnArgType::Array(FnArgPrimitive::String)),
-                        optional: false
+                        optional: false,
+                        default_value: None,
                     },
                     Parameter {
                         identifier: Identifier {
@@ -4640,7 +5905,8 @@ const firstExtrude = startSketchOn('XY')
                             name: "more".to_owned()
                         },
                         type_: Some(FnArgType::Primitive(FnArgPrimitive::String)),
-                        optional: true
+                        optional: true,
+                        default_value: None,
                     }
                 ]
             })
@@ -4751,6 +6017,7 @@ const thickness = sqrt(distance * p * FOS * 6 / (sigmaAllow * width))"#;
                         },
                         type_: None,
                         optional: false,
+                        default_value: None,
                     }],
                     body: Program {
                         start: 0,
@@ -4775,6 +6042,7 @@ const thickness = sqrt(distance * p * FOS * 6 / (sigmaAllow * width))"#;
                         },
                         type_: None,
                         optional: true,
+                        default_value: None,
                     }],
                     body: Program {
                         start: 0,
@@ -4800,6 +6068,7 @@ const thickness = sqrt(distance * p * FOS * 6 / (sigmaAllow * width))"#;
                             },
                             type_: None,
                             optional: false,
+                            default_value: None,
                         },
                         Parameter {
                             identifier: Identifier {
@@ -4809,6 +6078,7 @@ const thickness = sqrt(distance * p * FOS * 6 / (sigmaAllow * width))"#;
                             },
                             type_: None,
                             optional: true,
+                            default_value: None,
                         },
                     ],
                     body: Program {
@@ -1,9 +1,11 @@
 use databake::*;
+use parse_display::{Display, FromStr};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JValue;
 
 use super::{Literal, Value};
+use crate::settings::types::UnitLength;
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema, Bake)]
 #[databake(path = kcl_lib::ast::types)]
@@ -16,6 +18,80 @@ pub enum LiteralValue {
     Bool(bool),
 }
 
+/// A unit of measure written straight after a number, like the `mm` in `10mm`.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, ts_rs::TS, JsonSchema, Bake, Display, FromStr)]
+#[databake(path = kcl_lib::ast::types)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+#[display(style = "lowercase")]
+pub enum NumericSuffix {
+    /// Millimeters.
+    Mm,
+    /// Centimeters.
+    Cm,
+    /// Meters.
+    M,
+    /// Inches.
+    In,
+    /// Feet.
+    Ft,
+    /// Degrees.
+    Deg,
+    /// Radians.
+    Rad,
+}
+
+/// What a number with a unit of measure measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dimension {
+    Length,
+    Angle,
+}
+
+impl Dimension {
+    /// Describe the dimension for an error message, e.g. "a length".
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Dimension::Length => "a length",
+            Dimension::Angle => "an angle",
+        }
+    }
+}
+
+impl NumericSuffix {
+    pub fn dimension(&self) -> Dimension {
+        match self {
+            NumericSuffix::Mm | NumericSuffix::Cm | NumericSuffix::M | NumericSuffix::In | NumericSuffix::Ft => {
+                Dimension::Length
+            }
+            NumericSuffix::Deg | NumericSuffix::Rad => Dimension::Angle,
+        }
+    }
+
+    /// Convert a number in this unit to the unit KCL works in: lengths are in the scene's units,
+    /// and angles are in degrees.
+    pub fn convert(&self, value: f64, scene_units: &UnitLength) -> f64 {
+        let mm = match self {
+            NumericSuffix::Mm => 1.0,
+            NumericSuffix::Cm => 10.0,
+            NumericSuffix::M => 1000.0,
+            NumericSuffix::In => 25.4,
+            NumericSuffix::Ft => 304.8,
+            NumericSuffix::Deg => return value,
+            NumericSuffix::Rad => return value.to_degrees(),
+        };
+        let scene_mm = match scene_units {
+            UnitLength::Mm => 1.0,
+            UnitLength::Cm => 10.0,
+            UnitLength::M => 1000.0,
+            UnitLength::In => 25.4,
+            UnitLength::Ft => 304.8,
+            UnitLength::Yd => 914.4,
+        };
+        value * mm / scene_mm
+    }
+}
+
 impl From<Literal> for Value {
     fn from(literal: Literal) -> Self {
         Value::Literal(Box::new(literal))
@@ -31,6 +31,7 @@ impl From<&KclNone> for UserVal {
         UserVal {
             value: serde_json::to_value(none).expect("can always serialize a None"),
             meta: Default::default(),
+            ..Default::default()
         }
     }
 }
@@ -45,7 +45,7 @@ pub trait CoreDump: Clone {
             .meta()
             .create_debug_uploads(vec![kittycad::types::multipart::Attachment {
                 name: "".to_string(),
-                filename: Some("modeling-app/core-dump-screenshot.png".to_string()),
+                filepath: Some("modeling-app/core-dump-screenshot.png".into()),
                 content_type: Some("image/png".to_string()),
                 data,
             }])
@@ -8,7 +8,10 @@ use tower_lsp::lsp_types::{
     MarkupKind, ParameterInformation, ParameterLabel, SignatureHelp, SignatureInformation,
 };
 
-use crate::std::Primitive;
+use crate::{
+    ast::types::{Identifier, Parameter},
+    std::Primitive,
+};
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, ts_rs::TS)]
 #[ts(export)]
@@ -54,6 +57,13 @@ pub struct StdLibFnArg {
 impl StdLibFnArg {
     #[allow(dead_code)]
     pub fn get_type_string(&self) -> Result<(String, bool)> {
+        // Functions and arbitrary KCL values don't have a schema worth printing.
+        match self.type_.as_str() {
+            "FnAsArg" => return Ok(("fn".to_string(), false)),
+            "KclValue" => return Ok(("any".to_string(), false)),
+            "[KclValue]" => return Ok(("[any]".to_string(), false)),
+            _ => {}
+        }
         get_type_string_from_schema(&self.schema.clone())
     }
 
@@ -66,9 +76,19 @@ impl StdLibFnArg {
             || self.type_ == "ExtrudeGroup"
             || self.type_ == "SketchSurface"
             || self.type_ == "SketchGroupSet"
+            || self.type_ == "Solid"
         {
             return Ok(Some((index, format!("${{{}:{}}}", index, "%"))));
         }
+        if self.type_ == "FnAsArg" {
+            return Ok(Some((index, format!("${{{}:{}}}", index, "myFunction"))));
+        }
+        if self.type_ == "KclValue" {
+            return Ok(Some((index, format!("${{{}:{}}}", index, "0"))));
+        }
+        if self.type_ == "[KclValue]" {
+            return Ok(Some((index, format!("${{{}:{}}}", index, "[]"))));
+        }
         get_autocomplete_snippet_from_schema(&self.schema.clone(), index)
     }
 
@@ -77,6 +97,18 @@ impl StdLibFnArg {
     }
 }
 
+impl From<&StdLibFnArg> for Parameter {
+    /// The KCL parameter an argument corresponds to, so it can be passed by name.
+    fn from(arg: &StdLibFnArg) -> Self {
+        Parameter {
+            identifier: Identifier::new(&arg.name),
+            type_: None,
+            optional: !arg.required,
+            default_value: None,
+        }
+    }
+}
+
 impl From<StdLibFnArg> for ParameterInformation {
     fn from(arg: StdLibFnArg) -> Self {
         ParameterInformation {
@@ -1,12 +1,15 @@
 //! Functions for setting up our WebSocket and WebRTC connections for communications with the
 //! engine.
 
-use std::sync::{Arc, Mutex};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use anyhow::{anyhow, Result};
 use dashmap::DashMap;
-use futures::{SinkExt, StreamExt};
-use kittycad::types::{OkWebSocketResponseData, WebSocketRequest, WebSocketResponse};
+use futures::{future::BoxFuture, SinkExt, StreamExt};
+use kittycad::types::{ModelingCmd, ModelingCmdReq, OkWebSocketResponseData, WebSocketRequest, WebSocketResponse};
 use tokio::sync::{mpsc, oneshot, RwLock};
 use tokio_tungstenite::tungstenite::Message as WsMsg;
 
@@ -23,19 +26,129 @@ enum SocketHealth {
 }
 
 type WebSocketTcpWrite = futures::stream::SplitSink<tokio_tungstenite::WebSocketStream<reqwest::Upgraded>, WsMsg>;
+
+/// Opens a new websocket to the engine, to replace one which was lost.
+pub type Connector = Arc<dyn Fn() -> BoxFuture<'static, Result<reqwest::Upgraded>> + Send + Sync>;
+
+/// How to reconnect to the engine after losing the connection to it.
+#[derive(Debug, Clone)]
+pub struct ReconnectPolicy {
+    /// How many times to try to reconnect before giving up.
+    pub max_attempts: u32,
+    /// How long to wait before the first attempt. The wait doubles after every failed attempt.
+    pub initial_delay: Duration,
+    /// The longest to wait between two attempts.
+    pub max_delay: Duration,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// How long to wait before the given attempt, counting from 0.
+    fn delay(&self, attempt: u32) -> Duration {
+        self.initial_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay)
+    }
+}
+
+#[derive(Clone)]
+struct Reconnector {
+    connect: Connector,
+    policy: ReconnectPolicy,
+}
+
+impl std::fmt::Debug for Reconnector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Reconnector").field("policy", &self.policy).finish()
+    }
+}
+
 #[derive(Debug, Clone)]
-#[allow(dead_code)] // for the TcpReadHandle
 pub struct EngineConnection {
-    engine_req_tx: mpsc::Sender<ToEngineReq>,
+    /// The current websocket connection, which is replaced after reconnecting.
+    session: Arc<tokio::sync::Mutex<Arc<Session>>>,
     responses: Arc<DashMap<uuid::Uuid, WebSocketResponse>>,
-    tcp_read_handle: Arc<TcpReadHandle>,
-    socket_health: Arc<Mutex<SocketHealth>>,
+    /// Opens a new connection when this one is lost, or `None` to never reconnect.
+    reconnector: Option<Reconnector>,
+    /// The commands which made the current scene, to make it again after reconnecting.
+    scene_log: Arc<Mutex<SceneLog>>,
     batch: Arc<Mutex<Vec<(WebSocketRequest, crate::executor::SourceRange)>>>,
+    flush_lock: Arc<tokio::sync::Mutex<()>>,
+    id_generator: crate::executor::IdGenerator,
 
     /// The default planes for the scene.
     default_planes: Arc<RwLock<Option<DefaultPlanes>>>,
 }
 
+/// One websocket connection to the engine.
+#[derive(Debug)]
+#[allow(dead_code)] // for the TcpReadHandle
+struct Session {
+    engine_req_tx: mpsc::Sender<ToEngineReq>,
+    tcp_read_handle: TcpReadHandle,
+    socket_health: Arc<Mutex<SocketHealth>>,
+}
+
+/// Why a request to the engine failed.
+enum SendError {
+    /// The connection was lost, so the request might not have reached the engine.
+    ConnectionLost(String),
+    /// The engine answered with an error, or didn't answer in time.
+    Failed(String),
+}
+
+/// The commands which made the current scene, so it can be made again on a new connection.
+#[derive(Debug, Default)]
+struct SceneLog {
+    requests: Vec<ModelingCmdReq>,
+}
+
+impl SceneLog {
+    /// Note that the engine carried out a request.
+    fn record(&mut self, request: &WebSocketRequest) {
+        let requests = match request {
+            WebSocketRequest::ModelingCmdReq { cmd, cmd_id } => vec![ModelingCmdReq {
+                cmd: cmd.clone(),
+                cmd_id: *cmd_id,
+            }],
+            WebSocketRequest::ModelingCmdBatchReq { requests, .. } => requests.clone(),
+            _ => return,
+        };
+        for request in requests {
+            match &request.cmd {
+                // Clearing the scene removes everything except its settings.
+                ModelingCmd::SceneClearAll {} => self.requests.retain(|r| is_scene_setting(&r.cmd)),
+                // Exports don't change the scene.
+                ModelingCmd::Export { .. } => {}
+                // Only the latest value of a setting matters.
+                cmd if is_scene_setting(cmd) => {
+                    self.requests
+                        .retain(|r| std::mem::discriminant(&r.cmd) != std::mem::discriminant(cmd));
+                    self.requests.push(request);
+                }
+                _ => self.requests.push(request),
+            }
+        }
+    }
+}
+
+/// Whether a command changes a setting of the whole scene, rather than adding to it.
+fn is_scene_setting(cmd: &ModelingCmd) -> bool {
+    matches!(
+        cmd,
+        ModelingCmd::SetSceneUnits { .. } | ModelingCmd::EdgeLinesVisible { .. }
+    )
+}
+
 pub struct TcpRead {
     stream: futures::stream::SplitStream<tokio_tungstenite::WebSocketStream<reqwest::Upgraded>>,
 }
@@ -126,6 +239,81 @@ impl EngineConnection {
     }
 
     pub async fn new(ws: reqwest::Upgraded) -> Result<EngineConnection> {
+        let responses: Arc<DashMap<uuid::Uuid, WebSocketResponse>> = Arc::new(DashMap::new());
+        let session = Session::start(ws, responses.clone()).await;
+        Ok(Self::with_session(session, responses, None))
+    }
+
+    /// Connect to the engine with `connect`, and use it to reconnect whenever the connection is
+    /// lost. After reconnecting, the scene is made again before carrying on.
+    pub async fn new_with_reconnect(connect: Connector, policy: ReconnectPolicy) -> Result<EngineConnection> {
+        let ws = connect().await?;
+        let responses: Arc<DashMap<uuid::Uuid, WebSocketResponse>> = Arc::new(DashMap::new());
+        let session = Session::start(ws, responses.clone()).await;
+        Ok(Self::with_session(
+            session,
+            responses,
+            Some(Reconnector { connect, policy }),
+        ))
+    }
+
+    fn with_session(
+        session: Session,
+        responses: Arc<DashMap<uuid::Uuid, WebSocketResponse>>,
+        reconnector: Option<Reconnector>,
+    ) -> EngineConnection {
+        EngineConnection {
+            session: Arc::new(tokio::sync::Mutex::new(Arc::new(session))),
+            responses,
+            reconnector,
+            scene_log: Default::default(),
+            batch: Arc::new(Mutex::new(Vec::new())),
+            flush_lock: Default::default(),
+            id_generator: Default::default(),
+            default_planes: Default::default(),
+        }
+    }
+
+    /// Try once to replace the `lost` connection with a new one, unless another request already
+    /// did. `attempt` is how many times the caller tried before, which sets how long to wait.
+    async fn reconnect(&self, reconnector: &Reconnector, lost: &Arc<Session>, attempt: u32) -> Result<(), String> {
+        // Hold the lock while reconnecting, so other requests wait for the new connection.
+        let mut session = self.session.lock().await;
+        if !Arc::ptr_eq(&session, lost) {
+            return Ok(());
+        }
+
+        tokio::time::sleep(reconnector.policy.delay(attempt)).await;
+        *session = Arc::new(self.restore(reconnector).await?);
+        Ok(())
+    }
+
+    /// Open a new connection, and make the scene again on it.
+    async fn restore(&self, reconnector: &Reconnector) -> Result<Session, String> {
+        let ws = (reconnector.connect)()
+            .await
+            .map_err(|e| format!("could not connect to the engine: {e}"))?;
+        let session = Session::start(ws, self.responses.clone()).await;
+
+        let requests = self.scene_log.lock().unwrap().requests.clone();
+        if !requests.is_empty() {
+            let batch_id = self.id_generator.next_uuid();
+            let request = WebSocketRequest::ModelingCmdBatchReq {
+                requests,
+                batch_id,
+                responses: false,
+            };
+            session.send(&self.responses, batch_id, request).await.map_err(
+                |(SendError::ConnectionLost(e) | SendError::Failed(e))| format!("could not restore the scene: {e}"),
+            )?;
+        }
+
+        Ok(session)
+    }
+}
+
+impl Session {
+    async fn start(ws: reqwest::Upgraded, responses: Arc<DashMap<uuid::Uuid, WebSocketResponse>>) -> Session {
         let ws_stream = tokio_tungstenite::WebSocketStream::from_raw_socket(
             ws,
             tokio_tungstenite::tungstenite::protocol::Role::Client,
@@ -135,12 +323,10 @@ impl EngineConnection {
 
         let (tcp_write, tcp_read) = ws_stream.split();
         let (engine_req_tx, engine_req_rx) = mpsc::channel(10);
-        tokio::task::spawn(Self::start_write_actor(tcp_write, engine_req_rx));
+        tokio::task::spawn(EngineConnection::start_write_actor(tcp_write, engine_req_rx));
 
         let mut tcp_read = TcpRead { stream: tcp_read };
 
-        let responses: Arc<DashMap<uuid::Uuid, WebSocketResponse>> = Arc::new(DashMap::new());
-        let responses_clone = responses.clone();
         let socket_health = Arc::new(Mutex::new(SocketHealth::Active));
 
         let socket_health_tcp_read = socket_health.clone();
@@ -153,7 +339,7 @@ impl EngineConnection {
                             println!("got error message: {e}");
                         }
                         if let Some(id) = ws_resp.request_id {
-                            responses_clone.insert(id, ws_resp.clone());
+                            responses.insert(id, ws_resp.clone());
                         }
                     }
                     Err(e) => {
@@ -165,16 +351,56 @@ impl EngineConnection {
             }
         });
 
-        Ok(EngineConnection {
+        Session {
             engine_req_tx,
-            tcp_read_handle: Arc::new(TcpReadHandle {
+            tcp_read_handle: TcpReadHandle {
                 handle: Arc::new(tcp_read_handle),
-            }),
-            responses,
+            },
             socket_health,
-            batch: Arc::new(Mutex::new(Vec::new())),
-            default_planes: Default::default(),
-        })
+        }
+    }
+
+    /// Send a request, and wait for the engine to answer it.
+    async fn send(
+        &self,
+        responses: &DashMap<uuid::Uuid, WebSocketResponse>,
+        id: uuid::Uuid,
+        req: WebSocketRequest,
+    ) -> Result<OkWebSocketResponseData, SendError> {
+        let (tx, rx) = oneshot::channel();
+
+        // Send the request to the engine, via the actor.
+        self.engine_req_tx
+            .send(ToEngineReq { req, request_sent: tx })
+            .await
+            .map_err(|e| SendError::ConnectionLost(format!("Failed to send modeling command: {}", e)))?;
+
+        // Wait for the request to be sent.
+        rx.await
+            .map_err(|e| SendError::ConnectionLost(format!("could not send request to the engine actor: {e}")))?
+            .map_err(|e| SendError::ConnectionLost(format!("could not send request to the engine: {e}")))?;
+
+        // Wait for the response.
+        let current_time = std::time::Instant::now();
+        while current_time.elapsed().as_secs() < 60 {
+            if let Ok(guard) = self.socket_health.lock() {
+                if *guard == SocketHealth::Inactive {
+                    return Err(SendError::ConnectionLost(
+                        "Modeling command failed: websocket closed early".to_string(),
+                    ));
+                }
+            }
+            // We pop off the responses to cleanup our mappings.
+            if let Some((_, resp)) = responses.remove(&id) {
+                return if let Some(data) = &resp.resp {
+                    Ok(data.clone())
+                } else {
+                    Err(SendError::Failed(format!("Modeling command failed: {:?}", resp.errors)))
+                };
+            }
+        }
+
+        Err(SendError::Failed(format!("Modeling command timed out `{}`", id)))
     }
 }
 
@@ -184,6 +410,14 @@ impl EngineManager for EngineConnection {
         self.batch.clone()
     }
 
+    fn flush_lock(&self) -> Arc<tokio::sync::Mutex<()>> {
+        self.flush_lock.clone()
+    }
+
+    fn id_generator(&self) -> &crate::executor::IdGenerator {
+        &self.id_generator
+    }
+
     async fn default_planes(&self, source_range: crate::executor::SourceRange) -> Result<DefaultPlanes, KclError> {
         {
             let opt = self.default_planes.read().await.as_ref().cloned();
@@ -192,8 +426,14 @@ impl EngineManager for EngineConnection {
             }
         } // drop the read lock
 
+        // Check again now that no one else can be making them, in case another task just did.
+        let mut default_planes = self.default_planes.write().await;
+        if let Some(planes) = default_planes.as_ref() {
+            return Ok(planes.clone());
+        }
+
         let new_planes = self.new_default_planes(source_range).await?;
-        *self.default_planes.write().await = Some(new_planes.clone());
+        *default_planes = Some(new_planes.clone());
 
         Ok(new_planes)
     }
@@ -213,64 +453,116 @@ impl EngineManager for EngineConnection {
         cmd: kittycad::types::WebSocketRequest,
         _id_to_source_range: std::collections::HashMap<uuid::Uuid, crate::executor::SourceRange>,
     ) -> Result<OkWebSocketResponseData, KclError> {
-        let (tx, rx) = oneshot::channel();
-
-        // Send the request to the engine, via the actor.
-        self.engine_req_tx
-            .send(ToEngineReq {
-                req: cmd.clone(),
-                request_sent: tx,
-            })
-            .await
-            .map_err(|e| {
-                KclError::Engine(KclErrorDetails {
-                    message: format!("Failed to send modeling command: {}", e),
-                    source_ranges: vec![source_range],
-                })
-            })?;
-
-        // Wait for the request to be sent.
-        rx.await
-            .map_err(|e| {
-                KclError::Engine(KclErrorDetails {
-                    message: format!("could not send request to the engine actor: {e}"),
-                    source_ranges: vec![source_range],
-                })
-            })?
-            .map_err(|e| {
-                KclError::Engine(KclErrorDetails {
-                    message: format!("could not send request to the engine: {e}"),
-                    source_ranges: vec![source_range],
-                })
-            })?;
-
-        // Wait for the response.
-        let current_time = std::time::Instant::now();
-        while current_time.elapsed().as_secs() < 60 {
-            if let Ok(guard) = self.socket_health.lock() {
-                if *guard == SocketHealth::Inactive {
+        // Reconnect attempts are counted over the whole request, so it gives up after the
+        // policy's `max_attempts`, however often the connection is lost.
+        let mut attempt = 0;
+        loop {
+            let session = self.session.lock().await.clone();
+            let mut last_error = match session.send(&self.responses, id, cmd.clone()).await {
+                Ok(response) => {
+                    self.scene_log.lock().unwrap().record(&cmd);
+                    return Ok(response);
+                }
+                Err(SendError::Failed(message)) => {
                     return Err(KclError::Engine(KclErrorDetails {
-                        message: "Modeling command failed: websocket closed early".to_string(),
+                        message,
                         source_ranges: vec![source_range],
+                        ..Default::default()
                     }));
                 }
-            }
-            // We pop off the responses to cleanup our mappings.
-            if let Some((_, resp)) = self.responses.remove(&id) {
-                return if let Some(data) = &resp.resp {
-                    Ok(data.clone())
-                } else {
-                    Err(KclError::Engine(KclErrorDetails {
-                        message: format!("Modeling command failed: {:?}", resp.errors),
-                        source_ranges: vec![source_range],
-                    }))
-                };
+                Err(SendError::ConnectionLost(message)) => message,
+            };
+
+            // Reconnect, then send the request again on the new connection.
+            let connection_lost = |message: String| {
+                KclError::EngineConnectionLost(KclErrorDetails {
+                    message,
+                    source_ranges: vec![source_range],
+                    ..Default::default()
+                })
+            };
+            let Some(reconnector) = &self.reconnector else {
+                return Err(connection_lost(last_error));
+            };
+            loop {
+                if attempt >= reconnector.policy.max_attempts {
+                    // Only the last error is reported, since earlier attempts usually fail the same way.
+                    return Err(connection_lost(format!(
+                        "Lost the connection to the engine, and couldn't reconnect after {} attempts: {}",
+                        reconnector.policy.max_attempts, last_error
+                    )));
+                }
+                let reconnected = self.reconnect(reconnector, &session, attempt).await;
+                attempt += 1;
+                match reconnected {
+                    Ok(()) => break,
+                    Err(e) => last_error = e,
+                }
             }
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(cmd: ModelingCmd) -> WebSocketRequest {
+        WebSocketRequest::ModelingCmdReq {
+            cmd,
+            cmd_id: uuid::Uuid::new_v4(),
+        }
+    }
+
+    fn types(log: &SceneLog) -> Vec<String> {
+        log.requests
+            .iter()
+            .map(|r| serde_json::json!(r.cmd)["type"].as_str().unwrap().to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_scene_log() {
+        let mut log = SceneLog::default();
+        log.record(&request(ModelingCmd::EdgeLinesVisible { hidden: false }));
+        log.record(&request(ModelingCmd::SetSceneUnits {
+            unit: kittycad::types::UnitLength::Mm,
+        }));
+        log.record(&request(ModelingCmd::StartPath {}));
+        log.record(&WebSocketRequest::ModelingCmdBatchReq {
+            requests: vec![
+                ModelingCmdReq {
+                    cmd: ModelingCmd::SceneClearAll {},
+                    cmd_id: uuid::Uuid::new_v4(),
+                },
+                ModelingCmdReq {
+                    cmd: ModelingCmd::StartPath {},
+                    cmd_id: uuid::Uuid::new_v4(),
+                },
+            ],
+            batch_id: uuid::Uuid::new_v4(),
+            responses: false,
+        });
+        log.record(&request(ModelingCmd::SetSceneUnits {
+            unit: kittycad::types::UnitLength::In,
+        }));
+        assert_eq!(types(&log), vec!["edge_lines_visible", "start_path", "set_scene_units"]);
+        assert_eq!(
+            log.requests[2].cmd,
+            ModelingCmd::SetSceneUnits {
+                unit: kittycad::types::UnitLength::In
+            }
+        );
+    }
 
-        Err(KclError::Engine(KclErrorDetails {
-            message: format!("Modeling command timed out `{}`", id),
-            source_ranges: vec![source_range],
-        }))
+    #[test]
+    fn test_reconnect_delay() {
+        let policy = ReconnectPolicy::default();
+        let delays: Vec<Duration> = (0..7).map(|attempt| policy.delay(attempt)).collect();
+        assert_eq!(
+            delays,
+            [500, 1000, 2000, 4000, 8000, 10000, 10000].map(Duration::from_millis)
+        );
+        assert_eq!(policy.delay(u32::MAX), policy.max_delay);
     }
 }
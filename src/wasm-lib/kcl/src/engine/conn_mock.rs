@@ -11,12 +11,16 @@ use crate::{errors::KclError, executor::DefaultPlanes};
 #[derive(Debug, Clone)]
 pub struct EngineConnection {
     batch: Arc<Mutex<Vec<(WebSocketRequest, crate::executor::SourceRange)>>>,
+    flush_lock: Arc<tokio::sync::Mutex<()>>,
+    id_generator: crate::executor::IdGenerator,
 }
 
 impl EngineConnection {
     pub async fn new() -> Result<EngineConnection> {
         Ok(EngineConnection {
             batch: Arc::new(Mutex::new(Vec::new())),
+            flush_lock: Default::default(),
+            id_generator: Default::default(),
         })
     }
 }
@@ -27,6 +31,14 @@ impl crate::engine::EngineManager for EngineConnection {
         self.batch.clone()
     }
 
+    fn flush_lock(&self) -> Arc<tokio::sync::Mutex<()>> {
+        self.flush_lock.clone()
+    }
+
+    fn id_generator(&self) -> &crate::executor::IdGenerator {
+        &self.id_generator
+    }
+
     async fn default_planes(&self, _source_range: crate::executor::SourceRange) -> Result<DefaultPlanes, KclError> {
         Ok(DefaultPlanes::default())
     }
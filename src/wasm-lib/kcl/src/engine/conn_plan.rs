@@ -0,0 +1,123 @@
+//! An engine connection which doesn't send anything to the engine, but keeps every command
+//! it's given, so a program's commands can be inspected without executing them.
+
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use kittycad::types::{ModelingCmd, OkWebSocketResponseData, WebSocketRequest};
+use tokio::sync::RwLock;
+
+use crate::{
+    engine::EngineManager,
+    errors::{KclError, KclErrorDetails},
+    executor::{DefaultPlanes, SourceRange},
+};
+
+#[derive(Debug, Clone)]
+pub struct EngineConnection {
+    batch: Arc<Mutex<Vec<(WebSocketRequest, SourceRange)>>>,
+    flush_lock: Arc<tokio::sync::Mutex<()>>,
+    id_generator: crate::executor::IdGenerator,
+    /// Every command which would have been sent to the engine, in order.
+    commands: Arc<Mutex<Vec<(ModelingCmd, SourceRange)>>>,
+
+    /// The default planes for the scene.
+    default_planes: Arc<RwLock<Option<DefaultPlanes>>>,
+}
+
+impl EngineConnection {
+    pub async fn new() -> Result<EngineConnection> {
+        Ok(EngineConnection {
+            batch: Arc::new(Mutex::new(Vec::new())),
+            flush_lock: Default::default(),
+            id_generator: Default::default(),
+            commands: Arc::new(Mutex::new(Vec::new())),
+            default_planes: Default::default(),
+        })
+    }
+
+    /// Take the commands which would have been sent so far. Commands still waiting in the batch
+    /// aren't included until the batch is flushed.
+    pub fn take_commands(&self) -> Vec<(ModelingCmd, SourceRange)> {
+        std::mem::take(&mut *self.commands.lock().unwrap())
+    }
+
+    /// Make the IDs of the commands this connection sends by itself with `id_generator`.
+    pub fn with_id_generator(self, id_generator: crate::executor::IdGenerator) -> Self {
+        Self { id_generator, ..self }
+    }
+}
+
+#[async_trait::async_trait]
+impl EngineManager for EngineConnection {
+    fn batch(&self) -> Arc<Mutex<Vec<(WebSocketRequest, SourceRange)>>> {
+        self.batch.clone()
+    }
+
+    fn flush_lock(&self) -> Arc<tokio::sync::Mutex<()>> {
+        self.flush_lock.clone()
+    }
+
+    fn id_generator(&self) -> &crate::executor::IdGenerator {
+        &self.id_generator
+    }
+
+    async fn default_planes(&self, source_range: SourceRange) -> Result<DefaultPlanes, KclError> {
+        {
+            let opt = self.default_planes.read().await.as_ref().cloned();
+            if let Some(planes) = opt {
+                return Ok(planes);
+            }
+        } // drop the read lock
+
+        // Check again now that no one else can be making them, in case another task just did.
+        let mut default_planes = self.default_planes.write().await;
+        if let Some(planes) = default_planes.as_ref() {
+            return Ok(planes.clone());
+        }
+
+        // Make the planes like the real engine connection does, so their commands are included.
+        let new_planes = self.new_default_planes(source_range).await?;
+        *default_planes = Some(new_planes.clone());
+
+        Ok(new_planes)
+    }
+
+    async fn clear_scene_post_hook(&self, source_range: SourceRange) -> Result<(), KclError> {
+        // Remake the default planes, since they would have been removed after the scene was cleared.
+        let new_planes = self.new_default_planes(source_range).await?;
+        *self.default_planes.write().await = Some(new_planes);
+
+        Ok(())
+    }
+
+    async fn inner_send_modeling_cmd(
+        &self,
+        _id: uuid::Uuid,
+        source_range: SourceRange,
+        cmd: WebSocketRequest,
+        id_to_source_range: std::collections::HashMap<uuid::Uuid, SourceRange>,
+    ) -> Result<OkWebSocketResponseData, KclError> {
+        let mut commands = self.commands.lock().unwrap();
+        match cmd {
+            WebSocketRequest::ModelingCmdReq { cmd, cmd_id: _ } => commands.push((cmd, source_range)),
+            WebSocketRequest::ModelingCmdBatchReq { requests, .. } => {
+                for request in requests {
+                    let source_range = id_to_source_range.get(&request.cmd_id).copied().unwrap_or(source_range);
+                    commands.push((request.cmd, source_range));
+                }
+            }
+            other => {
+                return Err(KclError::Engine(KclErrorDetails {
+                    message: format!("The request is not a modeling command: {:?}", other),
+                    source_ranges: vec![source_range],
+                    ..Default::default()
+                }));
+            }
+        }
+
+        Ok(OkWebSocketResponseData::Modeling {
+            modeling_response: kittycad::types::OkModelingCmdResponse::Empty {},
+        })
+    }
+}
@@ -0,0 +1,155 @@
+//! An engine connection which records every request sent to another engine connection, and
+//! the response to it, so that the session can be replayed later without the engine.
+//! See `conn_replay` for the other half.
+
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Result;
+use kittycad::types::{OkWebSocketResponseData, WebSocketRequest};
+use serde::{Deserialize, Serialize};
+use tokio::{io::AsyncWriteExt, sync::RwLock};
+
+use crate::{
+    engine::EngineManager,
+    errors::{KclError, KclErrorDetails},
+    executor::DefaultPlanes,
+};
+
+/// A request sent to the engine, and the engine's response to it.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RecordedExchange {
+    pub request: WebSocketRequest,
+    pub response: Result<OkWebSocketResponseData, KclError>,
+}
+
+#[derive(Debug, Clone)]
+pub struct EngineConnection {
+    /// The connection the requests are actually sent to.
+    inner: Arc<Box<dyn EngineManager>>,
+    /// The recording, with one JSON-encoded `RecordedExchange` per line.
+    file: Arc<tokio::sync::Mutex<tokio::fs::File>>,
+    batch: Arc<Mutex<Vec<(WebSocketRequest, crate::executor::SourceRange)>>>,
+    flush_lock: Arc<tokio::sync::Mutex<()>>,
+    id_generator: crate::executor::IdGenerator,
+
+    /// The default planes for the scene.
+    default_planes: Arc<RwLock<Option<DefaultPlanes>>>,
+}
+
+impl EngineConnection {
+    /// Record everything sent to `inner` to the file at `path`, replacing any previous recording.
+    pub async fn new(inner: Arc<Box<dyn EngineManager>>, path: &Path) -> Result<EngineConnection> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let file = tokio::fs::File::create(path).await?;
+        Ok(EngineConnection {
+            inner,
+            file: Arc::new(tokio::sync::Mutex::new(file)),
+            batch: Arc::new(Mutex::new(Vec::new())),
+            flush_lock: Default::default(),
+            id_generator: Default::default(),
+            default_planes: Default::default(),
+        })
+    }
+
+    async fn record(
+        &self,
+        exchange: &RecordedExchange,
+        source_range: crate::executor::SourceRange,
+    ) -> Result<(), KclError> {
+        let to_engine_error = |message: String| {
+            KclError::Engine(KclErrorDetails {
+                message,
+                source_ranges: vec![source_range],
+                ..Default::default()
+            })
+        };
+        let mut line = serde_json::to_string(exchange)
+            .map_err(|e| to_engine_error(format!("Failed to serialize the engine response: {e}")))?;
+        line.push('\n');
+        let mut file = self.file.lock().await;
+        file.write_all(line.as_bytes())
+            .await
+            .map_err(|e| to_engine_error(format!("Failed to write to the engine recording: {e}")))?;
+        // Flush now, since tokio finishes writes in the background.
+        file.flush()
+            .await
+            .map_err(|e| to_engine_error(format!("Failed to write to the engine recording: {e}")))
+    }
+}
+
+#[async_trait::async_trait]
+impl EngineManager for EngineConnection {
+    fn batch(&self) -> Arc<Mutex<Vec<(WebSocketRequest, crate::executor::SourceRange)>>> {
+        self.batch.clone()
+    }
+
+    fn flush_lock(&self) -> Arc<tokio::sync::Mutex<()>> {
+        self.flush_lock.clone()
+    }
+
+    fn id_generator(&self) -> &crate::executor::IdGenerator {
+        &self.id_generator
+    }
+
+    fn supports_concurrency(&self) -> bool {
+        // The recording is replayed in the order the commands were sent.
+        false
+    }
+
+    async fn default_planes(&self, source_range: crate::executor::SourceRange) -> Result<DefaultPlanes, KclError> {
+        {
+            let opt = self.default_planes.read().await.as_ref().cloned();
+            if let Some(planes) = opt {
+                return Ok(planes);
+            }
+        } // drop the read lock
+
+        // Check again now that no one else can be making them, in case another task just did.
+        let mut default_planes = self.default_planes.write().await;
+        if let Some(planes) = default_planes.as_ref() {
+            return Ok(planes.clone());
+        }
+
+        // Make the planes through this connection rather than the inner one, so the commands
+        // are recorded.
+        let new_planes = self.new_default_planes(source_range).await?;
+        *default_planes = Some(new_planes.clone());
+
+        Ok(new_planes)
+    }
+
+    async fn clear_scene_post_hook(&self, source_range: crate::executor::SourceRange) -> Result<(), KclError> {
+        // Remake the default planes, since they would have been removed after the scene was cleared.
+        let new_planes = self.new_default_planes(source_range).await?;
+        *self.default_planes.write().await = Some(new_planes);
+
+        Ok(())
+    }
+
+    async fn inner_send_modeling_cmd(
+        &self,
+        id: uuid::Uuid,
+        source_range: crate::executor::SourceRange,
+        cmd: kittycad::types::WebSocketRequest,
+        id_to_source_range: std::collections::HashMap<uuid::Uuid, crate::executor::SourceRange>,
+    ) -> Result<OkWebSocketResponseData, KclError> {
+        let response = self
+            .inner
+            .inner_send_modeling_cmd(id, source_range, cmd.clone(), id_to_source_range)
+            .await;
+        self.record(
+            &RecordedExchange {
+                request: cmd,
+                response: response.clone(),
+            },
+            source_range,
+        )
+        .await?;
+        response
+    }
+}
@@ -0,0 +1,326 @@
+//! An engine connection which answers commands with the responses from a session recorded by
+//! `conn_record`, so that programs can be executed deterministically without the engine.
+//!
+//! Responses are served in the order they were recorded. The executor picks new random IDs for
+//! its commands every time it runs, so the IDs of the recorded commands are mapped to the IDs of
+//! the commands being replayed, and replaced in the responses.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Result;
+use kittycad::types::{OkWebSocketResponseData, WebSocketRequest};
+use serde_json::Value as JValue;
+use tokio::sync::RwLock;
+
+use crate::{
+    engine::{conn_record::RecordedExchange, EngineManager},
+    errors::{KclError, KclErrorDetails},
+    executor::DefaultPlanes,
+};
+
+#[derive(Debug, Clone)]
+pub struct EngineConnection {
+    /// The recorded requests and responses which haven't been replayed yet.
+    exchanges: Arc<Mutex<VecDeque<RecordedExchange>>>,
+    /// Maps the IDs of recorded commands to the IDs of the commands being replayed.
+    ids: Arc<Mutex<HashMap<uuid::Uuid, uuid::Uuid>>>,
+    batch: Arc<Mutex<Vec<(WebSocketRequest, crate::executor::SourceRange)>>>,
+    flush_lock: Arc<tokio::sync::Mutex<()>>,
+    id_generator: crate::executor::IdGenerator,
+
+    /// The default planes for the scene.
+    default_planes: Arc<RwLock<Option<DefaultPlanes>>>,
+}
+
+impl EngineConnection {
+    pub fn new(exchanges: Vec<RecordedExchange>) -> EngineConnection {
+        EngineConnection {
+            exchanges: Arc::new(Mutex::new(exchanges.into())),
+            ids: Default::default(),
+            batch: Arc::new(Mutex::new(Vec::new())),
+            flush_lock: Default::default(),
+            id_generator: Default::default(),
+            default_planes: Default::default(),
+        }
+    }
+
+    /// Load a recording written by `conn_record`.
+    pub fn load(path: &Path) -> Result<EngineConnection> {
+        let exchanges = std::fs::read_to_string(path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<RecordedExchange>, _>>()?;
+        Ok(Self::new(exchanges))
+    }
+
+    /// Remember which recorded IDs correspond to the IDs in the request being replayed.
+    fn map_ids(&self, recorded: &WebSocketRequest, actual: &WebSocketRequest) {
+        let mut ids = self.ids.lock().unwrap();
+        match (recorded, actual) {
+            (
+                WebSocketRequest::ModelingCmdReq { cmd_id: recorded, .. },
+                WebSocketRequest::ModelingCmdReq { cmd_id: actual, .. },
+            ) => {
+                ids.insert(*recorded, *actual);
+            }
+            (
+                WebSocketRequest::ModelingCmdBatchReq {
+                    requests: recorded_requests,
+                    batch_id: recorded_batch_id,
+                    ..
+                },
+                WebSocketRequest::ModelingCmdBatchReq {
+                    requests: actual_requests,
+                    batch_id: actual_batch_id,
+                    ..
+                },
+            ) => {
+                ids.insert(*recorded_batch_id, *actual_batch_id);
+                for (recorded, actual) in recorded_requests.iter().zip(actual_requests) {
+                    ids.insert(recorded.cmd_id, actual.cmd_id);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The types of the commands in a request, e.g. `["extrude"]`, used to check the request being
+/// replayed is the one that was recorded.
+fn command_types(request: &WebSocketRequest) -> Vec<String> {
+    let type_of = |value: JValue| value["type"].as_str().unwrap_or_default().to_owned();
+    match request {
+        WebSocketRequest::ModelingCmdReq { cmd, .. } => vec![type_of(serde_json::json!(cmd))],
+        WebSocketRequest::ModelingCmdBatchReq { requests, .. } => requests
+            .iter()
+            .map(|request| type_of(serde_json::json!(request.cmd)))
+            .collect(),
+        other => vec![type_of(serde_json::json!(other))],
+    }
+}
+
+/// Replace every recorded ID in a response (whether it's a value or a key) with the ID of the
+/// command being replayed.
+fn replace_ids(value: &mut JValue, ids: &HashMap<uuid::Uuid, uuid::Uuid>) {
+    let replace = |s: &str| {
+        s.parse::<uuid::Uuid>()
+            .ok()
+            .and_then(|id| ids.get(&id))
+            .map(|id| id.to_string())
+    };
+    match value {
+        JValue::String(s) => {
+            if let Some(id) = replace(s) {
+                *s = id;
+            }
+        }
+        JValue::Array(items) => {
+            for item in items {
+                replace_ids(item, ids);
+            }
+        }
+        JValue::Object(properties) => {
+            *properties = std::mem::take(properties)
+                .into_iter()
+                .map(|(key, mut value)| {
+                    replace_ids(&mut value, ids);
+                    (replace(&key).unwrap_or(key), value)
+                })
+                .collect();
+        }
+        JValue::Null | JValue::Bool(_) | JValue::Number(_) => {}
+    }
+}
+
+#[async_trait::async_trait]
+impl EngineManager for EngineConnection {
+    fn batch(&self) -> Arc<Mutex<Vec<(WebSocketRequest, crate::executor::SourceRange)>>> {
+        self.batch.clone()
+    }
+
+    fn flush_lock(&self) -> Arc<tokio::sync::Mutex<()>> {
+        self.flush_lock.clone()
+    }
+
+    fn id_generator(&self) -> &crate::executor::IdGenerator {
+        &self.id_generator
+    }
+
+    fn supports_concurrency(&self) -> bool {
+        // Replaying matches commands to the recording in the order they were sent.
+        false
+    }
+
+    async fn default_planes(&self, source_range: crate::executor::SourceRange) -> Result<DefaultPlanes, KclError> {
+        {
+            let opt = self.default_planes.read().await.as_ref().cloned();
+            if let Some(planes) = opt {
+                return Ok(planes);
+            }
+        } // drop the read lock
+
+        // Check again now that no one else can be making them, in case another task just did.
+        let mut default_planes = self.default_planes.write().await;
+        if let Some(planes) = default_planes.as_ref() {
+            return Ok(planes.clone());
+        }
+
+        let new_planes = self.new_default_planes(source_range).await?;
+        *default_planes = Some(new_planes.clone());
+
+        Ok(new_planes)
+    }
+
+    async fn clear_scene_post_hook(&self, source_range: crate::executor::SourceRange) -> Result<(), KclError> {
+        // Remake the default planes, since they would have been removed after the scene was cleared.
+        let new_planes = self.new_default_planes(source_range).await?;
+        *self.default_planes.write().await = Some(new_planes);
+
+        Ok(())
+    }
+
+    async fn inner_send_modeling_cmd(
+        &self,
+        _id: uuid::Uuid,
+        source_range: crate::executor::SourceRange,
+        cmd: kittycad::types::WebSocketRequest,
+        _id_to_source_range: std::collections::HashMap<uuid::Uuid, crate::executor::SourceRange>,
+    ) -> Result<OkWebSocketResponseData, KclError> {
+        let to_engine_error = |message: String| {
+            KclError::Engine(KclErrorDetails {
+                message,
+                source_ranges: vec![source_range],
+                ..Default::default()
+            })
+        };
+
+        let Some(exchange) = self.exchanges.lock().unwrap().pop_front() else {
+            return Err(to_engine_error(format!(
+                "The recording has no more responses, but {:?} was sent",
+                command_types(&cmd)
+            )));
+        };
+        let (expected, actual) = (command_types(&exchange.request), command_types(&cmd));
+        if expected != actual {
+            return Err(to_engine_error(format!(
+                "The commands don't match the recording: expected {expected:?}, but {actual:?} was sent"
+            )));
+        }
+        self.map_ids(&exchange.request, &cmd);
+
+        let mut response = serde_json::to_value(exchange.response)
+            .map_err(|e| to_engine_error(format!("Failed to serialize the recorded response: {e}")))?;
+        replace_ids(&mut response, &self.ids.lock().unwrap());
+        let response: Result<OkWebSocketResponseData, KclError> = serde_json::from_value(response)
+            .map_err(|e| to_engine_error(format!("Failed to deserialize the recorded response: {e}")))?;
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kittycad::types::{ModelingCmd, OkModelingCmdResponse};
+
+    use super::*;
+    use crate::executor::SourceRange;
+
+    fn get_parent(entity_id: uuid::Uuid) -> ModelingCmd {
+        ModelingCmd::EntityGetParentId { entity_id }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_replay_maps_ids() {
+        let (recorded_id, entity_id) = (uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+        let engine = EngineConnection::new(vec![RecordedExchange {
+            request: WebSocketRequest::ModelingCmdReq {
+                cmd: get_parent(entity_id),
+                cmd_id: recorded_id,
+            },
+            response: Ok(OkWebSocketResponseData::Modeling {
+                modeling_response: OkModelingCmdResponse::EntityGetParentId {
+                    data: kittycad::types::EntityGetParentId { entity_id: recorded_id },
+                },
+            }),
+        }]);
+
+        let id = uuid::Uuid::new_v4();
+        let response = engine
+            .send_modeling_cmd(id, SourceRange::default(), get_parent(entity_id))
+            .await
+            .unwrap();
+        let OkWebSocketResponseData::Modeling {
+            modeling_response: OkModelingCmdResponse::EntityGetParentId { data },
+        } = response
+        else {
+            panic!("unexpected response {response:?}");
+        };
+        assert_eq!(data.entity_id, id);
+
+        // The recording has run out.
+        let err = engine
+            .send_modeling_cmd(id, SourceRange::default(), get_parent(entity_id))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.message(),
+            r#"The recording has no more responses, but ["entity_get_parent_id"] was sent"#
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_record_and_replay() {
+        let path = std::env::temp_dir().join(format!("kcl_recording_{}.jsonl", uuid::Uuid::new_v4()));
+        let code = r#"const part001 = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line([0, 10], %)
+  |> line([10, 0], %)
+  |> close(%)
+  |> extrude(5, %)"#;
+        let program = crate::parser::Parser::new(crate::token::lexer(code).unwrap())
+            .ast()
+            .unwrap();
+
+        let mock: Arc<Box<dyn EngineManager>> = Arc::new(Box::new(
+            crate::engine::conn_mock::EngineConnection::new().await.unwrap(),
+        ));
+        let recorder = crate::engine::conn_record::EngineConnection::new(mock, &path)
+            .await
+            .unwrap();
+        // Independent statements must not interleave their commands, or replays would not match.
+        assert!(!recorder.supports_concurrency());
+        let ctx = crate::executor::ExecutorContext::new_with_engine(Arc::new(Box::new(recorder)), Default::default())
+            .await
+            .unwrap();
+        ctx.run(program.clone(), None).await.unwrap();
+
+        let replay = EngineConnection::load(&path).unwrap();
+        assert!(!replay.supports_concurrency());
+        assert!(!replay.exchanges.lock().unwrap().is_empty());
+        let ctx =
+            crate::executor::ExecutorContext::new_with_engine(Arc::new(Box::new(replay.clone())), Default::default())
+                .await
+                .unwrap();
+        ctx.run(program, None).await.unwrap();
+        assert!(replay.exchanges.lock().unwrap().is_empty());
+
+        // A different program doesn't match the recording.
+        let replay = EngineConnection::load(&path).unwrap();
+        let ctx = crate::executor::ExecutorContext::new_with_engine(Arc::new(Box::new(replay)), Default::default())
+            .await
+            .unwrap();
+        let program = crate::parser::Parser::new(crate::token::lexer("const x = startSketchOn('XY')").unwrap())
+            .ast()
+            .unwrap();
+        let err = ctx.run(program, None).await.unwrap_err();
+        assert!(
+            err.message().starts_with("The commands don't match the recording"),
+            "{err:?}"
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -39,6 +39,8 @@ extern "C" {
 pub struct EngineConnection {
     manager: Arc<EngineCommandManager>,
     batch: Arc<Mutex<Vec<(WebSocketRequest, crate::executor::SourceRange)>>>,
+    flush_lock: Arc<tokio::sync::Mutex<()>>,
+    id_generator: crate::executor::IdGenerator,
 }
 
 // Safety: WebAssembly will only ever run in a single-threaded context.
@@ -50,6 +52,8 @@ impl EngineConnection {
         Ok(EngineConnection {
             manager: Arc::new(manager),
             batch: Arc::new(Mutex::new(Vec::new())),
+            flush_lock: Default::default(),
+            id_generator: Default::default(),
         })
     }
 }
@@ -60,12 +64,21 @@ impl crate::engine::EngineManager for EngineConnection {
         self.batch.clone()
     }
 
+    fn flush_lock(&self) -> Arc<tokio::sync::Mutex<()>> {
+        self.flush_lock.clone()
+    }
+
+    fn id_generator(&self) -> &crate::executor::IdGenerator {
+        &self.id_generator
+    }
+
     async fn default_planes(&self, source_range: crate::executor::SourceRange) -> Result<DefaultPlanes, KclError> {
         // Get the default planes.
         let promise = self.manager.get_default_planes().map_err(|e| {
             KclError::Engine(KclErrorDetails {
                 message: e.to_string().into(),
                 source_ranges: vec![source_range],
+                ..Default::default()
             })
         })?;
 
@@ -73,6 +86,7 @@ impl crate::engine::EngineManager for EngineConnection {
             KclError::Engine(KclErrorDetails {
                 message: format!("Failed to wait for promise from get default planes: {:?}", e),
                 source_ranges: vec![source_range],
+                ..Default::default()
             })
         })?;
 
@@ -84,6 +98,7 @@ impl crate::engine::EngineManager for EngineConnection {
                     value
                 ),
                 source_ranges: vec![source_range],
+                ..Default::default()
             })
         })?;
 
@@ -92,6 +107,7 @@ impl crate::engine::EngineManager for EngineConnection {
             KclError::Engine(KclErrorDetails {
                 message: format!("Failed to deserialize default planes: {:?}", e),
                 source_ranges: vec![source_range],
+                ..Default::default()
             })
         })?;
 
@@ -103,6 +119,7 @@ impl crate::engine::EngineManager for EngineConnection {
             KclError::Engine(KclErrorDetails {
                 message: e.to_string().into(),
                 source_ranges: vec![source_range],
+                ..Default::default()
             })
         })?;
 
@@ -111,6 +128,7 @@ impl crate::engine::EngineManager for EngineConnection {
             KclError::Engine(KclErrorDetails {
                 message: e.to_string().into(),
                 source_ranges: vec![source_range],
+                ..Default::default()
             })
         })?;
 
@@ -118,6 +136,7 @@ impl crate::engine::EngineManager for EngineConnection {
             KclError::Engine(KclErrorDetails {
                 message: format!("Failed to wait for promise from start new session: {:?}", e),
                 source_ranges: vec![source_range],
+                ..Default::default()
             })
         })?;
 
@@ -135,18 +154,21 @@ impl crate::engine::EngineManager for EngineConnection {
             KclError::Engine(KclErrorDetails {
                 message: format!("Failed to serialize source range: {:?}", e),
                 source_ranges: vec![source_range],
+                ..Default::default()
             })
         })?;
         let cmd_str = serde_json::to_string(&cmd).map_err(|e| {
             KclError::Engine(KclErrorDetails {
                 message: format!("Failed to serialize modeling command: {:?}", e),
                 source_ranges: vec![source_range],
+                ..Default::default()
             })
         })?;
         let id_to_source_range_str = serde_json::to_string(&id_to_source_range).map_err(|e| {
             KclError::Engine(KclErrorDetails {
                 message: format!("Failed to serialize id to source range: {:?}", e),
                 source_ranges: vec![source_range],
+                ..Default::default()
             })
         })?;
 
@@ -157,6 +179,7 @@ impl crate::engine::EngineManager for EngineConnection {
                 KclError::Engine(KclErrorDetails {
                     message: e.to_string().into(),
                     source_ranges: vec![source_range],
+                    ..Default::default()
                 })
             })?;
 
@@ -164,6 +187,7 @@ impl crate::engine::EngineManager for EngineConnection {
             KclError::Engine(KclErrorDetails {
                 message: format!("Failed to wait for promise from engine: {:?}", e),
                 source_ranges: vec![source_range],
+                ..Default::default()
             })
         })?;
 
@@ -172,6 +196,7 @@ impl crate::engine::EngineManager for EngineConnection {
             KclError::Engine(KclErrorDetails {
                 message: format!("Failed to get string from response from engine: `{:?}`", value),
                 source_ranges: vec![source_range],
+                ..Default::default()
             })
         })?;
 
@@ -179,6 +204,7 @@ impl crate::engine::EngineManager for EngineConnection {
             KclError::Engine(KclErrorDetails {
                 message: format!("Failed to deserialize response from engine: {:?}", e),
                 source_ranges: vec![source_range],
+                ..Default::default()
             })
         })?;
 
@@ -188,11 +214,13 @@ impl crate::engine::EngineManager for EngineConnection {
             Err(KclError::Engine(KclErrorDetails {
                 message: format!("Modeling command failed: {:?}", errors),
                 source_ranges: vec![source_range],
+                ..Default::default()
             }))
         } else {
             Err(KclError::Engine(KclErrorDetails {
                 message: format!("Modeling command failed: {:?}", ws_result),
                 source_ranges: vec![source_range],
+                ..Default::default()
             }))
         }
     }
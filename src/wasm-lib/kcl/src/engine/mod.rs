@@ -4,6 +4,11 @@
 #[cfg(feature = "engine")]
 pub mod conn;
 pub mod conn_mock;
+pub mod conn_plan;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod conn_record;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod conn_replay;
 #[cfg(target_arch = "wasm32")]
 #[cfg(feature = "engine")]
 pub mod conn_wasm;
@@ -27,6 +32,21 @@ pub trait EngineManager: std::fmt::Debug + Send + Sync + 'static {
     /// Get the batch of commands to be sent to the engine.
     fn batch(&self) -> Arc<Mutex<Vec<(kittycad::types::WebSocketRequest, crate::executor::SourceRange)>>>;
 
+    /// Held while a batch is sent and answered, so batches taken by concurrent tasks reach the
+    /// engine in the order they were taken, and each task gets the response to its own command.
+    fn flush_lock(&self) -> Arc<tokio::sync::Mutex<()>>;
+
+    /// Makes the IDs of the commands the connection sends by itself, like the ones which make
+    /// the default planes.
+    fn id_generator(&self) -> &crate::executor::IdGenerator;
+
+    /// Whether independent parts of a program can send commands at the same time. Connections
+    /// which need every run to send commands in the same order, like recording and replaying,
+    /// return false.
+    fn supports_concurrency(&self) -> bool {
+        true
+    }
+
     /// Get the default planes.
     async fn default_planes(
         &self,
@@ -51,7 +71,7 @@ pub trait EngineManager: std::fmt::Debug + Send + Sync + 'static {
 
     async fn clear_scene(&self, source_range: crate::executor::SourceRange) -> Result<(), crate::errors::KclError> {
         self.send_modeling_cmd(
-            uuid::Uuid::new_v4(),
+            self.id_generator().next_uuid(),
             source_range,
             kittycad::types::ModelingCmd::SceneClearAll {},
         )
@@ -78,40 +98,57 @@ pub trait EngineManager: std::fmt::Debug + Send + Sync + 'static {
             cmd_id: id,
         };
 
-        // Add cmd to the batch.
-        self.batch().lock().unwrap().push((req, source_range));
+        // If the command expects a return value, fire it right away, along with everything
+        // else waiting in the batch.
+        if !is_cmd_with_return_values(&cmd) {
+            // Add cmd to the batch.
+            self.batch().lock().unwrap().push((req, source_range));
 
-        // If the batch only has this one command that expects a return value,
-        // fire it right away, or if we want to flush batch queue.
-        let is_sending = is_cmd_with_return_values(&cmd);
-
-        // Return a fake modeling_request empty response.
-        if !is_sending {
+            // Return a fake modeling_request empty response.
             return Ok(OkWebSocketResponseData::Modeling {
                 modeling_response: kittycad::types::OkModelingCmdResponse::Empty {},
             });
         }
 
-        // Flush the batch queue.
-        self.flush_batch(source_range).await
+        // Only add cmd once no one else is flushing, so it's the last command in the batch
+        // and the response is the one to it.
+        let flush_lock = self.flush_lock();
+        let _flushing = flush_lock.lock().await;
+        let batch = {
+            let batch = self.batch();
+            let mut batch = batch.lock().unwrap();
+            batch.push((req, source_range));
+            std::mem::take(&mut *batch)
+        };
+        self.send_batch(batch, source_range).await
     }
 
     /// Force flush the batch queue.
     async fn flush_batch(
         &self,
         source_range: crate::executor::SourceRange,
+    ) -> Result<kittycad::types::OkWebSocketResponseData, crate::errors::KclError> {
+        let flush_lock = self.flush_lock();
+        let _flushing = flush_lock.lock().await;
+        let batch = std::mem::take(&mut *self.batch().lock().unwrap());
+        self.send_batch(batch, source_range).await
+    }
+
+    /// Send commands taken from the batch queue, and wait for the response to the last one.
+    /// Only call this while holding the `flush_lock`.
+    async fn send_batch(
+        &self,
+        batch: Vec<(WebSocketRequest, crate::executor::SourceRange)>,
+        source_range: crate::executor::SourceRange,
     ) -> Result<kittycad::types::OkWebSocketResponseData, crate::errors::KclError> {
         // Return early if we have no commands to send.
-        if self.batch().lock().unwrap().is_empty() {
+        if batch.is_empty() {
             return Ok(OkWebSocketResponseData::Modeling {
                 modeling_response: kittycad::types::OkModelingCmdResponse::Empty {},
             });
         }
 
-        let requests = self
-            .batch()
-            .lock()
-            .unwrap()
+        let requests = batch
             .iter()
             .filter_map(|(val, _)| match val {
                 WebSocketRequest::ModelingCmdReq { cmd, cmd_id } => Some(kittycad::types::ModelingCmdReq {
@@ -127,9 +164,9 @@ pub trait EngineManager: std::fmt::Debug + Send + Sync + 'static {
             responses: false,
         };
 
-        let final_req = if self.batch().lock().unwrap().len() == 1 {
+        let final_req = if batch.len() == 1 {
             // We can unwrap here because we know the batch has only one element.
-            self.batch().lock().unwrap().first().unwrap().0.clone()
+            batch.first().unwrap().0.clone()
         } else {
             batched_requests
         };
@@ -137,7 +174,7 @@ pub trait EngineManager: std::fmt::Debug + Send + Sync + 'static {
         // Create the map of original command IDs to source range.
         // This is for the wasm side, kurt needs it for selections.
         let mut id_to_source_range = std::collections::HashMap::new();
-        for (req, range) in self.batch().lock().unwrap().iter() {
+        for (req, range) in batch.iter() {
             match req {
                 WebSocketRequest::ModelingCmdReq { cmd: _, cmd_id } => {
                     id_to_source_range.insert(*cmd_id, *range);
@@ -146,14 +183,12 @@ pub trait EngineManager: std::fmt::Debug + Send + Sync + 'static {
                     return Err(KclError::Engine(KclErrorDetails {
                         message: format!("The request is not a modeling command: {:?}", req),
                         source_ranges: vec![*range],
+                        ..Default::default()
                     }));
                 }
             }
         }
 
-        // Throw away the old batch queue.
-        self.batch().lock().unwrap().clear();
-
         // We pop off the responses to cleanup our mappings.
         let id_final = match final_req {
             WebSocketRequest::ModelingCmdBatchReq {
@@ -166,6 +201,7 @@ pub trait EngineManager: std::fmt::Debug + Send + Sync + 'static {
                 return Err(KclError::Engine(KclErrorDetails {
                     message: format!("The final request is not a modeling command: {:?}", final_req),
                     source_ranges: vec![source_range],
+                    ..Default::default()
                 }));
             }
         };
@@ -185,7 +221,7 @@ pub trait EngineManager: std::fmt::Debug + Send + Sync + 'static {
         let default_size = 100.0;
         let default_origin = Point3d { x: 0.0, y: 0.0, z: 0.0 }.into();
 
-        let plane_id = uuid::Uuid::new_v4();
+        let plane_id = self.id_generator().next_uuid();
         self.send_modeling_cmd(
             plane_id,
             source_range,
@@ -203,7 +239,7 @@ pub trait EngineManager: std::fmt::Debug + Send + Sync + 'static {
         if let Some(color) = color {
             // Set the color.
             self.send_modeling_cmd(
-                uuid::Uuid::new_v4(),
+                self.id_generator().next_uuid(),
                 source_range,
                 ModelingCmd::PlaneSetColor { color, plane_id },
             )
@@ -214,7 +250,9 @@ pub trait EngineManager: std::fmt::Debug + Send + Sync + 'static {
     }
 
     async fn new_default_planes(&self, source_range: crate::executor::SourceRange) -> Result<DefaultPlanes, KclError> {
-        let plane_settings: HashMap<PlaneName, (Point3d, Point3d, Option<Color>)> = HashMap::from([
+        // These are in a fixed order, so that the same commands are sent every time and a
+        // recorded session can be replayed.
+        let plane_settings: [(PlaneName, (Point3d, Point3d, Option<Color>)); 6] = [
             (
                 PlaneName::Xy,
                 (
@@ -294,7 +332,7 @@ pub trait EngineManager: std::fmt::Debug + Send + Sync + 'static {
                     None,
                 ),
             ),
-        ]);
+        ];
 
         let mut planes = HashMap::new();
         for (name, (x_axis, y_axis, color)) in plane_settings {
@@ -321,6 +359,7 @@ pub trait EngineManager: std::fmt::Debug + Send + Sync + 'static {
 pub fn is_cmd_with_return_values(cmd: &kittycad::types::ModelingCmd) -> bool {
     let (kittycad::types::ModelingCmd::Export { .. }
     | kittycad::types::ModelingCmd::Extrude { .. }
+    | kittycad::types::ModelingCmd::Loft { .. }
     | kittycad::types::ModelingCmd::DefaultCameraLookAt { .. }
     | kittycad::types::ModelingCmd::DefaultCameraFocusOn { .. }
     | kittycad::types::ModelingCmd::DefaultCameraGetSettings { .. }
@@ -374,6 +413,49 @@ pub fn is_cmd_with_return_values(cmd: &kittycad::types::ModelingCmd) -> bool {
     true
 }
 
+/// The IDs of the scene objects a command created, given the engine's response to it.
+/// Objects made by other objects (e.g. the faces of an extrusion) aren't included, since they're
+/// removed along with the object they belong to.
+pub fn created_object_ids(id: uuid::Uuid, cmd: &ModelingCmd, response: &OkWebSocketResponseData) -> Vec<uuid::Uuid> {
+    use kittycad::types::OkModelingCmdResponse;
+
+    match cmd {
+        ModelingCmd::StartPath {}
+        | ModelingCmd::MakePlane { .. }
+        | ModelingCmd::EntityMakeHelix { .. }
+        | ModelingCmd::BooleanUnion { .. }
+        | ModelingCmd::BooleanSubtract { .. }
+        | ModelingCmd::BooleanIntersection { .. } => {
+            return vec![id];
+        }
+        ModelingCmd::ImportFiles { .. }
+        | ModelingCmd::Loft { .. }
+        | ModelingCmd::EntityLinearPattern { .. }
+        | ModelingCmd::EntityCircularPattern { .. } => {}
+        _ => return Vec::new(),
+    }
+    let OkWebSocketResponseData::Modeling { modeling_response } = response else {
+        return Vec::new();
+    };
+    match modeling_response {
+        OkModelingCmdResponse::ImportFiles { data } => vec![data.object_id],
+        OkModelingCmdResponse::Loft { data } => vec![data.solid_id],
+        OkModelingCmdResponse::EntityLinearPattern { data } => data
+            .entity_face_edge_ids
+            .iter()
+            .flatten()
+            .map(|info| info.object_id)
+            .collect(),
+        OkModelingCmdResponse::EntityCircularPattern { data } => data
+            .entity_face_edge_ids
+            .iter()
+            .flatten()
+            .map(|info| info.object_id)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
 #[derive(Debug, Hash, Eq, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema)]
 #[ts(export)]
 #[serde(rename_all = "camelCase")]
@@ -1,6 +1,7 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
-use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, Url};
 
 use crate::executor::SourceRange;
 
@@ -30,15 +31,43 @@ pub enum KclError {
     Engine(KclErrorDetails),
     #[error("internal error, please report to KittyCAD team: {0:?}")]
     Internal(KclErrorDetails),
+    /// Execution was cancelled, or went over its budget.
+    #[error("interrupted: {0:?}")]
+    Interrupted(KclErrorDetails),
+    /// The connection to the engine was lost, and reconnecting to it failed.
+    #[error("engine connection lost: {0:?}")]
+    EngineConnectionLost(KclErrorDetails),
 }
 
-#[derive(Debug, Serialize, Deserialize, ts_rs::TS, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Serialize, Deserialize, ts_rs::TS, Clone, PartialEq, Eq)]
 #[ts(export)]
 pub struct KclErrorDetails {
     #[serde(rename = "sourceRanges")]
     pub source_ranges: Vec<SourceRange>,
     #[serde(rename = "msg")]
     pub message: String,
+    /// The user-defined functions which were being called when the error happened, innermost
+    /// first. Empty if the error didn't happen inside a function.
+    #[serde(default, rename = "callStack")]
+    pub call_stack: Vec<StackFrame>,
+}
+
+/// A call to a user-defined function which was in progress when an error happened.
+#[derive(Debug, Serialize, Deserialize, ts_rs::TS, Clone, PartialEq, Eq)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct StackFrame {
+    /// The name of the function being called.
+    pub function_name: String,
+    /// Where the function was called from.
+    pub source_range: SourceRange,
+}
+
+/// The 1-based line and 0-based column where a source range starts.
+fn line_column(input: &str, range: &SourceRange) -> (usize, usize) {
+    let line = input[..range.0[0]].lines().count();
+    let column = input[..range.0[0]].lines().last().map(|l| l.len()).unwrap_or_default();
+    (line, column)
 }
 
 impl KclError {
@@ -46,15 +75,23 @@ impl KclError {
     pub fn get_message_line_column(&self, input: &str) -> (String, Option<usize>, Option<usize>) {
         // Calculate the line and column of the error from the source range.
         let (line, column) = if let Some(range) = self.source_ranges().first() {
-            let line = input[..range.0[0]].lines().count();
-            let column = input[..range.0[0]].lines().last().map(|l| l.len()).unwrap_or_default();
+            let (line, column) = line_column(input, range);
 
             (Some(line), Some(column))
         } else {
             (None, None)
         };
 
-        (format!("{}: {}", self.error_type(), self.message()), line, column)
+        let mut message = format!("{}: {}", self.error_type(), self.message());
+        for frame in self.call_stack() {
+            let (line, column) = line_column(input, &frame.source_range);
+            message.push_str(&format!(
+                "\n    in `{}` called at line {}, column {}",
+                frame.function_name, line, column
+            ));
+        }
+
+        (message, line, column)
     }
 
     pub fn error_type(&self) -> &'static str {
@@ -70,6 +107,8 @@ impl KclError {
             KclError::InvalidExpression(_) => "invalid expression",
             KclError::Engine(_) => "engine",
             KclError::Internal(_) => "internal",
+            KclError::Interrupted(_) => "interrupted",
+            KclError::EngineConnectionLost(_) => "engine connection lost",
         }
     }
 
@@ -86,6 +125,60 @@ impl KclError {
             KclError::InvalidExpression(e) => e.source_ranges.clone(),
             KclError::Engine(e) => e.source_ranges.clone(),
             KclError::Internal(e) => e.source_ranges.clone(),
+            KclError::Interrupted(e) => e.source_ranges.clone(),
+            KclError::EngineConnectionLost(e) => e.source_ranges.clone(),
+        }
+    }
+
+    /// Get the user-defined functions which were being called when the error happened,
+    /// innermost first.
+    pub fn call_stack(&self) -> &[StackFrame] {
+        &self.details().call_stack
+    }
+
+    /// Record that the error happened inside a call to the given user-defined function. Frames
+    /// are added as the error leaves each call, so the innermost call comes first.
+    pub fn add_stack_frame(mut self, function_name: impl Into<String>, source_range: SourceRange) -> Self {
+        self.details_mut().call_stack.push(StackFrame {
+            function_name: function_name.into(),
+            source_range,
+        });
+        self
+    }
+
+    fn details(&self) -> &KclErrorDetails {
+        match self {
+            KclError::Lexical(e) => e,
+            KclError::Syntax(e) => e,
+            KclError::Semantic(e) => e,
+            KclError::Type(e) => e,
+            KclError::Unimplemented(e) => e,
+            KclError::Unexpected(e) => e,
+            KclError::ValueAlreadyDefined(e) => e,
+            KclError::UndefinedValue(e) => e,
+            KclError::InvalidExpression(e) => e,
+            KclError::Engine(e) => e,
+            KclError::Internal(e) => e,
+            KclError::Interrupted(e) => e,
+            KclError::EngineConnectionLost(e) => e,
+        }
+    }
+
+    fn details_mut(&mut self) -> &mut KclErrorDetails {
+        match self {
+            KclError::Lexical(e) => e,
+            KclError::Syntax(e) => e,
+            KclError::Semantic(e) => e,
+            KclError::Type(e) => e,
+            KclError::Unimplemented(e) => e,
+            KclError::Unexpected(e) => e,
+            KclError::ValueAlreadyDefined(e) => e,
+            KclError::UndefinedValue(e) => e,
+            KclError::InvalidExpression(e) => e,
+            KclError::Engine(e) => e,
+            KclError::Internal(e) => e,
+            KclError::Interrupted(e) => e,
+            KclError::EngineConnectionLost(e) => e,
         }
     }
 
@@ -103,12 +196,26 @@ impl KclError {
             KclError::InvalidExpression(e) => &e.message,
             KclError::Engine(e) => &e.message,
             KclError::Internal(e) => &e.message,
+            KclError::Interrupted(e) => &e.message,
+            KclError::EngineConnectionLost(e) => &e.message,
         }
     }
 
-    pub fn to_lsp_diagnostic(&self, code: &str) -> Diagnostic {
+    pub fn to_lsp_diagnostic(&self, code: &str, uri: &Url) -> Diagnostic {
         let (message, _, _) = self.get_message_line_column(code);
         let source_ranges = self.source_ranges();
+        // Point at each call site, so the user can find which call led to the error.
+        let related_information = self
+            .call_stack()
+            .iter()
+            .map(|frame| DiagnosticRelatedInformation {
+                location: Location {
+                    uri: uri.clone(),
+                    range: frame.source_range.to_lsp_range(code),
+                },
+                message: format!("in `{}` called here", frame.function_name),
+            })
+            .collect::<Vec<_>>();
 
         Diagnostic {
             range: source_ranges.first().map(|r| r.to_lsp_range(code)).unwrap_or_default(),
@@ -118,7 +225,7 @@ impl KclError {
             code_description: None,
             source: Some("kcl".to_string()),
             message,
-            related_information: None,
+            related_information: (!related_information.is_empty()).then_some(related_information),
             tags: None,
             data: None,
         }
@@ -138,6 +245,8 @@ impl KclError {
             KclError::InvalidExpression(e) => e.source_ranges = source_ranges,
             KclError::Engine(e) => e.source_ranges = source_ranges,
             KclError::Internal(e) => e.source_ranges = source_ranges,
+            KclError::Interrupted(e) => e.source_ranges = source_ranges,
+            KclError::EngineConnectionLost(e) => e.source_ranges = source_ranges,
         }
 
         new
@@ -157,3 +266,49 @@ impl From<String> for KclError {
         serde_json::from_str(&error).unwrap()
     }
 }
+
+/// How serious a warning is.
+#[derive(Debug, Serialize, Deserialize, ts_rs::TS, JsonSchema, Clone, Copy, PartialEq, Eq, Hash)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Probably a mistake.
+    Warning,
+    /// Worth knowing, but not necessarily a mistake.
+    Info,
+}
+
+/// A problem which doesn't stop the program from executing, e.g. calling a deprecated function
+/// or drawing a segment with no length.
+#[derive(Debug, Serialize, Deserialize, ts_rs::TS, JsonSchema, Clone, PartialEq, Eq, Hash)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct KclWarning {
+    pub severity: Severity,
+    #[serde(rename = "msg")]
+    pub message: String,
+    pub source_range: SourceRange,
+}
+
+impl KclWarning {
+    pub fn new(source_range: SourceRange, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message: message.into(),
+            source_range,
+        }
+    }
+
+    pub fn to_lsp_diagnostic(&self, code: &str) -> Diagnostic {
+        Diagnostic {
+            range: self.source_range.to_lsp_range(code),
+            severity: Some(match self.severity {
+                Severity::Warning => DiagnosticSeverity::WARNING,
+                Severity::Info => DiagnosticSeverity::INFORMATION,
+            }),
+            source: Some("kcl".to_string()),
+            message: self.message.clone(),
+            ..Default::default()
+        }
+    }
+}
@@ -12,20 +12,56 @@ use serde_json::Value as JValue;
 use tower_lsp::lsp_types::{Position as LspPosition, Range as LspRange};
 
 use crate::{
-    ast::types::{BodyItem, FunctionExpression, KclNone, Value},
+    ast::types::{
+        BindingPattern, BodyItem, Dimension, FunctionExpression, Identifier, ImportStatement, ItemVisibility, KclNone,
+        Parameter, Value,
+    },
     engine::EngineManager,
-    errors::{KclError, KclErrorDetails},
-    fs::FileManager,
+    errors::{KclError, KclErrorDetails, KclWarning},
+    fs::{FileManager, FileSystem},
     std::{FunctionKind, StdLib},
 };
 
+mod cache;
+mod concurrent;
+mod profiler;
+
+pub use cache::ExecutionCache;
+pub use profiler::{CommandCount, HotSpot, Profile, ProfileSpan, Profiler};
+
+/// The most times a single `for` loop may run, so a typo can't hang the app.
+pub const MAX_LOOP_ITERATIONS: u64 = 10_000;
+
+/// How close the end of a sketch group has to be to its start for it to be closed.
+const CLOSED_TOLERANCE: f64 = 0.0000001;
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema)]
 #[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct ProgramMemory {
+    #[serde(serialize_with = "serialize_sorted")]
     pub root: HashMap<String, MemoryItem>,
+    /// The scope this one is nested in. Names not found in `root` are looked up here.
+    /// For a function call, this is the scope the function was declared in.
+    #[serde(skip)]
+    pub parent: Option<Arc<ProgramMemory>>,
+    /// How many of the enclosing scopes were split off from this one by `share`, and still
+    /// belong to it.
+    #[serde(skip)]
+    shared_scopes: usize,
     #[serde(rename = "return")]
     pub return_: Option<ProgramReturn>,
+    /// Problems found while executing which didn't stop it. Only set on the memory returned by
+    /// a run.
+    #[serde(default)]
+    pub warnings: Vec<KclWarning>,
+}
+
+/// Serialize a map with its keys in order, so the same memory always serializes the same way.
+fn serialize_sorted<S: serde::Serializer>(map: &HashMap<String, MemoryItem>, serializer: S) -> Result<S::Ok, S::Error> {
+    map.iter()
+        .collect::<std::collections::BTreeMap<_, _>>()
+        .serialize(serializer)
 }
 
 impl ProgramMemory {
@@ -37,6 +73,7 @@ impl ProgramMemory {
                     MemoryItem::UserVal(UserVal {
                         value: serde_json::Value::Number(serde_json::value::Number::from(0)),
                         meta: Default::default(),
+                        ..Default::default()
                     }),
                 ),
                 (
@@ -44,6 +81,7 @@ impl ProgramMemory {
                     MemoryItem::UserVal(UserVal {
                         value: serde_json::Value::Number(serde_json::value::Number::from(90)),
                         meta: Default::default(),
+                        ..Default::default()
                     }),
                 ),
                 (
@@ -51,6 +89,7 @@ impl ProgramMemory {
                     MemoryItem::UserVal(UserVal {
                         value: serde_json::Value::Number(serde_json::value::Number::from(180)),
                         meta: Default::default(),
+                        ..Default::default()
                     }),
                 ),
                 (
@@ -58,19 +97,89 @@ impl ProgramMemory {
                     MemoryItem::UserVal(UserVal {
                         value: serde_json::Value::Number(serde_json::value::Number::from(270)),
                         meta: Default::default(),
+                        ..Default::default()
                     }),
                 ),
             ]),
+            parent: None,
+            shared_scopes: 0,
+            return_: None,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Create an empty scope nested inside the given one.
+    pub fn new_scope(parent: Arc<ProgramMemory>) -> Self {
+        Self {
+            root: HashMap::new(),
+            parent: Some(parent),
+            shared_scopes: 0,
+            return_: None,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Get everything declared in this scope so far, without copying it, so a function
+    /// declared here can keep it as the scope it was declared in. The names move into a new
+    /// enclosing scope, and `root` starts out empty again.
+    pub fn share(&mut self) -> Arc<ProgramMemory> {
+        // Nothing was declared since the last share, so it already has everything.
+        if self.root.is_empty() && self.shared_scopes > 0 {
+            if let Some(shared) = &self.parent {
+                return shared.clone();
+            }
+        }
+        let shared = Arc::new(Self {
+            root: std::mem::take(&mut self.root),
+            parent: self.parent.take(),
+            shared_scopes: 0,
             return_: None,
+            warnings: Vec::new(),
+        });
+        self.parent = Some(shared.clone());
+        self.shared_scopes += 1;
+        shared
+    }
+
+    /// Move everything which `share` split off from this scope back into `root`.
+    pub fn unshare(&mut self) {
+        for _ in 0..std::mem::take(&mut self.shared_scopes) {
+            let Some(parent) = self.parent.take() else {
+                break;
+            };
+            // Functions which captured the scope still hold it, so it usually has to be copied.
+            let parent = Arc::try_unwrap(parent).unwrap_or_else(|parent| (*parent).clone());
+            for (name, value) in parent.root {
+                self.root.entry(name).or_insert(value);
+            }
+            self.parent = parent.parent;
+        }
+    }
+
+    /// Whether a name is declared in this scope, including the parts `share` split off.
+    fn declared_here(&self, key: &str) -> bool {
+        let mut scope = self;
+        for _ in 0..self.shared_scopes {
+            if scope.root.contains_key(key) {
+                return true;
+            }
+            let Some(parent) = scope.parent.as_deref() else {
+                return false;
+            };
+            scope = parent;
         }
+        scope.root.contains_key(key)
     }
 
     /// Add to the program memory.
+    /// Names only need to be unique within a scope, so e.g. a function parameter can shadow a
+    /// name declared outside the function.
     pub fn add(&mut self, key: &str, value: MemoryItem, source_range: SourceRange) -> Result<(), KclError> {
-        if self.root.contains_key(key) {
+        if self.declared_here(key) {
             return Err(KclError::ValueAlreadyDefined(KclErrorDetails {
                 message: format!("Cannot redefine {}", key),
                 source_ranges: vec![source_range],
+                ..Default::default()
             }));
         }
 
@@ -81,13 +190,21 @@ impl ProgramMemory {
 
     /// Get a value from the program memory.
     /// Return Err if not found.
+    /// Looks in this scope first, then each enclosing scope in turn.
     pub fn get(&self, key: &str, source_range: SourceRange) -> Result<&MemoryItem, KclError> {
-        self.root.get(key).ok_or_else(|| {
-            KclError::UndefinedValue(KclErrorDetails {
-                message: format!("memory item key `{}` is not defined", key),
-                source_ranges: vec![source_range],
-            })
-        })
+        let mut scope = Some(self);
+        while let Some(memory) = scope {
+            if let Some(item) = memory.root.get(key) {
+                return Ok(item);
+            }
+            scope = memory.parent.as_deref();
+        }
+
+        Err(KclError::UndefinedValue(KclErrorDetails {
+            message: format!("memory item key `{}` is not defined", key),
+            source_ranges: vec![source_range],
+            ..Default::default()
+        }))
     }
 }
 
@@ -121,6 +238,7 @@ impl ProgramReturn {
             ProgramReturn::Arguments => Err(KclError::Semantic(KclErrorDetails {
                 message: "Cannot get value from arguments".to_owned(),
                 source_ranges: self.clone().into(),
+                ..Default::default()
             })),
         }
     }
@@ -148,6 +266,12 @@ pub enum MemoryItem {
         #[serde(skip)]
         func: Option<MemoryFunction>,
         expression: Box<FunctionExpression>,
+        /// The name the function was declared with, so its body can call it recursively.
+        #[serde(skip)]
+        name: Option<String>,
+        /// The scope the function was declared in, which its body can read from.
+        #[serde(skip)]
+        memory: Option<Arc<ProgramMemory>>,
         #[serde(rename = "__meta")]
         meta: Vec<Metadata>,
     },
@@ -198,6 +322,24 @@ pub enum ExtrudeGroupSet {
     ExtrudeGroups(Vec<Box<ExtrudeGroup>>),
 }
 
+/// A solid, which can be combined with other solids.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema)]
+#[ts(export)]
+#[serde(tag = "type")]
+pub enum Solid {
+    ExtrudeGroup(Box<ExtrudeGroup>),
+    ImportedGeometry(Box<ImportedGeometry>),
+}
+
+impl Solid {
+    pub fn id(&self) -> uuid::Uuid {
+        match self {
+            Solid::ExtrudeGroup(e) => e.id,
+            Solid::ImportedGeometry(i) => i.id,
+        }
+    }
+}
+
 /// Data for an imported geometry.
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema)]
 #[ts(export)]
@@ -288,7 +430,7 @@ pub enum PlaneType {
     Custom,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema)]
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema)]
 #[ts(export)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub struct UserVal {
@@ -296,11 +438,16 @@ pub struct UserVal {
     pub value: serde_json::Value,
     #[serde(rename = "__meta")]
     pub meta: Vec<Metadata>,
+    /// What the value measures, if it's a number with a unit of measure, e.g. `10mm`.
+    /// Lengths are in the scene's units, and angles in degrees.
+    #[serde(skip)]
+    pub dimension: Option<Dimension>,
 }
 
 pub type MemoryFunction =
     fn(
         s: Vec<MemoryItem>,
+        named_args: Vec<(Identifier, MemoryItem)>,
         memory: ProgramMemory,
         expression: Box<FunctionExpression>,
         metadata: Vec<Metadata>,
@@ -310,6 +457,7 @@ pub type MemoryFunction =
 fn force_memory_function<
     F: Fn(
         Vec<MemoryItem>,
+        Vec<(Identifier, MemoryItem)>,
         ProgramMemory,
         Box<FunctionExpression>,
         Vec<Metadata>,
@@ -344,6 +492,14 @@ impl From<MemoryItem> for Vec<SourceRange> {
 }
 
 impl MemoryItem {
+    /// What the value measures, if it's a number with a unit of measure.
+    pub fn dimension(&self) -> Option<Dimension> {
+        match self {
+            MemoryItem::UserVal(user_val) => user_val.dimension,
+            _ => None,
+        }
+    }
+
     pub fn get_json_value(&self) -> Result<serde_json::Value, KclError> {
         if let MemoryItem::UserVal(user_val) = self {
             Ok(user_val.value.clone())
@@ -352,6 +508,7 @@ impl MemoryItem {
                 KclError::Semantic(KclErrorDetails {
                     message: format!("Cannot convert memory item to json value: {:?}", err),
                     source_ranges: self.clone().into(),
+                    ..Default::default()
                 })
             })
         }
@@ -365,6 +522,7 @@ impl MemoryItem {
             KclError::Type(KclErrorDetails {
                 message: format!("Failed to deserialize struct from JSON: {}", e),
                 source_ranges: self.clone().into(),
+                ..Default::default()
             })
         })
     }
@@ -386,6 +544,7 @@ impl MemoryItem {
                 KclError::Type(KclErrorDetails {
                     message: format!("Failed to deserialize struct from JSON: {}", e),
                     source_ranges: self.clone().into(),
+                    ..Default::default()
                 })
             })
             .map(Some)
@@ -393,25 +552,53 @@ impl MemoryItem {
 
     /// If this memory item is a function, call it with the given arguments, return its val as Ok.
     /// If it's not a function, return Err.
+    /// The function runs in a new scope inside the one it was declared in, where its own name
+    /// refers to the function itself so it can call itself recursively.
     pub async fn call_fn(
         &self,
         args: Vec<MemoryItem>,
-        memory: ProgramMemory,
         ctx: ExecutorContext,
     ) -> Result<Option<ProgramReturn>, KclError> {
-        let MemoryItem::Function { func, expression, meta } = &self else {
+        self.call_fn_with_named_args(args, Vec::new(), ctx).await
+    }
+
+    /// Like `call_fn`, but some of the arguments are passed by the name of their parameter.
+    pub async fn call_fn_with_named_args(
+        &self,
+        args: Vec<MemoryItem>,
+        named_args: Vec<(Identifier, MemoryItem)>,
+        ctx: ExecutorContext,
+    ) -> Result<Option<ProgramReturn>, KclError> {
+        let MemoryItem::Function {
+            func,
+            expression,
+            name,
+            memory,
+            meta,
+        } = &self
+        else {
             return Err(KclError::Semantic(KclErrorDetails {
                 message: "not a in memory function".to_string(),
                 source_ranges: vec![],
+                ..Default::default()
             }));
         };
         let Some(func) = func else {
             return Err(KclError::Semantic(KclErrorDetails {
                 message: format!("Not a function: {:?}", expression),
                 source_ranges: vec![],
+                ..Default::default()
             }));
         };
-        func(args, memory, expression.clone(), meta.clone(), ctx).await
+        ctx.check_interrupted(SourceRange([expression.start, expression.end]))?;
+        let mut fn_memory = match memory {
+            Some(memory) => ProgramMemory::new_scope(memory.clone()),
+            None => ProgramMemory::new(),
+        };
+        if let Some(name) = name {
+            fn_memory.root.insert(name.to_string(), self.clone());
+        }
+        func(args, named_args, fn_memory, expression.clone(), meta.clone(), ctx).await
     }
 }
 
@@ -488,6 +675,16 @@ pub struct GetTangentialInfoFromPathsResult {
 }
 
 impl SketchGroup {
+    /// Whether the sketch group ends where it started.
+    pub fn is_closed(&self) -> bool {
+        let Some(last) = self.value.last() else {
+            return false;
+        };
+        let end = last.get_base().to;
+        let start = self.start.from;
+        (end[0] - start[0]).abs() < CLOSED_TOLERANCE && (end[1] - start[1]).abs() < CLOSED_TOLERANCE
+    }
+
     pub fn get_path_by_id(&self, id: &uuid::Uuid) -> Option<&Path> {
         self.value.iter().find(|p| p.get_id() == *id)
     }
@@ -580,6 +777,9 @@ pub struct ExtrudeGroup {
     pub start_cap_id: Option<uuid::Uuid>,
     /// The id of the extrusion end cap
     pub end_cap_id: Option<uuid::Uuid>,
+    /// The ids of the helices made on the extrude group, oldest first.
+    #[serde(default)]
+    pub helix_ids: Vec<uuid::Uuid>,
     /// Metadata.
     #[serde(rename = "__meta")]
     pub meta: Vec<Metadata>,
@@ -940,6 +1140,13 @@ impl ExtrudeSurface {
         }
     }
 
+    pub fn get_face_id(&self) -> uuid::Uuid {
+        match self {
+            ExtrudeSurface::ExtrudePlane(ep) => ep.face_id,
+            ExtrudeSurface::ExtrudeArc(ea) => ea.face_id,
+        }
+    }
+
     pub fn get_name(&self) -> String {
         match self {
             ExtrudeSurface::ExtrudePlane(ep) => ep.name.to_string(),
@@ -991,6 +1198,254 @@ pub struct ExecutorContext {
     /// Mock mode is only for the modeling app when they just want to mock engine calls and not
     /// actually make them.
     pub is_mock: bool,
+    /// The KCL files imported while running the program.
+    pub modules: ModuleState,
+    /// The results of the last incremental run, see `run_incremental`.
+    pub cache: ExecutionCache,
+    /// Stops the run when cancelled.
+    pub cancel: CancellationToken,
+    /// Stops the run when it takes too long or sends too many commands.
+    pub budget: ExecutionBudget,
+    /// Measures how long each statement takes, if set.
+    pub profiler: Option<Profiler>,
+    /// Makes every ID the run needs.
+    pub id_generator: IdGenerator,
+    /// The warnings from the last run, whether it succeeded or not.
+    pub warnings: Warnings,
+}
+
+/// Tracks KCL files imported with `import`, so each one is only executed once per run,
+/// and so import cycles can be detected.
+#[derive(Debug, Clone, Default)]
+pub struct ModuleState {
+    /// The modules currently being executed, outermost first.
+    /// The last one is the module being executed right now, if any.
+    import_stack: Vec<std::path::PathBuf>,
+    /// The exported items of every module which finished executing during this run.
+    exports: Arc<std::sync::Mutex<HashMap<std::path::PathBuf, HashMap<String, MemoryItem>>>>,
+    /// The directory imports in the main program are relative to, if any.
+    project_directory: Option<std::path::PathBuf>,
+}
+
+impl ModuleState {
+    /// Resolve imports in the main program relative to `dir`, rather than like any other path.
+    pub fn set_project_directory(&mut self, dir: impl Into<std::path::PathBuf>) {
+        self.project_directory = Some(dir.into());
+    }
+
+    /// Resolve an import path, relative to the module that is importing it.
+    /// Imports in the main program are resolved relative to the project directory if there is
+    /// one, or else like any other path, e.g. `std::import::import`.
+    /// The result is normalized, so the same module always has the same path however it was
+    /// imported.
+    fn resolve(&self, path: &str) -> std::path::PathBuf {
+        let dir = match self.import_stack.last() {
+            Some(current) => current.parent(),
+            None => self.project_directory.as_deref(),
+        };
+        match dir {
+            Some(dir) => normalize_path(&dir.join(path)),
+            None => normalize_path(std::path::Path::new(path)),
+        }
+    }
+
+    fn cached_exports(&self, path: &std::path::Path) -> Option<HashMap<String, MemoryItem>> {
+        self.exports.lock().unwrap().get(path).cloned()
+    }
+
+    fn cache_exports(&self, path: std::path::PathBuf, exports: HashMap<String, MemoryItem>) {
+        self.exports.lock().unwrap().insert(path, exports);
+    }
+
+    fn clear(&self) {
+        self.exports.lock().unwrap().clear();
+    }
+}
+
+/// Remove `.` and `..` from a path, without looking at the filesystem.
+fn normalize_path(path: &std::path::Path) -> std::path::PathBuf {
+    use std::path::Component;
+
+    let mut normalized = std::path::PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if matches!(normalized.components().next_back(), Some(Component::Normal(_))) => {
+                normalized.pop();
+            }
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Lets whoever started a run stop it early. The executor checks it between statements, on every
+/// loop iteration and on every function call, so a run never stops half way through a statement.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<std::sync::atomic::AtomicBool>);
+
+impl CancellationToken {
+    /// Stop the run at the next chance it gets.
+    pub fn cancel(&self) {
+        self.0.store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(std::sync::atomic::Ordering::Relaxed)
+    }
+}
+
+/// Makes the IDs of the commands a run sends and the objects it creates. By default every ID is
+/// random. A seeded generator makes the same IDs in the same order every time instead, so the
+/// same program run with the same seed gives identical memory and commands.
+#[derive(Debug, Clone, Default)]
+pub struct IdGenerator(Option<Arc<std::sync::Mutex<SeededIds>>>);
+
+#[derive(Debug)]
+struct SeededIds {
+    seed: u64,
+    /// How many IDs have been made so far.
+    count: u64,
+}
+
+impl IdGenerator {
+    /// A generator whose IDs only depend on the seed and how many IDs it made before.
+    pub fn seeded(seed: u64) -> Self {
+        Self(Some(Arc::new(std::sync::Mutex::new(SeededIds { seed, count: 0 }))))
+    }
+
+    pub fn next_uuid(&self) -> uuid::Uuid {
+        use sha2::Digest;
+
+        let Some(seeded) = &self.0 else {
+            return uuid::Uuid::new_v4();
+        };
+        let mut seeded = seeded.lock().unwrap();
+        let hash = sha2::Sha256::new()
+            .chain_update(seeded.seed.to_le_bytes())
+            .chain_update(seeded.count.to_le_bytes())
+            .finalize();
+        seeded.count += 1;
+        let mut bytes = [0; 16];
+        bytes.copy_from_slice(&hash[..16]);
+        uuid::Builder::from_random_bytes(bytes).into_uuid()
+    }
+
+    /// A separate generator for work done at the same time as this generator's. A seeded
+    /// generator's fork is seeded from its seed and `key`, so its IDs don't depend on how the
+    /// concurrent work happens to interleave.
+    pub fn fork(&self, key: u64) -> Self {
+        use sha2::Digest;
+
+        let Some(seeded) = &self.0 else {
+            return Self::default();
+        };
+        let hash = sha2::Sha256::new()
+            .chain_update(seeded.lock().unwrap().seed.to_le_bytes())
+            .chain_update(b"fork")
+            .chain_update(key.to_le_bytes())
+            .finalize();
+        let mut seed = [0; 8];
+        seed.copy_from_slice(&hash[..8]);
+        Self::seeded(u64::from_le_bytes(seed))
+    }
+}
+
+/// Limits on how long a run may take and how many commands it may send to the engine. It's
+/// checked at the same points as the `CancellationToken`.
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionBudget {
+    /// The longest a run may take, or `None` for no limit.
+    pub max_duration: Option<std::time::Duration>,
+    /// The most commands a run may send to the engine, or `None` for no limit.
+    pub max_commands: Option<usize>,
+    /// How much of the budget the current run has used.
+    usage: Arc<std::sync::Mutex<BudgetUsage>>,
+}
+
+#[derive(Debug, Default)]
+struct BudgetUsage {
+    started: Option<chrono::DateTime<chrono::Utc>>,
+    commands: usize,
+}
+
+impl ExecutionBudget {
+    pub fn new(max_duration: Option<std::time::Duration>, max_commands: Option<usize>) -> Self {
+        Self {
+            max_duration,
+            max_commands,
+            usage: Default::default(),
+        }
+    }
+
+    /// Start counting for a new run.
+    fn start(&self) {
+        *self.usage.lock().unwrap() = BudgetUsage {
+            started: Some(chrono::Utc::now()),
+            commands: 0,
+        };
+    }
+
+    /// Count a command sent to the engine.
+    pub(crate) fn count_command(&self) {
+        self.usage.lock().unwrap().commands += 1;
+    }
+
+    /// Describe how the budget was exceeded, if it was.
+    fn exceeded(&self) -> Option<String> {
+        let usage = self.usage.lock().unwrap();
+        if let Some(max_commands) = self.max_commands {
+            if usage.commands > max_commands {
+                return Some(format!(
+                    "Execution stopped because it sent more than its limit of {} commands to the engine",
+                    max_commands
+                ));
+            }
+        }
+        if let (Some(max_duration), Some(started)) = (self.max_duration, usage.started) {
+            let elapsed = (chrono::Utc::now() - started).to_std().unwrap_or_default();
+            if elapsed > max_duration {
+                return Some(format!(
+                    "Execution stopped because it took longer than its time limit of {:?}",
+                    max_duration
+                ));
+            }
+        }
+        None
+    }
+}
+
+/// The warnings found by a run, shared by every clone of the executor context.
+#[derive(Debug, Clone, Default)]
+pub struct Warnings(Arc<std::sync::Mutex<Vec<KclWarning>>>);
+
+impl Warnings {
+    /// Add a warning, unless the same one was already added, e.g. by a loop.
+    pub fn add(&self, warning: KclWarning) {
+        let mut warnings = self.0.lock().unwrap();
+        if !warnings.contains(&warning) {
+            warnings.push(warning);
+        }
+    }
+
+    /// Every warning added so far, in the order they were found.
+    pub fn all(&self) -> Vec<KclWarning> {
+        self.0.lock().unwrap().clone()
+    }
+
+    /// How many warnings have been added so far.
+    pub(crate) fn len(&self) -> usize {
+        self.0.lock().unwrap().len()
+    }
+
+    /// The warnings added after there were `count` of them.
+    pub(crate) fn since(&self, count: usize) -> Vec<KclWarning> {
+        self.0.lock().unwrap().get(count..).unwrap_or_default().to_vec()
+    }
+
+    fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
 }
 
 /// The executor settings.
@@ -1044,34 +1499,70 @@ impl From<crate::settings::types::ModelingSettings> for ExecutorSettings {
     }
 }
 
+/// The outcome of executing a program incrementally.
+struct IncrementalRun {
+    statements: Vec<cache::CachedStatement>,
+    /// Whether each statement's result was reused from the last run.
+    reused: Vec<bool>,
+    /// The error which stopped the run, and the scene objects made by the statement which failed.
+    error: Option<(KclError, Vec<uuid::Uuid>)>,
+}
+
+/// An incremental run has to start over, because a statement which has to be executed again uses
+/// geometry from the reused statement at index `stale`, so that one has to be executed again too.
+struct RestartRun {
+    stale: usize,
+    /// The scene objects made before starting over, which are no longer used.
+    orphans: Vec<uuid::Uuid>,
+}
+
 impl ExecutorContext {
     /// Create a new default executor context.
     #[cfg(not(target_arch = "wasm32"))]
     pub async fn new(client: &kittycad::Client, settings: ExecutorSettings) -> Result<Self> {
-        let ws = client
-            .modeling()
-            .commands_ws(
-                None,
-                None,
-                if settings.enable_ssao {
-                    Some(kittycad::types::PostEffectType::Ssao)
-                } else {
-                    None
-                },
-                None,
-                None,
-                None,
-                Some(false),
-            )
-            .await?;
+        let engine = Self::connect_engine(client, &settings).await?;
+        Self::new_with_engine(engine, settings).await
+    }
+
+    /// Open a websocket connection to the engine, which reconnects by itself if it's lost.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn connect_engine(
+        client: &kittycad::Client,
+        settings: &ExecutorSettings,
+    ) -> Result<Arc<Box<dyn EngineManager>>> {
+        let client = client.clone();
+        let post_effect = if settings.enable_ssao {
+            Some(kittycad::types::PostEffectType::Ssao)
+        } else {
+            None
+        };
+        let connect: crate::engine::conn::Connector = Arc::new(move || {
+            let client = client.clone();
+            let post_effect = post_effect.clone();
+            Box::pin(async move {
+                let (ws, _headers) = client
+                    .modeling()
+                    .commands_ws(None, None, None, post_effect, None, None, None, None, None, Some(false))
+                    .await?;
+                Ok(ws)
+            })
+        });
+
+        Ok(Arc::new(Box::new(
+            crate::engine::conn::EngineConnection::new_with_reconnect(connect, Default::default()).await?,
+        )))
+    }
 
-        let engine: Arc<Box<dyn EngineManager>> =
-            Arc::new(Box::new(crate::engine::conn::EngineConnection::new(ws).await?));
+    /// Create a new default executor context, which sends its commands to the given engine
+    /// connection, e.g. one which records or replays a session.
+    pub async fn new_with_engine(engine: Arc<Box<dyn EngineManager>>, settings: ExecutorSettings) -> Result<Self> {
+        // Make IDs in the same sequence as the connection does.
+        let id_generator = engine.id_generator().clone();
 
         // Set the edge visibility.
         engine
             .send_modeling_cmd(
-                uuid::Uuid::new_v4(),
+                id_generator.next_uuid(),
                 SourceRange::default(),
                 kittycad::types::ModelingCmd::EdgeLinesVisible {
                     hidden: !settings.highlight_edges,
@@ -1085,6 +1576,13 @@ impl ExecutorContext {
             stdlib: Arc::new(StdLib::new()),
             settings,
             is_mock: false,
+            modules: Default::default(),
+            cache: Default::default(),
+            cancel: Default::default(),
+            budget: Default::default(),
+            profiler: None,
+            id_generator,
+            warnings: Default::default(),
         })
     }
 
@@ -1096,23 +1594,273 @@ impl ExecutorContext {
         program: crate::ast::types::Program,
         memory: Option<ProgramMemory>,
     ) -> Result<ProgramMemory, KclError> {
+        // Imported files might have changed since the last run, so execute them again.
+        self.modules.clear();
+        self.budget.start();
+        self.warnings.clear();
+
+        let mut memory = if let Some(memory) = memory {
+            memory.clone()
+        } else {
+            Default::default()
+        };
+
+        // Catch type errors before sending anything to the engine.
+        if let Some(err) = crate::typecheck::check_program(&program, &self.stdlib, &memory)
+            .into_iter()
+            .next()
+        {
+            return Err(err);
+        }
+
         // Before we even start executing the program, set the units.
         self.engine
             .send_modeling_cmd(
-                uuid::Uuid::new_v4(),
+                self.id_generator.next_uuid(),
                 SourceRange::default(),
                 kittycad::types::ModelingCmd::SetSceneUnits {
                     unit: self.settings.units.clone().into(),
                 },
             )
             .await?;
-        let mut memory = if let Some(memory) = memory {
-            memory.clone()
+        let end = SourceRange([program.end, program.end]);
+        let result = self
+            .inner_execute(program, &mut memory, crate::executor::BodyType::Root)
+            .await;
+        if let Err(KclError::Interrupted(_)) = result {
+            // Send what was made before the run stopped, rather than leaving it waiting in the
+            // batch for the next run.
+            self.flush_batch(end).await?;
+        }
+        let mut memory = result?;
+        memory.warnings = self.warnings.all();
+        Ok(memory)
+    }
+
+    /// Execute the program without sending anything to the engine.
+    /// Returns the memory, and the commands that would have been sent to the engine, in order.
+    pub async fn run_plan_only(
+        &self,
+        program: crate::ast::types::Program,
+        memory: Option<ProgramMemory>,
+    ) -> Result<(ProgramMemory, Vec<(kittycad::types::ModelingCmd, SourceRange)>), KclError> {
+        let engine = crate::engine::conn_plan::EngineConnection::new()
+            .await
+            .map_err(|e| {
+                KclError::Internal(KclErrorDetails {
+                    message: format!("Failed to create the plan-only engine connection: {e}"),
+                    source_ranges: vec![SourceRange::default()],
+                    ..Default::default()
+                })
+            })?
+            // The commands it makes by itself are part of the plan, so they use this run's IDs.
+            .with_id_generator(self.id_generator.clone());
+        let ctx = ExecutorContext {
+            engine: Arc::new(Box::new(engine.clone())),
+            // Nothing comes back from the engine, so fill in made-up IDs where it would.
+            is_mock: true,
+            ..self.clone()
+        };
+        let memory = ctx.run(program, memory).await?;
+        // Include the commands still waiting to be sent in a batch.
+        ctx.flush_batch(SourceRange::default()).await?;
+
+        Ok((memory, engine.take_commands()))
+    }
+
+    /// Execute a program which was probably executed before with small edits, e.g. by the LSP on
+    /// every keystroke. Unchanged statements whose inputs are unchanged reuse their results from
+    /// the last run, and their geometry stays in the scene. Only the statements which changed,
+    /// and the ones which depend on them, are executed again, after their old geometry is removed.
+    ///
+    /// The first run (and the first one after the units change) clears the scene. If something
+    /// else changes the scene in between runs, call `ExecutionCache::clear` first.
+    pub async fn run_incremental(&self, program: crate::ast::types::Program) -> Result<ProgramMemory, KclError> {
+        // Imported files might have changed since the last run, so execute them again.
+        self.modules.clear();
+        self.budget.start();
+        self.warnings.clear();
+
+        // Catch type errors before sending anything to the engine.
+        if let Some(err) = crate::typecheck::check_program(&program, &self.stdlib, &Default::default())
+            .into_iter()
+            .next()
+        {
+            return Err(err);
+        }
+
+        let (previous, mut orphans) = match self.cache.begin(&self.settings.units) {
+            Some(cached) => cached,
+            None => {
+                self.engine.clear_scene(SourceRange::default()).await?;
+                self.engine
+                    .send_modeling_cmd(
+                        self.id_generator.next_uuid(),
+                        SourceRange::default(),
+                        kittycad::types::ModelingCmd::SetSceneUnits {
+                            unit: self.settings.units.clone().into(),
+                        },
+                    )
+                    .await?;
+                (Vec::new(), Vec::new())
+            }
+        };
+
+        // Unchanged statements which have to be executed again anyway, because a statement which
+        // was executed again uses their geometry.
+        let mut stale = std::collections::HashSet::new();
+        let mut memory = ProgramMemory::default();
+        let IncrementalRun {
+            statements,
+            reused,
+            error,
+        } = loop {
+            match self
+                .execute_incrementally(&program, &mut memory, &previous, &stale)
+                .await
+            {
+                Ok(run) => break run,
+                Err(restart) => {
+                    stale.insert(restart.stale);
+                    orphans.extend(restart.orphans);
+                }
+            }
+        };
+        memory.unshare();
+
+        // Remove the geometry from the last run which wasn't reused.
+        let mut removed = std::mem::take(&mut orphans);
+        for (index, statement) in previous.into_iter().enumerate() {
+            if !reused.get(index).copied().unwrap_or_default() {
+                removed.extend(statement.objects);
+            }
+        }
+        let cleanup = async {
+            if !removed.is_empty() {
+                self.engine
+                    .send_modeling_cmd(
+                        self.id_generator.next_uuid(),
+                        SourceRange::default(),
+                        kittycad::types::ModelingCmd::RemoveSceneObjects { object_ids: removed },
+                    )
+                    .await?;
+            }
+            self.flush_batch(SourceRange([program.end, program.end])).await
+        };
+
+        match error {
+            None => {
+                cleanup.await?;
+                self.cache.finish(self.settings.units.clone(), statements, orphans);
+                memory.warnings = self.warnings.all();
+                Ok(memory)
+            }
+            Some((err, created)) => {
+                // The statement's error is more useful than any error from cleaning up.
+                let _ = cleanup.await;
+                orphans.extend(created);
+                self.cache.finish(self.settings.units.clone(), statements, orphans);
+                Err(err)
+            }
+        }
+    }
+
+    /// Execute each statement of a program, unless its result from the last run can be reused.
+    /// Returns an error if the run has to start over with more statements executed again.
+    async fn execute_incrementally(
+        &self,
+        program: &crate::ast::types::Program,
+        memory: &mut ProgramMemory,
+        previous: &[cache::CachedStatement],
+        stale: &std::collections::HashSet<usize>,
+    ) -> Result<IncrementalRun, RestartRun> {
+        let pipe_info = PipeInfo::default();
+        *memory = ProgramMemory::default();
+        self.warnings.clear();
+        let mut statements: Vec<cache::CachedStatement> = Vec::with_capacity(program.body.len());
+        let mut reused = Vec::with_capacity(program.body.len());
+
+        for (index, item) in program.body.iter().enumerate() {
+            let inputs = cache::inputs(item, memory);
+            if let Some(cached) = previous
+                .get(index)
+                .filter(|cached| !stale.contains(&index) && cached.is_valid_for(item, &inputs))
+            {
+                for (name, value) in &cached.outputs {
+                    memory.root.insert(name.clone(), value.clone());
+                }
+                for warning in &cached.warnings {
+                    self.warnings.add(warning.clone());
+                }
+                statements.push(cached.clone());
+                reused.push(true);
+                continue;
+            }
+
+            if let Some(owner) = cache::reused_owner(&inputs, &statements, &reused) {
+                // Everything made so far by this attempt will be made again.
+                let orphans = statements
+                    .into_iter()
+                    .zip(reused)
+                    .filter(|(_, reused)| !reused)
+                    .flat_map(|(statement, _)| statement.objects)
+                    .collect();
+                return Err(RestartRun { stale: owner, orphans });
+            }
+
+            let declared_before: std::collections::HashSet<String> = memory.root.keys().cloned().collect();
+            let warned_before = self.warnings.len();
+            let result = match self.check_interrupted(item.into()) {
+                Ok(()) => self.execute_statement(item, memory, &pipe_info).await,
+                Err(err) => Err(err),
+            };
+            let objects = self.cache.take_created();
+            if let Err(err) = result {
+                return Ok(IncrementalRun {
+                    statements,
+                    reused,
+                    error: Some((err, objects)),
+                });
+            }
+
+            let outputs = memory
+                .root
+                .iter()
+                .filter(|(name, _)| !declared_before.contains(*name))
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect();
+            statements.push(cache::CachedStatement {
+                item: item.clone(),
+                inputs,
+                outputs,
+                objects,
+                warnings: self.warnings.since(warned_before),
+            });
+            reused.push(false);
+        }
+
+        Ok(IncrementalRun {
+            statements,
+            reused,
+            error: None,
+        })
+    }
+
+    /// Stop the run if it was cancelled or went over its budget, blaming the statement (or
+    /// function) at the given source range.
+    pub(crate) fn check_interrupted(&self, source_range: SourceRange) -> Result<(), KclError> {
+        let message = if self.cancel.is_cancelled() {
+            "Execution was cancelled".to_owned()
+        } else if let Some(message) = self.budget.exceeded() {
+            message
         } else {
-            Default::default()
+            return Ok(());
         };
-        self.inner_execute(program, &mut memory, crate::executor::BodyType::Root)
-            .await
+        Err(KclError::Interrupted(KclErrorDetails {
+            message,
+            source_ranges: vec![source_range],
+            ..Default::default()
+        }))
     }
 
     /// Execute an AST's program.
@@ -1121,217 +1869,593 @@ impl ExecutorContext {
         &self,
         program: crate::ast::types::Program,
         memory: &mut ProgramMemory,
-        _body_type: BodyType,
+        body_type: BodyType,
     ) -> Result<ProgramMemory, KclError> {
         let pipe_info = PipeInfo::default();
 
-        // Iterate over the body of the program.
-        for statement in &program.body {
-            match statement {
-                BodyItem::ExpressionStatement(expression_statement) => {
-                    if let Value::PipeExpression(pipe_expr) = &expression_statement.expression {
-                        pipe_expr.get_result(memory, &pipe_info, self).await?;
-                    } else if let Value::CallExpression(call_expr) = &expression_statement.expression {
-                        let fn_name = call_expr.callee.name.to_string();
-                        let mut args: Vec<MemoryItem> = Vec::new();
-                        for arg in &call_expr.arguments {
-                            match arg {
-                                Value::Literal(literal) => args.push(literal.into()),
-                                Value::Identifier(identifier) => {
-                                    let memory_item = memory.get(&identifier.name, identifier.into())?;
-                                    args.push(memory_item.clone());
-                                }
-                                Value::CallExpression(call_expr) => {
-                                    let result = call_expr.execute(memory, &pipe_info, self).await?;
-                                    args.push(result);
-                                }
-                                Value::BinaryExpression(binary_expression) => {
-                                    let result = binary_expression.get_result(memory, &pipe_info, self).await?;
-                                    args.push(result);
-                                }
-                                Value::UnaryExpression(unary_expression) => {
-                                    let result = unary_expression.get_result(memory, &pipe_info, self).await?;
-                                    args.push(result);
-                                }
-                                Value::ObjectExpression(object_expression) => {
-                                    let result = object_expression.execute(memory, &pipe_info, self).await?;
-                                    args.push(result);
-                                }
-                                Value::ArrayExpression(array_expression) => {
-                                    let result = array_expression.execute(memory, &pipe_info, self).await?;
-                                    args.push(result);
-                                }
-                                // We do nothing for the rest.
-                                _ => (),
-                            }
-                        }
-                        match self.stdlib.get_either(&call_expr.callee.name) {
-                            FunctionKind::Core(func) => {
-                                let args = crate::std::Args::new(args, call_expr.into(), self.clone());
-                                let result = func.std_lib_fn()(args).await?;
-                                memory.return_ = Some(ProgramReturn::Value(result));
-                            }
-                            FunctionKind::Std(func) => {
-                                let mut newmem = memory.clone();
-                                let result = self
-                                    .inner_execute(func.program().to_owned(), &mut newmem, BodyType::Block)
-                                    .await?;
-                                memory.return_ = result.return_;
-                            }
-                            FunctionKind::UserDefined => {
-                                if let Some(func) = memory.clone().root.get(&fn_name) {
-                                    let result = func.call_fn(args.clone(), memory.clone(), self.clone()).await?;
-
-                                    memory.return_ = result;
-                                } else {
-                                    return Err(KclError::Semantic(KclErrorDetails {
-                                        message: format!("No such name {} defined", fn_name),
-                                        source_ranges: vec![call_expr.into()],
-                                    }));
-                                }
-                            }
-                        }
+        // Mock runs don't wait for the engine, so there's nothing to gain from executing
+        // independent statements at the same time.
+        let groups = if body_type == BodyType::Root && !self.is_mock && self.engine.supports_concurrency() {
+            concurrent::independent_groups(&program.body)
+        } else {
+            Vec::new()
+        };
+        if groups.len() > 1 {
+            self.execute_groups(&program, memory, groups).await?;
+        } else {
+            // Iterate over the body of the program.
+            for statement in &program.body {
+                self.check_interrupted(statement.into())?;
+                self.execute_statement(statement, memory, &pipe_info).await?;
+            }
+        }
+
+        // Flush the batch queue.
+        self.flush_batch(SourceRange([program.end, program.end])).await?;
+
+        if body_type == BodyType::Root {
+            memory.unshare();
+        }
+        Ok(memory.clone())
+    }
+
+    /// Execute groups of top-level statements which don't depend on each other at the same
+    /// time, each in its own copy of `memory`, then add everything they declared to `memory`.
+    /// If any group fails, the error from the earliest statement is returned.
+    async fn execute_groups(
+        &self,
+        program: &crate::ast::types::Program,
+        memory: &mut ProgramMemory,
+        groups: Vec<Vec<usize>>,
+    ) -> Result<(), KclError> {
+        let mut results = Vec::with_capacity(groups.len());
+        for group in groups {
+            let statements: Vec<(usize, BodyItem)> = group
+                .iter()
+                .map(|index| (*index, program.body[*index].clone()))
+                .collect();
+            let mut ctx = self.clone();
+            ctx.id_generator = self.id_generator.fork(group[0] as u64);
+            let mut group_memory = memory.clone();
+            let (tx, rx) = futures::channel::oneshot::channel();
+            crate::thread::JoinHandle::new(async move {
+                let result = ctx
+                    .execute_group(&statements, &mut group_memory)
+                    .await
+                    .map(|returned| (group_memory, returned));
+                // The receiver is only gone if the run was dropped, so no one wants the result.
+                let _ = tx.send(result);
+            });
+            results.push(rx);
+        }
+
+        let mut finished = Vec::with_capacity(results.len());
+        let mut first_error: Option<(usize, KclError)> = None;
+        for result in results {
+            match result.await {
+                Ok(Ok(group)) => finished.push(group),
+                Ok(Err((index, err))) => {
+                    if first_error.as_ref().map_or(true, |(first, _)| index < *first) {
+                        first_error = Some((index, err));
                     }
                 }
-                BodyItem::VariableDeclaration(variable_declaration) => {
-                    for declaration in &variable_declaration.declarations {
-                        let var_name = declaration.id.name.to_string();
-                        let source_range: SourceRange = declaration.init.clone().into();
-                        let metadata = Metadata { source_range };
-
-                        match &declaration.init {
-                            Value::None(none) => {
-                                memory.add(&var_name, none.into(), source_range)?;
-                            }
-                            Value::Literal(literal) => {
-                                memory.add(&var_name, literal.into(), source_range)?;
-                            }
-                            Value::Identifier(identifier) => {
-                                let value = memory.get(&identifier.name, identifier.into())?;
-                                memory.add(&var_name, value.clone(), source_range)?;
-                            }
-                            Value::BinaryExpression(binary_expression) => {
-                                let result = binary_expression.get_result(memory, &pipe_info, self).await?;
-                                memory.add(&var_name, result, source_range)?;
-                            }
-                            Value::FunctionExpression(function_expression) => {
-                                let mem_func = force_memory_function(
-                                    |args: Vec<MemoryItem>,
-                                     memory: ProgramMemory,
-                                     function_expression: Box<FunctionExpression>,
-                                     _metadata: Vec<Metadata>,
-                                     ctx: ExecutorContext| {
-                                        Box::pin(async move {
-                                            let mut fn_memory =
-                                                assign_args_to_params(&function_expression, args, memory.clone())?;
-
-                                            let result = ctx
-                                                .inner_execute(
-                                                    function_expression.body.clone(),
-                                                    &mut fn_memory,
-                                                    BodyType::Block,
-                                                )
-                                                .await?;
+                Err(_) => {
+                    return Err(KclError::Internal(KclErrorDetails {
+                        message: "A part of the program executed concurrently stopped without a result".to_owned(),
+                        source_ranges: vec![SourceRange([program.start, program.end])],
+                        ..Default::default()
+                    }));
+                }
+            }
+        }
+        if let Some((_, err)) = first_error {
+            return Err(err);
+        }
 
-                                            Ok(result.return_)
-                                        })
-                                    },
-                                );
-                                memory.add(
-                                    &var_name,
-                                    MemoryItem::Function {
-                                        expression: function_expression.clone(),
-                                        meta: vec![metadata],
-                                        func: Some(mem_func),
-                                    },
-                                    source_range,
-                                )?;
+        // Keep the return value of the last statement which set one, as if the statements had
+        // been executed in order.
+        finished.sort_by_key(|(_, returned)| *returned);
+        for (mut group_memory, returned) in finished {
+            group_memory.unshare();
+            for (name, value) in group_memory.root {
+                memory.root.entry(name).or_insert(value);
+            }
+            if returned.is_some() {
+                memory.return_ = group_memory.return_;
+            }
+        }
+        Ok(())
+    }
+
+    /// Execute some of a program's top-level statements in order. Returns the index of the last
+    /// statement which set `memory.return_`, or the index of the statement which failed.
+    async fn execute_group(
+        &self,
+        statements: &[(usize, BodyItem)],
+        memory: &mut ProgramMemory,
+    ) -> Result<Option<usize>, (usize, KclError)> {
+        let pipe_info = PipeInfo::default();
+        let mut returned = None;
+        for (index, statement) in statements {
+            let previous = memory.return_.take();
+            let result = match self.check_interrupted(statement.into()) {
+                Ok(()) => self.execute_statement(statement, memory, &pipe_info).await,
+                Err(err) => Err(err),
+            };
+            result.map_err(|err| (*index, err))?;
+            if memory.return_.is_some() {
+                returned = Some(*index);
+            } else {
+                memory.return_ = previous;
+            }
+        }
+        Ok(returned)
+    }
+
+    /// Send the commands waiting in the batch to the engine, and wait for it to answer.
+    pub(crate) async fn flush_batch(
+        &self,
+        source_range: SourceRange,
+    ) -> Result<kittycad::types::OkWebSocketResponseData, KclError> {
+        let commands = self.engine.batch().lock().unwrap().len();
+        let Some(profiler) = self.profiler.as_ref().filter(|_| commands > 0) else {
+            return self.engine.flush_batch(source_range).await;
+        };
+        let start = profiler.start();
+        let result = self.engine.flush_batch(source_range).await;
+        profiler.end_flush(start, source_range, commands);
+        result
+    }
+
+    /// Execute one statement of a program's body, timing it if there's a profiler.
+    async fn execute_statement(
+        &self,
+        statement: &BodyItem,
+        memory: &mut ProgramMemory,
+        pipe_info: &PipeInfo,
+    ) -> Result<(), KclError> {
+        let Some(profiler) = &self.profiler else {
+            return self.inner_execute_statement(statement, memory, pipe_info).await;
+        };
+        let start = profiler.start();
+        let result = self.inner_execute_statement(statement, memory, pipe_info).await;
+        profiler.end_statement(start, statement.into());
+        result
+    }
+
+    async fn inner_execute_statement(
+        &self,
+        statement: &BodyItem,
+        memory: &mut ProgramMemory,
+        pipe_info: &PipeInfo,
+    ) -> Result<(), KclError> {
+        match statement {
+            BodyItem::ExpressionStatement(expression_statement) => {
+                if let Value::PipeExpression(pipe_expr) = &expression_statement.expression {
+                    pipe_expr.get_result(memory, pipe_info, self).await?;
+                } else if let Value::IfExpression(if_expr) = &expression_statement.expression {
+                    if_expr.get_result(memory, pipe_info, self).await?;
+                } else if let Value::CallExpression(call_expr) = &expression_statement.expression {
+                    let fn_name = call_expr.callee.name.to_string();
+                    let mut args: Vec<MemoryItem> = Vec::new();
+                    for arg in &call_expr.arguments {
+                        match arg {
+                            Value::Literal(literal) => args.push(literal.get_result(self)),
+                            Value::Identifier(identifier) => {
+                                let memory_item = memory.get(&identifier.name, identifier.into())?;
+                                args.push(memory_item.clone());
+                            }
+                            Value::CallExpression(call_expr) => {
+                                let result = call_expr.execute(memory, pipe_info, self).await?;
+                                args.push(result);
                             }
-                            Value::CallExpression(call_expression) => {
-                                let result = call_expression.execute(memory, &pipe_info, self).await?;
-                                memory.add(&var_name, result, source_range)?;
+                            Value::BinaryExpression(binary_expression) => {
+                                let result = binary_expression.get_result(memory, pipe_info, self).await?;
+                                args.push(result);
                             }
-                            Value::PipeExpression(pipe_expression) => {
-                                let result = pipe_expression.get_result(memory, &pipe_info, self).await?;
-                                memory.add(&var_name, result, source_range)?;
+                            Value::UnaryExpression(unary_expression) => {
+                                let result = unary_expression.get_result(memory, pipe_info, self).await?;
+                                args.push(result);
                             }
-                            Value::PipeSubstitution(pipe_substitution) => {
-                                return Err(KclError::Semantic(KclErrorDetails {
-                                    message: format!(
-                                        "pipe substitution not implemented for declaration of variable {}",
-                                        var_name
-                                    ),
-                                    source_ranges: vec![pipe_substitution.into()],
-                                }));
+                            Value::ObjectExpression(object_expression) => {
+                                let result = object_expression.execute(memory, pipe_info, self).await?;
+                                args.push(result);
                             }
                             Value::ArrayExpression(array_expression) => {
-                                let result = array_expression.execute(memory, &pipe_info, self).await?;
-                                memory.add(&var_name, result, source_range)?;
+                                let result = array_expression.execute(memory, pipe_info, self).await?;
+                                args.push(result);
                             }
-                            Value::ObjectExpression(object_expression) => {
-                                let result = object_expression.execute(memory, &pipe_info, self).await?;
-                                memory.add(&var_name, result, source_range)?;
+                            // We do nothing for the rest.
+                            _ => (),
+                        }
+                    }
+                    let mut named_args = Vec::with_capacity(call_expr.named_arguments.len());
+                    for arg in &call_expr.named_arguments {
+                        let result = self.execute_value(&arg.value, memory, pipe_info).await?;
+                        named_args.push((arg.name.clone(), result));
+                    }
+                    match self.stdlib.get_either(&call_expr.callee.name) {
+                        FunctionKind::Core(func) => {
+                            if !named_args.is_empty() {
+                                let params: Vec<Parameter> = func.args().iter().map(Parameter::from).collect();
+                                args = order_named_args(&params, args, named_args, call_expr.into())?;
                             }
-                            Value::MemberExpression(member_expression) => {
-                                let result = member_expression.get_result(memory)?;
-                                memory.add(&var_name, result, source_range)?;
+                            if func.deprecated() {
+                                self.warnings.add(KclWarning::new(
+                                    call_expr.into(),
+                                    format!("`{}` is deprecated", func.name()),
+                                ));
                             }
-                            Value::UnaryExpression(unary_expression) => {
-                                let result = unary_expression.get_result(memory, &pipe_info, self).await?;
-                                memory.add(&var_name, result, source_range)?;
+                            let args = crate::std::Args::new(args, call_expr.into(), self.clone());
+                            let result = func.std_lib_fn()(args).await?;
+                            memory.return_ = Some(ProgramReturn::Value(result));
+                        }
+                        FunctionKind::Std(func) => {
+                            let mut newmem = memory.clone();
+                            let result = self
+                                .inner_execute(func.program().to_owned(), &mut newmem, BodyType::Block)
+                                .await?;
+                            memory.return_ = result.return_;
+                        }
+                        FunctionKind::UserDefined => {
+                            if let Ok(func) = memory.get(&fn_name, call_expr.into()) {
+                                let result = func
+                                    .call_fn_with_named_args(args.clone(), named_args, self.clone())
+                                    .await
+                                    .map_err(|e| e.add_stack_frame(&fn_name, call_expr.into()))?;
+
+                                memory.return_ = result;
+                            } else {
+                                return Err(KclError::Semantic(KclErrorDetails {
+                                    message: format!("No such name {} defined", fn_name),
+                                    source_ranges: vec![call_expr.into()],
+                                    ..Default::default()
+                                }));
                             }
                         }
                     }
                 }
-                BodyItem::ReturnStatement(return_statement) => match &return_statement.argument {
-                    Value::BinaryExpression(bin_expr) => {
-                        let result = bin_expr.get_result(memory, &pipe_info, self).await?;
-                        memory.return_ = Some(ProgramReturn::Value(result));
-                    }
-                    Value::UnaryExpression(unary_expr) => {
-                        let result = unary_expr.get_result(memory, &pipe_info, self).await?;
-                        memory.return_ = Some(ProgramReturn::Value(result));
-                    }
-                    Value::Identifier(identifier) => {
-                        let value = memory.get(&identifier.name, identifier.into())?.clone();
-                        memory.return_ = Some(ProgramReturn::Value(value));
-                    }
-                    Value::Literal(literal) => {
-                        memory.return_ = Some(ProgramReturn::Value(literal.into()));
-                    }
-                    Value::ArrayExpression(array_expr) => {
-                        let result = array_expr.execute(memory, &pipe_info, self).await?;
-                        memory.return_ = Some(ProgramReturn::Value(result));
-                    }
-                    Value::ObjectExpression(obj_expr) => {
-                        let result = obj_expr.execute(memory, &pipe_info, self).await?;
-                        memory.return_ = Some(ProgramReturn::Value(result));
-                    }
-                    Value::CallExpression(call_expr) => {
-                        let result = call_expr.execute(memory, &pipe_info, self).await?;
-                        memory.return_ = Some(ProgramReturn::Value(result));
-                    }
-                    Value::MemberExpression(member_expr) => {
-                        let result = member_expr.get_result(memory)?;
-                        memory.return_ = Some(ProgramReturn::Value(result));
-                    }
-                    Value::PipeExpression(pipe_expr) => {
-                        let result = pipe_expr.get_result(memory, &pipe_info, self).await?;
-                        memory.return_ = Some(ProgramReturn::Value(result));
-                    }
-                    Value::PipeSubstitution(_) => {}
-                    Value::FunctionExpression(_) => {}
-                    Value::None(none) => {
-                        memory.return_ = Some(ProgramReturn::Value(MemoryItem::from(none)));
+            }
+            BodyItem::VariableDeclaration(variable_declaration) => {
+                for declaration in &variable_declaration.declarations {
+                    let BindingPattern::Identifier(id) = &declaration.id else {
+                        let value = self.execute_value(&declaration.init, memory, pipe_info).await?;
+                        destructure(&declaration.id, value, memory)?;
+                        continue;
+                    };
+                    let var_name = id.name.to_string();
+                    let source_range: SourceRange = declaration.init.clone().into();
+                    let metadata = Metadata { source_range };
+
+                    match &declaration.init {
+                        Value::None(none) => {
+                            memory.add(&var_name, none.into(), source_range)?;
+                        }
+                        Value::Literal(literal) => {
+                            memory.add(&var_name, literal.get_result(self), source_range)?;
+                        }
+                        Value::Identifier(identifier) => {
+                            let value = memory.get(&identifier.name, identifier.into())?;
+                            memory.add(&var_name, value.clone(), source_range)?;
+                        }
+                        Value::BinaryExpression(binary_expression) => {
+                            let result = binary_expression.get_result(memory, pipe_info, self).await?;
+                            memory.add(&var_name, result, source_range)?;
+                        }
+                        Value::FunctionExpression(function_expression) => {
+                            let mem_func = force_memory_function(
+                                |args: Vec<MemoryItem>,
+                                 named_args: Vec<(Identifier, MemoryItem)>,
+                                 memory: ProgramMemory,
+                                 function_expression: Box<FunctionExpression>,
+                                 _metadata: Vec<Metadata>,
+                                 ctx: ExecutorContext| {
+                                    Box::pin(async move {
+                                        let mut fn_memory =
+                                            assign_args_to_params(&function_expression, args, named_args, memory)?;
+
+                                        // Any parameter the caller left out which has a default
+                                        // gets it now, so defaults can use the other parameters.
+                                        for param in &function_expression.params {
+                                            let Some(default_value) = &param.default_value else {
+                                                continue;
+                                            };
+                                            if fn_memory.root.contains_key(&param.identifier.name) {
+                                                continue;
+                                            }
+                                            let value = ctx
+                                                .execute_value(default_value, &mut fn_memory, &PipeInfo::new())
+                                                .await?;
+                                            fn_memory.add(&param.identifier.name, value, (&param.identifier).into())?;
+                                        }
+
+                                        let result = ctx
+                                            .inner_execute(
+                                                function_expression.body.clone(),
+                                                &mut fn_memory,
+                                                BodyType::Block,
+                                            )
+                                            .await?;
+
+                                        Ok(result.return_)
+                                    })
+                                },
+                            );
+                            // Capture everything declared so far, so the function always sees
+                            // the names it was declared alongside.
+                            let scope = memory.share();
+                            memory.add(
+                                &var_name,
+                                MemoryItem::Function {
+                                    expression: function_expression.clone(),
+                                    name: Some(var_name.clone()),
+                                    memory: Some(scope),
+                                    meta: vec![metadata],
+                                    func: Some(mem_func),
+                                },
+                                source_range,
+                            )?;
+                        }
+                        Value::CallExpression(call_expression) => {
+                            let result = call_expression.execute(memory, pipe_info, self).await?;
+                            memory.add(&var_name, result, source_range)?;
+                        }
+                        Value::PipeExpression(pipe_expression) => {
+                            let result = pipe_expression.get_result(memory, pipe_info, self).await?;
+                            memory.add(&var_name, result, source_range)?;
+                        }
+                        Value::PipeSubstitution(pipe_substitution) => {
+                            return Err(KclError::Semantic(KclErrorDetails {
+                                message: format!(
+                                    "pipe substitution not implemented for declaration of variable {}",
+                                    var_name
+                                ),
+                                source_ranges: vec![pipe_substitution.into()],
+                                ..Default::default()
+                            }));
+                        }
+                        Value::ArrayExpression(array_expression) => {
+                            let result = array_expression.execute(memory, pipe_info, self).await?;
+                            memory.add(&var_name, result, source_range)?;
+                        }
+                        Value::ObjectExpression(object_expression) => {
+                            let result = object_expression.execute(memory, pipe_info, self).await?;
+                            memory.add(&var_name, result, source_range)?;
+                        }
+                        Value::MemberExpression(member_expression) => {
+                            let result = member_expression.get_result(memory)?;
+                            memory.add(&var_name, result, source_range)?;
+                        }
+                        Value::UnaryExpression(unary_expression) => {
+                            let result = unary_expression.get_result(memory, pipe_info, self).await?;
+                            memory.add(&var_name, result, source_range)?;
+                        }
+                        Value::IfExpression(if_expression) => {
+                            let result = if_expression.get_result(memory, pipe_info, self).await?;
+                            memory.add(&var_name, result, source_range)?;
+                        }
                     }
-                },
+                }
+            }
+            BodyItem::ReturnStatement(return_statement) => match &return_statement.argument {
+                Value::BinaryExpression(bin_expr) => {
+                    let result = bin_expr.get_result(memory, pipe_info, self).await?;
+                    memory.return_ = Some(ProgramReturn::Value(result));
+                }
+                Value::UnaryExpression(unary_expr) => {
+                    let result = unary_expr.get_result(memory, pipe_info, self).await?;
+                    memory.return_ = Some(ProgramReturn::Value(result));
+                }
+                Value::Identifier(identifier) => {
+                    let value = memory.get(&identifier.name, identifier.into())?.clone();
+                    memory.return_ = Some(ProgramReturn::Value(value));
+                }
+                Value::Literal(literal) => {
+                    memory.return_ = Some(ProgramReturn::Value(literal.get_result(self)));
+                }
+                Value::ArrayExpression(array_expr) => {
+                    let result = array_expr.execute(memory, pipe_info, self).await?;
+                    memory.return_ = Some(ProgramReturn::Value(result));
+                }
+                Value::ObjectExpression(obj_expr) => {
+                    let result = obj_expr.execute(memory, pipe_info, self).await?;
+                    memory.return_ = Some(ProgramReturn::Value(result));
+                }
+                Value::CallExpression(call_expr) => {
+                    let result = call_expr.execute(memory, pipe_info, self).await?;
+                    memory.return_ = Some(ProgramReturn::Value(result));
+                }
+                Value::MemberExpression(member_expr) => {
+                    let result = member_expr.get_result(memory)?;
+                    memory.return_ = Some(ProgramReturn::Value(result));
+                }
+                Value::PipeExpression(pipe_expr) => {
+                    let result = pipe_expr.get_result(memory, pipe_info, self).await?;
+                    memory.return_ = Some(ProgramReturn::Value(result));
+                }
+                Value::IfExpression(if_expr) => {
+                    let result = if_expr.get_result(memory, pipe_info, self).await?;
+                    memory.return_ = Some(ProgramReturn::Value(result));
+                }
+                Value::PipeSubstitution(_) => {}
+                Value::FunctionExpression(_) => {}
+                Value::None(none) => {
+                    memory.return_ = Some(ProgramReturn::Value(MemoryItem::from(none)));
+                }
+            },
+            BodyItem::ForStatement(for_statement) => {
+                for_statement.execute(memory, pipe_info, self).await?;
+            }
+            BodyItem::ImportStatement(import_statement) => {
+                self.import_module(import_statement, memory).await?;
             }
         }
 
-        // Flush the batch queue.
-        self.engine.flush_batch(SourceRange([program.end, program.end])).await?;
+        Ok(())
+    }
 
-        Ok(memory.clone())
+    /// Execute the KCL file an import statement refers to (unless it already ran),
+    /// then bind the items it imports.
+    async fn import_module(&self, import: &ImportStatement, memory: &mut ProgramMemory) -> Result<(), KclError> {
+        let source_range: SourceRange = import.into();
+        let path = self.modules.resolve(&import.path);
+        if path.extension().and_then(|ext| ext.to_str()) != Some("kcl") {
+            return Err(KclError::Semantic(KclErrorDetails {
+                message: format!(
+                    "You can only `import` from KCL files, but `{}` is not a .kcl file. To load other CAD files, use the `import` function",
+                    import.path
+                ),
+                source_ranges: vec![source_range],
+                ..Default::default()
+            }));
+        }
+        if self.modules.import_stack.contains(&path) {
+            let cycle = self
+                .modules
+                .import_stack
+                .iter()
+                .skip_while(|module| **module != path)
+                .chain(std::iter::once(&path))
+                .map(|module| module.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            return Err(KclError::Semantic(KclErrorDetails {
+                message: format!("Import cycle detected: {}", cycle),
+                source_ranges: vec![source_range],
+                ..Default::default()
+            }));
+        }
+
+        let exports = match self.modules.cached_exports(&path) {
+            Some(exports) => exports,
+            None => {
+                let exports = self.execute_module(&path, source_range).await?;
+                self.modules.cache_exports(path.clone(), exports.clone());
+                exports
+            }
+        };
+
+        for item in &import.items {
+            let Some(value) = exports.get(&item.name.name) else {
+                return Err(KclError::UndefinedValue(KclErrorDetails {
+                    message: format!("`{}` is not exported from `{}`", item.name.name, import.path),
+                    source_ranges: vec![item.into()],
+                    ..Default::default()
+                }));
+            };
+            let local_name = item.local_name();
+            memory.add(&local_name.name, value.clone(), local_name.into())?;
+        }
+
+        Ok(())
+    }
+
+    /// Execute a KCL file in its own memory, returning the items it exports.
+    async fn execute_module(
+        &self,
+        path: &std::path::Path,
+        source_range: SourceRange,
+    ) -> Result<HashMap<String, MemoryItem>, KclError> {
+        let source = self.fs.read(path, source_range).await?;
+        let source = String::from_utf8(source).map_err(|e| {
+            KclError::Semantic(KclErrorDetails {
+                message: format!("Could not read `{}` as UTF-8: {}", path.display(), e),
+                source_ranges: vec![source_range],
+                ..Default::default()
+            })
+        })?;
+        // Errors inside the module point at the module's source, which isn't the file being
+        // executed, so report them at the import statement instead.
+        let in_module = |e: KclError| {
+            KclError::Semantic(KclErrorDetails {
+                message: format!("Error in imported module `{}`: {}", path.display(), e.message()),
+                source_ranges: vec![source_range],
+                ..Default::default()
+            })
+        };
+        let tokens = crate::token::lexer(&source).map_err(in_module)?;
+        let program = crate::parser::Parser::new(tokens).ast().map_err(in_module)?;
+        let mut module_memory = ProgramMemory::new();
+        if let Some(err) = crate::typecheck::check_program(&program, &self.stdlib, &module_memory)
+            .into_iter()
+            .next()
+        {
+            return Err(in_module(err));
+        }
+
+        let mut module_ctx = self.clone();
+        module_ctx.modules.import_stack.push(path.to_path_buf());
+        // The same goes for warnings.
+        module_ctx.warnings = Default::default();
+        let result = module_ctx
+            .inner_execute(program.clone(), &mut module_memory, BodyType::Root)
+            .await;
+        for warning in module_ctx.warnings.all() {
+            self.warnings.add(KclWarning {
+                message: format!("In imported module `{}`: {}", path.display(), warning.message),
+                source_range,
+                ..warning
+            });
+        }
+        result.map_err(in_module)?;
+
+        let mut exports = HashMap::new();
+        for item in &program.body {
+            let BodyItem::VariableDeclaration(variable_declaration) = item else {
+                continue;
+            };
+            if variable_declaration.visibility != ItemVisibility::Export {
+                continue;
+            }
+            for id in variable_declaration.declarations.iter().flat_map(|d| d.id.names()) {
+                let value = module_memory.get(&id.name, id.into())?;
+                exports.insert(id.name.clone(), value.clone());
+            }
+        }
+        Ok(exports)
+    }
+
+    /// Execute a single value (i.e. an expression) and return its result.
+    #[async_recursion]
+    pub(crate) async fn execute_value(
+        &self,
+        value: &Value,
+        memory: &mut ProgramMemory,
+        pipe_info: &PipeInfo,
+    ) -> Result<MemoryItem, KclError> {
+        let result = match value {
+            Value::None(none) => none.into(),
+            Value::Literal(literal) => literal.get_result(self),
+            Value::Identifier(identifier) => memory.get(&identifier.name, identifier.into())?.clone(),
+            Value::BinaryExpression(binary_expression) => binary_expression.get_result(memory, pipe_info, self).await?,
+            Value::CallExpression(call_expression) => call_expression.execute(memory, pipe_info, self).await?,
+            Value::UnaryExpression(unary_expression) => unary_expression.get_result(memory, pipe_info, self).await?,
+            Value::ObjectExpression(object_expression) => object_expression.execute(memory, pipe_info, self).await?,
+            Value::ArrayExpression(array_expression) => array_expression.execute(memory, pipe_info, self).await?,
+            Value::PipeExpression(pipe_expression) => pipe_expression.get_result(memory, pipe_info, self).await?,
+            Value::MemberExpression(member_expression) => member_expression.get_result(memory)?,
+            Value::IfExpression(if_expression) => if_expression.get_result(memory, pipe_info, self).await?,
+            Value::PipeSubstitution(pipe_substitution) => pipe_info
+                .previous_results
+                .as_ref()
+                .ok_or_else(|| {
+                    KclError::Semantic(KclErrorDetails {
+                        message: format!("PipeSubstitution index out of bounds: {:?}", pipe_info),
+                        source_ranges: vec![pipe_substitution.into()],
+                        ..Default::default()
+                    })
+                })?
+                .clone(),
+            Value::FunctionExpression(function_expression) => {
+                return Err(KclError::Semantic(KclErrorDetails {
+                    message: format!("FunctionExpression not implemented here: {:?}", function_expression),
+                    source_ranges: vec![function_expression.into()],
+                    ..Default::default()
+                }));
+            }
+        };
+
+        Ok(result)
     }
 
     /// Update the units for the executor.
@@ -1342,15 +2466,18 @@ impl ExecutorContext {
 
 /// For each argument given,
 /// assign it to a parameter of the function, in the given block of function memory.
+/// Arguments passed by name are matched to the parameter with that name.
+/// Parameters with a default value which weren't given are left unassigned, for the caller to fill in.
 /// Returns Err if too few/too many arguments were given for the function.
 fn assign_args_to_params(
     function_expression: &FunctionExpression,
     args: Vec<MemoryItem>,
+    named_args: Vec<(Identifier, MemoryItem)>,
     mut fn_memory: ProgramMemory,
 ) -> Result<ProgramMemory, KclError> {
     let num_args = function_expression.number_of_args();
     let (min_params, max_params) = num_args.into_inner();
-    let n = args.len();
+    let n = args.len() + named_args.len();
 
     // Check if the user supplied too many arguments
     // (we'll check for too few arguments below).
@@ -1361,16 +2488,20 @@ fn assign_args_to_params(
             format!("Expected {min_params}-{max_params} arguments, got {n}")
         },
         source_ranges: vec![function_expression.into()],
+        ..Default::default()
     });
-    if n > max_params {
+    if args.len() > max_params {
         return Err(err_wrong_number_args);
     }
+    let named_args = match_named_args(&function_expression.params, args.len(), named_args)?;
 
     // Add the arguments to the memory.
     for (index, param) in function_expression.params.iter().enumerate() {
-        if let Some(arg) = args.get(index) {
+        if let Some(arg) = args.get(index).or(named_args.get(&index)) {
             // Argument was provided.
             fn_memory.add(&param.identifier.name, arg.clone(), (&param.identifier).into())?;
+        } else if param.default_value.is_some() {
+            // The default value is evaluated once all the given arguments are in memory.
         } else {
             // Argument was not provided.
             if param.optional {
@@ -1395,6 +2526,131 @@ fn assign_args_to_params(
     Ok(fn_memory)
 }
 
+/// Bind the parts of a value to the names in a destructuring pattern, e.g. `[x, y]` or `{ width, height }`.
+fn destructure(pattern: &BindingPattern, value: MemoryItem, memory: &mut ProgramMemory) -> Result<(), KclError> {
+    let source_range = SourceRange::from(pattern);
+    let wrong_type = |expected: &str| {
+        KclError::Semantic(KclErrorDetails {
+            message: format!(
+                "Only {} can be destructured like this, but this is {}",
+                expected,
+                crate::typecheck::Type::of(&value).describe()
+            ),
+            source_ranges: vec![source_range],
+            ..Default::default()
+        })
+    };
+    match pattern {
+        BindingPattern::Identifier(id) => memory.add(&id.name, value, id.as_ref().into()),
+        BindingPattern::ArrayPattern(array_pattern) => {
+            let items: Vec<MemoryItem> = match &value {
+                MemoryItem::SketchGroups { value } => value.iter().cloned().map(MemoryItem::SketchGroup).collect(),
+                MemoryItem::ExtrudeGroups { value } => value.iter().cloned().map(MemoryItem::ExtrudeGroup).collect(),
+                MemoryItem::UserVal(UserVal {
+                    value: JValue::Array(items),
+                    ..
+                }) => items
+                    .iter()
+                    .map(|item| crate::std::array::array_element_to_memory_item(item.clone(), source_range))
+                    .collect(),
+                _ => return Err(wrong_type("an array")),
+            };
+            if items.len() < array_pattern.elements.len() {
+                return Err(KclError::Semantic(KclErrorDetails {
+                    message: format!(
+                        "Expected an array of at least {} items to destructure, but it only has {}",
+                        array_pattern.elements.len(),
+                        items.len()
+                    ),
+                    source_ranges: vec![source_range],
+                    ..Default::default()
+                }));
+            }
+            for (id, item) in array_pattern.elements.iter().zip(items) {
+                memory.add(&id.name, item, id.into())?;
+            }
+            Ok(())
+        }
+        BindingPattern::ObjectPattern(object_pattern) => {
+            let JValue::Object(object) = value.get_json_value()? else {
+                return Err(wrong_type("an object"));
+            };
+            for id in &object_pattern.properties {
+                let Some(property) = object.get(&id.name) else {
+                    return Err(KclError::UndefinedValue(KclErrorDetails {
+                        message: format!("Property {} not found in object", id.name),
+                        source_ranges: vec![id.into()],
+                        ..Default::default()
+                    }));
+                };
+                let item = crate::std::array::array_element_to_memory_item(property.clone(), id.into());
+                memory.add(&id.name, item, id.into())?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Find which parameter each argument passed by name belongs to.
+/// Returns a map from the parameter's index to the argument.
+/// Returns Err if there's no parameter with that name, or if it was already given an argument.
+fn match_named_args(
+    params: &[Parameter],
+    num_positional_args: usize,
+    named_args: Vec<(Identifier, MemoryItem)>,
+) -> Result<HashMap<usize, MemoryItem>, KclError> {
+    let mut matched = HashMap::with_capacity(named_args.len());
+    for (name, arg) in named_args {
+        let Some(index) = params.iter().position(|param| param.identifier.name == name.name) else {
+            return Err(KclError::Semantic(KclErrorDetails {
+                message: format!("This function has no parameter named `{}`", name.name),
+                source_ranges: vec![(&name).into()],
+                ..Default::default()
+            }));
+        };
+        if index < num_positional_args || matched.contains_key(&index) {
+            return Err(KclError::Semantic(KclErrorDetails {
+                message: format!("The `{}` argument was given more than once", name.name),
+                source_ranges: vec![(&name).into()],
+                ..Default::default()
+            }));
+        }
+        matched.insert(index, arg);
+    }
+    Ok(matched)
+}
+
+/// Put the arguments passed by name into the positions of their parameters, after the positional ones.
+/// Optional parameters which are skipped over are passed as KCL none.
+pub(crate) fn order_named_args(
+    params: &[Parameter],
+    mut args: Vec<MemoryItem>,
+    named_args: Vec<(Identifier, MemoryItem)>,
+    source_range: SourceRange,
+) -> Result<Vec<MemoryItem>, KclError> {
+    let mut named_args = match_named_args(params, args.len(), named_args)?;
+    let Some(last) = named_args.keys().max().copied() else {
+        return Ok(args);
+    };
+    for (index, param) in params.iter().enumerate().take(last + 1).skip(args.len()) {
+        if let Some(arg) = named_args.remove(&index) {
+            args.push(arg);
+        } else if param.optional {
+            args.push(MemoryItem::from(&KclNone {
+                start: source_range.start(),
+                end: source_range.end(),
+            }));
+        } else {
+            return Err(KclError::Semantic(KclErrorDetails {
+                message: format!("The `{}` argument is missing", param.identifier.name),
+                source_ranges: vec![source_range],
+                ..Default::default()
+            }));
+        }
+    }
+    Ok(args)
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -1402,22 +2658,36 @@ mod tests {
     use pretty_assertions::assert_eq;
 
     use super::*;
-    use crate::ast::types::{Identifier, Parameter};
 
     pub async fn parse_execute(code: &str) -> Result<ProgramMemory> {
         let tokens = crate::token::lexer(code)?;
         let parser = crate::parser::Parser::new(tokens);
         let program = parser.ast()?;
-        let ctx = ExecutorContext {
-            engine: Arc::new(Box::new(crate::engine::conn_mock::EngineConnection::new().await?)),
+        let ctx = mock_context().await;
+        let memory = ctx.run(program, None).await?;
+
+        Ok(memory)
+    }
+
+    /// A context which sends its commands to a mock engine, with everything else left as the
+    /// default. Tests which need something else can override the fields they care about.
+    pub async fn mock_context() -> ExecutorContext {
+        ExecutorContext {
+            engine: Arc::new(Box::new(
+                crate::engine::conn_mock::EngineConnection::new().await.unwrap(),
+            )),
             fs: Arc::new(crate::fs::FileManager::new()),
             stdlib: Arc::new(crate::std::StdLib::new()),
             settings: Default::default(),
             is_mock: false,
-        };
-        let memory = ctx.run(program, None).await?;
-
-        Ok(memory)
+            modules: Default::default(),
+            cache: Default::default(),
+            cancel: Default::default(),
+            budget: Default::default(),
+            profiler: None,
+            id_generator: Default::default(),
+            warnings: Default::default(),
+        }
     }
 
     #[tokio::test(flavor = "multi_thread")]
@@ -1920,6 +3190,1547 @@ const bracket = startSketchOn('XY')
         parse_execute(ast).await.unwrap();
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_if_else() {
+        let ast = r#"const a = if true {
+  3
+} else if true {
+  4
+} else {
+  5
+}
+const b = if false {
+  3
+} else if true {
+  4
+} else {
+  5
+}
+const c = if false {
+  3
+} else {
+  const x = 10
+  x + 1
+}"#;
+        let memory = parse_execute(ast).await.unwrap();
+        assert_eq!(
+            serde_json::json!(3),
+            memory.root.get("a").unwrap().get_json_value().unwrap()
+        );
+        assert_eq!(
+            serde_json::json!(4),
+            memory.root.get("b").unwrap().get_json_value().unwrap()
+        );
+        assert_eq!(
+            serde_json::json!(11.0),
+            memory.root.get("c").unwrap().get_json_value().unwrap()
+        );
+        // Variables declared inside a block don't leak out of it.
+        assert!(memory.root.get("x").is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_if_else_in_function() {
+        let ast = r#"fn thickness = (heavyDuty) => {
+  return if heavyDuty {
+    2
+  } else {
+    1
+  }
+}
+const thick = thickness(true)
+const thin = thickness(false)"#;
+        let memory = parse_execute(ast).await.unwrap();
+        assert_eq!(
+            serde_json::json!(2),
+            memory.root.get("thick").unwrap().get_json_value().unwrap()
+        );
+        assert_eq!(
+            serde_json::json!(1),
+            memory.root.get("thin").unwrap().get_json_value().unwrap()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_if_else_condition_must_be_bool() {
+        let ast = r#"const a = if 1 {
+  3
+} else {
+  4
+}"#;
+        let result = parse_execute(ast).await;
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            r#"type: KclErrorDetails { source_ranges: [SourceRange([13, 14])], message: "Expected a boolean, but this is a number", call_stack: [] }"#
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_comparison_and_logic() {
+        let ast = r#"const a = 1 < 2
+const b = 2 <= 1
+const c = 3 > 2 && 2 >= 2
+const d = 1 == 1.0 && "x" != "y"
+const f = !(1 > 2) || false
+const g = 2 * 3 ^ 2
+const h = if 3 > 2 { "big" } else { "small" }"#;
+        let memory = parse_execute(ast).await.unwrap();
+        for (name, expected) in [
+            ("a", serde_json::json!(true)),
+            ("b", serde_json::json!(false)),
+            ("c", serde_json::json!(true)),
+            ("d", serde_json::json!(true)),
+            ("f", serde_json::json!(true)),
+            ("g", serde_json::json!(36.0)),
+            ("h", serde_json::json!("big")),
+        ] {
+            assert_eq!(
+                expected,
+                memory.root.get(name).unwrap().get_json_value().unwrap(),
+                "wrong value for {name}"
+            );
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_logic_short_circuits() {
+        // The right-hand side isn't a boolean, but it's never evaluated.
+        let ast = r#"const a = true || "oops"
+const b = false && "oops""#;
+        let memory = parse_execute(ast).await.unwrap();
+        assert_eq!(
+            serde_json::json!(true),
+            memory.root.get("a").unwrap().get_json_value().unwrap()
+        );
+        assert_eq!(
+            serde_json::json!(false),
+            memory.root.get("b").unwrap().get_json_value().unwrap()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_logic_needs_booleans() {
+        let ast = r#"const a = true && 1"#;
+        let result = parse_execute(ast).await;
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            r#"type: KclErrorDetails { source_ranges: [SourceRange([18, 19])], message: "Expected a boolean, found: 1", call_stack: [] }"#
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_for_loop() {
+        let ast = r#"const n = 3
+for i in 0..n {
+  const x = i * 2
+}
+for p in [[0, 0], [1, 1]] {
+  const y = p[1]
+}"#;
+        let memory = parse_execute(ast).await.unwrap();
+        // Nothing declared by the loop is visible after it.
+        assert!(!memory.root.contains_key("i"));
+        assert!(!memory.root.contains_key("x"));
+        assert!(!memory.root.contains_key("p"));
+        assert!(!memory.root.contains_key("y"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_for_loop_binds_each_item() {
+        // The body runs once per item, so the error only happens on the last item.
+        let ast = r#"for p in [[0, 0], [1, 1], 2] {
+  const y = p[1]
+}"#;
+        let result = parse_execute(ast).await;
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            r#"semantic: KclErrorDetails { source_ranges: [SourceRange([43, 47])], message: "MemberExpression array is not an array: UserVal(UserVal { value: Number(2), meta: [Metadata { source_range: SourceRange([4, 5]) }], dimension: None })", call_stack: [] }"#
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_for_loop_compares_each_item() {
+        // Only the item which is more than 3 takes the branch which fails.
+        let ast = r#"for n in [1, 3, 5] {
+  const y = if n > 3 { n[0] } else { n }
+}"#;
+        let result = parse_execute(ast).await;
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("MemberExpression array is not an array: UserVal(UserVal { value: Number(5)"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_for_loop_over_descending_range() {
+        // The end of the range is less than the start, so the body never runs.
+        let ast = r#"for i in 3..1 {
+  const x = i[0]
+}"#;
+        let memory = parse_execute(ast).await.unwrap();
+        assert!(!memory.root.contains_key("i"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_for_loop_too_many_iterations() {
+        let ast = r#"for i in 0..100000 {
+  const x = i
+}"#;
+        let result = parse_execute(ast).await;
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            r#"semantic: KclErrorDetails { source_ranges: [SourceRange([9, 18])], message: "This for loop would run 100001 times, but loops can run at most 10000 times", call_stack: [] }"#
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_for_loop_must_iterate_over_array() {
+        let ast = r#"for i in 5 {
+  const x = i
+}"#;
+        let result = parse_execute(ast).await;
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            r#"type: KclErrorDetails { source_ranges: [SourceRange([9, 10])], message: "A for loop can only iterate over a range or an array, but this is a number", call_stack: [] }"#
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_import_module() {
+        let ast = r#"import TWO, double as dbl from "tests/inputs/modules/math.kcl"
+const x = dbl(TWO)"#;
+        let memory = parse_execute(ast).await.unwrap();
+        assert_eq!(
+            serde_json::json!(4.0),
+            memory.root.get("x").unwrap().get_json_value().unwrap()
+        );
+        assert!(!memory.root.contains_key("double"));
+        assert!(!memory.root.contains_key("hidden"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_import_relative_to_module() {
+        let ast = r#"import four from "tests/inputs/modules/uses_math.kcl"
+import TWO from "tests/inputs/modules/math.kcl""#;
+        let memory = parse_execute(ast).await.unwrap();
+        assert_eq!(
+            serde_json::json!(4.0),
+            memory.root.get("four").unwrap().get_json_value().unwrap()
+        );
+        assert_eq!(
+            serde_json::json!(2),
+            memory.root.get("TWO").unwrap().get_json_value().unwrap()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_import_function_using_private_name() {
+        let ast = r#"import addHidden from "tests/inputs/modules/math.kcl"
+const x = addHidden(1)"#;
+        let memory = parse_execute(ast).await.unwrap();
+        assert_eq!(
+            serde_json::json!(6.0),
+            memory.root.get("x").unwrap().get_json_value().unwrap()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_import_not_exported() {
+        let ast = r#"import hidden from "tests/inputs/modules/math.kcl""#;
+        let result = parse_execute(ast).await;
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            r#"undefined value: KclErrorDetails { source_ranges: [SourceRange([7, 13])], message: "`hidden` is not exported from `tests/inputs/modules/math.kcl`", call_stack: [] }"#
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_import_cycle() {
+        let ast = r#"import a from "tests/inputs/modules/cycle_a.kcl""#;
+        let result = parse_execute(ast).await;
+        let err = result.unwrap_err().to_string();
+        assert!(
+            err.contains("Import cycle detected: tests/inputs/modules/cycle_a.kcl -> tests/inputs/modules/cycle_b.kcl -> tests/inputs/modules/cycle_a.kcl"),
+            "{}",
+            err
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_import_cycle_with_different_spellings() {
+        // Each module is imported with a different spelling of its path.
+        let ast = r#"import a from "./tests/inputs/modules/cycle_spelling_a.kcl""#;
+        let result = parse_execute(ast).await;
+        let err = result.unwrap_err().to_string();
+        assert!(
+            err.contains("Import cycle detected: tests/inputs/modules/cycle_spelling_a.kcl -> tests/inputs/modules/cycle_spelling_b.kcl -> tests/inputs/modules/cycle_spelling_a.kcl"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        for (path, expected) in [
+            ("a.kcl", "a.kcl"),
+            ("./a.kcl", "a.kcl"),
+            ("dir/./a.kcl", "dir/a.kcl"),
+            ("dir/../a.kcl", "a.kcl"),
+            ("dir/sub/../../a.kcl", "a.kcl"),
+            ("../a.kcl", "../a.kcl"),
+            ("/dir/../a.kcl", "/a.kcl"),
+        ] {
+            assert_eq!(
+                normalize_path(std::path::Path::new(path)),
+                std::path::PathBuf::from(expected),
+                "{path}"
+            );
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_import_relative_to_project_directory() {
+        let program = crate::parser::Parser::new(crate::token::lexer(r#"import TWO from "math.kcl""#).unwrap())
+            .ast()
+            .unwrap();
+        let mut ctx = ExecutorContext::new_with_engine(
+            Arc::new(Box::new(
+                crate::engine::conn_mock::EngineConnection::new().await.unwrap(),
+            )),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+        ctx.modules.set_project_directory("tests/inputs/modules");
+        let memory = ctx.run(program, None).await.unwrap();
+        assert_eq!(
+            serde_json::json!(2),
+            memory.root.get("TWO").unwrap().get_json_value().unwrap()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_function_uses_declaration_scope() {
+        let ast = r#"const x = 1
+fn f = () => {
+  return x
+}
+fn g = (x) => {
+  return f()
+}
+const y = g(5)"#;
+        let memory = parse_execute(ast).await.unwrap();
+        assert_eq!(
+            serde_json::json!(1),
+            memory.root.get("y").unwrap().get_json_value().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_share_memory() {
+        let value = || {
+            MemoryItem::UserVal(UserVal {
+                value: serde_json::json!(1),
+                meta: Default::default(),
+                ..Default::default()
+            })
+        };
+        let mut memory = ProgramMemory::new();
+        memory.add("a", value(), SourceRange::default()).unwrap();
+        let first = memory.share();
+        memory.add("b", value(), SourceRange::default()).unwrap();
+        let second = memory.share();
+
+        // Each shared scope encloses the one before it, rather than copying it.
+        assert!(Arc::ptr_eq(second.parent.as_ref().unwrap(), &first));
+        // Nothing new was declared, so there's no need for another scope.
+        assert!(Arc::ptr_eq(&memory.share(), &second));
+        assert!(second.get("a", SourceRange::default()).is_ok());
+        assert!(first.get("b", SourceRange::default()).is_err());
+        // The names are still declared in this scope.
+        assert!(memory.add("a", value(), SourceRange::default()).is_err());
+
+        memory.unshare();
+        assert!(memory.parent.is_none());
+        assert!(memory.root.contains_key("a"));
+        assert!(memory.root.contains_key("b"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_cannot_redefine_name_captured_by_function() {
+        let ast = r#"const x = 1
+fn f = () => {
+  return x
+}
+const x = 2"#;
+        let result = parse_execute(ast).await;
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            r#"value already defined: KclErrorDetails { source_ranges: [SourceRange([50, 51])], message: "Cannot redefine x", call_stack: [] }"#
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_closure() {
+        let ast = r#"fn makeAdder = (n) => {
+  fn add = (x) => {
+    return x + n
+  }
+  return add
+}
+const add2 = makeAdder(2)
+const y = add2(3)"#;
+        let memory = parse_execute(ast).await.unwrap();
+        assert_eq!(
+            serde_json::json!(5.0),
+            memory.root.get("y").unwrap().get_json_value().unwrap()
+        );
+        assert!(!memory.root.contains_key("n"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_recursive_function() {
+        let ast = r#"fn fact = (n) => {
+  return if n <= 1 {
+    1
+  } else {
+    n * fact(n - 1)
+  }
+}
+const y = fact(5)"#;
+        let memory = parse_execute(ast).await.unwrap();
+        assert_eq!(
+            serde_json::json!(120.0),
+            memory.root.get("y").unwrap().get_json_value().unwrap()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_function_cannot_see_later_names() {
+        let ast = r#"fn f = () => {
+  return later
+}
+const later = 3
+const y = f()"#;
+        let result = parse_execute(ast).await;
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            r#"undefined value: KclErrorDetails { source_ranges: [SourceRange([24, 29])], message: "memory item key `later` is not defined", call_stack: [StackFrame { function_name: "f", source_range: SourceRange([58, 61]) }] }"#
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_map_filter_reduce() {
+        let ast = r#"const k = 3
+fn times = (x) => {
+  return x * k
+}
+fn isEven = (x) => {
+  return x % 2 == 0
+}
+fn add = (total, x) => {
+  return total + x
+}
+const tripled = map(range(1, 3), times)
+const evens = filter(range(0, 6), isEven)
+const sum = reduce([1, 2, 3], 0, add)"#;
+        let memory = parse_execute(ast).await.unwrap();
+        assert_eq!(
+            serde_json::json!([3.0, 6.0, 9.0]),
+            memory.root.get("tripled").unwrap().get_json_value().unwrap()
+        );
+        assert_eq!(
+            serde_json::json!([0, 2, 4, 6]),
+            memory.root.get("evens").unwrap().get_json_value().unwrap()
+        );
+        assert_eq!(
+            serde_json::json!(6.0),
+            memory.root.get("sum").unwrap().get_json_value().unwrap()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_map_recursive_function() {
+        let ast = r#"fn fact = (n) => {
+  return if n <= 1 {
+    1
+  } else {
+    n * fact(n - 1)
+  }
+}
+const facts = map([1, 2, 3], fact)"#;
+        let memory = parse_execute(ast).await.unwrap();
+        assert_eq!(
+            serde_json::json!([1, 2.0, 6.0]),
+            memory.root.get("facts").unwrap().get_json_value().unwrap()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_map_sketch_groups() {
+        let ast = r#"fn drawCircle = (x) => {
+  return startSketchOn('XY')
+    |> circle([x, 0], 1, %)
+}
+const circles = map([0, 5, 10], drawCircle)
+  |> extrude(2, %)"#;
+        let memory = parse_execute(ast).await.unwrap();
+        let MemoryItem::ExtrudeGroups { value } = memory.root.get("circles").unwrap() else {
+            panic!("expected extrude groups, found {:?}", memory.root.get("circles"));
+        };
+        assert_eq!(value.len(), 3);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_range() {
+        let ast = r#"const quarters = range(0, 1, 0.25)
+const down = range(5, 1, -2)
+const ones = range(1, 3)"#;
+        let memory = parse_execute(ast).await.unwrap();
+        assert_eq!(
+            serde_json::json!([0.0, 0.25, 0.5, 0.75, 1.0]),
+            memory.root.get("quarters").unwrap().get_json_value().unwrap()
+        );
+        assert_eq!(
+            serde_json::json!([5, 3, 1]),
+            memory.root.get("down").unwrap().get_json_value().unwrap()
+        );
+        assert_eq!(
+            serde_json::json!([1, 2, 3]),
+            memory.root.get("ones").unwrap().get_json_value().unwrap()
+        );
+
+        let result = parse_execute("const xs = range(0, 5, -1)").await;
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            r#"semantic: KclErrorDetails { source_ranges: [SourceRange([11, 26])], message: "A range from 0 to 5 can never be reached with a step of -1", call_stack: [] }"#
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_filter_needs_booleans() {
+        let ast = r#"fn double = (x) => {
+  return x * 2
+}
+const xs = filter([1, 2], double)"#;
+        let result = parse_execute(ast).await;
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            r#"type: KclErrorDetails { source_ranges: [SourceRange([49, 71])], message: "The function passed to `filter` must return true or false, found: 2.0", call_stack: [] }"#
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_named_arguments() {
+        let ast = r#"fn box = (width, height = width, offset = [0, 0]) => {
+  return [width, height, offset[0]]
+}
+const a = box(2)
+const b = box(2, height = 3)
+const c = box(2, offset = [1, 1])
+const d = box(offset = [5, 0], width = 4)
+const r = range(0, 6, step = 3)"#;
+        let memory = parse_execute(ast).await.unwrap();
+        for (name, expected) in [
+            ("a", serde_json::json!([2, 2, 0])),
+            ("b", serde_json::json!([2, 3, 0])),
+            ("c", serde_json::json!([2, 2, 1])),
+            ("d", serde_json::json!([4, 4, 5])),
+            ("r", serde_json::json!([0, 3, 6])),
+        ] {
+            assert_eq!(
+                expected,
+                memory.root.get(name).unwrap().get_json_value().unwrap(),
+                "wrong value for {name}"
+            );
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_named_argument_errors() {
+        for (ast, expected) in [
+            (
+                r#"fn f = (x, y = 1) => {
+  return x + y
+}
+const a = f(1, z = 2)"#,
+                r#"semantic: KclErrorDetails { source_ranges: [SourceRange([55, 56])], message: "This function has no parameter named `z`", call_stack: [StackFrame { function_name: "f", source_range: SourceRange([50, 61]) }] }"#,
+            ),
+            (
+                r#"fn f = (x, y = 1) => {
+  return x + y
+}
+const a = f(1, x = 2)"#,
+                r#"semantic: KclErrorDetails { source_ranges: [SourceRange([55, 56])], message: "The `x` argument was given more than once", call_stack: [StackFrame { function_name: "f", source_range: SourceRange([50, 61]) }] }"#,
+            ),
+        ] {
+            let result = parse_execute(ast).await;
+            assert_eq!(result.unwrap_err().to_string(), expected);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_named_stdlib_arguments() {
+        let ast = r#"const c = circle([0, 0], 1, startSketchOn('XY'), tag = 'a')
+const d = circle([0, 0], 1, tag = 'b', sketch_surface_or_group = startSketchOn('XY'))
+const len = segLen('a', c)
+const len2 = segLen('b', d)"#;
+        let memory = parse_execute(ast).await.unwrap();
+        for name in ["len", "len2"] {
+            let len = memory.root.get(name).unwrap().get_json_value().unwrap();
+            assert!(len.as_f64().unwrap() > 0.0, "wrong value for {name}: {len}");
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_chamfer() {
+        let code = r#"const part001 = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line([0, 10], %, "thing")
+  |> line([10, 0], %, "thing1")
+  |> line([0, -10], %, "thing2")
+  |> close(%, "thing3")
+  |> extrude(10, %)
+  |> chamfer({ length: 2, tags: ["thing", "thing2"] }, %)
+  |> chamfer({ length: 1, otherLength: 3, tags: ["thing1"] }, %)
+  |> chamfer({ length: 1, angle: 30, tags: ["thing3"] }, %)"#;
+        let program = crate::parser::Parser::new(crate::token::lexer(code).unwrap())
+            .ast()
+            .unwrap();
+        let ctx = mock_context().await;
+        let (_, commands) = ctx.run_plan_only(program, None).await.unwrap();
+        let chamfers: Vec<_> = commands
+            .iter()
+            .filter_map(|(cmd, _)| match cmd {
+                kittycad::types::ModelingCmd::Solid3DCutEdges { cut_type, .. } => Some(cut_type.clone()),
+                _ => None,
+            })
+            .collect();
+        let chamfer = |distance, second_distance, angle: Option<f64>| kittycad::types::CutTypeV2::Chamfer {
+            angle: angle.map(kittycad::types::Angle::from_degrees),
+            distance,
+            second_distance,
+            swap: false,
+        };
+        assert_eq!(
+            chamfers,
+            vec![
+                chamfer(2.0, None, None),
+                chamfer(2.0, None, None),
+                chamfer(1.0, Some(3.0), None),
+                chamfer(1.0, None, Some(30.0)),
+            ]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_chamfer_negative_length() {
+        let code = r#"const part001 = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line([0, 10], %, "thing")
+  |> line([10, 0], %)
+  |> line([0, -10], %, "thing2")
+  |> close(%)
+  |> extrude(10, %)
+  |> chamfer({ length: -1, tags: ["thing"] }, %)"#;
+        let err = parse_execute(code).await.unwrap_err().downcast::<KclError>().unwrap();
+        assert_eq!(err.message(), "Expected the chamfer length to be positive, found `-1`");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_chamfer_other_length_and_angle() {
+        let code = r#"const part001 = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line([0, 10], %, "thing")
+  |> line([10, 0], %)
+  |> line([0, -10], %, "thing2")
+  |> close(%)
+  |> extrude(10, %)
+  |> chamfer({ length: 1, otherLength: 2, angle: 30, tags: ["thing"] }, %)"#;
+        let err = parse_execute(code).await.unwrap_err().downcast::<KclError>().unwrap();
+        assert_eq!(
+            err.message(),
+            "A chamfer can have an other length or an angle, but not both"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_shell() {
+        let code = r#"const box = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line([0, 10], %, "left")
+  |> line([10, 0], %, "back")
+  |> line([0, -10], %)
+  |> close(%)
+  |> extrude(10, %)
+const shelled = shell({ thickness: 1, faces: ["end", "back"] }, box)"#;
+        let program = crate::parser::Parser::new(crate::token::lexer(code).unwrap())
+            .ast()
+            .unwrap();
+        let ctx = mock_context().await;
+        let (memory, commands) = ctx.run_plan_only(program, None).await.unwrap();
+        let MemoryItem::ExtrudeGroup(extrude_group) = memory.get("box", SourceRange::default()).unwrap() else {
+            panic!("box should be an extrude group");
+        };
+        let MemoryItem::ExtrudeGroup(shelled) = memory.get("shelled", SourceRange::default()).unwrap() else {
+            panic!("shelled should be an extrude group");
+        };
+
+        let face_ids = commands
+            .iter()
+            .find_map(|(cmd, _)| match cmd {
+                kittycad::types::ModelingCmd::Solid3DShellFace {
+                    face_ids,
+                    shell_thickness,
+                    ..
+                } => Some((face_ids.clone(), *shell_thickness)),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(
+            face_ids,
+            (
+                vec![
+                    extrude_group.end_cap_id.unwrap(),
+                    extrude_group.get_path_by_name("back").unwrap().get_face_id()
+                ],
+                1.0
+            )
+        );
+
+        // The removed faces can't be used any more, but the others can.
+        assert_eq!(shelled.start_cap_id, extrude_group.start_cap_id);
+        assert_eq!(shelled.end_cap_id, None);
+        assert!(shelled.get_path_by_name("back").is_none());
+        assert!(shelled.get_path_by_name("left").is_some());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_loft() {
+        let code = r#"const bottom = startSketchOn('XY')
+  |> startProfileAt([-10, -10], %)
+  |> line([20, 0], %)
+  |> line([0, 20], %)
+  |> line([-20, 0], %, "bottomBack")
+  |> close(%)
+const top = startSketchOn({
+       plane: {
+         origin: { x: 0, y: 0, z: 20 },
+         x_axis: { x: 1, y: 0, z: 0 },
+         y_axis: { x: 0, y: 1, z: 0 },
+         z_axis: { x: 0, y: 0, z: 1 }
+       }
+     })
+  |> startProfileAt([-5, -5], %)
+  |> line([10, 0], %)
+  |> line([0, 10], %)
+  |> line([-10, 0], %, "topBack")
+  |> close(%)
+const frustum = loft([bottom, top], { ruled: true })"#;
+        let program = crate::parser::Parser::new(crate::token::lexer(code).unwrap())
+            .ast()
+            .unwrap();
+        let ctx = mock_context().await;
+        let (memory, commands) = ctx.run_plan_only(program, None).await.unwrap();
+        let MemoryItem::SketchGroup(bottom) = memory.get("bottom", SourceRange::default()).unwrap() else {
+            panic!("bottom should be a sketch group");
+        };
+        let MemoryItem::SketchGroup(top) = memory.get("top", SourceRange::default()).unwrap() else {
+            panic!("top should be a sketch group");
+        };
+        let MemoryItem::ExtrudeGroup(frustum) = memory.get("frustum", SourceRange::default()).unwrap() else {
+            panic!("frustum should be an extrude group");
+        };
+
+        let loft = commands
+            .iter()
+            .find_map(|(cmd, _)| match cmd {
+                kittycad::types::ModelingCmd::Loft {
+                    section_ids, v_degree, ..
+                } => Some((section_ids.clone(), *v_degree)),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(loft, (vec![bottom.id, top.id], 1));
+
+        // The caps can be sketched on, and the edges around both of them can be filleted.
+        assert!(frustum.start_cap_id.is_some());
+        assert!(frustum.end_cap_id.is_some());
+        assert_eq!(frustum.height, 20.0);
+        assert!(frustum.sketch_group_values.iter().any(|p| p.get_name() == "bottomBack"));
+        assert!(frustum.sketch_group_values.iter().any(|p| p.get_name() == "topBack"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_loft_profiles_on_the_same_plane() {
+        let code = r#"const small = startSketchOn('XY')
+  |> circle([0, 0], 5, %)
+const big = startSketchOn('XY')
+  |> circle([0, 0], 10, %)
+const part = loft([small, big])"#;
+
+        let result = parse_execute(code).await;
+        assert!(result.is_err());
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            r#"semantic: KclErrorDetails { source_ranges: [SourceRange([132, 150])], message: "Expected every profile to be on a different plane, but profiles 0 and 1 are on the same plane", call_stack: [] }"#
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_loft_tags_must_be_unique() {
+        let code = r#"const bottom = startSketchOn('XY')
+  |> startProfileAt([-10, -10], %)
+  |> line([20, 0], %, "back")
+  |> line([0, 20], %)
+  |> close(%)
+const top = startSketchOn({
+       plane: {
+         origin: { x: 0, y: 0, z: 20 },
+         x_axis: { x: 1, y: 0, z: 0 },
+         y_axis: { x: 0, y: 1, z: 0 },
+         z_axis: { x: 0, y: 0, z: 1 }
+       }
+     })
+  |> startProfileAt([-5, -5], %)
+  |> line([10, 0], %, "back")
+  |> line([0, 10], %)
+  |> close(%)
+const part = loft([bottom, top])"#;
+        let program = crate::parser::Parser::new(crate::token::lexer(code).unwrap())
+            .ast()
+            .unwrap();
+
+        let result = mock_context().await.run_plan_only(program, None).await;
+        assert!(result.is_err());
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            r#"semantic: KclErrorDetails { source_ranges: [SourceRange([465, 484])], message: "Expected the tags on the first and last profiles to be unique, but `back` is used by both", call_stack: [] }"#
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_loft_needs_two_profiles() {
+        let code = r#"const profile = startSketchOn('XY')
+  |> circle([0, 0], 5, %)
+const part = loft([profile])"#;
+
+        let result = parse_execute(code).await;
+        assert!(result.is_err());
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            r#"semantic: KclErrorDetails { source_ranges: [SourceRange([75, 90])], message: "Expected at least 2 profiles to loft between, found `1`", call_stack: [] }"#
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_sweep() {
+        let code = r#"const coil = startSketchOn('XY')
+  |> circle([0, 0], 10, %)
+  |> extrude(40, %)
+  |> helix({ revolutions: 8, angle_start: 0 }, %)
+const spring = startSketchOn('XZ')
+  |> circle([10, 0], 1, %)
+  |> sweep({ path: coil }, %)
+const path = startSketchOn('XZ')
+  |> startProfileAt([0, 0], %)
+  |> line([0, 20], %)
+const rod = startSketchOn('XY')
+  |> circle([0, 0], 1, %)
+  |> sweep({ path: path, twist: false }, %)"#;
+        let program = crate::parser::Parser::new(crate::token::lexer(code).unwrap())
+            .ast()
+            .unwrap();
+        let ctx = mock_context().await;
+        let (memory, commands) = ctx.run_plan_only(program, None).await.unwrap();
+        let MemoryItem::ExtrudeGroup(coil) = memory.get("coil", SourceRange::default()).unwrap() else {
+            panic!("coil should be an extrude group");
+        };
+        let MemoryItem::ExtrudeGroup(spring) = memory.get("spring", SourceRange::default()).unwrap() else {
+            panic!("spring should be an extrude group");
+        };
+        let MemoryItem::SketchGroup(path) = memory.get("path", SourceRange::default()).unwrap() else {
+            panic!("path should be a sketch group");
+        };
+        let MemoryItem::ExtrudeGroup(rod) = memory.get("rod", SourceRange::default()).unwrap() else {
+            panic!("rod should be an extrude group");
+        };
+
+        let sweeps = commands
+            .iter()
+            .filter_map(|(cmd, _)| match cmd {
+                kittycad::types::ModelingCmd::Sweep {
+                    target,
+                    trajectory,
+                    relative_to,
+                    ..
+                } => Some((*target, *trajectory, relative_to.clone())),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(coil.helix_ids.len(), 1);
+        assert_eq!(
+            sweeps,
+            vec![
+                (
+                    spring.id,
+                    coil.helix_ids[0],
+                    Some(kittycad::types::RelativeTo::TrajectoryCurve)
+                ),
+                (rod.id, path.id, Some(kittycad::types::RelativeTo::SketchPlane)),
+            ]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_sweep_along_extrude_group_without_helix() {
+        let code = r#"const cylinder = startSketchOn('XY')
+  |> circle([0, 0], 10, %)
+  |> extrude(40, %)
+const spring = startSketchOn('XZ')
+  |> circle([10, 0], 1, %)
+  |> sweep({ path: cylinder }, %)"#;
+
+        let result = parse_execute(code).await;
+        assert!(result.is_err());
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            r#"type: KclErrorDetails { source_ranges: [SourceRange([151, 179])], message: "Expected a helix to sweep along, but no helix was made on this extrude group", call_stack: [] }"#
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_union_keeps_tags() {
+        let code = r#"const base = startSketchOn('XY')
+  |> startProfileAt([-10, -10], %)
+  |> line([20, 0], %)
+  |> line([0, 20], %)
+  |> line([-20, 0], %, "back")
+  |> close(%)
+  |> extrude(5, %)
+const post = startSketchOn('XY')
+  |> circle([0, 0], 3, %)
+  |> extrude(15, %)
+const part = union([base, post])
+  |> fillet({ radius: 1, tags: ["back"] }, %)"#;
+        let program = crate::parser::Parser::new(crate::token::lexer(code).unwrap())
+            .ast()
+            .unwrap();
+        let ctx = mock_context().await;
+        let (memory, commands) = ctx.run_plan_only(program, None).await.unwrap();
+        let MemoryItem::ExtrudeGroup(base) = memory.get("base", SourceRange::default()).unwrap() else {
+            panic!("base should be an extrude group");
+        };
+        let MemoryItem::ExtrudeGroup(post) = memory.get("post", SourceRange::default()).unwrap() else {
+            panic!("post should be an extrude group");
+        };
+        let MemoryItem::ExtrudeGroup(part) = memory.get("part", SourceRange::default()).unwrap() else {
+            panic!("part should be an extrude group");
+        };
+
+        let unions = commands
+            .iter()
+            .filter_map(|(cmd, _)| match cmd {
+                kittycad::types::ModelingCmd::BooleanUnion { solid_ids, .. } => Some(solid_ids.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(unions, vec![vec![base.id, post.id]]);
+        assert_ne!(part.id, base.id);
+        assert_eq!(part.value.len(), base.value.len() + post.value.len());
+
+        // The tag on the base still finds its edge, on the new solid.
+        let back = base
+            .sketch_group_values
+            .iter()
+            .find(|p| p.get_name() == "back")
+            .unwrap();
+        let fillets = commands
+            .iter()
+            .filter_map(|(cmd, _)| match cmd {
+                kittycad::types::ModelingCmd::Solid3DFilletEdge { edge_id, object_id, .. } => {
+                    Some((*edge_id, *object_id))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(fillets, vec![(Some(back.get_base().geo_meta.id), part.id)]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_subtract_solid_from_itself() {
+        let code = r#"const plate = startSketchOn('XY')
+  |> circle([0, 0], 10, %)
+  |> extrude(4, %)
+const washer = plate
+  |> subtract([plate], %)"#;
+
+        let result = parse_execute(code).await;
+        assert!(result.is_err());
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            r#"semantic: KclErrorDetails { source_ranges: [SourceRange([106, 126])], message: "Cannot subtract a solid from itself", call_stack: [] }"#
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_intersection_of_solids_with_the_same_tag() {
+        let code = r#"const a = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line([10, 0], %, "side")
+  |> line([0, 10], %)
+  |> close(%)
+  |> extrude(5, %)
+const b = startSketchOn('XZ')
+  |> startProfileAt([0, 0], %)
+  |> line([10, 0], %, "side")
+  |> line([0, 10], %)
+  |> close(%)
+  |> extrude(5, %)
+const c = intersection([a, b])"#;
+
+        let result = parse_execute(code).await;
+        assert!(result.is_err());
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            r#"semantic: KclErrorDetails { source_ranges: [SourceRange([302, 322])], message: "Expected the tags on the solids to be unique, but `side` is used by more than one of them", call_stack: [] }"#
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_run_plan_only() {
+        let code = r#"const part001 = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line([0, 10], %)
+  |> line([10, 0], %)
+  |> close(%)
+  |> extrude(5, %)
+const part002 = startSketchOn('XZ')
+  |> startProfileAt([0, 0], %)
+  |> line([0, 10], %)"#;
+        let tokens = crate::token::lexer(code).unwrap();
+        let program = crate::parser::Parser::new(tokens).ast().unwrap();
+        let ctx = mock_context().await;
+        let (memory, commands) = ctx.run_plan_only(program, None).await.unwrap();
+        assert!(memory.root.contains_key("part001"));
+
+        let types: Vec<String> = commands
+            .iter()
+            .map(|(cmd, _)| serde_json::json!(cmd)["type"].as_str().unwrap().to_owned())
+            .filter(|ty| !matches!(ty.as_str(), "make_plane" | "plane_set_color"))
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                "set_scene_units",
+                "enable_sketch_mode",
+                "start_path",
+                "move_path_pen",
+                "extend_path",
+                "extend_path",
+                "close_path",
+                "sketch_mode_disable",
+                "extrude",
+                "object_bring_to_front",
+                "solid3d_get_extrusion_face_info",
+                // These were still waiting to be sent when the program finished.
+                "enable_sketch_mode",
+                "start_path",
+                "move_path_pen",
+                "extend_path",
+            ]
+        );
+        let (_, extrude_range) = commands
+            .iter()
+            .find(|(cmd, _)| matches!(cmd, kittycad::types::ModelingCmd::Extrude { .. }))
+            .unwrap();
+        assert_eq!(*extrude_range, SourceRange([130, 143]));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_cancellation_and_budgets() {
+        let code = r#"const part001 = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line([0, 10], %)
+  |> line([10, 0], %)
+fn double = (x) => {
+  return x * 2
+}
+const four = double(2)"#;
+        let program = crate::parser::Parser::new(crate::token::lexer(code).unwrap())
+            .ast()
+            .unwrap();
+        let ctx = mock_context().await;
+        ctx.run(program.clone(), None).await.unwrap();
+
+        let over_budget = ExecutorContext {
+            budget: ExecutionBudget::new(None, Some(2)),
+            ..ctx.clone()
+        };
+        assert_eq!(
+            over_budget.run(program.clone(), None).await.unwrap_err().to_string(),
+            r#"interrupted: KclErrorDetails { source_ranges: [SourceRange([111, 148])], message: "Execution stopped because it sent more than its limit of 2 commands to the engine", call_stack: [] }"#
+        );
+
+        let out_of_time = ExecutorContext {
+            budget: ExecutionBudget::new(Some(std::time::Duration::ZERO), None),
+            ..ctx.clone()
+        };
+        assert_eq!(
+            out_of_time.run(program.clone(), None).await.unwrap_err().to_string(),
+            r#"interrupted: KclErrorDetails { source_ranges: [SourceRange([0, 110])], message: "Execution stopped because it took longer than its time limit of 0ns", call_stack: [] }"#
+        );
+
+        let cancelled = ExecutorContext {
+            cancel: Default::default(),
+            ..ctx.clone()
+        };
+        cancelled.cancel.cancel();
+        assert_eq!(
+            cancelled.run(program, None).await.unwrap_err().to_string(),
+            r#"interrupted: KclErrorDetails { source_ranges: [SourceRange([0, 110])], message: "Execution was cancelled", call_stack: [] }"#
+        );
+        // The original context has its own token.
+        assert!(!ctx.cancel.is_cancelled());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_profiler() {
+        let code = r#"const part001 = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line([0, 10], %)
+  |> line([10, 0], %)
+fn double = (x) => {
+  return x * 2
+}
+const four = double(2)"#;
+        let program = crate::parser::Parser::new(crate::token::lexer(code).unwrap())
+            .ast()
+            .unwrap();
+        let ctx = ExecutorContext {
+            profiler: Some(Profiler::new()),
+            ..mock_context().await
+        };
+        ctx.run(program, None).await.unwrap();
+
+        let profile = ctx.profiler.unwrap().profile();
+        // The statement in the function body finishes before the statement which called it.
+        let statements: Vec<[usize; 2]> = profile.statements.iter().map(|span| span.source_range.0).collect();
+        assert_eq!(statements, vec![[0, 110], [111, 148], [134, 146], [149, 171]]);
+
+        let sent: usize = profile.commands.iter().map(|count| count.commands).sum();
+        assert_eq!(profile.statements[0].commands, sent);
+        assert_eq!(profile.statements[3].commands, 0);
+        assert!(profile
+            .commands
+            .iter()
+            .all(|count| SourceRange([0, 110]).contains(count.source_range.start())));
+
+        // The sketch's commands were still waiting in the batch when the function's body
+        // finished, which flushed them.
+        let last_flush = profile.flushes.last().unwrap();
+        assert_eq!(last_flush.source_range, SourceRange([148, 148]));
+        assert!(last_flush.commands > 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_execute_independent_statements() {
+        let code = r#"const size = 10
+const part001 = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line([0, size], %)
+  |> line([size, 0], %)
+  |> close(%)
+const part002 = startSketchOn('XZ')
+  |> circle([0, 0], 5, %)
+  |> extrude(5, %)
+const part003 = extrude(size, part001)
+const total = size * 2"#;
+        let memory = parse_execute(code).await.unwrap();
+        for name in ["size", "part001", "part002", "part003", "total"] {
+            assert!(memory.root.contains_key(name), "{name} is missing");
+        }
+        assert_eq!(
+            memory.root.get("total").unwrap().get_json_value().unwrap(),
+            serde_json::json!(20.0)
+        );
+
+        // The error from the earliest statement wins, whichever group finishes first.
+        let code = r#"const arr = [1]
+const other = [2]
+const part001 = startSketchOn('XY')
+  |> circle([0, 0], 5, %)
+  |> extrude(5, %)
+const first = arr[3]
+const second = other[4]"#;
+        let err = parse_execute(code).await.unwrap_err();
+        let err = err.downcast::<KclError>().unwrap();
+        assert_eq!(err.source_ranges(), vec![SourceRange([129, 135])]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_error_call_stack() {
+        let code = r#"const arr = [1, 2]
+fn pick = (a) => {
+  return a[3]
+}
+fn outer = (a) => {
+  return pick(a)
+}
+const x = outer(arr)"#;
+        let err = parse_execute(code).await.unwrap_err().downcast::<KclError>().unwrap();
+        assert_eq!(
+            err.call_stack(),
+            vec![
+                crate::errors::StackFrame {
+                    function_name: "pick".to_string(),
+                    source_range: SourceRange([83, 90]),
+                },
+                crate::errors::StackFrame {
+                    function_name: "outer".to_string(),
+                    source_range: SourceRange([103, 113]),
+                },
+            ]
+        );
+        let (message, _, _) = err.get_message_line_column(code);
+        assert!(
+            message.ends_with("\n    in `pick` called at line 6, column 9\n    in `outer` called at line 8, column 10"),
+            "{message}"
+        );
+
+        let uri = tower_lsp::lsp_types::Url::parse("file:///test.kcl").unwrap();
+        let related_information = err.to_lsp_diagnostic(code, &uri).related_information.unwrap();
+        assert_eq!(
+            related_information
+                .iter()
+                .map(|info| info.message.as_str())
+                .collect::<Vec<_>>(),
+            vec!["in `pick` called here", "in `outer` called here"]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_warnings() {
+        let code = r#"const part001 = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line([0, 10], %)
+  |> lineTo([0, 10], %)
+  |> line([10, 0], %)
+  |> close(%)"#;
+        let memory = parse_execute(code).await.unwrap();
+        assert_eq!(
+            memory.warnings,
+            vec![KclWarning::new(
+                SourceRange([94, 112]),
+                "This segment has no length, so it doesn't add anything to the sketch"
+            )]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_warnings_kept_on_error() {
+        // The mock engine can't pattern solids, so this fails after the warning was added.
+        let code = r#"const part001 = startSketchOn('XY')
+  |> circle([0, 0], 2, %)
+  |> extrude(5, %)
+  |> patternLinear3d({
+       axis: [1, 0, 0],
+       repetitions: 2,
+       distance: 6,
+       center: [0, 0, 0]
+     }, %)"#;
+        let program = crate::parser::Parser::new(crate::token::lexer(code).unwrap())
+            .ast()
+            .unwrap();
+        let ctx = mock_context().await;
+        assert!(ctx.run(program, None).await.is_err());
+        assert_eq!(
+            ctx.warnings.all(),
+            vec![KclWarning::new(
+                SourceRange([86, 206]),
+                "`center` isn't used by this pattern, so it's ignored"
+            )]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_seeded_ids() {
+        let code = r#"const part001 = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line([0, 10], %)
+  |> line([10, 0], %)
+  |> close(%)
+  |> extrude(5, %)
+const part002 = startSketchOn({
+       plane: {
+         origin: { x: 0, y: 0, z: 0 },
+         x_axis: { x: 1, y: 0, z: 0 },
+         y_axis: { x: 0, y: 1, z: 0 },
+         z_axis: { x: 0, y: 0, z: 1 }
+       }
+     })
+  |> circle([0, 0], 5, %)"#;
+        let program = crate::parser::Parser::new(crate::token::lexer(code).unwrap())
+            .ast()
+            .unwrap();
+        let run = |id_generator: IdGenerator| {
+            let program = program.clone();
+            async move {
+                let ctx = ExecutorContext {
+                    id_generator,
+                    ..mock_context().await
+                };
+                let (memory, commands) = ctx.run_plan_only(program, None).await.unwrap();
+                serde_json::to_string(&(memory, commands)).unwrap()
+            }
+        };
+
+        let output = run(IdGenerator::seeded(42)).await;
+        assert_eq!(output, run(IdGenerator::seeded(42)).await);
+        assert_ne!(output, run(IdGenerator::seeded(43)).await);
+        assert_ne!(run(Default::default()).await, run(Default::default()).await);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_run_incremental() {
+        let engine = crate::engine::conn_plan::EngineConnection::new().await.unwrap();
+        let ctx = ExecutorContext {
+            engine: Arc::new(Box::new(engine.clone())),
+            is_mock: true,
+            ..mock_context().await
+        };
+        // Run the code, and return the types of the commands sent to the engine.
+        let run = |code: &str| {
+            let code = code.to_owned();
+            let ctx = ctx.clone();
+            let engine = engine.clone();
+            async move {
+                let program = crate::parser::Parser::new(crate::token::lexer(&code).unwrap())
+                    .ast()
+                    .unwrap();
+                let memory = ctx.run_incremental(program).await.unwrap();
+                let types: Vec<String> = engine
+                    .take_commands()
+                    .iter()
+                    .map(|(cmd, _)| serde_json::json!(cmd)["type"].as_str().unwrap().to_owned())
+                    .filter(|ty| !matches!(ty.as_str(), "make_plane" | "plane_set_color"))
+                    .collect();
+                (memory, types)
+            }
+        };
+        let sketch = |types: &[&'static str]| -> Vec<&'static str> {
+            let mut sketch = vec![
+                "enable_sketch_mode",
+                "start_path",
+                "move_path_pen",
+                "extend_path",
+                "extend_path",
+                "close_path",
+                "sketch_mode_disable",
+                "extrude",
+                "object_bring_to_front",
+                "solid3d_get_extrusion_face_info",
+            ];
+            sketch.extend(types);
+            sketch
+        };
+
+        let mut code = r#"const width = 10
+const part001 = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line([width, 0], %)
+  |> line([0, 10], %)
+  |> close(%)
+const part002 = extrude(5, part001)
+const part003 = startSketchOn('XZ')
+  |> startProfileAt([0, 0], %)
+  |> line([10, 0], %)
+  |> line([0, 10], %)
+  |> close(%)
+  |> extrude(5, %)"#
+            .to_owned();
+        let (memory, types) = run(&code).await;
+        let mut expected = vec!["scene_clear_all", "set_scene_units"];
+        expected.extend(sketch(&sketch(&[])));
+        assert_eq!(types, expected);
+
+        // Nothing changed, so nothing is sent.
+        let (cached_memory, types) = run(&code).await;
+        assert_eq!(cached_memory, memory);
+        assert!(types.is_empty(), "{types:?}");
+
+        // Only the last part is made again.
+        code = code.replace("extrude(5, %)", "extrude(8, %)");
+        let (_, types) = run(&code).await;
+        assert_eq!(types, sketch(&["remove_scene_objects"]));
+
+        // Extruding the first part again means sketching it again.
+        code = code.replace("extrude(5, part001)", "extrude(6, part001)");
+        let (_, types) = run(&code).await;
+        assert_eq!(types, sketch(&["remove_scene_objects"]));
+
+        // The last part doesn't depend on the width.
+        code = code.replace("const width = 10", "const width = 12");
+        let (_, types) = run(&code).await;
+        assert_eq!(types, sketch(&["remove_scene_objects"]));
+
+        // Statements which moved are executed again.
+        code = code.replace("const width = 12", "const width = 100");
+        let (_, types) = run(&code).await;
+        assert_eq!(types, sketch(&sketch(&["remove_scene_objects"])));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_numbers_with_units() {
+        let ast = r#"const a = 1in
+const b = 2cm + 5mm
+const c = 0.5m
+const d = 1ft
+const e2 = -90deg
+const f = 0.5rad"#;
+        let memory = parse_execute(ast).await.unwrap();
+        for (name, expected) in [("a", 25.4), ("b", 25.0), ("c", 500.0), ("d", 304.8), ("e2", -90.0)] {
+            assert_eq!(
+                serde_json::json!(expected),
+                memory.root.get(name).unwrap().get_json_value().unwrap(),
+                "wrong value for {name}"
+            );
+        }
+        let f = memory.root.get("f").unwrap().get_json_value().unwrap();
+        assert!((f.as_f64().unwrap() - 0.5f64.to_degrees()).abs() < 1e-9);
+
+        // Lengths are converted into the scene's units.
+        let tokens = crate::token::lexer("const a = 254mm").unwrap();
+        let program = crate::parser::Parser::new(tokens).ast().unwrap();
+        let ctx = ExecutorContext {
+            settings: ExecutorSettings {
+                units: crate::settings::types::UnitLength::In,
+                ..Default::default()
+            },
+            ..mock_context().await
+        };
+        let memory = ctx.run(program, None).await.unwrap();
+        assert_eq!(
+            serde_json::json!(10.0),
+            memory.root.get("a").unwrap().get_json_value().unwrap()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_mixing_units() {
+        let result = parse_execute("const a = 10mm + 45deg").await;
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            r#"type: KclErrorDetails { source_ranges: [SourceRange([17, 22])], message: "Expected a length, but this is an angle", call_stack: [] }"#
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_mixing_units_through_function_params() {
+        let ast = r#"fn addAngle = (x) => {
+  return x + 45deg
+}
+const a = addAngle(-90deg)
+const b = addAngle(10mm)"#;
+        let result = parse_execute(ast).await;
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            r#"type: KclErrorDetails { source_ranges: [SourceRange([36, 41])], message: "Expected a length, but this is an angle", call_stack: [StackFrame { function_name: "addAngle", source_range: SourceRange([81, 95]) }] }"#
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_trig_with_units() {
+        let ast = r#"const a = cos(90deg)
+const b = cos(1rad)
+const c = cos(1)
+const angle = 30deg
+const d = sin(angle * 2)
+const t = tan(-45deg)"#;
+        let memory = parse_execute(ast).await.unwrap();
+        for (name, expected) in [
+            ("a", 0.0),
+            ("b", 1f64.cos()),
+            ("c", 1f64.cos()),
+            ("d", 3f64.sqrt() / 2.0),
+            ("t", -1.0),
+        ] {
+            let value = memory.root.get(name).unwrap().get_json_value().unwrap();
+            assert!(
+                (value.as_f64().unwrap() - expected).abs() < 1e-9,
+                "wrong value for {name}: {value}"
+            );
+        }
+
+        let result = parse_execute("const a = cos(10mm)").await;
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            r#"type: KclErrorDetails { source_ranges: [SourceRange([10, 19])], message: "Expected an angle, but this is a length", call_stack: [] }"#
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_destructuring() {
+        let ast = r#"const [x, y] = [1, 2, 3]
+const size = { width: 4, height: 5 }
+const { width, height } = size
+const area = width * height + x + y"#;
+        let memory = parse_execute(ast).await.unwrap();
+        for (name, expected) in [
+            ("x", serde_json::json!(1)),
+            ("y", serde_json::json!(2)),
+            ("width", serde_json::json!(4)),
+            ("height", serde_json::json!(5)),
+            ("area", serde_json::json!(23.0)),
+        ] {
+            assert_eq!(
+                expected,
+                memory.root.get(name).unwrap().get_json_value().unwrap(),
+                "wrong value for {name}"
+            );
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_destructuring_errors() {
+        // The values come in through a parameter, so that only the executor can see their types.
+        for (ast, expected) in [
+            (
+                r#"fn f = (v) => {
+  const [x, y] = v
+  return x
+}
+const a = f([1])"#,
+                r#"semantic: KclErrorDetails { source_ranges: [SourceRange([24, 30])], message: "Expected an array of at least 2 items to destructure, but it only has 1", call_stack: [StackFrame { function_name: "f", source_range: SourceRange([58, 64]) }] }"#,
+            ),
+            (
+                r#"fn f = (v) => {
+  const [x, y] = v
+  return x
+}
+const a = f(3)"#,
+                r#"semantic: KclErrorDetails { source_ranges: [SourceRange([24, 30])], message: "Only an array can be destructured like this, but this is a number", call_stack: [StackFrame { function_name: "f", source_range: SourceRange([58, 62]) }] }"#,
+            ),
+            (
+                r#"fn f = (v) => {
+  const { a, b } = v
+  return a
+}
+const a = f({ a: 1 })"#,
+                r#"undefined value: KclErrorDetails { source_ranges: [SourceRange([29, 30])], message: "Property b not found in object", call_stack: [StackFrame { function_name: "f", source_range: SourceRange([60, 71]) }] }"#,
+            ),
+        ] {
+            let result = parse_execute(ast).await;
+            assert_eq!(result.unwrap_err().to_string(), expected);
+        }
+    }
+
     #[test]
     fn test_assign_args_to_params() {
         // Set up a little framework for this test.
@@ -1927,6 +4738,7 @@ const bracket = startSketchOn('XY')
             MemoryItem::UserVal(UserVal {
                 value: number.into(),
                 meta: Default::default(),
+                ..Default::default()
             })
         }
         fn ident(s: &'static str) -> Identifier {
@@ -1941,6 +4753,7 @@ const bracket = startSketchOn('XY')
                 identifier: ident(s),
                 type_: None,
                 optional: true,
+                default_value: None,
             }
         }
         fn req_param(s: &'static str) -> Parameter {
@@ -1948,6 +4761,7 @@ const bracket = startSketchOn('XY')
                 identifier: ident(s),
                 type_: None,
                 optional: false,
+                default_value: None,
             }
         }
         fn additional_program_memory(items: &[(String, MemoryItem)]) -> ProgramMemory {
@@ -1973,6 +4787,7 @@ const bracket = startSketchOn('XY')
                 Err(KclError::Semantic(KclErrorDetails {
                     source_ranges: vec![SourceRange([0, 0])],
                     message: "Expected 1 arguments, got 0".to_owned(),
+                    ..Default::default()
                 })),
             ),
             (
@@ -1991,6 +4806,7 @@ const bracket = startSketchOn('XY')
                 Err(KclError::Semantic(KclErrorDetails {
                     source_ranges: vec![SourceRange([0, 0])],
                     message: "Expected 1-2 arguments, got 0".to_owned(),
+                    ..Default::default()
                 })),
             ),
             (
@@ -2018,6 +4834,7 @@ const bracket = startSketchOn('XY')
                 Err(KclError::Semantic(KclErrorDetails {
                     source_ranges: vec![SourceRange([0, 0])],
                     message: "Expected 1-2 arguments, got 3".to_owned(),
+                    ..Default::default()
                 })),
             ),
         ] {
@@ -2034,7 +4851,7 @@ const bracket = startSketchOn('XY')
                 },
                 return_type: None,
             };
-            let actual = assign_args_to_params(func_expr, args, ProgramMemory::new());
+            let actual = assign_args_to_params(func_expr, args, Vec::new(), ProgramMemory::new());
             assert_eq!(
                 actual, expected,
                 "failed test '{test_name}':\ngot {actual:?}\nbut expected\n{expected:?}"
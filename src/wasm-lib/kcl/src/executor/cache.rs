@@ -0,0 +1,263 @@
+//! Remembers the result of every top-level statement of the last program executed by
+//! `ExecutorContext::run_incremental`, so when the program is edited, only the statements
+//! affected by the edit have to be executed again.
+//!
+//! A statement's cached result is reused if the statement is unchanged (including its position
+//! in the source) and every name it refers to still has the same value. Geometry gets new IDs
+//! every time it's made, so the statements which depend on a statement which was executed again
+//! are always executed again too.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
+use serde_json::Value as JValue;
+
+use crate::{
+    ast::types::BodyItem,
+    errors::KclWarning,
+    executor::{MemoryItem, ProgramMemory},
+    settings::types::UnitLength,
+};
+
+/// The result of executing a top-level statement.
+#[derive(Debug, Clone)]
+pub(crate) struct CachedStatement {
+    /// The statement which was executed.
+    pub item: BodyItem,
+    /// The value of every name the statement refers to, from just before it was executed.
+    /// Names which weren't defined (e.g. stdlib functions) have no value.
+    pub inputs: Vec<(String, Option<MemoryItem>)>,
+    /// The names the statement added to memory, and their values.
+    pub outputs: Vec<(String, MemoryItem)>,
+    /// The scene objects the statement created.
+    pub objects: Vec<uuid::Uuid>,
+    /// The warnings found while executing the statement.
+    pub warnings: Vec<KclWarning>,
+}
+
+impl CachedStatement {
+    /// Whether this result can be used instead of executing `item` again, given the values of
+    /// the names it refers to now.
+    pub fn is_valid_for(&self, item: &BodyItem, inputs: &[(String, Option<MemoryItem>)]) -> bool {
+        // Imported files can change without the import statement changing.
+        !matches!(item, BodyItem::ImportStatement(_)) && self.item == *item && self.inputs == inputs
+    }
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    /// The units the cached statements were executed with, or `None` if nothing is cached, in
+    /// which case the scene's contents are unknown.
+    units: Option<UnitLength>,
+    statements: Vec<CachedStatement>,
+    /// Scene objects which don't belong to any cached statement, e.g. the ones made by a
+    /// statement which failed. They're removed on the next run.
+    orphans: Vec<uuid::Uuid>,
+    /// Whether a run is recording the scene objects created by each statement.
+    recording: bool,
+    /// The scene objects created since they were last taken.
+    created: Vec<uuid::Uuid>,
+}
+
+/// The results of the last incremental run, shared by every clone of the executor context.
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionCache {
+    state: Arc<Mutex<CacheState>>,
+}
+
+impl ExecutionCache {
+    /// Forget every cached result, so the next incremental run clears the scene and executes the
+    /// whole program. Use this after the scene was changed by something else.
+    pub fn clear(&self) {
+        *self.state.lock().unwrap() = Default::default();
+    }
+
+    /// Start an incremental run. Returns the cached statements and the orphaned scene objects,
+    /// or `None` if the cache can't be used with these units and the scene has to be cleared.
+    pub(crate) fn begin(&self, units: &UnitLength) -> Option<(Vec<CachedStatement>, Vec<uuid::Uuid>)> {
+        let mut state = self.state.lock().unwrap();
+        state.recording = true;
+        state.created.clear();
+        let cached = state.units.as_ref() == Some(units);
+        let statements = std::mem::take(&mut state.statements);
+        let orphans = std::mem::take(&mut state.orphans);
+        state.units = None;
+        cached.then_some((statements, orphans))
+    }
+
+    /// Finish an incremental run, caching the results of its statements.
+    pub(crate) fn finish(&self, units: UnitLength, statements: Vec<CachedStatement>, orphans: Vec<uuid::Uuid>) {
+        *self.state.lock().unwrap() = CacheState {
+            units: Some(units),
+            statements,
+            orphans,
+            recording: false,
+            created: Vec::new(),
+        };
+    }
+
+    /// Note that a command created these scene objects. Does nothing outside incremental runs.
+    pub(crate) fn record_objects(&self, ids: Vec<uuid::Uuid>) {
+        let mut state = self.state.lock().unwrap();
+        if state.recording {
+            state.created.extend(ids);
+        }
+    }
+
+    /// The scene objects created since this was last called.
+    pub(crate) fn take_created(&self) -> Vec<uuid::Uuid> {
+        std::mem::take(&mut self.state.lock().unwrap().created)
+    }
+}
+
+/// The value of every name a statement refers to, in the given memory.
+pub(crate) fn inputs(item: &BodyItem, memory: &ProgramMemory) -> Vec<(String, Option<MemoryItem>)> {
+    referenced_names(item)
+        .into_iter()
+        .map(|name| {
+            let value = memory.get(&name, Default::default()).ok().cloned();
+            (name, value)
+        })
+        .collect()
+}
+
+/// Every name used anywhere in a statement, in sorted order.
+/// This includes names which don't refer to anything outside the statement (like function
+/// parameters), which only means the statement is executed again more often than it has to be.
+pub(crate) fn referenced_names(item: &BodyItem) -> Vec<String> {
+    fn walk(value: &JValue, names: &mut HashSet<String>) {
+        match value {
+            JValue::Array(items) => {
+                for item in items {
+                    walk(item, names);
+                }
+            }
+            JValue::Object(properties) => {
+                if let (Some("Identifier"), Some(JValue::String(name))) =
+                    (properties.get("type").and_then(JValue::as_str), properties.get("name"))
+                {
+                    names.insert(name.clone());
+                }
+                for value in properties.values() {
+                    walk(value, names);
+                }
+            }
+            JValue::Null | JValue::Bool(_) | JValue::Number(_) | JValue::String(_) => {}
+        }
+    }
+
+    let mut names = HashSet::new();
+    walk(&serde_json::json!(item), &mut names);
+    let mut names: Vec<String> = names.into_iter().collect();
+    names.sort();
+    names
+}
+
+/// Every UUID in a JSON value.
+fn collect_ids(value: &JValue, ids: &mut HashSet<uuid::Uuid>) {
+    match value {
+        JValue::String(s) => {
+            if let Ok(id) = s.parse() {
+                ids.insert(id);
+            }
+        }
+        JValue::Array(items) => {
+            for item in items {
+                collect_ids(item, ids);
+            }
+        }
+        JValue::Object(properties) => {
+            for value in properties.values() {
+                collect_ids(value, ids);
+            }
+        }
+        JValue::Null | JValue::Bool(_) | JValue::Number(_) => {}
+    }
+}
+
+/// Find a reused statement which made scene objects that a statement about to be executed
+/// refers to, either directly or through the functions it calls. Executing the statement might
+/// modify those objects (e.g. extruding a sketch), so the statement which made them has to be
+/// executed again as well, instead of being reused.
+///
+/// `statements` are the statements before the one about to be executed, and `reused` says which
+/// of them were reused.
+pub(crate) fn reused_owner(
+    inputs: &[(String, Option<MemoryItem>)],
+    statements: &[CachedStatement],
+    reused: &[bool],
+) -> Option<usize> {
+    let owners: HashMap<uuid::Uuid, usize> = statements
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| reused[*index])
+        .flat_map(|(index, statement)| statement.objects.iter().map(move |id| (*id, index)))
+        .collect();
+    if owners.is_empty() {
+        return None;
+    }
+    let declared_by: HashMap<&str, usize> = statements
+        .iter()
+        .enumerate()
+        .flat_map(|(index, statement)| statement.outputs.iter().map(move |(name, _)| (name.as_str(), index)))
+        .collect();
+
+    let mut pending: Vec<&(String, Option<MemoryItem>)> = inputs.iter().collect();
+    let mut visited = HashSet::new();
+    while let Some((name, value)) = pending.pop() {
+        let Some(value) = value else {
+            continue;
+        };
+        let mut ids = HashSet::new();
+        collect_ids(&serde_json::json!(value), &mut ids);
+        if let Some(owner) = ids.iter().find_map(|id| owners.get(id)) {
+            return Some(*owner);
+        }
+        // A function's body can refer to other names, which are the inputs of the statement
+        // which declared it.
+        if let MemoryItem::Function { .. } = value {
+            if let Some(index) = declared_by.get(name.as_str()) {
+                if visited.insert(*index) {
+                    pending.extend(&statements[*index].inputs);
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(code: &str) -> crate::ast::types::Program {
+        crate::parser::Parser::new(crate::token::lexer(code).unwrap())
+            .ast()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_referenced_names() {
+        let program = parse(
+            r#"const part = startSketchOn('XY')
+  |> startProfileAt([0, size], %)
+  |> line([width, 0], %)
+  |> extrude(depth * 2, %)"#,
+        );
+        assert_eq!(
+            referenced_names(&program.body[0]),
+            vec![
+                "depth",
+                "extrude",
+                "line",
+                "part",
+                "size",
+                "startProfileAt",
+                "startSketchOn",
+                "width"
+            ]
+        );
+    }
+}
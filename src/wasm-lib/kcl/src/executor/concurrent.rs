@@ -0,0 +1,114 @@
+//! Splits a program's top-level statements into groups which don't depend on each other, so
+//! `ExecutorContext::inner_execute` can execute the groups at the same time. While one group
+//! waits for the engine to answer, the others keep adding commands to the batch.
+//!
+//! A statement depends on an earlier one if it uses (or declares again) a name the earlier one
+//! declared, or if both import the same file. Statements which don't share any names can't see
+//! each other's results, so executing them in a different order gives the same memory. Within a
+//! group, statements are executed in order, so each group's engine commands are still sent in the
+//! order they were made.
+
+use std::collections::HashMap;
+
+use crate::{ast::types::BodyItem, executor::cache::referenced_names};
+
+/// The names a top-level statement adds to memory.
+fn declared_names(item: &BodyItem) -> Vec<&str> {
+    match item {
+        BodyItem::VariableDeclaration(variable_declaration) => variable_declaration
+            .declarations
+            .iter()
+            .flat_map(|declaration| declaration.id.names())
+            .map(|id| id.name.as_str())
+            .collect(),
+        BodyItem::ImportStatement(import_statement) => import_statement
+            .items
+            .iter()
+            .map(|item| item.local_name().name.as_str())
+            .collect(),
+        BodyItem::ExpressionStatement(_) | BodyItem::ReturnStatement(_) | BodyItem::ForStatement(_) => Vec::new(),
+    }
+}
+
+/// Group the indices of statements which depend on each other, directly or through other
+/// statements. Each group is in order, and the groups are ordered by their first statement.
+pub(crate) fn independent_groups(body: &[BodyItem]) -> Vec<Vec<usize>> {
+    // Every statement starts in its own group, and is merged with the groups it depends on.
+    let mut group_of: Vec<usize> = (0..body.len()).collect();
+    fn find(group_of: &mut [usize], index: usize) -> usize {
+        let mut root = index;
+        while group_of[root] != root {
+            root = group_of[root];
+        }
+        group_of[index] = root;
+        root
+    }
+    fn merge(group_of: &mut [usize], a: usize, b: usize) {
+        let (a, b) = (find(group_of, a), find(group_of, b));
+        group_of[a.max(b)] = a.min(b);
+    }
+
+    let mut declared_by: HashMap<&str, usize> = HashMap::new();
+    let mut imported_by: HashMap<&str, usize> = HashMap::new();
+    for (index, item) in body.iter().enumerate() {
+        for name in referenced_names(item) {
+            if let Some(declarer) = declared_by.get(name.as_str()) {
+                merge(&mut group_of, *declarer, index);
+            }
+        }
+        // Importing a file executes it, which should only happen once.
+        if let BodyItem::ImportStatement(import_statement) = item {
+            if let Some(importer) = imported_by.insert(&import_statement.path, index) {
+                merge(&mut group_of, importer, index);
+            }
+        }
+        for name in declared_names(item) {
+            declared_by.insert(name, index);
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut group_index: HashMap<usize, usize> = HashMap::new();
+    for index in 0..body.len() {
+        let root = find(&mut group_of, index);
+        let group = *group_index.entry(root).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[group].push(index);
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_independent_groups() {
+        let program = crate::parser::Parser::new(
+            crate::token::lexer(
+                r#"const width = 10
+const a = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line([width, 0], %)
+const b = startSketchOn('XZ')
+  |> startProfileAt([0, 0], %)
+  |> line([0, 5], %)
+const c = extrude(5, a)
+import thing from "thing.kcl"
+import other from "thing.kcl"
+const d = extrude(2, b)
+"#,
+            )
+            .unwrap(),
+        )
+        .ast()
+        .unwrap();
+
+        assert_eq!(
+            independent_groups(&program.body),
+            vec![vec![0, 1, 3], vec![2, 6], vec![4, 5]]
+        );
+    }
+}
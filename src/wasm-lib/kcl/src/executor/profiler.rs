@@ -0,0 +1,305 @@
+//! Measures where a run spends its time: how long each statement takes, how many commands it
+//! sends to the engine, and how long the engine takes to answer each batch of commands.
+//!
+//! Set `ExecutorContext::profiler` before a run, then take the `Profile` afterwards. It can be
+//! saved as JSON, or as a Chrome trace to look at in `chrome://tracing` or Perfetto.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::executor::SourceRange;
+
+/// Something which happened during a run, and how long it took.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileSpan {
+    pub source_range: SourceRange,
+    /// When it started, in microseconds since the profiler was made.
+    pub start_us: u64,
+    pub duration_us: u64,
+    /// For a statement, how many commands it sent to the engine (including the ones sent by the
+    /// functions it called). For a flush, how many commands were in the batch.
+    pub commands: usize,
+}
+
+/// How many commands a call sent to the engine.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandCount {
+    pub source_range: SourceRange,
+    pub commands: usize,
+}
+
+/// A statement which took a long time, summed over every time it ran (e.g. in a loop, or in a
+/// function called more than once).
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotSpot {
+    pub source_range: SourceRange,
+    pub duration_us: u64,
+    pub commands: usize,
+    /// How many times the statement ran.
+    pub runs: usize,
+}
+
+/// Everything the profiler measured.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    /// Every statement executed, in the order they finished. Statements in function bodies are
+    /// included, inside the statement which called the function.
+    pub statements: Vec<ProfileSpan>,
+    /// Every time the executor waited for the engine to answer a batch of commands.
+    pub flushes: Vec<ProfileSpan>,
+    /// The commands sent by each call, in source order.
+    pub commands: Vec<CommandCount>,
+}
+
+impl Profile {
+    /// The statements which took the longest, slowest first.
+    pub fn hot_spots(&self) -> Vec<HotSpot> {
+        let mut by_range: HashMap<SourceRange, HotSpot> = HashMap::new();
+        for span in &self.statements {
+            let hot_spot = by_range.entry(span.source_range).or_insert(HotSpot {
+                source_range: span.source_range,
+                duration_us: 0,
+                commands: 0,
+                runs: 0,
+            });
+            hot_spot.duration_us += span.duration_us;
+            hot_spot.commands += span.commands;
+            hot_spot.runs += 1;
+        }
+        let mut hot_spots: Vec<HotSpot> = by_range.into_values().collect();
+        hot_spots.sort_by(|a, b| {
+            b.duration_us
+                .cmp(&a.duration_us)
+                .then(a.source_range.start().cmp(&b.source_range.start()))
+        });
+        hot_spots
+    }
+
+    /// Convert to the Chrome trace event format, naming each statement after its code.
+    pub fn to_chrome_trace(&self, code: &str) -> serde_json::Value {
+        let event = |category: &str, name: String, span: &ProfileSpan| {
+            serde_json::json!({
+                "name": name,
+                "cat": category,
+                "ph": "X",
+                "ts": span.start_us,
+                "dur": span.duration_us,
+                "pid": 1,
+                "tid": 1,
+                "args": {
+                    "sourceRange": span.source_range,
+                    "commands": span.commands,
+                },
+            })
+        };
+        let mut events: Vec<(u64, serde_json::Value)> = self
+            .statements
+            .iter()
+            .map(|span| {
+                (
+                    span.start_us,
+                    event("statement", snippet(code, span.source_range), span),
+                )
+            })
+            .chain(
+                self.flushes
+                    .iter()
+                    .map(|span| (span.start_us, event("engine", "flush_batch".to_owned(), span))),
+            )
+            .collect();
+        events.sort_by_key(|(start, _)| *start);
+
+        serde_json::json!({
+            "traceEvents": events.into_iter().map(|(_, event)| event).collect::<Vec<_>>(),
+            "displayTimeUnit": "ms",
+        })
+    }
+}
+
+/// The first line of the code at a source range, shortened if it's long.
+fn snippet(code: &str, source_range: SourceRange) -> String {
+    const MAX_CHARS: usize = 60;
+
+    let Some(text) = code.get(source_range.start()..source_range.end()) else {
+        return format!("{:?}", source_range.0);
+    };
+    let line = text.lines().next().unwrap_or_default().trim();
+    if line.chars().count() > MAX_CHARS {
+        format!("{}…", line.chars().take(MAX_CHARS).collect::<String>())
+    } else {
+        line.to_owned()
+    }
+}
+
+/// When a span started, returned by `Profiler::start`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SpanStart {
+    at: chrono::DateTime<chrono::Utc>,
+    commands: usize,
+}
+
+#[derive(Debug)]
+struct ProfilerState {
+    started: chrono::DateTime<chrono::Utc>,
+    /// Every command sent so far.
+    commands: usize,
+    statements: Vec<ProfileSpan>,
+    flushes: Vec<ProfileSpan>,
+    commands_by_range: HashMap<SourceRange, usize>,
+}
+
+/// Records a `Profile`, shared by every clone of the executor context.
+#[derive(Debug, Clone)]
+pub struct Profiler {
+    state: Arc<Mutex<ProfilerState>>,
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(ProfilerState {
+                started: chrono::Utc::now(),
+                commands: 0,
+                statements: Vec::new(),
+                flushes: Vec::new(),
+                commands_by_range: HashMap::new(),
+            })),
+        }
+    }
+
+    /// Everything measured so far.
+    pub fn profile(&self) -> Profile {
+        let state = self.state.lock().unwrap();
+        let mut commands: Vec<CommandCount> = state
+            .commands_by_range
+            .iter()
+            .map(|(source_range, commands)| CommandCount {
+                source_range: *source_range,
+                commands: *commands,
+            })
+            .collect();
+        commands.sort_by_key(|count| (count.source_range.start(), count.source_range.end()));
+        Profile {
+            statements: state.statements.clone(),
+            flushes: state.flushes.clone(),
+            commands,
+        }
+    }
+
+    pub(crate) fn start(&self) -> SpanStart {
+        SpanStart {
+            at: chrono::Utc::now(),
+            commands: self.state.lock().unwrap().commands,
+        }
+    }
+
+    /// Record that the statement at `source_range`, which started at `start`, has finished.
+    pub(crate) fn end_statement(&self, start: SpanStart, source_range: SourceRange) {
+        let mut state = self.state.lock().unwrap();
+        let span = state.span(start, source_range, state.commands - start.commands);
+        state.statements.push(span);
+    }
+
+    /// Record that the engine answered a batch of `commands` commands, which was sent at `start`.
+    pub(crate) fn end_flush(&self, start: SpanStart, source_range: SourceRange, commands: usize) {
+        let mut state = self.state.lock().unwrap();
+        let span = state.span(start, source_range, commands);
+        state.flushes.push(span);
+    }
+
+    /// Count a command sent to the engine by the call at `source_range`.
+    pub(crate) fn count_command(&self, source_range: SourceRange) {
+        let mut state = self.state.lock().unwrap();
+        state.commands += 1;
+        *state.commands_by_range.entry(source_range).or_default() += 1;
+    }
+}
+
+impl ProfilerState {
+    fn span(&self, start: SpanStart, source_range: SourceRange, commands: usize) -> ProfileSpan {
+        let micros = |duration: chrono::Duration| duration.num_microseconds().unwrap_or_default().max(0) as u64;
+        ProfileSpan {
+            source_range,
+            start_us: micros(start.at - self.started),
+            duration_us: micros(chrono::Utc::now() - start.at),
+            commands,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(source_range: [usize; 2], start_us: u64, duration_us: u64, commands: usize) -> ProfileSpan {
+        ProfileSpan {
+            source_range: SourceRange(source_range),
+            start_us,
+            duration_us,
+            commands,
+        }
+    }
+
+    #[test]
+    fn test_hot_spots_and_chrome_trace() {
+        let code = "const a = 1\nconst b = startSketchOn('XY')\n  |> startProfileAt([0, 0], %)";
+        let profile = Profile {
+            statements: vec![
+                span([0, 11], 0, 5, 0),
+                span([12, 68], 5, 40, 3),
+                span([0, 11], 50, 10, 0),
+            ],
+            flushes: vec![span([12, 68], 20, 25, 2)],
+            commands: Vec::new(),
+        };
+
+        assert_eq!(
+            profile.hot_spots(),
+            vec![
+                HotSpot {
+                    source_range: SourceRange([12, 68]),
+                    duration_us: 40,
+                    commands: 3,
+                    runs: 1,
+                },
+                HotSpot {
+                    source_range: SourceRange([0, 11]),
+                    duration_us: 15,
+                    commands: 0,
+                    runs: 2,
+                },
+            ]
+        );
+
+        let trace = profile.to_chrome_trace(code);
+        let events = trace["traceEvents"].as_array().unwrap();
+        let names: Vec<&str> = events.iter().map(|event| event["name"].as_str().unwrap()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "const a = 1",
+                "const b = startSketchOn('XY')",
+                "flush_batch",
+                "const a = 1"
+            ]
+        );
+        assert_eq!(events[2]["cat"], "engine");
+        assert_eq!(events[2]["ts"], 20);
+        assert_eq!(events[2]["dur"], 25);
+        assert_eq!(events[2]["args"]["commands"], 2);
+    }
+}
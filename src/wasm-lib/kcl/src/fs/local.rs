@@ -33,6 +33,7 @@ impl FileSystem for FileManager {
             KclError::Engine(KclErrorDetails {
                 message: format!("Failed to read file `{}`: {}", path.as_ref().display(), e),
                 source_ranges: vec![source_range],
+                ..Default::default()
             })
         })
     }
@@ -49,6 +50,7 @@ impl FileSystem for FileManager {
                 Err(KclError::Engine(KclErrorDetails {
                     message: format!("Failed to check if file `{}` exists: {}", path.as_ref().display(), e),
                     source_ranges: vec![source_range],
+                    ..Default::default()
                 }))
             }
         })
@@ -71,6 +73,7 @@ impl FileSystem for FileManager {
                 KclError::Engine(KclErrorDetails {
                     message: format!("Failed to read directory `{}`: {}", path.display(), e),
                     source_ranges: vec![source_range],
+                    ..Default::default()
                 })
             })?;
 
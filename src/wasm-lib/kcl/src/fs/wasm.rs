@@ -54,6 +54,7 @@ impl FileSystem for FileManager {
                         KclError::Engine(KclErrorDetails {
                             message: "Failed to convert path to string".to_string(),
                             source_ranges: vec![source_range],
+                            ..Default::default()
                         })
                     })?
                     .to_string(),
@@ -62,6 +63,7 @@ impl FileSystem for FileManager {
                 KclError::Engine(KclErrorDetails {
                     message: e.to_string().into(),
                     source_ranges: vec![source_range],
+                    ..Default::default()
                 })
             })?;
 
@@ -69,6 +71,7 @@ impl FileSystem for FileManager {
             KclError::Engine(KclErrorDetails {
                 message: format!("Failed to wait for promise from engine: {:?}", e),
                 source_ranges: vec![source_range],
+                ..Default::default()
             })
         })?;
 
@@ -92,6 +95,7 @@ impl FileSystem for FileManager {
                         KclError::Engine(KclErrorDetails {
                             message: "Failed to convert path to string".to_string(),
                             source_ranges: vec![source_range],
+                            ..Default::default()
                         })
                     })?
                     .to_string(),
@@ -100,6 +104,7 @@ impl FileSystem for FileManager {
                 KclError::Engine(KclErrorDetails {
                     message: e.to_string().into(),
                     source_ranges: vec![source_range],
+                    ..Default::default()
                 })
             })?;
 
@@ -107,6 +112,7 @@ impl FileSystem for FileManager {
             KclError::Engine(KclErrorDetails {
                 message: format!("Failed to wait for promise from engine: {:?}", e),
                 source_ranges: vec![source_range],
+                ..Default::default()
             })
         })?;
 
@@ -114,6 +120,7 @@ impl FileSystem for FileManager {
             KclError::Engine(KclErrorDetails {
                 message: "Failed to convert value to bool".to_string(),
                 source_ranges: vec![source_range],
+                ..Default::default()
             })
         })?;
 
@@ -134,6 +141,7 @@ impl FileSystem for FileManager {
                         KclError::Engine(KclErrorDetails {
                             message: "Failed to convert path to string".to_string(),
                             source_ranges: vec![source_range],
+                            ..Default::default()
                         })
                     })?
                     .to_string(),
@@ -142,6 +150,7 @@ impl FileSystem for FileManager {
                 KclError::Engine(KclErrorDetails {
                     message: e.to_string().into(),
                     source_ranges: vec![source_range],
+                    ..Default::default()
                 })
             })?;
 
@@ -149,6 +158,7 @@ impl FileSystem for FileManager {
             KclError::Engine(KclErrorDetails {
                 message: format!("Failed to wait for promise from javascript: {:?}", e),
                 source_ranges: vec![source_range],
+                ..Default::default()
             })
         })?;
 
@@ -156,6 +166,7 @@ impl FileSystem for FileManager {
             KclError::Engine(KclErrorDetails {
                 message: format!("Failed to get string from response from javascript: `{:?}`", value),
                 source_ranges: vec![source_range],
+                ..Default::default()
             })
         })?;
 
@@ -163,6 +174,7 @@ impl FileSystem for FileManager {
             KclError::Engine(KclErrorDetails {
                 message: format!("Failed to parse json from javascript: `{}` `{:?}`", s, e),
                 source_ranges: vec![source_range],
+                ..Default::default()
             })
         })?;
 
@@ -17,5 +17,6 @@ pub mod settings;
 pub mod std;
 pub mod thread;
 pub mod token;
+pub mod typecheck;
 #[cfg(target_arch = "wasm32")]
 pub mod wasm;
@@ -17,6 +17,9 @@ use crate::{
     thread::{JoinHandle, Thread},
 };
 
+/// How long to wait for a cancelled run to stop by itself before aborting it.
+const CANCEL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+
 #[derive(Clone)]
 pub struct InnerHandle(Arc<JoinHandle>);
 
@@ -32,6 +35,11 @@ impl InnerHandle {
     pub fn cancel(&self) {
         self.0.abort();
     }
+
+    /// Wait for the handle's task to finish.
+    pub async fn finished(&self) {
+        self.0.finished().await
+    }
 }
 
 #[derive(Clone)]
@@ -99,12 +107,23 @@ where
     /// On change event.
     async fn inner_on_change(&self, params: TextDocumentItem, force: bool);
 
-    /// Check if the file has diagnostics.
+    /// Ask the task handling the last change to stop soon, if this backend knows how.
+    /// Returns false if it doesn't, in which case the task is aborted instead.
+    async fn cancel_current_run(&self) -> bool {
+        false
+    }
+
+    /// Check if the file has errors. Warnings don't count, since they'd still be there if the
+    /// file was processed again.
     async fn has_diagnostics(&self, uri: &str) -> bool {
         if let Some(tower_lsp::lsp_types::DocumentDiagnosticReport::Full(diagnostics)) =
             self.current_diagnostics_map().get(uri).await
         {
-            !diagnostics.full_document_diagnostic_report.items.is_empty()
+            diagnostics
+                .full_document_diagnostic_report
+                .items
+                .iter()
+                .any(|diagnostic| diagnostic.severity == Some(tower_lsp::lsp_types::DiagnosticSeverity::ERROR))
         } else {
             false
         }
@@ -123,8 +142,19 @@ where
         // Check if we already have a handle running.
         if let Some(current_handle) = self.current_handle().await {
             self.set_current_handle(None).await;
-            // Drop that handle to cancel it.
-            current_handle.cancel();
+            if self.cancel_current_run().await {
+                // Wait for it to stop by itself, so it doesn't leave the engine half way through a
+                // batch of commands. If it's stuck waiting on the engine, stop it anyway.
+                if tokio::time::timeout(CANCEL_TIMEOUT, current_handle.finished())
+                    .await
+                    .is_err()
+                {
+                    current_handle.cancel();
+                }
+            } else {
+                // Drop that handle to cancel it.
+                current_handle.cancel();
+            }
         }
 
         let cloned = self.clone();
@@ -141,6 +141,7 @@ impl Backend {
             prompt: Some(prompt.clone()),
             suffix: Some(suffix.clone()),
             max_tokens: Some(500),
+            model_version: None,
             temperature: Some(1.0),
             top_p: Some(1.0),
             // We only handle one completion at a time, for now so don't even waste the tokens.
@@ -160,7 +161,7 @@ impl Backend {
 
         let resp = self
             .zoo_client
-            .ai()
+            .ml()
             .create_kcl_code_completions(&body)
             .await
             .map_err(|err| Error {
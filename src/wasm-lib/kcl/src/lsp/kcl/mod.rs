@@ -13,16 +13,17 @@ use sha2::Digest;
 use tower_lsp::{
     jsonrpc::Result as RpcResult,
     lsp_types::{
-        CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams, CompletionResponse, CreateFilesParams,
-        DeleteFilesParams, DiagnosticOptions, DiagnosticServerCapabilities, DidChangeConfigurationParams,
-        DidChangeTextDocumentParams, DidChangeWatchedFilesParams, DidChangeWorkspaceFoldersParams,
-        DidCloseTextDocumentParams, DidOpenTextDocumentParams, DidSaveTextDocumentParams, DocumentDiagnosticParams,
-        DocumentDiagnosticReport, DocumentDiagnosticReportResult, DocumentFilter, DocumentFormattingParams,
-        DocumentSymbol, DocumentSymbolParams, DocumentSymbolResponse, Documentation, FoldingRange, FoldingRangeParams,
-        FoldingRangeProviderCapability, FullDocumentDiagnosticReport, Hover, HoverContents, HoverParams,
-        HoverProviderCapability, InitializeParams, InitializeResult, InitializedParams, InlayHint, InlayHintParams,
-        InsertTextFormat, MarkupContent, MarkupKind, MessageType, OneOf, Position, RelatedFullDocumentDiagnosticReport,
-        RenameFilesParams, RenameParams, SemanticToken, SemanticTokenType, SemanticTokens, SemanticTokensFullOptions,
+        CodeLens, CodeLensOptions, CodeLensParams, Command, CompletionItem, CompletionItemKind, CompletionOptions,
+        CompletionParams, CompletionResponse, CreateFilesParams, DeleteFilesParams, Diagnostic, DiagnosticOptions,
+        DiagnosticServerCapabilities, DidChangeConfigurationParams, DidChangeTextDocumentParams,
+        DidChangeWatchedFilesParams, DidChangeWorkspaceFoldersParams, DidCloseTextDocumentParams,
+        DidOpenTextDocumentParams, DidSaveTextDocumentParams, DocumentDiagnosticParams, DocumentDiagnosticReport,
+        DocumentDiagnosticReportResult, DocumentFilter, DocumentFormattingParams, DocumentSymbol, DocumentSymbolParams,
+        DocumentSymbolResponse, Documentation, FoldingRange, FoldingRangeParams, FoldingRangeProviderCapability,
+        FullDocumentDiagnosticReport, Hover, HoverContents, HoverParams, HoverProviderCapability, InitializeParams,
+        InitializeResult, InitializedParams, InlayHint, InlayHintParams, InsertTextFormat, MarkupContent, MarkupKind,
+        MessageType, OneOf, ParameterLabel, Position, Range, RelatedFullDocumentDiagnosticReport, RenameFilesParams,
+        RenameParams, SemanticToken, SemanticTokenType, SemanticTokens, SemanticTokensFullOptions,
         SemanticTokensLegend, SemanticTokensOptions, SemanticTokensParams, SemanticTokensRegistrationOptions,
         SemanticTokensResult, SemanticTokensServerCapabilities, ServerCapabilities, SignatureHelp,
         SignatureHelpOptions, SignatureHelpParams, StaticRegistrationOptions, TextDocumentItem,
@@ -36,7 +37,7 @@ use tower_lsp::{
 use super::backend::{InnerHandle, UpdateHandle};
 use crate::{
     ast::types::VariableKind,
-    errors::KclError,
+    errors::{KclError, KclWarning},
     executor::SourceRange,
     lsp::{backend::Backend as _, safemap::SafeMap},
     parser::PIPE_OPERATOR,
@@ -64,6 +65,8 @@ pub struct Backend {
     pub fs: Arc<crate::fs::FileManager>,
     /// The workspace folders.
     pub workspace_folders: SafeMap<String, WorkspaceFolder>,
+    /// The standard library, shared with the executor context.
+    pub stdlib: Arc<crate::std::StdLib>,
     /// The stdlib completions for the language.
     pub stdlib_completions: HashMap<String, CompletionItem>,
     /// The stdlib signatures for the language.
@@ -84,6 +87,8 @@ pub struct Backend {
     pub symbols_map: SafeMap<String, Vec<DocumentSymbol>>,
     /// Semantic tokens map.
     pub semantic_tokens_map: SafeMap<String, Vec<SemanticToken>>,
+    /// Profiles of the last time each file was executed, to show its slowest statements.
+    pub profile_map: SafeMap<String, crate::executor::Profile>,
     /// The Zoo API client.
     pub zoo_client: kittycad::Client,
     /// If we can send telemetry for this user.
@@ -248,17 +253,35 @@ impl crate::lsp::backend::Backend for Backend {
                 .await;
         }
 
+        // Check the types before executing, so we can report every type error at once, even if
+        // we can't execute.
+        let type_errors = crate::typecheck::check_program(&ast, &self.stdlib, &crate::executor::ProgramMemory::new());
+        if !type_errors.is_empty() {
+            self.add_all_to_diagnostics(&params, type_errors).await;
+            return;
+        }
+
         // Execute the code if we have an executor context.
         // This function automatically executes if we should & updates the diagnostics if we got
         // errors.
-        let result = self.execute(&params, ast).await;
-        if result.is_err() {
+        let Ok(warnings) = self.execute(&params, ast).await else {
             // We return early because we got errors, and we don't want to clear the diagnostics.
             return;
-        }
+        };
 
-        // Lets update the diagnostics, since we got no errors.
-        self.clear_diagnostics(&params.uri).await;
+        // Lets update the diagnostics, since we got no errors. Only the warnings are left.
+        let diagnostics = warnings
+            .iter()
+            .map(|warning| warning.to_lsp_diagnostic(&params.text))
+            .collect();
+        self.set_diagnostics(&params.uri, diagnostics).await;
+    }
+
+    async fn cancel_current_run(&self) -> bool {
+        if let Some(executor_ctx) = self.executor_ctx().await {
+            executor_ctx.cancel.cancel();
+        }
+        true
     }
 }
 
@@ -357,29 +380,20 @@ impl Backend {
     }
 
     async fn add_to_diagnostics(&self, params: &TextDocumentItem, err: KclError) {
-        let diagnostic = err.to_lsp_diagnostic(&params.text);
-        // We got errors, update the diagnostics.
-        self.diagnostics_map
-            .insert(
-                params.uri.to_string(),
-                DocumentDiagnosticReport::Full(RelatedFullDocumentDiagnosticReport {
-                    related_documents: None,
-                    full_document_diagnostic_report: FullDocumentDiagnosticReport {
-                        result_id: None,
-                        items: vec![diagnostic.clone()],
-                    },
-                }),
-            )
-            .await;
+        self.add_all_to_diagnostics(params, vec![err]).await;
+    }
 
-        // Publish the diagnostic.
-        // If the client supports it.
-        self.client
-            .publish_diagnostics(params.uri.clone(), vec![diagnostic], None)
-            .await;
+    async fn add_all_to_diagnostics(&self, params: &TextDocumentItem, errs: Vec<KclError>) {
+        let diagnostics: Vec<_> = errs
+            .iter()
+            .map(|err| err.to_lsp_diagnostic(&params.text, &params.uri))
+            .collect();
+        // We got errors, update the diagnostics.
+        self.set_diagnostics(&params.uri, diagnostics).await;
     }
 
-    async fn clear_diagnostics(&self, uri: &url::Url) {
+    /// Replace the diagnostics for a file, and publish them.
+    async fn set_diagnostics(&self, uri: &url::Url, diagnostics: Vec<Diagnostic>) {
         self.diagnostics_map
             .insert(
                 uri.to_string(),
@@ -387,56 +401,88 @@ impl Backend {
                     related_documents: None,
                     full_document_diagnostic_report: FullDocumentDiagnosticReport {
                         result_id: None,
-                        items: vec![],
+                        items: diagnostics.clone(),
                     },
                 }),
             )
             .await;
 
-        // Publish the diagnostic, we reset it here so the client knows the code compiles now.
+        // Publish the diagnostics.
         // If the client supports it.
-        self.client.publish_diagnostics(uri.clone(), vec![], None).await;
+        self.client.publish_diagnostics(uri.clone(), diagnostics, None).await;
     }
 
-    async fn execute(&self, params: &TextDocumentItem, ast: crate::ast::types::Program) -> Result<()> {
+    /// Execute the code, returning the warnings it found.
+    async fn execute(&self, params: &TextDocumentItem, ast: crate::ast::types::Program) -> Result<Vec<KclWarning>> {
         // Check if we can execute.
         if !self.can_execute().await {
-            return Ok(());
+            return Ok(Vec::new());
         }
 
         // Execute the code if we have an executor context.
         let Some(executor_ctx) = self.executor_ctx().await else {
-            return Ok(());
+            return Ok(Vec::new());
         };
 
         if !self.is_initialized().await {
             // We are not initialized yet.
-            return Ok(());
+            return Ok(Vec::new());
         }
 
-        // Clear the scene, before we execute so it's not fugly as shit.
-        executor_ctx.engine.clear_scene(SourceRange::default()).await?;
+        // Give this run its own cancellation token, so cancelling it doesn't cancel the next one,
+        // and its own profiler.
+        let mut executor_ctx = crate::executor::ExecutorContext {
+            cancel: Default::default(),
+            profiler: Some(Default::default()),
+            ..executor_ctx
+        };
+        if let Some(dir) = document_directory(&params.uri) {
+            executor_ctx.modules.set_project_directory(dir);
+        }
+        self.set_executor_ctx(executor_ctx.clone()).await;
 
-        let memory = match executor_ctx.run(ast, None).await {
+        // Only execute the statements which changed since the last time, so the scene doesn't
+        // have to be cleared and made again from scratch on every keystroke.
+        let memory = match executor_ctx.run_incremental(ast).await {
             Ok(memory) => memory,
             Err(err) => {
-                self.add_to_diagnostics(params, err).await;
+                // A newer change cancelled this run, so the error is about code which is gone.
+                if executor_ctx.cancel.is_cancelled() {
+                    return Err(anyhow::anyhow!("execution was cancelled"));
+                }
+                // Show the warnings found before the error too.
+                let diagnostics = std::iter::once(err.to_lsp_diagnostic(&params.text, &params.uri))
+                    .chain(
+                        executor_ctx
+                            .warnings
+                            .all()
+                            .iter()
+                            .map(|warning| warning.to_lsp_diagnostic(&params.text)),
+                    )
+                    .collect();
+                self.set_diagnostics(&params.uri, diagnostics).await;
 
                 // Since we already published the diagnostics we don't really care about the error
                 // string.
                 return Err(anyhow::anyhow!("failed to execute code"));
             }
         };
+        let profile = executor_ctx.profiler.as_ref().map(crate::executor::Profiler::profile);
         drop(executor_ctx);
 
         self.memory_map.insert(params.uri.to_string(), memory.clone()).await;
+        if let Some(profile) = profile {
+            self.profile_map.insert(params.uri.to_string(), profile).await;
+        }
+
+        let warnings = memory.warnings.clone();
 
         // Send the notification to the client that the memory was updated.
         self.client
             .send_notification::<custom_notifications::MemoryUpdated>(memory)
             .await;
 
-        Ok(())
+        Ok(warnings)
     }
 
     fn get_semantic_token_type_index(&self, token_type: SemanticTokenType) -> Option<usize> {
@@ -455,11 +501,17 @@ impl Backend {
             match item {
                 crate::ast::types::BodyItem::ExpressionStatement(_) => continue,
                 crate::ast::types::BodyItem::ReturnStatement(_) => continue,
+                crate::ast::types::BodyItem::ForStatement(_) => continue,
+                crate::ast::types::BodyItem::ImportStatement(_) => continue,
                 crate::ast::types::BodyItem::VariableDeclaration(variable) => {
                     // We only want to complete variables.
-                    for declaration in &variable.declarations {
+                    for id in variable
+                        .declarations
+                        .iter()
+                        .flat_map(|declaration| declaration.id.names())
+                    {
                         completions.push(CompletionItem {
-                            label: declaration.id.name.to_string(),
+                            label: id.name.to_string(),
                             label_details: None,
                             kind: Some(match variable.kind {
                                 crate::ast::types::VariableKind::Let => CompletionItemKind::VARIABLE,
@@ -546,7 +598,7 @@ impl Backend {
                 vec![kittycad::types::multipart::Attachment {
                     // Clean the URI part.
                     name: "attachment".to_string(),
-                    filename: Some("attachment.zip".to_string()),
+                    filepath: Some("attachment.zip".into()),
                     content_type: Some("application/x-zip".to_string()),
                     data: self.create_zip().await?,
                 }],
@@ -678,6 +730,9 @@ impl LanguageServer for Backend {
                 })),
                 document_formatting_provider: Some(OneOf::Left(true)),
                 folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+                code_lens_provider: Some(CodeLensOptions {
+                    resolve_provider: Some(false),
+                }),
                 hover_provider: Some(HoverProviderCapability::Simple(true)),
                 inlay_hint_provider: Some(OneOf::Left(true)),
                 rename_provider: Some(OneOf::Left(true)),
@@ -800,6 +855,22 @@ impl LanguageServer for Backend {
         }
     }
 
+    async fn code_lens(&self, params: CodeLensParams) -> RpcResult<Option<Vec<CodeLens>>> {
+        let filename = params.text_document.uri.to_string();
+
+        let Some(current_code) = self.code_map.get(&filename).await else {
+            return Ok(None);
+        };
+        let Ok(current_code) = std::str::from_utf8(&current_code) else {
+            return Ok(None);
+        };
+        let Some(profile) = self.profile_map.get(&filename).await else {
+            return Ok(None);
+        };
+
+        Ok(Some(hot_spot_code_lenses(&profile, current_code)))
+    }
+
     async fn hover(&self, params: HoverParams) -> RpcResult<Option<Hover>> {
         let filename = params.text_document_position_params.text_document.uri.to_string();
 
@@ -854,6 +925,7 @@ impl LanguageServer for Backend {
                 }))
             }
             crate::ast::types::Hover::Signature { .. } => Ok(None),
+            crate::ast::types::Hover::NamedArgument { .. } => Ok(None),
             crate::ast::types::Hover::Comment { value, range } => Ok(Some(Hover {
                 contents: HoverContents::Markup(MarkupContent {
                     kind: MarkupKind::Markdown,
@@ -969,6 +1041,31 @@ impl LanguageServer for Backend {
 
                 Ok(Some(signature.clone()))
             }
+            crate::ast::types::Hover::NamedArgument {
+                name,
+                argument,
+                range: _,
+            } => {
+                let Some(signature) = self.stdlib_signatures.get(&name) else {
+                    return Ok(None);
+                };
+
+                let mut signature = signature.clone();
+
+                // The stdlib's parameters are labelled with their names, so find the one this argument is for.
+                signature.active_parameter = signature
+                    .signatures
+                    .first()
+                    .and_then(|info| info.parameters.as_ref())
+                    .and_then(|parameters| {
+                        parameters
+                            .iter()
+                            .position(|parameter| parameter.label == ParameterLabel::Simple(argument.clone()))
+                    })
+                    .map(|index| index as u32);
+
+                Ok(Some(signature.clone()))
+            }
             crate::ast::types::Hover::Comment { value: _, range: _ } => {
                 return Ok(None);
             }
@@ -1131,6 +1228,17 @@ pub fn get_signatures_from_stdlib(stdlib: &crate::std::StdLib) -> Result<HashMap
     Ok(signatures)
 }
 
+/// The directory imports in a document are relative to: the one the document is in.
+/// In the browser, the app's URIs are paths within the project directory, which is where the
+/// file manager reads files from, so the directory is relative to that.
+pub(crate) fn document_directory(uri: &url::Url) -> Option<std::path::PathBuf> {
+    #[cfg(target_arch = "wasm32")]
+    let path = std::path::PathBuf::from(uri.path().trim_start_matches('/'));
+    #[cfg(not(target_arch = "wasm32"))]
+    let path = uri.to_file_path().ok()?;
+    path.parent().map(std::path::Path::to_path_buf)
+}
+
 /// Convert a position to a character index from the start of the file.
 fn position_to_char_index(position: Position, code: &str) -> usize {
     // Get the character position from the start of the file.
@@ -1146,3 +1254,43 @@ fn position_to_char_index(position: Position, code: &str) -> usize {
 
     char_position
 }
+
+/// Show how long the slowest statements took, and how many engine commands they sent, above
+/// each of them.
+fn hot_spot_code_lenses(profile: &crate::executor::Profile, code: &str) -> Vec<CodeLens> {
+    const MAX_HOT_SPOTS: usize = 5;
+
+    profile
+        .hot_spots()
+        .into_iter()
+        .filter(|hot_spot| hot_spot.duration_us > 0 && hot_spot.source_range.end() <= code.len())
+        .take(MAX_HOT_SPOTS)
+        .map(|hot_spot| {
+            let mut title = format!(
+                "{:.1} ms, {} engine command{}",
+                hot_spot.duration_us as f64 / 1000.0,
+                hot_spot.commands,
+                if hot_spot.commands == 1 { "" } else { "s" }
+            );
+            if hot_spot.runs > 1 {
+                title.push_str(&format!(" over {} runs", hot_spot.runs));
+            }
+            // The lens goes above the line the statement starts on. `start_to_lsp_position`
+            // puts the start of a line at the end of the line before, so count the lines here.
+            let line = code[..hot_spot.source_range.start()].matches('\n').count() as u32;
+            CodeLens {
+                range: Range {
+                    start: Position { line, character: 0 },
+                    end: hot_spot.source_range.end_to_lsp_position(code),
+                },
+                command: Some(Command {
+                    title,
+                    // The lens is only there to be read.
+                    command: String::new(),
+                    arguments: None,
+                }),
+                data: None,
+            }
+        })
+        .collect()
+}
@@ -61,6 +61,7 @@ async fn kcl_lsp_server(execute: bool) -> Result<crate::lsp::kcl::Backend> {
         client,
         fs: Arc::new(crate::fs::FileManager::new()),
         workspace_folders: Default::default(),
+        stdlib: Arc::new(stdlib),
         stdlib_completions,
         stdlib_signatures,
         token_types,
@@ -71,6 +72,7 @@ async fn kcl_lsp_server(execute: bool) -> Result<crate::lsp::kcl::Backend> {
         diagnostics_map: Default::default(),
         symbols_map: Default::default(),
         semantic_tokens_map: Default::default(),
+        profile_map: Default::default(),
         zoo_client,
         can_send_telemetry: true,
         executor_ctx: Arc::new(tokio::sync::RwLock::new(executor_ctx)),
@@ -980,6 +982,50 @@ async fn test_kcl_lsp_signature_help() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kcl_lsp_signature_help_named_argument() {
+    let server = kcl_lsp_server(false).await.unwrap();
+
+    // Send open file.
+    server
+        .did_change(tower_lsp::lsp_types::DidChangeTextDocumentParams {
+            text_document: tower_lsp::lsp_types::VersionedTextDocumentIdentifier {
+                uri: "file:///test.kcl".try_into().unwrap(),
+                version: 1,
+            },
+            content_changes: vec![tower_lsp::lsp_types::TextDocumentContentChangeEvent {
+                range: None,
+                range_length: None,
+                text: "const r = range(0, 10, step = 2)".to_string(),
+            }],
+        })
+        .await;
+    server.wait_on_handle().await;
+
+    // Send signature help request, with the cursor on the named argument.
+    let signature_help = server
+        .signature_help(tower_lsp::lsp_types::SignatureHelpParams {
+            text_document_position_params: tower_lsp::lsp_types::TextDocumentPositionParams {
+                text_document: tower_lsp::lsp_types::TextDocumentIdentifier {
+                    uri: "file:///test.kcl".try_into().unwrap(),
+                },
+                position: tower_lsp::lsp_types::Position { line: 0, character: 25 },
+            },
+            context: None,
+            work_done_progress_params: Default::default(),
+        })
+        .await
+        .unwrap();
+
+    // Check the signature help points at the `step` parameter.
+    if let Some(signature_help) = signature_help {
+        assert_eq!(signature_help.signatures[0].label, "range");
+        assert_eq!(signature_help.active_parameter, Some(2));
+    } else {
+        panic!("Expected signature help");
+    }
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_kcl_lsp_semantic_tokens() {
     let server = kcl_lsp_server(false).await.unwrap();
@@ -2044,6 +2090,46 @@ async fn test_kcl_lsp_diagnostics_on_parse_error() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kcl_lsp_diagnostics_on_type_errors() {
+    let server = kcl_lsp_server(false).await.unwrap();
+
+    // Send open file.
+    server
+        .did_open(tower_lsp::lsp_types::DidOpenTextDocumentParams {
+            text_document: tower_lsp::lsp_types::TextDocumentItem {
+                uri: "file:///test.kcl".try_into().unwrap(),
+                language_id: "kcl".to_string(),
+                version: 1,
+                text: r#"const part001 = startSketchOn('XY')
+  |> startProfileAt(5, %)
+  |> line([20, 0], %)
+  |> close(%)
+  |> extrude('tall', %)"#
+                    .to_string(),
+            },
+        })
+        .await;
+    server.wait_on_handle().await;
+
+    // Get the diagnostics.
+    // Every type error is reported, even though we can't execute.
+    let diagnostics = server.diagnostics_map.get("file:///test.kcl").await.unwrap().clone();
+    // Check the diagnostics.
+    if let tower_lsp::lsp_types::DocumentDiagnosticReport::Full(diagnostics) = diagnostics {
+        let items = diagnostics.full_document_diagnostic_report.items;
+        assert_eq!(items.len(), 2);
+        assert_eq!(
+            items[0].message,
+            "type: Argument 1 of `startProfileAt` should be `to: [number]`, but it is a number"
+        );
+        assert_eq!(items[0].range.start, tower_lsp::lsp_types::Position::new(1, 20));
+        assert_eq!(items[1].range.start, tower_lsp::lsp_types::Position::new(4, 13));
+    } else {
+        panic!("Expected full diagnostics");
+    }
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn serial_test_kcl_lsp_diagnostics_on_execution_error() {
     let server = kcl_lsp_server(true).await.unwrap();
@@ -2843,3 +2929,72 @@ async fn test_kcl_lsp_folding() {
         }
     );
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kcl_lsp_code_lens_hot_spots() {
+    let server = kcl_lsp_server(false).await.unwrap();
+
+    // Send open file.
+    server
+        .did_open(tower_lsp::lsp_types::DidOpenTextDocumentParams {
+            text_document: tower_lsp::lsp_types::TextDocumentItem {
+                uri: "file:///test.kcl".try_into().unwrap(),
+                language_id: "kcl".to_string(),
+                version: 1,
+                text: r#"const thing = 1
+const part001 = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)"#
+                    .to_string(),
+            },
+        })
+        .await;
+    server.wait_on_handle().await;
+
+    // Pretend the file was executed, since that needs the engine.
+    let span = |source_range: [usize; 2], duration_us: u64, commands: usize| crate::executor::ProfileSpan {
+        source_range: crate::executor::SourceRange(source_range),
+        start_us: 0,
+        duration_us,
+        commands,
+    };
+    server
+        .profile_map
+        .insert(
+            "file:///test.kcl".to_string(),
+            crate::executor::Profile {
+                statements: vec![span([0, 15], 0, 0), span([16, 81], 12345, 3)],
+                ..Default::default()
+            },
+        )
+        .await;
+
+    // Send code lens request.
+    let lenses = server
+        .code_lens(tower_lsp::lsp_types::CodeLensParams {
+            text_document: tower_lsp::lsp_types::TextDocumentIdentifier {
+                uri: "file:///test.kcl".try_into().unwrap(),
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Statements which took no time don't get a lens.
+    assert_eq!(lenses.len(), 1);
+    assert_eq!(
+        lenses[0].range.start,
+        tower_lsp::lsp_types::Position { line: 1, character: 0 }
+    );
+    assert_eq!(lenses[0].command.as_ref().unwrap().title, "12.3 ms, 3 engine commands");
+}
+
+#[test]
+fn test_kcl_lsp_document_directory() {
+    // Imports in a document are relative to the directory it's in.
+    assert_eq!(
+        crate::lsp::kcl::document_directory(&"file:///project/parts/bracket.kcl".parse().unwrap()),
+        Some(std::path::PathBuf::from("/project/parts"))
+    );
+}
@@ -34,7 +34,11 @@ impl Parser {
             } else {
                 format!("found unknown tokens [{}]", token_list.join(", "))
             };
-            return Err(KclError::Lexical(KclErrorDetails { source_ranges, message }));
+            return Err(KclError::Lexical(KclErrorDetails {
+                source_ranges,
+                message,
+                ..Default::default()
+            }));
         }
 
         // Important, to not call this before the unknown tokens check.
@@ -18,6 +18,7 @@ fn evaluate(rpn: Vec<BinaryExpressionToken>) -> Result<BinaryExpression, KclErro
     let e = KclError::Internal(KclErrorDetails {
         source_ranges,
         message: "error parsing binary math expressions".to_owned(),
+        ..Default::default()
     });
     for item in rpn {
         let expr = match item {
@@ -129,6 +130,7 @@ mod tests {
                 end: 0,
                 value: n.into(),
                 raw: n.to_string(),
+                suffix: None,
             }))
         }
         let tests: Vec<Vec<BinaryExpressionToken>> = vec![
@@ -1,7 +1,7 @@
 use std::str::FromStr;
 
 use winnow::{
-    combinator::{alt, delimited, opt, peek, preceded, repeat, separated, terminated},
+    combinator::{alt, cut_err, delimited, opt, peek, preceded, repeat, separated, terminated},
     dispatch,
     error::{ErrMode, StrContext, StrContextValue},
     prelude::*,
@@ -10,11 +10,13 @@ use winnow::{
 
 use crate::{
     ast::types::{
-        ArrayExpression, BinaryExpression, BinaryOperator, BinaryPart, BodyItem, CallExpression, CommentStyle,
-        ExpressionStatement, FnArgPrimitive, FnArgType, FunctionExpression, Identifier, Literal, LiteralIdentifier,
-        LiteralValue, MemberExpression, MemberObject, NonCodeMeta, NonCodeNode, NonCodeValue, ObjectExpression,
-        ObjectProperty, Parameter, PipeExpression, PipeSubstitution, Program, ReturnStatement, UnaryExpression,
-        UnaryOperator, Value, VariableDeclaration, VariableDeclarator, VariableKind,
+        ArrayExpression, ArrayPattern, BinaryExpression, BinaryOperator, BinaryPart, BindingPattern, BodyItem,
+        CallExpression, CommentStyle, ElseIf, ExpressionStatement, FnArgPrimitive, FnArgType, ForIterable,
+        ForStatement, FunctionExpression, Identifier, IfExpression, ImportItem, ImportStatement, ItemVisibility,
+        Literal, LiteralIdentifier, LiteralValue, MemberExpression, MemberObject, NamedArgument, NonCodeMeta,
+        NonCodeNode, NonCodeValue, NumericSuffix, ObjectExpression, ObjectPattern, ObjectProperty, Parameter,
+        PipeExpression, PipeSubstitution, Program, RangeExpression, ReturnStatement, UnaryExpression, UnaryOperator,
+        Value, VariableDeclaration, VariableDeclarator, VariableKind,
     },
     errors::{KclError, KclErrorDetails},
     executor::SourceRange,
@@ -170,6 +172,7 @@ fn pipe_expression(i: TokenSlice) -> PResult<PipeExpression> {
         let err = KclError::Syntax(KclErrorDetails {
             source_ranges,
             message: "All expressions in a pipeline must use the % (substitution operator)".to_owned(),
+            ..Default::default()
         });
         return Err(ErrMode::Cut(err.into()));
     }
@@ -204,6 +207,7 @@ fn bool_value(i: TokenSlice) -> PResult<Literal> {
             _ => Err(KclError::Syntax(KclErrorDetails {
                 source_ranges: token.as_source_ranges(),
                 message: "invalid boolean literal".to_owned(),
+                ..Default::default()
             })),
         })
         .context(expected("a boolean literal (either true or false)"))
@@ -213,6 +217,7 @@ fn bool_value(i: TokenSlice) -> PResult<Literal> {
         end: token.end,
         value: LiteralValue::Bool(value),
         raw: value.to_string(),
+        suffix: None,
     })
 }
 
@@ -233,6 +238,7 @@ pub fn string_literal(i: TokenSlice) -> PResult<Literal> {
             _ => Err(KclError::Syntax(KclErrorDetails {
                 source_ranges: token.as_source_ranges(),
                 message: "invalid string literal".to_owned(),
+                ..Default::default()
             })),
         })
         .context(expected("string literal (like \"myPart\""))
@@ -242,29 +248,35 @@ pub fn string_literal(i: TokenSlice) -> PResult<Literal> {
         end: token.end,
         value,
         raw: token.value.clone(),
+        suffix: None,
     })
 }
 
 /// Parse a KCL literal number, with no - sign.
 pub(crate) fn unsigned_number_literal(i: TokenSlice) -> PResult<Literal> {
-    let (value, token) = any
+    let ((value, suffix), token) = any
         .try_map(|token: Token| match token.token_type {
             TokenType::Number => {
-                if let Ok(x) = token.value.parse::<u64>() {
-                    return Ok((LiteralValue::IInteger(x as i64), token));
+                // The tokeniser only keeps letters after a number if they're a unit of measure.
+                let digits = token.value.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+                let suffix = token.value[digits.len()..].parse::<NumericSuffix>().ok();
+                if let Ok(x) = digits.parse::<u64>() {
+                    return Ok(((LiteralValue::IInteger(x as i64), suffix), token));
                 }
-                let x: f64 = token.value.parse().map_err(|_| {
+                let x: f64 = digits.parse().map_err(|_| {
                     KclError::Syntax(KclErrorDetails {
                         source_ranges: token.as_source_ranges(),
                         message: format!("Invalid float: {}", token.value),
+                        ..Default::default()
                     })
                 })?;
 
-                Ok((LiteralValue::Fractional(x), token))
+                Ok(((LiteralValue::Fractional(x), suffix), token))
             }
             _ => Err(KclError::Syntax(KclErrorDetails {
                 source_ranges: token.as_source_ranges(),
                 message: "invalid literal".to_owned(),
+                ..Default::default()
             })),
         })
         .context(expected("an unsigned number literal (e.g. 3 or 12.5)"))
@@ -274,6 +286,7 @@ pub(crate) fn unsigned_number_literal(i: TokenSlice) -> PResult<Literal> {
         end: token.end,
         value,
         raw: token.value.clone(),
+        suffix,
     })
 }
 
@@ -284,6 +297,7 @@ fn binary_operator(i: TokenSlice) -> PResult<BinaryOperator> {
             return Err(KclError::Syntax(KclErrorDetails {
                 source_ranges: token.as_source_ranges(),
                 message: format!("unexpected token, should be an operator but was {}", token.token_type),
+                ..Default::default()
             }));
         }
         let op = match token.value.as_str() {
@@ -293,10 +307,19 @@ fn binary_operator(i: TokenSlice) -> PResult<BinaryOperator> {
             "*" => BinaryOperator::Mul,
             "%" => BinaryOperator::Mod,
             "^" => BinaryOperator::Pow,
+            "==" => BinaryOperator::Eq,
+            "!=" => BinaryOperator::Neq,
+            "<" => BinaryOperator::Lt,
+            "<=" => BinaryOperator::Lte,
+            ">" => BinaryOperator::Gt,
+            ">=" => BinaryOperator::Gte,
+            "&&" => BinaryOperator::And,
+            "||" => BinaryOperator::Or,
             _ => {
                 return Err(KclError::Syntax(KclErrorDetails {
                     source_ranges: token.as_source_ranges(),
                     message: format!("{} is not a binary operator", token.value.as_str()),
+                    ..Default::default()
                 }))
             }
         };
@@ -320,10 +343,12 @@ fn operand(i: TokenSlice) -> PResult<BinaryPart> {
                 | Value::PipeExpression(_)
                 | Value::PipeSubstitution(_)
                 | Value::ArrayExpression(_)
-                | Value::ObjectExpression(_) => {
+                | Value::ObjectExpression(_)
+                | Value::IfExpression(_) => {
                     return Err(KclError::Syntax(KclErrorDetails {
                         source_ranges,
                         message: TODO_783.to_owned(),
+                        ..Default::default()
                     }))
                 }
                 Value::None(_) => {
@@ -333,6 +358,7 @@ fn operand(i: TokenSlice) -> PResult<BinaryPart> {
                         // Once we have ways to use None values (e.g. by replacing with a default value)
                         // we should suggest one of them here.
                         message: "cannot use a KCL None value as an operand".to_owned(),
+                        ..Default::default()
                     }));
                 }
                 Value::UnaryExpression(x) => BinaryPart::UnaryExpression(x),
@@ -362,6 +388,7 @@ impl TokenType {
                         token.value.as_str(),
                         token.token_type
                     ),
+                    ..Default::default()
                 }))
             }
         })
@@ -384,6 +411,7 @@ fn whitespace(i: TokenSlice) -> PResult<Vec<Token>> {
                         token.value.as_str(),
                         token.token_type
                     ),
+                    ..Default::default()
                 }))
             }
         }),
@@ -408,6 +436,7 @@ fn shebang(i: TokenSlice) -> PResult<NonCodeNode> {
             KclError::Syntax(KclErrorDetails {
                 source_ranges: vec![],
                 message: "expected a shebang value after #!".to_owned(),
+                ..Default::default()
             })
             .into(),
         ));
@@ -459,6 +488,7 @@ fn integer_range(i: TokenSlice) -> PResult<Vec<Value>> {
                 end: token0.end,
                 value: num.into(),
                 raw: num.to_string(),
+                suffix: None,
             }))
         })
         .collect())
@@ -519,6 +549,7 @@ fn pipe_sub(i: TokenSlice) -> PResult<PipeSubstitution> {
                     "expected a pipe substitution symbol (%) but found {}",
                     token.value.as_str()
                 ),
+                ..Default::default()
             }))
         }
     })
@@ -542,7 +573,7 @@ fn function_expression(i: TokenSlice) -> PResult<FunctionExpression> {
     let return_type = opt(argument_type).parse_next(i)?;
     ignore_whitespace(i);
     open_brace(i)?;
-    let body = function_body(i)?;
+    let body = nested_body(i)?;
     let end = close_brace(i)?.end;
     Ok(FunctionExpression {
         start,
@@ -553,6 +584,247 @@ fn function_expression(i: TokenSlice) -> PResult<FunctionExpression> {
     })
 }
 
+/// Parse the body of a function, block or loop.
+/// Imports and exports are only allowed at the top level of a file, so they're rejected here.
+fn nested_body(i: TokenSlice) -> PResult<Program> {
+    let body = function_body(i)?;
+    for item in &body.body {
+        let (start, end, what) = match item {
+            BodyItem::ImportStatement(import_statement) => (import_statement.start, import_statement.end, "import"),
+            BodyItem::VariableDeclaration(variable_declaration)
+                if variable_declaration.visibility == ItemVisibility::Export =>
+            {
+                (variable_declaration.start, variable_declaration.end, "export")
+            }
+            _ => continue,
+        };
+        return Err(ErrMode::Cut(
+            KclError::Syntax(KclErrorDetails {
+                source_ranges: vec![SourceRange([start, end])],
+                message: format!("You can only `{}` at the top level of a file", what),
+                ..Default::default()
+            })
+            .into(),
+        ));
+    }
+    Ok(body)
+}
+
+/// Parse one name in an import statement, e.g. `a` or `a as b`.
+fn import_item(i: TokenSlice) -> PResult<ImportItem> {
+    let name = identifier
+        .context(expected("the name of an item to import"))
+        .parse_next(i)?;
+    let alias = opt(preceded(
+        (require_whitespace, one_of((TokenType::Word, "as")), require_whitespace),
+        cut_err(binding_name),
+    ))
+    .parse_next(i)?;
+    let end = alias.as_ref().unwrap_or(&name).end;
+    Ok(ImportItem {
+        start: name.start,
+        end,
+        name,
+        alias,
+    })
+}
+
+/// Parse an `import a, b as c from "lib.kcl"` statement.
+fn import_stmt(i: TokenSlice) -> PResult<ImportStatement> {
+    let start = one_of((TokenType::Word, "import")).parse_next(i)?.start;
+    require_whitespace(i)?;
+    // Don't commit to an import statement yet, this might still be a call to the `import` function.
+    let items = separated(1.., import_item, comma_sep).parse_next(i)?;
+    require_whitespace(i).map_err(|e| e.cut())?;
+    one_of((TokenType::Word, "from"))
+        .context(expected("the `from` keyword, e.g. `import a from \"lib.kcl\"`"))
+        .parse_next(i)
+        .map_err(|e| e.cut())?;
+    require_whitespace(i).map_err(|e| e.cut())?;
+    let path = string_literal
+        .context(expected("the path of the KCL file to import from, e.g. \"lib.kcl\""))
+        .parse_next(i)
+        .map_err(|e| e.cut())?;
+    let LiteralValue::String(path_str) = path.value else {
+        unreachable!("string_literal always parses a string");
+    };
+    Ok(ImportStatement {
+        start,
+        end: path.end,
+        items,
+        path: path_str,
+    })
+}
+
+/// Parse the given keyword.
+fn keyword(symbol: &'static str, i: TokenSlice) -> PResult<Token> {
+    one_of((TokenType::Keyword, symbol))
+        .context(expected(symbol))
+        .parse_next(i)
+}
+
+/// Parse a block, i.e. the body of an if/else branch, and the end of its closing brace.
+/// The last item in a block must be an expression, which becomes the value of the block.
+fn block(i: TokenSlice) -> PResult<(Program, usize)> {
+    let start = open_brace(i)?.start;
+    let body = nested_body(i)?;
+    let end = close_brace(i)?.end;
+    match body.body.last() {
+        Some(BodyItem::ExpressionStatement(_)) => {}
+        Some(BodyItem::ReturnStatement(ReturnStatement { start, end, .. })) => {
+            return Err(ErrMode::Cut(
+                KclError::Syntax(KclErrorDetails {
+                    source_ranges: vec![SourceRange([*start, *end])],
+                    message: "You cannot `return` from inside an if/else block. Instead, end the block with the value you want it to have".to_owned(),
+                    ..Default::default()
+                })
+                .into(),
+            ));
+        }
+        _ => {
+            return Err(ErrMode::Cut(
+                KclError::Syntax(KclErrorDetails {
+                    source_ranges: vec![SourceRange([start, end])],
+                    message: "An if/else block must end with an expression, which becomes the value of the block"
+                        .to_owned(),
+                    ..Default::default()
+                })
+                .into(),
+            ));
+        }
+    }
+    Ok((body, end))
+}
+
+/// Parse an `else if cond { ... }` branch.
+fn else_if(i: TokenSlice) -> PResult<ElseIf> {
+    ignore_whitespace(i);
+    let start = keyword("else", i)?.start;
+    require_whitespace(i)?;
+    keyword("if", i)?;
+    // After `else if`, we're definitely parsing an else-if branch, so any errors should be fatal.
+    require_whitespace(i).map_err(|e| e.cut())?;
+    let cond = value
+        .context(expected("a condition, e.g. `x > 3`"))
+        .parse_next(i)
+        .map_err(|e| e.cut())?;
+    ignore_whitespace(i);
+    let (then_val, end) = block.parse_next(i).map_err(|e| e.cut())?;
+    Ok(ElseIf {
+        start,
+        end,
+        cond,
+        then_val: Box::new(then_val),
+    })
+}
+
+// Looks like
+// if x > 3 {
+//     1
+// } else if x > 2 {
+//     2
+// } else {
+//     3
+// }
+fn if_expr(i: TokenSlice) -> PResult<IfExpression> {
+    let start = keyword("if", i)?.start;
+    // After `if`, we're definitely parsing an if expression, so any errors should be fatal.
+    require_whitespace(i).map_err(|e| e.cut())?;
+    let cond = value
+        .context(expected("a condition, e.g. `x > 3`"))
+        .parse_next(i)
+        .map_err(|e| e.cut())?;
+    ignore_whitespace(i);
+    let (then_val, _) = block.parse_next(i).map_err(|e| e.cut())?;
+    let else_ifs = repeat(0.., else_if).parse_next(i)?;
+    ignore_whitespace(i);
+    one_of((TokenType::Keyword, "else"))
+        .context(expected("an else branch, which every if expression must have"))
+        .parse_next(i)
+        .map_err(|e| e.cut())?;
+    ignore_whitespace(i);
+    let (final_else, end) = block.parse_next(i).map_err(|e| e.cut())?;
+    Ok(IfExpression {
+        start,
+        end,
+        cond: Box::new(cond),
+        then_val: Box::new(then_val),
+        else_ifs,
+        final_else: Box::new(final_else),
+    })
+}
+
+/// Parse a range of integers, e.g. `0..n`.
+fn range_expression(i: TokenSlice) -> PResult<RangeExpression> {
+    let from = value.parse_next(i)?;
+    double_period.parse_next(i)?;
+    // After the `..`, we're definitely parsing a range, so any errors should be fatal.
+    let to = value
+        .context(expected("the end of the range, e.g. the `n` in `0..n`"))
+        .parse_next(i)
+        .map_err(|e| e.cut())?;
+    Ok(RangeExpression {
+        start: from.start(),
+        end: to.end(),
+        from,
+        to,
+    })
+}
+
+// Looks like
+// for i in 0..n {
+//     ...
+// }
+fn for_stmt(i: TokenSlice) -> PResult<ForStatement> {
+    let start = keyword("for", i)?.start;
+    // After `for`, we're definitely parsing a for loop, so any errors should be fatal.
+    require_whitespace(i).map_err(|e| e.cut())?;
+    let variable = identifier
+        .context(expected(
+            "the name of the loop variable, e.g. the `i` in `for i in 0..n`",
+        ))
+        .parse_next(i)
+        .map_err(|e| e.cut())?;
+    require_whitespace(i).map_err(|e| e.cut())?;
+    one_of((TokenType::Word, "in"))
+        .context(expected("the `in` keyword, e.g. `for i in 0..n`"))
+        .parse_next(i)
+        .map_err(|e| e.cut())?;
+    require_whitespace(i).map_err(|e| e.cut())?;
+    let iterable = alt((
+        range_expression.map(Box::new).map(ForIterable::Range),
+        value.map(ForIterable::Value),
+    ))
+    .context(expected("a range (like 0..n) or an array to loop over"))
+    .parse_next(i)
+    .map_err(|e| e.cut())?;
+    ignore_whitespace(i);
+    open_brace(i).map_err(|e| e.cut())?;
+    let body = nested_body(i).map_err(|e| e.cut())?;
+    let end = close_brace(i).map_err(|e| e.cut())?.end;
+    if let Some(BodyItem::ReturnStatement(ReturnStatement { start, end, .. })) = body
+        .body
+        .iter()
+        .find(|item| matches!(item, BodyItem::ReturnStatement(_)))
+    {
+        return Err(ErrMode::Cut(
+            KclError::Syntax(KclErrorDetails {
+                source_ranges: vec![SourceRange([*start, *end])],
+                message: "You cannot `return` from inside a for loop".to_owned(),
+                ..Default::default()
+            })
+            .into(),
+        ));
+    }
+    Ok(ForStatement {
+        start,
+        end,
+        variable,
+        iterable,
+        body: Box::new(body),
+    })
+}
+
 /// E.g. `person.name`
 fn member_expression_dot(i: TokenSlice) -> PResult<(LiteralIdentifier, usize, bool)> {
     period.parse_next(i)?;
@@ -693,6 +965,15 @@ fn body_items_within_function(i: TokenSlice) -> PResult<WithinFunction> {
             (declaration.map(BodyItem::VariableDeclaration), opt(noncode_just_after_code)).map(WithinFunction::BodyItem),
         Token { ref value, .. } if value == "return" =>
             (return_stmt.map(BodyItem::ReturnStatement), opt(noncode_just_after_code)).map(WithinFunction::BodyItem),
+        Token { ref value, .. } if value == "for" =>
+            (for_stmt.map(Box::new).map(BodyItem::ForStatement), opt(noncode_just_after_code)).map(WithinFunction::BodyItem),
+        Token { ref value, .. } if value == "export" =>
+            (declaration.map(BodyItem::VariableDeclaration), opt(noncode_just_after_code)).map(WithinFunction::BodyItem),
+        Token { ref value, .. } if value == "import" =>
+            (alt((
+                import_stmt.map(Box::new).map(BodyItem::ImportStatement),
+                expression.map(BodyItem::ExpressionStatement),
+            )), opt(noncode_just_after_code)).map(WithinFunction::BodyItem),
         token if !token.is_code_token() => {
             non_code_node.map(WithinFunction::NonCode)
         },
@@ -854,6 +1135,7 @@ pub fn return_stmt(i: TokenSlice) -> PResult<ReturnStatement> {
                 Err(KclError::Syntax(KclErrorDetails {
                     source_ranges: token.as_source_ranges(),
                     message: format!("{} is not a return keyword", token.value.as_str()),
+                    ..Default::default()
                 }))
             }
         })
@@ -910,6 +1192,7 @@ fn value_allowed_in_pipe_expr(i: TokenSlice) -> PResult<Value> {
         object.map(Box::new).map(Value::ObjectExpression),
         pipe_sub.map(Box::new).map(Value::PipeSubstitution),
         function_expression.map(Box::new).map(Value::FunctionExpression),
+        if_expr.map(Box::new).map(Value::IfExpression),
         unnecessarily_bracketed,
     ))
     .context(expected("a KCL value (but not a pipe expression)"))
@@ -936,19 +1219,27 @@ fn possible_operands(i: TokenSlice) -> PResult<Value> {
 /// Parse a variable/constant declaration.
 fn declaration(i: TokenSlice) -> PResult<VariableDeclaration> {
     const EXPECTED: &str = "expected a variable declaration keyword (e.g. 'let') but found";
+    let export = opt(terminated(one_of((TokenType::Word, "export")), require_whitespace)).parse_next(i)?;
     let (kind, start, dec_end) = any
         .try_map(|token: Token| {
             let Some(kind) = token.declaration_keyword() else {
                 return Err(KclError::Syntax(KclErrorDetails {
                     source_ranges: token.as_source_ranges(),
                     message: format!("{EXPECTED} {}", token.value.as_str()),
+                    ..Default::default()
                 }));
             };
 
             Ok((kind, token.start, token.end))
         })
         .context(expected("declaring a name, e.g. 'let width = 3'"))
-        .parse_next(i)?;
+        .parse_next(i)
+        // Only declarations can be exported, so anything else after `export` is an error.
+        .map_err(|e| if export.is_some() { e.cut() } else { e })?;
+    let (start, visibility) = match export {
+        Some(export) => (export.start, ItemVisibility::Export),
+        None => (start, ItemVisibility::Default),
+    };
 
     // After this point, the parser is DEFINITELY parsing a variable declaration, because
     // `fn`, `let`, `const` etc are all unambiguous. If you've parsed one of those tokens --
@@ -959,12 +1250,19 @@ fn declaration(i: TokenSlice) -> PResult<VariableDeclaration> {
     // not ErrMode::Backtrack. Because the parser is definitely parsing a variable declaration.
     // If there's an error, there's no point backtracking -- instead the parser should fail.
     require_whitespace(i).map_err(|e| e.cut())?;
-    let id = binding_name
+    let id = binding_pattern
         .context(expected(
             "an identifier, which becomes name you're binding the value to",
         ))
         .parse_next(i)
         .map_err(|e| e.cut())?;
+    if kind == VariableKind::Fn && !matches!(id, BindingPattern::Identifier(_)) {
+        return Err(ErrMode::Cut(ContextError::from(KclError::Syntax(KclErrorDetails {
+            source_ranges: vec![(&id).into()],
+            message: "A function needs a single name, it can't be destructured".to_owned(),
+            ..Default::default()
+        }))));
+    }
 
     ignore_whitespace(i);
     equals(i).map_err(|e| e.cut())?;
@@ -985,6 +1283,7 @@ fn declaration(i: TokenSlice) -> PResult<VariableDeclaration> {
                     return Err(KclError::Syntax(KclErrorDetails {
                         source_ranges: vec![SourceRange([start, dec_end])],
                         message: format!("Expected a `fn` variable kind, found: `{}`", kind),
+                        ..Default::default()
                     }));
                 }
                 Ok(val)
@@ -999,12 +1298,13 @@ fn declaration(i: TokenSlice) -> PResult<VariableDeclaration> {
         start,
         end,
         declarations: vec![VariableDeclarator {
-            start: id.start,
+            start: id.start(),
             end,
             id,
             init: val,
         }],
         kind,
+        visibility,
     })
 }
 
@@ -1025,6 +1325,7 @@ impl TryFrom<Token> for Identifier {
                     "Cannot assign a variable to a reserved keyword: {}",
                     token.value.as_str()
                 ),
+                ..Default::default()
             }))
         }
     }
@@ -1057,13 +1358,14 @@ fn unary_expression(i: TokenSlice) -> PResult<UnaryExpression> {
     let (operator, op_token) = any
         .try_map(|token: Token| match token.token_type {
             TokenType::Operator if token.value == "-" => Ok((UnaryOperator::Neg, token)),
-            // TODO: negation. Original parser doesn't support `not` yet.
+            TokenType::Bang => Ok((UnaryOperator::Not, token)),
             TokenType::Operator => Err(KclError::Syntax(KclErrorDetails {
                 source_ranges: token.as_source_ranges(),
                 message: format!(
                     "{EXPECTED} but found {} which is an operator, but not a unary one (unary operators apply to just a single operand, your operator applies to two or more operands)",
                     token.value.as_str(),
                 ),
+                ..Default::default()
             })),
             other => Err(KclError::Syntax(KclErrorDetails {
                 source_ranges: token.as_source_ranges(),
@@ -1072,9 +1374,10 @@ fn unary_expression(i: TokenSlice) -> PResult<UnaryExpression> {
                     token.value.as_str(),
                     other,
                 ),
+                ..Default::default()
             })),
         })
-        .context(expected("a unary expression, e.g. -x or -3"))
+        .context(expected("a unary expression, e.g. -x, -3 or !x"))
         .parse_next(i)?;
     let argument = operand.parse_next(i)?;
     Ok(UnaryExpression {
@@ -1173,6 +1476,7 @@ fn integer(i: TokenSlice) -> PResult<(Token, u64)> {
                 KclError::Syntax(KclErrorDetails {
                     source_ranges,
                     message: format!("invalid integer {value}: {e}"),
+                    ..Default::default()
                 })
             })
         })
@@ -1272,6 +1576,7 @@ fn double_period(i: TokenSlice) -> PResult<Token> {
                     token.value.as_str(),
                     token.token_type
                 ),
+                ..Default::default()
             }))
         }
     })
@@ -1297,11 +1602,48 @@ fn comma_sep(i: TokenSlice) -> PResult<()> {
     Ok(())
 }
 
+/// An argument passed by the name of its parameter, e.g. `radius = 2`.
+fn named_argument(i: TokenSlice) -> PResult<NamedArgument> {
+    let name = identifier(i)?;
+    (opt(whitespace), equals, opt(whitespace)).parse_next(i)?;
+    let value = value
+        .context(expected("the value of the argument, after the ="))
+        .parse_next(i)?;
+    Ok(NamedArgument {
+        start: name.start,
+        end: value.end(),
+        name,
+        value,
+    })
+}
+
 /// Arguments are passed into a function.
-fn arguments(i: TokenSlice) -> PResult<Vec<Value>> {
-    separated(0.., value, comma_sep)
-        .context(expected("function arguments"))
-        .parse_next(i)
+/// First come the positional ones, then the ones passed by name.
+fn arguments(i: TokenSlice) -> PResult<(Vec<Value>, Vec<NamedArgument>)> {
+    let candidates: Vec<Result<Value, NamedArgument>> =
+        separated(0.., alt((named_argument.map(Err), value.map(Ok))), comma_sep)
+            .context(expected("function arguments"))
+            .parse_next(i)?;
+
+    let mut positional = Vec::new();
+    let mut named: Vec<NamedArgument> = Vec::new();
+    for candidate in candidates {
+        match candidate {
+            Ok(arg) => {
+                if !named.is_empty() {
+                    let e = KclError::Syntax(KclErrorDetails {
+                        source_ranges: vec![SourceRange([arg.start(), arg.end()])],
+                        message: "positional arguments must come before arguments passed by name".to_owned(),
+                        ..Default::default()
+                    });
+                    return Err(ErrMode::Cut(ContextError::from(e)));
+                }
+                positional.push(arg);
+            }
+            Err(arg) => named.push(arg),
+        }
+    }
+    Ok((positional, named))
 }
 
 /// A type of a function argument.
@@ -1321,6 +1663,7 @@ fn argument_type(i: TokenSlice) -> PResult<FnArgType> {
                     KclError::Syntax(KclErrorDetails {
                         source_ranges: token.as_source_ranges(),
                         message: format!("Invalid type: {}", err),
+                        ..Default::default()
                     })
                 })
         }),
@@ -1332,6 +1675,7 @@ fn argument_type(i: TokenSlice) -> PResult<FnArgType> {
                     KclError::Syntax(KclErrorDetails {
                         source_ranges: token.as_source_ranges(),
                         message: format!("Invalid type: {}", err),
+                        ..Default::default()
                     })
                 })
         }),
@@ -1341,17 +1685,18 @@ fn argument_type(i: TokenSlice) -> PResult<FnArgType> {
     Ok(type_)
 }
 
-fn parameter(i: TokenSlice) -> PResult<(Token, std::option::Option<FnArgType>, bool)> {
-    let (arg_name, optional, _, _, _, type_) = (
+fn parameter(i: TokenSlice) -> PResult<(Token, std::option::Option<FnArgType>, bool, Option<Value>)> {
+    let (arg_name, optional, _, _, _, type_, default_value) = (
         any.verify(|token: &Token| !matches!(token.token_type, TokenType::Brace) || token.value != ")"),
         opt(question_mark),
         opt(whitespace),
         opt(colon),
         opt(whitespace),
         opt(argument_type),
+        opt(preceded((opt(whitespace), equals, opt(whitespace)), value)),
     )
         .parse_next(i)?;
-    Ok((arg_name, type_, optional.is_some()))
+    Ok((arg_name, type_, optional.is_some(), default_value))
 }
 
 /// Parameters are declared in a function signature, and used within a function.
@@ -1364,13 +1709,22 @@ fn parameters(i: TokenSlice) -> PResult<Vec<Parameter>> {
     // Make sure all those tokens are valid parameters.
     let params: Vec<Parameter> = candidates
         .into_iter()
-        .map(|(arg_name, type_, optional)| {
+        .map(|(arg_name, type_, optional, default_value)| {
             let identifier = Identifier::try_from(arg_name).and_then(Identifier::into_valid_binding_name)?;
+            if optional && default_value.is_some() {
+                return Err(KclError::Syntax(KclErrorDetails {
+                    source_ranges: vec![(&identifier).into()],
+                    message: "a parameter with a default value is already optional, so it can't also be marked with ?"
+                        .to_owned(),
+                    ..Default::default()
+                }));
+            }
 
             Ok(Parameter {
                 identifier,
                 type_,
-                optional,
+                optional: optional || default_value.is_some(),
+                default_value,
             })
         })
         .collect::<Result<_, _>>()
@@ -1393,6 +1747,7 @@ fn optional_after_required(params: &[Parameter]) -> Result<(), KclError> {
             let e = KclError::Syntax(KclErrorDetails {
                 source_ranges: vec![(&p.identifier).into()],
                 message: "mandatory parameters must be declared before optional parameters".to_owned(),
+                ..Default::default()
             });
             return Err(e);
         }
@@ -1407,6 +1762,7 @@ impl Identifier {
             return Err(KclError::Syntax(KclErrorDetails {
                 source_ranges: vec![SourceRange([self.start, self.end])],
                 message: format!("Cannot assign a variable to a reserved keyword: {}", self.name),
+                ..Default::default()
             }));
         }
         Ok(self)
@@ -1422,16 +1778,52 @@ fn binding_name(i: TokenSlice) -> PResult<Identifier> {
         .parse_next(i)
 }
 
+/// The names a declaration binds its value to: a single name, or an array or object pattern
+/// which destructures the value, e.g. `[x, y]` or `{ width, height }`.
+fn binding_pattern(i: TokenSlice) -> PResult<BindingPattern> {
+    alt((
+        array_pattern.map(Box::new).map(BindingPattern::ArrayPattern),
+        object_pattern.map(Box::new).map(BindingPattern::ObjectPattern),
+        binding_name.map(Box::new).map(BindingPattern::Identifier),
+    ))
+    .parse_next(i)
+}
+
+/// Destructure an array, e.g. `[x, y]`.
+fn array_pattern(i: TokenSlice) -> PResult<ArrayPattern> {
+    let start = open_bracket(i)?.start;
+    ignore_whitespace(i);
+    let elements = separated(1.., binding_name, comma_sep)
+        .context(expected("a name for each item of the array"))
+        .parse_next(i)?;
+    ignore_whitespace(i);
+    let end = close_bracket(i)?.end;
+    Ok(ArrayPattern { start, end, elements })
+}
+
+/// Destructure an object, e.g. `{ width, height }`.
+fn object_pattern(i: TokenSlice) -> PResult<ObjectPattern> {
+    let start = open_brace(i)?.start;
+    ignore_whitespace(i);
+    let properties = separated(1.., binding_name, comma_sep)
+        .context(expected("the names of the object's properties"))
+        .parse_next(i)?;
+    ignore_whitespace(i);
+    let end = close_brace(i)?.end;
+    Ok(ObjectPattern { start, end, properties })
+}
+
 fn fn_call(i: TokenSlice) -> PResult<CallExpression> {
     let fn_name = identifier(i)?;
     let _ = terminated(open_paren, opt(whitespace)).parse_next(i)?;
-    let args = arguments(i)?;
+    let (args, named_args) = arguments(i)?;
     let end = preceded(opt(whitespace), close_paren).parse_next(i)?.end;
     Ok(CallExpression {
         start: fn_name.start,
         end,
         callee: fn_name,
         arguments: args,
+        named_arguments: named_args,
         optional: false,
     })
 }
@@ -1447,7 +1839,7 @@ mod tests {
     fn parse_args() {
         for (i, (test, expected_len)) in [("someVar", 1), ("5, 3", 2), (r#""a""#, 1)].into_iter().enumerate() {
             let tokens = crate::token::lexer(test).unwrap();
-            let actual = match arguments.parse(&tokens) {
+            let (actual, _) = match arguments.parse(&tokens) {
                 Ok(x) => x,
                 Err(e) => panic!("Failed test {i}, could not parse function arguments from \"{test}\": {e:?}"),
             };
@@ -1455,6 +1847,91 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_named_args() {
+        let tokens = crate::token::lexer("5, radius = 3, tag = 'a'").unwrap();
+        let (positional, named) = arguments.parse(&tokens).unwrap();
+        assert_eq!(positional.len(), 1);
+        let names: Vec<_> = named.iter().map(|arg| arg.name.name.as_str()).collect();
+        assert_eq!(names, vec!["radius", "tag"]);
+        assert_eq!(SourceRange::from(&named[0]), SourceRange([3, 13]));
+
+        let tokens = crate::token::lexer("f(radius = 3, 5)").unwrap();
+        let err: KclError = program.parse(&tokens).unwrap_err().into();
+        assert_eq!(err.source_ranges(), vec![SourceRange([14, 15])]);
+        assert_eq!(
+            err.message(),
+            "positional arguments must come before arguments passed by name"
+        );
+    }
+
+    #[test]
+    fn parse_param_defaults() {
+        let tokens = crate::token::lexer("fn f = (x, y: number = x * 2) => { return y }").unwrap();
+        let ast = program.parse(&tokens).unwrap();
+        let BodyItem::VariableDeclaration(decl) = &ast.body[0] else {
+            panic!("expected a variable declaration");
+        };
+        let Value::FunctionExpression(func) = &decl.declarations[0].init else {
+            panic!("expected a function expression");
+        };
+        assert!(!func.params[0].optional);
+        assert!(func.params[1].optional);
+        assert_eq!(func.params[1].type_, Some(FnArgType::Primitive(FnArgPrimitive::Number)));
+        assert!(matches!(func.params[1].default_value, Some(Value::BinaryExpression(_))));
+
+        let tokens = crate::token::lexer("fn f = (x? = 1) => { return x }").unwrap();
+        let err: KclError = program.parse(&tokens).unwrap_err().into();
+        assert_eq!(err.source_ranges(), vec![SourceRange([8, 9])]);
+    }
+
+    #[test]
+    fn parse_numbers_with_units() {
+        let tokens = crate::token::lexer("const x = 10mm + 2.5in").unwrap();
+        let ast = program.parse(&tokens).unwrap();
+        let BodyItem::VariableDeclaration(decl) = &ast.body[0] else {
+            panic!("expected a variable declaration");
+        };
+        let Value::BinaryExpression(expr) = &decl.declarations[0].init else {
+            panic!("expected a binary expression");
+        };
+        let (BinaryPart::Literal(left), BinaryPart::Literal(right)) = (&expr.left, &expr.right) else {
+            panic!("expected two literals");
+        };
+        assert_eq!(left.value, LiteralValue::IInteger(10));
+        assert_eq!(left.suffix, Some(NumericSuffix::Mm));
+        assert_eq!(left.raw, "10mm");
+        assert_eq!(right.value, LiteralValue::Fractional(2.5));
+        assert_eq!(right.suffix, Some(NumericSuffix::In));
+    }
+
+    #[test]
+    fn parse_destructuring() {
+        let tokens = crate::token::lexer("const [x, y] = pos\nlet { width, height } = size").unwrap();
+        let ast = program.parse(&tokens).unwrap();
+        let BodyItem::VariableDeclaration(decl) = &ast.body[0] else {
+            panic!("expected a variable declaration");
+        };
+        let BindingPattern::ArrayPattern(pattern) = &decl.declarations[0].id else {
+            panic!("expected an array pattern");
+        };
+        assert_eq!((pattern.start, pattern.end), (6, 12));
+        assert_eq!(decl.declarations[0].start, 6);
+        assert!(decl.declarations[0].declares("x") && decl.declarations[0].declares("y"));
+        let BodyItem::VariableDeclaration(decl) = &ast.body[1] else {
+            panic!("expected a variable declaration");
+        };
+        assert!(matches!(decl.declarations[0].id, BindingPattern::ObjectPattern(_)));
+        assert!(decl.declarations[0].declares("width") && decl.declarations[0].declares("height"));
+
+        let tokens = crate::token::lexer("fn [f, g] = () => { return 1 }").unwrap();
+        let err: KclError = program.parse(&tokens).unwrap_err().into();
+        assert_eq!(
+            err.message(),
+            "A function needs a single name, it can't be destructured"
+        );
+    }
+
     #[test]
     fn weird_program_unclosed_paren() {
         let tokens = crate::token::lexer("fn firstPrime=(").unwrap();
@@ -1609,6 +2086,7 @@ const mySk1 = startSketchAt([0, 0])"#;
                             end: 33,
                             value: 2u32.into(),
                             raw: "2".to_owned(),
+                            suffix: None,
                         })),
                     })],
                     non_code_meta: NonCodeMeta {
@@ -1765,6 +2243,7 @@ const mySk1 = startSketchAt([0, 0])"#;
                 end: 10,
                 value: 3u32.into(),
                 raw: "3".to_owned(),
+                suffix: None,
             }))
         );
     }
@@ -2104,7 +2583,7 @@ const mySk1 = startSketchAt([0, 0])"#;
             assert_eq!(actual.start, 0);
             assert_eq!(actual.declarations.len(), 1);
             let decl = actual.declarations.pop().unwrap();
-            assert_eq!(decl.id.name, "myVar");
+            assert!(decl.declares("myVar"));
             let Value::Literal(value) = decl.init else {
                 panic!("value should be a literal")
             };
@@ -2126,12 +2605,14 @@ const mySk1 = startSketchAt([0, 0])"#;
                 end: 1,
                 value: 5u32.into(),
                 raw: "5".to_owned(),
+                suffix: None,
             })),
             right: BinaryPart::Literal(Box::new(Literal {
                 start: 4,
                 end: 7,
                 value: "a".into(),
                 raw: r#""a""#.to_owned(),
+                suffix: None,
             })),
         };
         let expected = vec![BodyItem::ExpressionStatement(ExpressionStatement {
@@ -2239,6 +2720,7 @@ const mySk1 = startSketchAt([0, 0])"#;
                         end: 1,
                         value: 5u32.into(),
                         raw: "5".to_string(),
+                        suffix: None,
                     })),
                     operator: BinaryOperator::Add,
                     right: BinaryPart::Literal(Box::new(Literal {
@@ -2246,6 +2728,7 @@ const mySk1 = startSketchAt([0, 0])"#;
                         end: 4,
                         value: 6u32.into(),
                         raw: "6".to_string(),
+                        suffix: None,
                     })),
                 })),
             })],
@@ -2382,7 +2865,7 @@ const secondExtrude = startSketchOn('XY')
         let err = parser.ast().unwrap_err();
         assert_eq!(
             err.to_string(),
-            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([0, 1])], message: "Unexpected token" }"#
+            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([0, 1])], message: "Unexpected token", call_stack: [] }"#
         );
     }
 
@@ -2394,7 +2877,7 @@ const secondExtrude = startSketchOn('XY')
         assert!(result.is_err());
         assert_eq!(
             result.err().unwrap().to_string(),
-            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([1, 2])], message: "Unexpected token" }"#
+            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([1, 2])], message: "Unexpected token", call_stack: [] }"#
         );
     }
 
@@ -2406,7 +2889,7 @@ const secondExtrude = startSketchOn('XY')
         assert!(result.is_err());
         assert_eq!(
             result.err().unwrap().to_string(),
-            r#"lexical: KclErrorDetails { source_ranges: [SourceRange([1, 2])], message: "found unknown token 'ޜ'" }"#
+            r#"lexical: KclErrorDetails { source_ranges: [SourceRange([1, 2])], message: "found unknown token 'ޜ'", call_stack: [] }"#
         );
     }
 
@@ -2449,7 +2932,7 @@ z(-[["#,
         assert!(result.is_err());
         assert_eq!(
             result.err().unwrap().to_string(),
-            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([3, 4])], message: "Unexpected token" }"#
+            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([3, 4])], message: "Unexpected token", call_stack: [] }"#
         );
     }
 
@@ -2461,7 +2944,7 @@ z(-[["#,
         assert!(result.is_err());
         assert_eq!(
             result.err().unwrap().to_string(),
-            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([2, 3])], message: "Unexpected token" }"#
+            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([2, 3])], message: "Unexpected token", call_stack: [] }"#
         );
     }
 
@@ -2521,6 +3004,7 @@ e
                     },
                     type_: None,
                     optional: true,
+                    default_value: None,
                 }],
                 true,
             ),
@@ -2533,6 +3017,7 @@ e
                     },
                     type_: None,
                     optional: false,
+                    default_value: None,
                 }],
                 true,
             ),
@@ -2546,6 +3031,7 @@ e
                         },
                         type_: None,
                         optional: false,
+                        default_value: None,
                     },
                     Parameter {
                         identifier: Identifier {
@@ -2555,6 +3041,7 @@ e
                         },
                         type_: None,
                         optional: true,
+                        default_value: None,
                     },
                 ],
                 true,
@@ -2569,6 +3056,7 @@ e
                         },
                         type_: None,
                         optional: true,
+                        default_value: None,
                     },
                     Parameter {
                         identifier: Identifier {
@@ -2578,6 +3066,7 @@ e
                         },
                         type_: None,
                         optional: false,
+                        default_value: None,
                     },
                 ],
                 false,
@@ -2605,11 +3094,11 @@ e
                 declarations: vec![VariableDeclarator {
                     start: 6,
                     end: 23,
-                    id: Identifier {
+                    id: BindingPattern::Identifier(Box::new(Identifier {
                         start: 6,
                         end: 13,
                         name: "myArray".to_string(),
-                    },
+                    })),
                     init: Value::ArrayExpression(Box::new(ArrayExpression {
                         start: 16,
                         end: 23,
@@ -2619,71 +3108,83 @@ e
                                 end: 18,
                                 value: 0u32.into(),
                                 raw: "0".to_string(),
+                                suffix: None,
                             })),
                             Value::Literal(Box::new(Literal {
                                 start: 17,
                                 end: 18,
                                 value: 1u32.into(),
                                 raw: "1".to_string(),
+                                suffix: None,
                             })),
                             Value::Literal(Box::new(Literal {
                                 start: 17,
                                 end: 18,
                                 value: 2u32.into(),
                                 raw: "2".to_string(),
+                                suffix: None,
                             })),
                             Value::Literal(Box::new(Literal {
                                 start: 17,
                                 end: 18,
                                 value: 3u32.into(),
                                 raw: "3".to_string(),
+                                suffix: None,
                             })),
                             Value::Literal(Box::new(Literal {
                                 start: 17,
                                 end: 18,
                                 value: 4u32.into(),
                                 raw: "4".to_string(),
+                                suffix: None,
                             })),
                             Value::Literal(Box::new(Literal {
                                 start: 17,
                                 end: 18,
                                 value: 5u32.into(),
                                 raw: "5".to_string(),
+                                suffix: None,
                             })),
                             Value::Literal(Box::new(Literal {
                                 start: 17,
                                 end: 18,
                                 value: 6u32.into(),
                                 raw: "6".to_string(),
+                                suffix: None,
                             })),
                             Value::Literal(Box::new(Literal {
                                 start: 17,
                                 end: 18,
                                 value: 7u32.into(),
                                 raw: "7".to_string(),
+                                suffix: None,
                             })),
                             Value::Literal(Box::new(Literal {
                                 start: 17,
                                 end: 18,
                                 value: 8u32.into(),
                                 raw: "8".to_string(),
+                                suffix: None,
                             })),
                             Value::Literal(Box::new(Literal {
                                 start: 17,
                                 end: 18,
                                 value: 9u32.into(),
                                 raw: "9".to_string(),
+                                suffix: None,
                             })),
                             Value::Literal(Box::new(Literal {
                                 start: 17,
                                 end: 18,
                                 value: 10u32.into(),
                                 raw: "10".to_string(),
+                                suffix: None,
                             })),
                         ],
                     })),
                 }],
                 kind: VariableKind::Const,
+                visibility: ItemVisibility::Default,
             })],
             non_code_meta: NonCodeMeta::default(),
         };
@@ -2700,7 +3201,7 @@ e
         assert!(result.is_err());
         assert_eq!(
             result.err().unwrap().to_string(),
-            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([6, 9])], message: "Cannot assign a variable to a reserved keyword: let" }"#
+            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([6, 9])], message: "Cannot assign a variable to a reserved keyword: let", call_stack: [] }"#
         );
     }
 
@@ -2713,7 +3214,7 @@ e
         assert!(result.is_err());
         assert_eq!(
             result.err().unwrap().to_string(),
-            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([3, 6])], message: "Cannot assign a variable to a reserved keyword: let" }"#
+            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([3, 6])], message: "Cannot assign a variable to a reserved keyword: let", call_stack: [] }"#
         );
     }
 
@@ -2728,7 +3229,7 @@ e
         assert!(result.is_err());
         assert_eq!(
             result.err().unwrap().to_string(),
-            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([3, 6])], message: "Cannot assign a variable to a reserved keyword: cos" }"#
+            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([3, 6])], message: "Cannot assign a variable to a reserved keyword: cos", call_stack: [] }"#
         );
     }
 
@@ -2743,7 +3244,7 @@ e
         assert!(result.is_err());
         assert_eq!(
             result.err().unwrap().to_string(),
-            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([12, 15])], message: "Cannot assign a variable to a reserved keyword: let" }"#
+            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([12, 15])], message: "Cannot assign a variable to a reserved keyword: let", call_stack: [] }"#
         );
     }
 
@@ -2758,7 +3259,7 @@ e
         assert!(result.is_err());
         assert_eq!(
             result.err().unwrap().to_string(),
-            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([12, 15])], message: "Cannot assign a variable to a reserved keyword: cos" }"#
+            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([12, 15])], message: "Cannot assign a variable to a reserved keyword: cos", call_stack: [] }"#
         );
     }
 
@@ -2807,7 +3308,7 @@ thing(false)
             assert_eq!(
                 result.err().unwrap().to_string(),
                 format!(
-                    r#"syntax: KclErrorDetails {{ source_ranges: [SourceRange([0, {}])], message: "Expected a `fn` variable kind, found: `{}`" }}"#,
+                    r#"syntax: KclErrorDetails {{ source_ranges: [SourceRange([0, {}])], message: "Expected a `fn` variable kind, found: `{}`", call_stack: [] }}"#,
                     name.len(),
                     name
                 )
@@ -2827,7 +3328,7 @@ thing(false)
         // It should say that the compiler is expecting a function expression on the RHS.
         assert_eq!(
             result.err().unwrap().to_string(),
-            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([11, 18])], message: "Unexpected token" }"#
+            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([11, 18])], message: "Unexpected token", call_stack: [] }"#
         );
     }
 
@@ -2898,6 +3399,206 @@ let myBox = box([0,0], -3, -16, -10)
         let parser = crate::parser::Parser::new(tokens);
         parser.ast().unwrap();
     }
+    #[test]
+    fn test_parse_if_else() {
+        let some_program_string = r#"const x = if a {
+  1
+} else if b {
+  2
+} else {
+  3
+}"#;
+        let tokens = crate::token::lexer(some_program_string).unwrap();
+        let parser = crate::parser::Parser::new(tokens);
+        let program = parser.ast().unwrap();
+        let BodyItem::VariableDeclaration(decl) = &program.body[0] else {
+            panic!("expected a variable declaration");
+        };
+        let Value::IfExpression(if_expr) = &decl.declarations[0].init else {
+            panic!("expected an if expression");
+        };
+        assert_eq!(if_expr.start, 10);
+        assert_eq!(if_expr.end, some_program_string.len());
+        assert_eq!(if_expr.else_ifs.len(), 1);
+    }
+
+    #[test]
+    fn test_error_if_without_else() {
+        let some_program_string = r#"const x = if a {
+  1
+}"#;
+        let tokens = crate::token::lexer(some_program_string).unwrap();
+        let parser = crate::parser::Parser::new(tokens);
+        let result = parser.ast();
+        assert!(result.is_err());
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([21, 22])], message: "Unexpected end of file. The compiler expected an else branch, which every if expression must have", call_stack: [] }"#
+        );
+    }
+
+    #[test]
+    fn test_error_if_block_must_end_with_expression() {
+        let some_program_string = r#"const x = if a {
+  const y = 1
+} else {
+  3
+}"#;
+        let tokens = crate::token::lexer(some_program_string).unwrap();
+        let parser = crate::parser::Parser::new(tokens);
+        let result = parser.ast();
+        assert!(result.is_err());
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([15, 32])], message: "An if/else block must end with an expression, which becomes the value of the block", call_stack: [] }"#
+        );
+    }
+
+    #[test]
+    fn test_comparison_and_logic_precedence() {
+        let tokens = crate::token::lexer("1 + 2 < 3 == true && false || 2 ^ 2 >= 4").unwrap();
+        let actual = binary_expression.parse(&tokens).unwrap();
+        // || binds loosest, then &&, then ==, then <, then +.
+        assert_eq!(actual.operator, BinaryOperator::Or);
+        let BinaryPart::BinaryExpression(and) = &actual.left else {
+            panic!("expected a binary expression, found {:?}", actual.left);
+        };
+        assert_eq!(and.operator, BinaryOperator::And);
+        let BinaryPart::BinaryExpression(eq) = &and.left else {
+            panic!("expected a binary expression, found {:?}", and.left);
+        };
+        assert_eq!(eq.operator, BinaryOperator::Eq);
+        let BinaryPart::BinaryExpression(lt) = &eq.left else {
+            panic!("expected a binary expression, found {:?}", eq.left);
+        };
+        assert_eq!(lt.operator, BinaryOperator::Lt);
+        // ^ binds tighter than >=.
+        let BinaryPart::BinaryExpression(gte) = &actual.right else {
+            panic!("expected a binary expression, found {:?}", actual.right);
+        };
+        assert_eq!(gte.operator, BinaryOperator::Gte);
+        let BinaryPart::BinaryExpression(pow) = &gte.left else {
+            panic!("expected a binary expression, found {:?}", gte.left);
+        };
+        assert_eq!(pow.operator, BinaryOperator::Pow);
+    }
+
+    #[test]
+    fn test_pow_precedence() {
+        // ^ binds looser than * and /, so this is `(2 * 3) ^ 2`, not `2 * (3 ^ 2)`.
+        let tokens = crate::token::lexer("2 * 3 ^ 2").unwrap();
+        let actual = binary_expression.parse(&tokens).unwrap();
+        assert_eq!(actual.operator, BinaryOperator::Pow);
+        let BinaryPart::BinaryExpression(mul) = &actual.left else {
+            panic!("expected a binary expression, found {:?}", actual.left);
+        };
+        assert_eq!(mul.operator, BinaryOperator::Mul);
+
+        // ^ is right-associative, so this is `2 ^ (3 ^ 2)`.
+        let tokens = crate::token::lexer("2 ^ 3 ^ 2").unwrap();
+        let actual = binary_expression.parse(&tokens).unwrap();
+        assert_eq!(actual.operator, BinaryOperator::Pow);
+        let BinaryPart::BinaryExpression(pow) = &actual.right else {
+            panic!("expected a binary expression, found {:?}", actual.right);
+        };
+        assert_eq!(pow.operator, BinaryOperator::Pow);
+    }
+
+    #[test]
+    fn test_parse_import_and_export() {
+        let some_program_string = r#"import double, TWO as two from "lib.kcl"
+import("cube.obj")
+export fn triple = (x) => { return x * 3 }
+const y = 1"#;
+        let tokens = crate::token::lexer(some_program_string).unwrap();
+        let program = crate::parser::Parser::new(tokens).ast().unwrap();
+        assert_eq!(program.body.len(), 4);
+        let BodyItem::ImportStatement(import) = &program.body[0] else {
+            panic!("expected an import statement, found {:?}", program.body[0]);
+        };
+        assert_eq!(import.path, "lib.kcl");
+        assert_eq!(import.items.len(), 2);
+        assert_eq!(import.items[0].local_name().name, "double");
+        assert_eq!(import.items[1].name.name, "TWO");
+        assert_eq!(import.items[1].local_name().name, "two");
+        // Calls to the `import` function are still expressions.
+        assert!(matches!(program.body[1], BodyItem::ExpressionStatement(_)));
+        let BodyItem::VariableDeclaration(triple) = &program.body[2] else {
+            panic!("expected a declaration, found {:?}", program.body[2]);
+        };
+        assert_eq!(triple.visibility, ItemVisibility::Export);
+        assert_eq!(triple.start, 60);
+        let BodyItem::VariableDeclaration(y) = &program.body[3] else {
+            panic!("expected a declaration, found {:?}", program.body[3]);
+        };
+        assert_eq!(y.visibility, ItemVisibility::Default);
+    }
+
+    #[test]
+    fn test_error_export_inside_function() {
+        let some_program_string = r#"fn f = () => {
+  export const x = 1
+  return x
+}"#;
+        let tokens = crate::token::lexer(some_program_string).unwrap();
+        let result = crate::parser::Parser::new(tokens).ast();
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([17, 35])], message: "You can only `export` at the top level of a file", call_stack: [] }"#
+        );
+    }
+
+    #[test]
+    fn test_error_import_without_from() {
+        let some_program_string = r#"import a "lib.kcl""#;
+        let tokens = crate::token::lexer(some_program_string).unwrap();
+        let result = crate::parser::Parser::new(tokens).ast();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_for_loop() {
+        let some_program_string = r#"for i in 0..n {
+  const x = i
+}
+for p in [1, 2] {
+  const y = p
+}"#;
+        let tokens = crate::token::lexer(some_program_string).unwrap();
+        let program = crate::parser::Parser::new(tokens).ast().unwrap();
+        assert_eq!(program.body.len(), 2);
+        let BodyItem::ForStatement(range_loop) = &program.body[0] else {
+            panic!("expected a for loop, found {:?}", program.body[0]);
+        };
+        assert_eq!(range_loop.variable.name, "i");
+        assert!(matches!(range_loop.iterable, ForIterable::Range(_)));
+        let BodyItem::ForStatement(array_loop) = &program.body[1] else {
+            panic!("expected a for loop, found {:?}", program.body[1]);
+        };
+        assert_eq!(array_loop.variable.name, "p");
+        assert!(matches!(
+            array_loop.iterable,
+            ForIterable::Value(Value::ArrayExpression(_))
+        ));
+    }
+
+    #[test]
+    fn test_error_return_inside_for_loop() {
+        let some_program_string = r#"fn f = () => {
+  for i in 0..3 {
+    return i
+  }
+}"#;
+        let tokens = crate::token::lexer(some_program_string).unwrap();
+        let parser = crate::parser::Parser::new(tokens);
+        let result = parser.ast();
+        assert!(result.is_err());
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            r#"syntax: KclErrorDetails { source_ranges: [SourceRange([37, 45])], message: "You cannot `return` from inside a for loop", call_stack: [] }"#
+        );
+    }
+
     #[test]
     fn must_use_percent_in_pipeline_fn() {
         let some_program_string = r#"
@@ -3080,4 +3781,13 @@ mod snapshot_tests {
     snapshot_test!(at, "line([0, l], %)");
     snapshot_test!(au, include_str!("../../../tests/executor/inputs/cylinder.kcl"));
     snapshot_test!(av, "fn f = (angle?) => { return default(angle, 360) }");
+    snapshot_test!(aw, "const x = if a { 1 } else if b { 2 } else { 3 }");
+    snapshot_test!(ax, "for i in 0..n { startSketchOn('XY') }");
+    snapshot_test!(ay, "for p in [[0, 0], [1, 1]] { const x = p[0] }");
+    snapshot_test!(az, "const x = a + 1 < b * 2 && !c || d != e");
+    snapshot_test!(
+        ba,
+        r#"import a, b as c from "lib.kcl"
+export const d = c(a)"#
+    );
 }
@@ -31,6 +31,7 @@ impl From<ParseError<Located<&str>, winnow::error::ContextError>> for KclError {
         KclError::Lexical(KclErrorDetails {
             source_ranges: vec![SourceRange([offset, offset + 1])],
             message: format!("found unknown token '{}'", bad_token),
+            ..Default::default()
         })
     }
 }
@@ -41,6 +42,7 @@ impl From<ParseError<&[Token], ContextError>> for KclError {
             return KclError::Syntax(KclErrorDetails {
                 source_ranges: Default::default(),
                 message: "file is empty".to_owned(),
+                ..Default::default()
             });
         };
 
@@ -59,6 +61,7 @@ impl From<ParseError<&[Token], ContextError>> for KclError {
                     Some(what) => format!("Unexpected end of file. The compiler {what}"),
                     None => "Unexpected end of file while still parsing".to_owned(),
                 },
+                ..Default::default()
             });
         }
 
@@ -68,6 +71,7 @@ impl From<ParseError<&[Token], ContextError>> for KclError {
         KclError::Syntax(KclErrorDetails {
             source_ranges: bad_token.as_source_ranges(),
             message: "Unexpected token".to_string(),
+            ..Default::default()
         })
     }
 }
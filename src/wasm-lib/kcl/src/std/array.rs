@@ -0,0 +1,257 @@
+//! Functions for building and transforming arrays.
+
+use anyhow::Result;
+use derive_docs::stdlib;
+use schemars::JsonSchema;
+use serde_json::Value as JValue;
+
+use crate::{
+    errors::{KclError, KclErrorDetails},
+    executor::{MemoryItem, Metadata, SourceRange, UserVal, MAX_LOOP_ITERATIONS},
+    std::{
+        types::{FnAsArg, KclValue},
+        Args,
+    },
+};
+
+/// Apply a function to every element of an array.
+pub async fn map(args: Args) -> Result<MemoryItem, KclError> {
+    let (array, map_fn) = args.get_array_and_fn()?;
+    let items = inner_map(array, map_fn, args.clone()).await?;
+
+    array_to_memory_item(items, args.source_range)
+}
+
+/// Apply a function to every element of an array, returning an array of the results.
+///
+/// ```no_run
+/// fn drawCircle = (x) => {
+///   return startSketchOn('XY')
+///     |> circle([x, 0], 1, %)
+/// }
+///
+/// const circles = map([0, 5, 10], drawCircle)
+///   |> extrude(2, %)
+/// ```
+#[stdlib {
+    name = "map",
+    tags = ["array"],
+}]
+async fn inner_map(array: Vec<KclValue>, map_fn: FnAsArg, args: Args) -> Result<Vec<KclValue>, KclError> {
+    let mut results = Vec::with_capacity(array.len());
+    for item in array {
+        results.push(KclValue(map_fn.call(vec![item.0], &args).await?));
+    }
+
+    Ok(results)
+}
+
+/// Keep only the elements of an array for which a function returns true.
+pub async fn filter(args: Args) -> Result<MemoryItem, KclError> {
+    let (array, filter_fn) = args.get_array_and_fn()?;
+    let items = inner_filter(array, filter_fn, args.clone()).await?;
+
+    array_to_memory_item(items, args.source_range)
+}
+
+/// Keep only the elements of an array for which a function returns true.
+///
+/// ```no_run
+/// fn isBig = (x) => {
+///   return x > 2
+/// }
+///
+/// const bigOnes = filter([1, 2, 3, 4], isBig)
+///
+/// const part = startSketchOn('XY')
+///   |> circle([0, 0], bigOnes[0], %)
+///   |> extrude(5, %)
+/// ```
+#[stdlib {
+    name = "filter",
+    tags = ["array"],
+}]
+async fn inner_filter(array: Vec<KclValue>, filter_fn: FnAsArg, args: Args) -> Result<Vec<KclValue>, KclError> {
+    let mut results = Vec::new();
+    for item in array {
+        let keep = filter_fn.call(vec![item.0.clone()], &args).await?.get_json_value()?;
+        let JValue::Bool(keep) = keep else {
+            return Err(KclError::Type(KclErrorDetails {
+                message: format!(
+                    "The function passed to `filter` must return true or false, found: {}",
+                    keep
+                ),
+                source_ranges: vec![args.source_range],
+                ..Default::default()
+            }));
+        };
+        if keep {
+            results.push(item);
+        }
+    }
+
+    Ok(results)
+}
+
+/// Combine the elements of an array into a single value.
+pub async fn reduce(args: Args) -> Result<MemoryItem, KclError> {
+    let (array, start, reduce_fn) = args.get_array_start_and_fn()?;
+
+    let result = inner_reduce(array, start, reduce_fn, args).await?;
+
+    Ok(result.0)
+}
+
+/// Combine the elements of an array into a single value.
+///
+/// The function is called with the value so far and each element in turn.
+/// The first call gets the `start` value.
+///
+/// ```no_run
+/// fn add = (total, x) => {
+///   return total + x
+/// }
+///
+/// const sum = reduce([1, 2, 3], 0, add)
+///
+/// const part = startSketchOn('XY')
+///   |> circle([0, 0], sum, %)
+///   |> extrude(5, %)
+/// ```
+#[stdlib {
+    name = "reduce",
+    tags = ["array"],
+}]
+async fn inner_reduce(
+    array: Vec<KclValue>,
+    start: KclValue,
+    reduce_fn: FnAsArg,
+    args: Args,
+) -> Result<KclValue, KclError> {
+    let mut result = start;
+    for item in array {
+        result = KclValue(reduce_fn.call(vec![result.0, item.0], &args).await?);
+    }
+
+    Ok(result)
+}
+
+/// Make an array of evenly spaced numbers.
+pub async fn range(args: Args) -> Result<MemoryItem, KclError> {
+    let (start, end, step) = args.get_range_args()?;
+    let numbers = inner_range(start, end, step, args.clone())?;
+
+    args.make_user_val_from_json(numbers.into())
+}
+
+/// Make an array of evenly spaced numbers, from `start` up to and including `end`.
+///
+/// The `step` between numbers defaults to 1, and can be negative to count down.
+///
+/// ```no_run
+/// const xs = range(0, 10, 5)
+///
+/// fn drawCircle = (x) => {
+///   return startSketchOn('XY')
+///     |> circle([x, 0], 1, %)
+/// }
+///
+/// const circles = map(xs, drawCircle)
+///   |> extrude(2, %)
+/// ```
+#[stdlib {
+    name = "range",
+    tags = ["array"],
+}]
+fn inner_range(start: f64, end: f64, step: Option<f64>, args: Args) -> Result<Vec<JValue>, KclError> {
+    let step = step.unwrap_or(1.0);
+    if step == 0.0 || (end - start) * step < 0.0 {
+        return Err(KclError::Semantic(KclErrorDetails {
+            message: format!(
+                "A range from {} to {} can never be reached with a step of {}",
+                start, end, step
+            ),
+            source_ranges: vec![args.source_range],
+            ..Default::default()
+        }));
+    }
+
+    // Round to allow for floating point error, e.g. 0 to 1 in steps of 0.1.
+    let count = ((end - start) / step + 1e-9).floor() as u64 + 1;
+    if count > MAX_LOOP_ITERATIONS {
+        return Err(KclError::Semantic(KclErrorDetails {
+            message: format!(
+                "This range would have {} numbers, but ranges can have at most {}",
+                count, MAX_LOOP_ITERATIONS
+            ),
+            source_ranges: vec![args.source_range],
+            ..Default::default()
+        }));
+    }
+
+    let is_integer = |n: f64| n.fract() == 0.0;
+    let numbers = (0..count)
+        .map(|i| {
+            let n = start + step * i as f64;
+            if is_integer(start) && is_integer(step) {
+                JValue::from(n as i64)
+            } else {
+                JValue::from(n)
+            }
+        })
+        .collect();
+
+    Ok(numbers)
+}
+
+/// Arrays are stored as JSON, so turn an element of one back into a memory item.
+/// Geometry (e.g. sketch groups) keeps its type, so it can be passed to functions like `extrude`.
+pub(crate) fn array_element_to_memory_item(value: JValue, source_range: SourceRange) -> MemoryItem {
+    if let Ok(item @ (MemoryItem::SketchGroup(_) | MemoryItem::ExtrudeGroup(_) | MemoryItem::ImportedGeometry(_))) =
+        serde_json::from_value::<MemoryItem>(value.clone())
+    {
+        return item;
+    }
+
+    MemoryItem::UserVal(UserVal {
+        value,
+        meta: vec![Metadata { source_range }],
+        ..Default::default()
+    })
+}
+
+/// Turn the results of e.g. `map` into an array.
+/// Arrays of sketch groups or extrude groups are kept as groups, so they can be extruded, patterned, etc.
+fn array_to_memory_item(items: Vec<KclValue>, source_range: SourceRange) -> Result<MemoryItem, KclError> {
+    let items: Vec<MemoryItem> = items.into_iter().map(|item| item.0).collect();
+    if !items.is_empty() && items.iter().all(|item| matches!(item, MemoryItem::SketchGroup(_))) {
+        let value = items
+            .into_iter()
+            .filter_map(|item| match item {
+                MemoryItem::SketchGroup(sketch_group) => Some(sketch_group),
+                _ => None,
+            })
+            .collect();
+        return Ok(MemoryItem::SketchGroups { value });
+    }
+    if !items.is_empty() && items.iter().all(|item| matches!(item, MemoryItem::ExtrudeGroup(_))) {
+        let value = items
+            .into_iter()
+            .filter_map(|item| match item {
+                MemoryItem::ExtrudeGroup(extrude_group) => Some(extrude_group),
+                _ => None,
+            })
+            .collect();
+        return Ok(MemoryItem::ExtrudeGroups { value });
+    }
+
+    let value = items
+        .iter()
+        .map(|item| item.get_json_value())
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(MemoryItem::UserVal(UserVal {
+        value: value.into(),
+        meta: vec![Metadata { source_range }],
+        ..Default::default()
+    }))
+}
@@ -0,0 +1,260 @@
+//! Standard library booleans of solids.
+
+use anyhow::Result;
+use derive_docs::stdlib;
+use kittycad::types::ModelingCmd;
+use schemars::JsonSchema;
+
+use crate::{
+    errors::{KclError, KclErrorDetails},
+    executor::{ExtrudeGroup, ImportedGeometry, MemoryItem, Solid},
+    std::{fillet::DEFAULT_TOLERANCE, Args},
+};
+
+/// Join solids together into one solid.
+pub async fn union(args: Args) -> Result<MemoryItem, KclError> {
+    let solids = args.get_solids()?;
+
+    let solid = inner_union(solids, args).await?;
+    Ok(solid_to_memory_item(solid))
+}
+
+/// Join solids together into one solid.
+///
+/// The faces and edges of every solid are kept, so their tags can still be used on the new
+/// solid, e.g. to fillet it.
+///
+/// ```no_run
+/// const base = startSketchOn('XY')
+///     |> startProfileAt([-10, -10], %)
+///     |> line([20, 0], %)
+///     |> line([0, 20], %)
+///     |> line([-20, 0], %, "back")
+///     |> close(%)
+///     |> extrude(5, %)
+///
+/// const post = startSketchOn('XY')
+///     |> circle([0, 0], 3, %)
+///     |> extrude(15, %)
+///
+/// const part = union([base, post])
+///     |> fillet({radius: 1, tags: ["back"]}, %)
+/// ```
+#[stdlib {
+    name = "union",
+}]
+async fn inner_union(solids: Vec<Solid>, args: Args) -> Result<Solid, KclError> {
+    check_at_least_two(&solids, "union", &args)?;
+
+    let id = args.ctx.id_generator.next_uuid();
+    args.send_modeling_cmd(
+        id,
+        ModelingCmd::BooleanUnion {
+            solid_ids: solids.iter().map(|solid| solid.id()).collect(),
+            tolerance: DEFAULT_TOLERANCE,
+        },
+    )
+    .await?;
+
+    combine(id, solids, &args)
+}
+
+/// Cut solids away from another solid.
+pub async fn subtract(args: Args) -> Result<MemoryItem, KclError> {
+    let (tools, target) = args.get_solids_and_solid()?;
+
+    let solid = inner_subtract(tools, target, args).await?;
+    Ok(solid_to_memory_item(solid))
+}
+
+/// Cut solids away from another solid.
+///
+/// The tools are the solids cut away, and the target is the solid they are cut from. The faces
+/// left by the tools keep their tags, as do the faces and edges of the target.
+///
+/// ```no_run
+/// const plate = startSketchOn('XY')
+///     |> startProfileAt([-20, -20], %)
+///     |> line([40, 0], %)
+///     |> line([0, 40], %)
+///     |> line([-40, 0], %)
+///     |> close(%)
+///     |> extrude(4, %)
+///
+/// const boltHole = startSketchOn('XY')
+///     |> circle([0, 0], 5, %)
+///     |> extrude(4, %)
+///
+/// const washer = plate
+///     |> subtract([boltHole], %)
+/// ```
+#[stdlib {
+    name = "subtract",
+}]
+async fn inner_subtract(tools: Vec<Solid>, target: Solid, args: Args) -> Result<Solid, KclError> {
+    if tools.is_empty() {
+        return Err(KclError::Semantic(KclErrorDetails {
+            message: "Expected at least 1 solid to subtract".to_string(),
+            source_ranges: vec![args.source_range],
+            ..Default::default()
+        }));
+    }
+    if tools.iter().any(|tool| tool.id() == target.id()) {
+        return Err(KclError::Semantic(KclErrorDetails {
+            message: "Cannot subtract a solid from itself".to_string(),
+            source_ranges: vec![args.source_range],
+            ..Default::default()
+        }));
+    }
+
+    let id = args.ctx.id_generator.next_uuid();
+    args.send_modeling_cmd(
+        id,
+        ModelingCmd::BooleanSubtract {
+            target_ids: vec![target.id()],
+            tool_ids: tools.iter().map(|tool| tool.id()).collect(),
+            tolerance: DEFAULT_TOLERANCE,
+        },
+    )
+    .await?;
+
+    // The target goes first, so the new solid is placed like it.
+    let mut solids = vec![target];
+    solids.extend(tools);
+    combine(id, solids, &args)
+}
+
+/// Make the solid which is inside all of the given solids.
+pub async fn intersection(args: Args) -> Result<MemoryItem, KclError> {
+    let solids = args.get_solids()?;
+
+    let solid = inner_intersection(solids, args).await?;
+    Ok(solid_to_memory_item(solid))
+}
+
+/// Make the solid which is inside all of the given solids.
+///
+/// The faces and edges of every solid are kept, so the tags of the ones which are left can
+/// still be used on the new solid.
+///
+/// ```no_run
+/// const cube = startSketchOn('XY')
+///     |> startProfileAt([-10, -10], %)
+///     |> line([20, 0], %)
+///     |> line([0, 20], %)
+///     |> line([-20, 0], %)
+///     |> close(%)
+///     |> extrude(20, %)
+///
+/// const rod = startSketchOn('XY')
+///     |> circle([0, 0], 12, %)
+///     |> extrude(20, %)
+///
+/// const roundedCube = intersection([cube, rod])
+/// ```
+#[stdlib {
+    name = "intersection",
+}]
+async fn inner_intersection(solids: Vec<Solid>, args: Args) -> Result<Solid, KclError> {
+    check_at_least_two(&solids, "intersect", &args)?;
+
+    let id = args.ctx.id_generator.next_uuid();
+    args.send_modeling_cmd(
+        id,
+        ModelingCmd::BooleanIntersection {
+            solid_ids: solids.iter().map(|solid| solid.id()).collect(),
+            tolerance: DEFAULT_TOLERANCE,
+        },
+    )
+    .await?;
+
+    combine(id, solids, &args)
+}
+
+fn check_at_least_two(solids: &[Solid], verb: &str, args: &Args) -> Result<(), KclError> {
+    if solids.len() < 2 {
+        return Err(KclError::Semantic(KclErrorDetails {
+            message: format!("Expected at least 2 solids to {}, found `{}`", verb, solids.len()),
+            source_ranges: vec![args.source_range],
+            ..Default::default()
+        }));
+    }
+
+    Ok(())
+}
+
+/// Make the solid a boolean gives back, from the solids it was made of.
+///
+/// The new solid is placed like the first extrude group, and keeps the faces and edges of every
+/// extrude group, so their tags still refer to them. Imported geometry has no tags, so if there
+/// are no extrude groups the new solid is imported geometry too.
+fn combine(id: uuid::Uuid, solids: Vec<Solid>, args: &Args) -> Result<Solid, KclError> {
+    let mut combined: Option<Box<ExtrudeGroup>> = None;
+    let mut file_paths = Vec::new();
+    let mut meta = Vec::new();
+
+    for solid in solids {
+        let extrude_group = match solid {
+            Solid::ExtrudeGroup(extrude_group) => extrude_group,
+            Solid::ImportedGeometry(imported_geometry) => {
+                file_paths.extend(imported_geometry.value);
+                meta.extend(imported_geometry.meta);
+                continue;
+            }
+        };
+
+        match &mut combined {
+            Some(combined) => merge(combined, *extrude_group, args)?,
+            None => combined = Some(extrude_group),
+        }
+    }
+
+    Ok(match combined {
+        Some(mut extrude_group) => {
+            extrude_group.id = id;
+            extrude_group.meta.extend(meta);
+            Solid::ExtrudeGroup(extrude_group)
+        }
+        None => Solid::ImportedGeometry(Box::new(ImportedGeometry {
+            id,
+            value: file_paths,
+            meta,
+        })),
+    })
+}
+
+/// Add the faces and edges of an extrude group to the combined solid.
+fn merge(combined: &mut ExtrudeGroup, extrude_group: ExtrudeGroup, args: &Args) -> Result<(), KclError> {
+    // A tag which is on more than one of the solids can't tell which edge it means.
+    if let Some(path) = extrude_group.sketch_group_values.iter().find(|path| {
+        let name = path.get_name();
+        !name.is_empty()
+            && combined
+                .sketch_group_values
+                .iter()
+                .any(|other| other.get_name() == name)
+    }) {
+        return Err(KclError::Semantic(KclErrorDetails {
+            message: format!(
+                "Expected the tags on the solids to be unique, but `{}` is used by more than one of them",
+                path.get_name()
+            ),
+            source_ranges: vec![args.source_range],
+            ..Default::default()
+        }));
+    }
+
+    combined.value.extend(extrude_group.value);
+    combined.sketch_group_values.extend(extrude_group.sketch_group_values);
+    combined.helix_ids.extend(extrude_group.helix_ids);
+    combined.meta.extend(extrude_group.meta);
+
+    Ok(())
+}
+
+fn solid_to_memory_item(solid: Solid) -> MemoryItem {
+    match solid {
+        Solid::ExtrudeGroup(extrude_group) => MemoryItem::ExtrudeGroup(extrude_group),
+        Solid::ImportedGeometry(imported_geometry) => MemoryItem::ImportedGeometry(*imported_geometry),
+    }
+}
@@ -41,7 +41,7 @@ pub async fn extrude(args: Args) -> Result<MemoryItem, KclError> {
     name = "extrude"
 }]
 async fn inner_extrude(length: f64, sketch_group_set: SketchGroupSet, args: Args) -> Result<ExtrudeGroupSet, KclError> {
-    let id = uuid::Uuid::new_v4();
+    let id = args.ctx.id_generator.next_uuid();
 
     // Extrude the element(s).
     let sketch_groups = match sketch_group_set {
@@ -55,7 +55,9 @@ async fn inner_extrude(length: f64, sketch_group_set: SketchGroupSet, args: Args
             kittycad::types::ModelingCmd::Extrude {
                 target: sketch_group.id,
                 distance: length,
-                cap: true,
+                extrude_method: None,
+                faces: None,
+                opposite: None,
             },
         )
         .await?;
@@ -78,14 +80,17 @@ pub(crate) async fn do_post_extrude(
     // We need to do this after extrude for sketch on face.
     if let SketchSurface::Face(_) = sketch_group.on {
         // Disable the sketch mode.
-        args.send_modeling_cmd(uuid::Uuid::new_v4(), kittycad::types::ModelingCmd::SketchModeDisable {})
-            .await?;
+        args.send_modeling_cmd(
+            args.ctx.id_generator.next_uuid(),
+            kittycad::types::ModelingCmd::SketchModeDisable {},
+        )
+        .await?;
     }
 
     // Bring the object to the front of the scene.
     // See: https://github.com/KittyCAD/modeling-app/issues/806
     args.send_modeling_cmd(
-        uuid::Uuid::new_v4(),
+        args.ctx.id_generator.next_uuid(),
         kittycad::types::ModelingCmd::ObjectBringToFront {
             object_id: sketch_group.id,
         },
@@ -96,6 +101,7 @@ pub(crate) async fn do_post_extrude(
         return Err(KclError::Type(KclErrorDetails {
             message: "Expected a non-empty sketch group".to_string(),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         }));
     }
 
@@ -111,6 +117,7 @@ pub(crate) async fn do_post_extrude(
         return Err(KclError::Type(KclErrorDetails {
             message: "Expected a Path::ToPoint variant".to_string(),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         }));
     };
 
@@ -143,8 +150,16 @@ pub(crate) async fn do_post_extrude(
     // Create a hashmap for quick id lookup
     let mut face_id_map = std::collections::HashMap::new();
     // creating fake ids for start and end caps is to make extrudes mock-execute safe
-    let mut start_cap_id = if args.ctx.is_mock { Some(Uuid::new_v4()) } else { None };
-    let mut end_cap_id = if args.ctx.is_mock { Some(Uuid::new_v4()) } else { None };
+    let mut start_cap_id = if args.ctx.is_mock {
+        Some(args.ctx.id_generator.next_uuid())
+    } else {
+        None
+    };
+    let mut end_cap_id = if args.ctx.is_mock {
+        Some(args.ctx.id_generator.next_uuid())
+    } else {
+        None
+    };
 
     for face_info in face_infos {
         match face_info.cap {
@@ -196,7 +211,7 @@ pub(crate) async fn do_post_extrude(
                 position: sketch_group.position, // TODO should be for the extrude surface
                 rotation: sketch_group.rotation, // TODO should be for the extrude surface
                 // pushing this values with a fake face_id to make extrudes mock-execute safe
-                face_id: Uuid::new_v4(),
+                face_id: args.ctx.id_generator.next_uuid(),
                 name: path.get_base().name.clone(),
                 geo_meta: GeoMeta {
                     id: path.get_base().geo_meta.id,
@@ -221,6 +236,7 @@ pub(crate) async fn do_post_extrude(
         z_axis: sketch_group.z_axis,
         start_cap_id,
         end_cap_id,
+        helix_ids: Vec::new(),
         meta: sketch_group.meta,
     }))
 }
@@ -1,8 +1,8 @@
-//! Standard library fillets.
+//! Standard library fillets and chamfers.
 
 use anyhow::Result;
 use derive_docs::stdlib;
-use kittycad::types::ModelingCmd;
+use kittycad::types::{CutType, CutTypeV2, ModelingCmd};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -65,44 +65,138 @@ async fn inner_fillet(
     extrude_group: Box<ExtrudeGroup>,
     args: Args,
 ) -> Result<Box<ExtrudeGroup>, KclError> {
-    // Check if tags contains any duplicate values.
-    let mut tags = data.tags.clone();
-    tags.sort();
-    tags.dedup();
-    if tags.len() != data.tags.len() {
-        return Err(KclError::Type(KclErrorDetails {
-            message: "Duplicate tags are not allowed.".to_string(),
+    for edge_id in get_edge_ids(&data.tags, &extrude_group, &args)? {
+        args.send_modeling_cmd(
+            args.ctx.id_generator.next_uuid(),
+            ModelingCmd::Solid3DFilletEdge {
+                cut_type: Some(CutType::Fillet),
+                edge_id: Some(edge_id),
+                edge_ids: Vec::new(),
+                extra_face_ids: Vec::new(),
+                object_id: extrude_group.id,
+                radius: data.radius,
+                strategy: None,
+                tolerance: DEFAULT_TOLERANCE, // We can let the user set this in the future.
+            },
+        )
+        .await?;
+    }
+
+    Ok(extrude_group)
+}
+
+/// Data for chamfers.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ChamferData {
+    /// How far the chamfer cuts back from the edge along the first face.
+    pub length: f64,
+    /// How far the chamfer cuts back from the edge along the second face. Defaults to `length`.
+    #[serde(default)]
+    pub other_length: Option<f64>,
+    /// The angle (in degrees) between the chamfer and the first face, instead of `otherLength`.
+    #[serde(default)]
+    pub angle: Option<f64>,
+    /// The tags of the paths you want to chamfer.
+    pub tags: Vec<EdgeReference>,
+}
+
+/// Create chamfers on tagged paths.
+pub async fn chamfer(args: Args) -> Result<MemoryItem, KclError> {
+    let (data, extrude_group): (ChamferData, Box<ExtrudeGroup>) = args.get_data_and_extrude_group()?;
+
+    let extrude_group = inner_chamfer(data, extrude_group, args).await?;
+    Ok(MemoryItem::ExtrudeGroup(extrude_group))
+}
+
+/// Create chamfers on tagged paths, cutting away each edge with a flat face.
+///
+/// The chamfer cuts back `length` from the edge along the first face. Along the second face it
+/// cuts back `otherLength`, or as far as makes the chamfer meet the first face at `angle`. With
+/// neither, it cuts back `length` along both faces.
+///
+/// ```no_run
+/// const part001 = startSketchOn('XY')
+///     |> startProfileAt([0,0], %)
+///     |> line([0, 10], %, "thing")
+///     |> line([10, 0], %)
+///     |> line([0, -10], %, "thing2")
+///     |> close(%)
+///     |> extrude(10, %)
+///     |> chamfer({length: 2, tags: ["thing", getOppositeEdge("thing2", %)]}, %)
+/// ```
+///
+/// ```no_run
+/// const part001 = startSketchOn('XY')
+///     |> startProfileAt([0,0], %)
+///     |> line([0, 10], %, "thing")
+///     |> line([10, 0], %)
+///     |> line([0, -10], %, "thing2")
+///     |> close(%)
+///     |> extrude(10, %)
+///     |> chamfer({length: 1, otherLength: 3, tags: ["thing"]}, %)
+///     |> chamfer({length: 2, angle: 30, tags: [getNextAdjacentEdge("thing2", %)]}, %)
+/// ```
+#[stdlib {
+    name = "chamfer",
+}]
+async fn inner_chamfer(
+    data: ChamferData,
+    extrude_group: Box<ExtrudeGroup>,
+    args: Args,
+) -> Result<Box<ExtrudeGroup>, KclError> {
+    if data.length <= 0.0 {
+        return Err(KclError::Semantic(KclErrorDetails {
+            message: format!("Expected the chamfer length to be positive, found `{}`", data.length),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         }));
     }
+    if let Some(other_length) = data.other_length {
+        if other_length <= 0.0 {
+            return Err(KclError::Semantic(KclErrorDetails {
+                message: format!(
+                    "Expected the other chamfer length to be positive, found `{}`",
+                    other_length
+                ),
+                source_ranges: vec![args.source_range],
+                ..Default::default()
+            }));
+        }
+    }
+    if let Some(angle) = data.angle {
+        if data.other_length.is_some() {
+            return Err(KclError::Semantic(KclErrorDetails {
+                message: "A chamfer can have an other length or an angle, but not both".to_string(),
+                source_ranges: vec![args.source_range],
+                ..Default::default()
+            }));
+        }
+        if angle <= 0.0 || angle >= 90.0 {
+            return Err(KclError::Semantic(KclErrorDetails {
+                message: format!("Expected the chamfer angle to be between 0 and 90, found `{}`", angle),
+                source_ranges: vec![args.source_range],
+                ..Default::default()
+            }));
+        }
+    }
 
-    for tag in data.tags {
-        let edge_id = match tag {
-            EdgeReference::Uuid(uuid) => uuid,
-            EdgeReference::Tag(tag) => {
-                extrude_group
-                    .sketch_group_values
-                    .iter()
-                    .find(|p| p.get_name() == tag)
-                    .ok_or_else(|| {
-                        KclError::Type(KclErrorDetails {
-                            message: format!("No edge found with tag: `{}`", tag),
-                            source_ranges: vec![args.source_range],
-                        })
-                    })?
-                    .get_base()
-                    .geo_meta
-                    .id
-            }
-        };
-
+    for edge_id in get_edge_ids(&data.tags, &extrude_group, &args)? {
         args.send_modeling_cmd(
-            uuid::Uuid::new_v4(),
-            ModelingCmd::Solid3DFilletEdge {
-                edge_id,
+            args.ctx.id_generator.next_uuid(),
+            ModelingCmd::Solid3DCutEdges {
+                cut_type: CutTypeV2::Chamfer {
+                    angle: data.angle.map(kittycad::types::Angle::from_degrees),
+                    distance: data.length,
+                    second_distance: data.other_length,
+                    swap: false,
+                },
+                edge_ids: vec![edge_id],
+                extra_face_ids: Vec::new(),
                 object_id: extrude_group.id,
-                radius: data.radius,
-                tolerance: DEFAULT_TOLERANCE, // We can let the user set this in the future.
+                strategy: None,
+                tolerance: DEFAULT_TOLERANCE,
             },
         )
         .await?;
@@ -111,6 +205,41 @@ async fn inner_fillet(
     Ok(extrude_group)
 }
 
+/// Find the edges the given tags refer to.
+fn get_edge_ids(tags: &[EdgeReference], extrude_group: &ExtrudeGroup, args: &Args) -> Result<Vec<Uuid>, KclError> {
+    // Check if tags contains any duplicate values.
+    let mut unique_tags = tags.to_vec();
+    unique_tags.sort();
+    unique_tags.dedup();
+    if unique_tags.len() != tags.len() {
+        return Err(KclError::Type(KclErrorDetails {
+            message: "Duplicate tags are not allowed.".to_string(),
+            source_ranges: vec![args.source_range],
+            ..Default::default()
+        }));
+    }
+
+    tags.iter()
+        .map(|tag| match tag {
+            EdgeReference::Uuid(uuid) => Ok(*uuid),
+            EdgeReference::Tag(tag) => Ok(extrude_group
+                .sketch_group_values
+                .iter()
+                .find(|p| p.get_name() == *tag)
+                .ok_or_else(|| {
+                    KclError::Type(KclErrorDetails {
+                        message: format!("No edge found with tag: `{}`", tag),
+                        source_ranges: vec![args.source_range],
+                        ..Default::default()
+                    })
+                })?
+                .get_base()
+                .geo_meta
+                .id),
+        })
+        .collect()
+}
+
 /// Get the opposite edge to the edge given.
 pub async fn get_opposite_edge(args: Args) -> Result<MemoryItem, KclError> {
     let (tag, extrude_group): (String, Box<ExtrudeGroup>) = args.get_data_and_extrude_group()?;
@@ -121,9 +250,11 @@ pub async fn get_opposite_edge(args: Args) -> Result<MemoryItem, KclError> {
             KclError::Type(KclErrorDetails {
                 message: format!("Failed to convert Uuid to json: {}", e),
                 source_ranges: vec![args.source_range],
+                ..Default::default()
             })
         })?,
         meta: vec![args.source_range.into()],
+        ..Default::default()
     }))
 }
 
@@ -151,6 +282,7 @@ async fn inner_get_opposite_edge(tag: String, extrude_group: Box<ExtrudeGroup>,
             KclError::Type(KclErrorDetails {
                 message: format!("No edge found with tag: `{}`", tag),
                 source_ranges: vec![args.source_range],
+                ..Default::default()
             })
         })?
         .get_base();
@@ -159,7 +291,7 @@ async fn inner_get_opposite_edge(tag: String, extrude_group: Box<ExtrudeGroup>,
 
     let resp = args
         .send_modeling_cmd(
-            uuid::Uuid::new_v4(),
+            args.ctx.id_generator.next_uuid(),
             ModelingCmd::Solid3DGetOppositeEdge {
                 edge_id: tagged_path.geo_meta.id,
                 object_id: extrude_group.id,
@@ -174,6 +306,7 @@ async fn inner_get_opposite_edge(tag: String, extrude_group: Box<ExtrudeGroup>,
         return Err(KclError::Engine(KclErrorDetails {
             message: format!("Solid3DGetOppositeEdge response was not as expected: {:?}", resp),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         }));
     };
 
@@ -190,9 +323,11 @@ pub async fn get_next_adjacent_edge(args: Args) -> Result<MemoryItem, KclError>
             KclError::Type(KclErrorDetails {
                 message: format!("Failed to convert Uuid to json: {}", e),
                 source_ranges: vec![args.source_range],
+                ..Default::default()
             })
         })?,
         meta: vec![args.source_range.into()],
+        ..Default::default()
     }))
 }
 
@@ -224,6 +359,7 @@ async fn inner_get_next_adjacent_edge(
             KclError::Type(KclErrorDetails {
                 message: format!("No edge found with tag: `{}`", tag),
                 source_ranges: vec![args.source_range],
+                ..Default::default()
             })
         })?
         .get_base();
@@ -232,7 +368,7 @@ async fn inner_get_next_adjacent_edge(
 
     let resp = args
         .send_modeling_cmd(
-            uuid::Uuid::new_v4(),
+            args.ctx.id_generator.next_uuid(),
             ModelingCmd::Solid3DGetPrevAdjacentEdge {
                 edge_id: tagged_path.geo_meta.id,
                 object_id: extrude_group.id,
@@ -247,6 +383,7 @@ async fn inner_get_next_adjacent_edge(
         return Err(KclError::Engine(KclErrorDetails {
             message: format!("Solid3DGetNextAdjacentEdge response was not as expected: {:?}", resp),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         }));
     };
 
@@ -254,6 +391,7 @@ async fn inner_get_next_adjacent_edge(
         KclError::Type(KclErrorDetails {
             message: format!("No edge found next adjacent to tag: `{}`", tag),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         })
     })
 }
@@ -268,9 +406,11 @@ pub async fn get_previous_adjacent_edge(args: Args) -> Result<MemoryItem, KclErr
             KclError::Type(KclErrorDetails {
                 message: format!("Failed to convert Uuid to json: {}", e),
                 source_ranges: vec![args.source_range],
+                ..Default::default()
             })
         })?,
         meta: vec![args.source_range.into()],
+        ..Default::default()
     }))
 }
 
@@ -302,6 +442,7 @@ async fn inner_get_previous_adjacent_edge(
             KclError::Type(KclErrorDetails {
                 message: format!("No edge found with tag: `{}`", tag),
                 source_ranges: vec![args.source_range],
+                ..Default::default()
             })
         })?
         .get_base();
@@ -310,7 +451,7 @@ async fn inner_get_previous_adjacent_edge(
 
     let resp = args
         .send_modeling_cmd(
-            uuid::Uuid::new_v4(),
+            args.ctx.id_generator.next_uuid(),
             ModelingCmd::Solid3DGetNextAdjacentEdge {
                 edge_id: tagged_path.geo_meta.id,
                 object_id: extrude_group.id,
@@ -325,6 +466,7 @@ async fn inner_get_previous_adjacent_edge(
         return Err(KclError::Engine(KclErrorDetails {
             message: format!("Solid3DGetPrevAdjacentEdge response was not as expected: {:?}", resp),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         }));
     };
 
@@ -332,6 +474,7 @@ async fn inner_get_previous_adjacent_edge(
         KclError::Type(KclErrorDetails {
             message: format!("No edge found previous adjacent to tag: `{}`", tag),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         })
     })
 }
@@ -349,6 +492,7 @@ fn get_adjacent_face_to_tag(extrude_group: &ExtrudeGroup, tag: &str, args: &Args
             KclError::Type(KclErrorDetails {
                 message: format!("Expected a face with the tag `{}`", tag),
                 source_ranges: vec![args.source_range],
+                ..Default::default()
             })
         })?
 }
@@ -53,18 +53,22 @@ async fn inner_helix(
     extrude_group: Box<ExtrudeGroup>,
     args: Args,
 ) -> Result<Box<ExtrudeGroup>, KclError> {
-    let id = uuid::Uuid::new_v4();
+    let id = args.ctx.id_generator.next_uuid();
     args.send_modeling_cmd(
         id,
         ModelingCmd::EntityMakeHelix {
             cylinder_id: extrude_group.id,
             is_clockwise: !data.ccw,
-            length: data.length.unwrap_or(extrude_group.height),
+            length: data.length,
             revolutions: data.revolutions,
-            start_angle: kittycad::types::Angle::from_degrees(data.angle_start),
+            start_angle: Some(kittycad::types::Angle::from_degrees(data.angle_start)),
         },
     )
     .await?;
 
+    // Keep the helix, so it can be swept along.
+    let mut extrude_group = extrude_group;
+    extrude_group.helix_ids.push(id);
+
     Ok(extrude_group)
 }
@@ -83,22 +83,26 @@ pub enum ImportFormat {
     },
 }
 
-impl From<ImportFormat> for kittycad::types::InputFormat {
+impl From<ImportFormat> for kittycad::types::InputFormat3D {
     fn from(format: ImportFormat) -> Self {
         match format {
-            ImportFormat::Fbx {} => kittycad::types::InputFormat::Fbx {},
-            ImportFormat::Gltf {} => kittycad::types::InputFormat::Gltf {},
-            ImportFormat::Obj { coords, units } => kittycad::types::InputFormat::Obj {
+            ImportFormat::Fbx {} => kittycad::types::InputFormat3D::Fbx {},
+            ImportFormat::Gltf {} => kittycad::types::InputFormat3D::Gltf {},
+            ImportFormat::Obj { coords, units } => kittycad::types::InputFormat3D::Obj {
                 coords: coords.unwrap_or(ZOO_COORD_SYSTEM),
                 units,
             },
-            ImportFormat::Ply { coords, units } => kittycad::types::InputFormat::Ply {
+            ImportFormat::Ply { coords, units } => kittycad::types::InputFormat3D::Ply {
                 coords: coords.unwrap_or(ZOO_COORD_SYSTEM),
                 units,
             },
-            ImportFormat::Sldprt {} => kittycad::types::InputFormat::Sldprt {},
-            ImportFormat::Step {} => kittycad::types::InputFormat::Step {},
-            ImportFormat::Stl { coords, units } => kittycad::types::InputFormat::Stl {
+            ImportFormat::Sldprt {} => kittycad::types::InputFormat3D::Sldprt {
+                split_closed_faces: false,
+            },
+            ImportFormat::Step {} => kittycad::types::InputFormat3D::Step {
+                split_closed_faces: false,
+            },
+            ImportFormat::Stl { coords, units } => kittycad::types::InputFormat3D::Stl {
                 coords: coords.unwrap_or(ZOO_COORD_SYSTEM),
                 units,
             },
@@ -160,6 +164,7 @@ async fn inner_import(
         return Err(KclError::Semantic(KclErrorDetails {
             message: "No file path was provided.".to_string(),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         }));
     }
 
@@ -168,6 +173,7 @@ async fn inner_import(
         return Err(KclError::Semantic(KclErrorDetails {
             message: format!("File `{}` does not exist.", file_path),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         }));
     }
 
@@ -175,23 +181,26 @@ async fn inner_import(
         KclError::Semantic(KclErrorDetails {
             message: format!("No file extension found for `{}`", file_path),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         })
     })?)
     .map_err(|e| {
         KclError::Semantic(KclErrorDetails {
             message: e.to_string(),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         })
     })?;
 
     // Get the format type from the extension of the file.
     let format = if let Some(options) = options {
         // Validate the given format with the extension format.
-        let format: kittycad::types::InputFormat = options.into();
+        let format: kittycad::types::InputFormat3D = options.into();
         validate_extension_format(ext_format, format.clone()).map_err(|e| {
             KclError::Semantic(KclErrorDetails {
                 message: e.to_string(),
                 source_ranges: vec![args.source_range],
+                ..Default::default()
             })
         })?;
         format
@@ -204,6 +213,7 @@ async fn inner_import(
         KclError::Semantic(KclErrorDetails {
             message: e.to_string(),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         })
     })?;
 
@@ -215,6 +225,7 @@ async fn inner_import(
             KclError::Semantic(KclErrorDetails {
                 message: format!("Could not get the file name from the path `{}`", file_path),
                 source_ranges: vec![args.source_range],
+                ..Default::default()
             })
         })?;
     let mut import_files = vec![kittycad::types::ImportFile {
@@ -224,7 +235,7 @@ async fn inner_import(
 
     // In the case of a gltf importing a bin file we need to handle that! and figure out where the
     // file is relative to our current file.
-    if let kittycad::types::InputFormat::Gltf {} = format {
+    if let kittycad::types::InputFormat3D::Gltf {} = format {
         // Check if the file is a binary gltf file, in that case we don't need to import the bin
         // file.
         if !file_contents.starts_with(b"glTF") {
@@ -232,6 +243,7 @@ async fn inner_import(
                 KclError::Semantic(KclErrorDetails {
                     message: e.to_string(),
                     source_ranges: vec![args.source_range],
+                    ..Default::default()
                 })
             })?;
 
@@ -248,6 +260,7 @@ async fn inner_import(
                                 KclError::Semantic(KclErrorDetails {
                                     message: format!("Could not get the parent path of the file `{}`", file_path),
                                     source_ranges: vec![args.source_range],
+                                    ..Default::default()
                                 })
                             })?;
 
@@ -255,6 +268,7 @@ async fn inner_import(
                             KclError::Semantic(KclErrorDetails {
                                 message: e.to_string(),
                                 source_ranges: vec![args.source_range],
+                                ..Default::default()
                             })
                         })?;
 
@@ -268,7 +282,7 @@ async fn inner_import(
         }
     }
 
-    let id = uuid::Uuid::new_v4();
+    let id = args.ctx.id_generator.next_uuid();
     let resp = args
         .send_modeling_cmd(
             id,
@@ -286,6 +300,7 @@ async fn inner_import(
         return Err(KclError::Engine(KclErrorDetails {
             message: format!("ImportFiles response was not as expected: {:?}", resp),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         }));
     };
 
@@ -297,7 +312,7 @@ async fn inner_import(
 }
 
 /// Get the source format from the extension.
-fn get_import_format_from_extension(ext: &str) -> Result<kittycad::types::InputFormat> {
+fn get_import_format_from_extension(ext: &str) -> Result<kittycad::types::InputFormat3D> {
     let format = match kittycad::types::FileImportFormat::from_str(ext) {
         Ok(format) => format,
         Err(_) => {
@@ -323,40 +338,44 @@ fn get_import_format_from_extension(ext: &str) -> Result<kittycad::types::InputF
     // * Up: +Z
     // * Handedness: Right
     match format {
-        kittycad::types::FileImportFormat::Step => Ok(kittycad::types::InputFormat::Step {}),
-        kittycad::types::FileImportFormat::Stl => Ok(kittycad::types::InputFormat::Stl {
+        kittycad::types::FileImportFormat::Step => Ok(kittycad::types::InputFormat3D::Step {
+            split_closed_faces: false,
+        }),
+        kittycad::types::FileImportFormat::Stl => Ok(kittycad::types::InputFormat3D::Stl {
             coords: ZOO_COORD_SYSTEM,
             units: ul,
         }),
-        kittycad::types::FileImportFormat::Obj => Ok(kittycad::types::InputFormat::Obj {
+        kittycad::types::FileImportFormat::Obj => Ok(kittycad::types::InputFormat3D::Obj {
             coords: ZOO_COORD_SYSTEM,
             units: ul,
         }),
-        kittycad::types::FileImportFormat::Gltf => Ok(kittycad::types::InputFormat::Gltf {}),
-        kittycad::types::FileImportFormat::Ply => Ok(kittycad::types::InputFormat::Ply {
+        kittycad::types::FileImportFormat::Gltf => Ok(kittycad::types::InputFormat3D::Gltf {}),
+        kittycad::types::FileImportFormat::Ply => Ok(kittycad::types::InputFormat3D::Ply {
             coords: ZOO_COORD_SYSTEM,
             units: ul,
         }),
-        kittycad::types::FileImportFormat::Fbx => Ok(kittycad::types::InputFormat::Fbx {}),
-        kittycad::types::FileImportFormat::Sldprt => Ok(kittycad::types::InputFormat::Sldprt {}),
+        kittycad::types::FileImportFormat::Fbx => Ok(kittycad::types::InputFormat3D::Fbx {}),
+        kittycad::types::FileImportFormat::Sldprt => Ok(kittycad::types::InputFormat3D::Sldprt {
+            split_closed_faces: false,
+        }),
     }
 }
 
-fn validate_extension_format(ext: kittycad::types::InputFormat, given: kittycad::types::InputFormat) -> Result<()> {
-    if let kittycad::types::InputFormat::Stl { coords: _, units: _ } = ext {
-        if let kittycad::types::InputFormat::Stl { coords: _, units: _ } = given {
+fn validate_extension_format(ext: kittycad::types::InputFormat3D, given: kittycad::types::InputFormat3D) -> Result<()> {
+    if let kittycad::types::InputFormat3D::Stl { coords: _, units: _ } = ext {
+        if let kittycad::types::InputFormat3D::Stl { coords: _, units: _ } = given {
             return Ok(());
         }
     }
 
-    if let kittycad::types::InputFormat::Obj { coords: _, units: _ } = ext {
-        if let kittycad::types::InputFormat::Obj { coords: _, units: _ } = given {
+    if let kittycad::types::InputFormat3D::Obj { coords: _, units: _ } = ext {
+        if let kittycad::types::InputFormat3D::Obj { coords: _, units: _ } = given {
             return Ok(());
         }
     }
 
-    if let kittycad::types::InputFormat::Ply { coords: _, units: _ } = ext {
-        if let kittycad::types::InputFormat::Ply { coords: _, units: _ } = given {
+    if let kittycad::types::InputFormat3D::Ply { coords: _, units: _ } = ext {
+        if let kittycad::types::InputFormat3D::Ply { coords: _, units: _ } = given {
             return Ok(());
         }
     }
@@ -372,14 +391,14 @@ fn validate_extension_format(ext: kittycad::types::InputFormat, given: kittycad:
     )
 }
 
-fn get_name_of_format(type_: kittycad::types::InputFormat) -> String {
+fn get_name_of_format(type_: kittycad::types::InputFormat3D) -> String {
     match type_ {
-        kittycad::types::InputFormat::Fbx {} => "fbx".to_string(),
-        kittycad::types::InputFormat::Gltf {} => "gltf".to_string(),
-        kittycad::types::InputFormat::Obj { coords: _, units: _ } => "obj".to_string(),
-        kittycad::types::InputFormat::Ply { coords: _, units: _ } => "ply".to_string(),
-        kittycad::types::InputFormat::Sldprt {} => "sldprt".to_string(),
-        kittycad::types::InputFormat::Step {} => "step".to_string(),
-        kittycad::types::InputFormat::Stl { coords: _, units: _ } => "stl".to_string(),
+        kittycad::types::InputFormat3D::Fbx {} => "fbx".to_string(),
+        kittycad::types::InputFormat3D::Gltf {} => "gltf".to_string(),
+        kittycad::types::InputFormat3D::Obj { coords: _, units: _ } => "obj".to_string(),
+        kittycad::types::InputFormat3D::Ply { coords: _, units: _ } => "ply".to_string(),
+        kittycad::types::InputFormat3D::Sldprt { .. } => "sldprt".to_string(),
+        kittycad::types::InputFormat3D::Step { .. } => "step".to_string(),
+        kittycad::types::InputFormat3D::Stl { coords: _, units: _ } => "stl".to_string(),
     }
 }
@@ -0,0 +1,213 @@
+//! Standard library lofts.
+
+use anyhow::Result;
+use derive_docs::stdlib;
+use kittycad::types::ModelingCmd;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    errors::{KclError, KclErrorDetails},
+    executor::{ExtrudeGroup, MemoryItem, SketchGroup, SketchSurface},
+    std::{extrude::do_post_extrude, fillet::DEFAULT_TOLERANCE, Args},
+};
+
+/// Data for lofts.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct LoftData {
+    /// Join each profile to the next with straight, ruled surfaces, rather than one smooth
+    /// surface through all of the profiles.
+    /// The default is `false`.
+    #[serde(default)]
+    pub ruled: bool,
+}
+
+/// Create a solid which passes through each of the given profiles in turn.
+pub async fn loft(args: Args) -> Result<MemoryItem, KclError> {
+    let (sketch_groups, data): (Vec<SketchGroup>, Option<LoftData>) =
+        args.get_sketch_group_array_and_optional_data()?;
+
+    let extrude_group = inner_loft(sketch_groups, data, args).await?;
+    Ok(MemoryItem::ExtrudeGroup(extrude_group))
+}
+
+/// Create a solid which passes through each of the given profiles in turn.
+///
+/// The profiles must be closed, and each must be on a different plane. The first and last
+/// profiles become the `start` and `end` caps of the solid, and the tags on their paths can be
+/// used to fillet or chamfer the edges around the caps.
+///
+/// ```no_run
+/// const squareSketch = startSketchOn('XY')
+///     |> startProfileAt([-100, 200], %)
+///     |> line([200, 0], %)
+///     |> line([0, -200], %)
+///     |> line([-200, 0], %)
+///     |> close(%)
+///
+/// const circleSketch = startSketchOn({
+///        plane: {
+///          origin: { x: 0, y: 100, z: 300 },
+///          x_axis: { x: 1, y: 0, z: 0 },
+///          y_axis: { x: 0, y: 1, z: 0 },
+///          z_axis: { x: 0, y: 0, z: 1 }
+///        }
+///      })
+///     |> circle([0, 100], 50, %)
+///
+/// const duct = loft([squareSketch, circleSketch])
+/// ```
+///
+/// ```no_run
+/// const bottom = startSketchOn('XY')
+///     |> startProfileAt([-10, -10], %)
+///     |> line([20, 0], %)
+///     |> line([0, 20], %)
+///     |> line([-20, 0], %, "back")
+///     |> close(%)
+///
+/// const top = startSketchOn({
+///        plane: {
+///          origin: { x: 0, y: 0, z: 20 },
+///          x_axis: { x: 1, y: 0, z: 0 },
+///          y_axis: { x: 0, y: 1, z: 0 },
+///          z_axis: { x: 0, y: 0, z: 1 }
+///        }
+///      })
+///     |> startProfileAt([-5, -5], %)
+///     |> line([10, 0], %)
+///     |> line([0, 10], %)
+///     |> line([-10, 0], %)
+///     |> close(%)
+///
+/// const frustum = loft([bottom, top], {ruled: true})
+///     |> fillet({radius: 1, tags: ["back"]}, %)
+/// ```
+#[stdlib {
+    name = "loft",
+}]
+async fn inner_loft(
+    sketch_groups: Vec<SketchGroup>,
+    data: Option<LoftData>,
+    args: Args,
+) -> Result<Box<ExtrudeGroup>, KclError> {
+    let data = data.unwrap_or_default();
+
+    if sketch_groups.len() < 2 {
+        return Err(KclError::Semantic(KclErrorDetails {
+            message: format!(
+                "Expected at least 2 profiles to loft between, found `{}`",
+                sketch_groups.len()
+            ),
+            source_ranges: vec![args.source_range],
+            ..Default::default()
+        }));
+    }
+
+    for (i, sketch_group) in sketch_groups.iter().enumerate() {
+        if !sketch_group.is_closed() {
+            return Err(KclError::Semantic(KclErrorDetails {
+                message: format!("Expected every profile to be closed, but profile {} is open", i),
+                source_ranges: vec![args.source_range],
+                ..Default::default()
+            }));
+        }
+        if let Some(j) = sketch_groups[..i]
+            .iter()
+            .position(|other| on_same_plane(&other.on, &sketch_group.on))
+        {
+            return Err(KclError::Semantic(KclErrorDetails {
+                message: format!(
+                    "Expected every profile to be on a different plane, but profiles {} and {} are on the same plane",
+                    j, i
+                ),
+                source_ranges: vec![args.source_range],
+                ..Default::default()
+            }));
+        }
+    }
+
+    // The tags on both end profiles are kept, so a tag on both couldn't tell which edge it means.
+    let first = sketch_groups.first().unwrap();
+    let last = sketch_groups.last().unwrap();
+    if let Some(path) = last.value.iter().find(|path| {
+        let name = path.get_name();
+        !name.is_empty() && first.value.iter().any(|other| other.get_name() == name)
+    }) {
+        return Err(KclError::Semantic(KclErrorDetails {
+            message: format!(
+                "Expected the tags on the first and last profiles to be unique, but `{}` is used by both",
+                path.get_name()
+            ),
+            source_ranges: vec![args.source_range],
+            ..Default::default()
+        }));
+    }
+
+    let id = args.ctx.id_generator.next_uuid();
+    let resp = args
+        .send_modeling_cmd(
+            id,
+            ModelingCmd::Loft {
+                section_ids: sketch_groups.iter().map(|sketch_group| sketch_group.id).collect(),
+                // A linear interpolation between the sections gives ruled surfaces.
+                v_degree: if data.ruled { 1 } else { 2 },
+                bez_approximate_rational: false,
+                base_curve_index: None,
+                tolerance: DEFAULT_TOLERANCE,
+            },
+        )
+        .await?;
+
+    let solid_id = match &resp {
+        kittycad::types::OkWebSocketResponseData::Modeling {
+            modeling_response: kittycad::types::OkModelingCmdResponse::Loft { data },
+        } => data.solid_id,
+        // The mock engine doesn't make any geometry, so stand in an id to make lofts mock-execute safe.
+        _ if args.ctx.is_mock => id,
+        _ => {
+            return Err(KclError::Engine(KclErrorDetails {
+                message: format!("Loft response was not as expected: {:?}", resp),
+                source_ranges: vec![args.source_range],
+                ..Default::default()
+            }));
+        }
+    };
+
+    // Helices on a lofted solid default to running between the end caps.
+    let height = match (&first.on, &last.on) {
+        (SketchSurface::Plane(a), SketchSurface::Plane(b)) => {
+            let (dx, dy, dz) = (
+                b.origin.x - a.origin.x,
+                b.origin.y - a.origin.y,
+                b.origin.z - a.origin.z,
+            );
+            (dx * dx + dy * dy + dz * dz).sqrt()
+        }
+        _ => 0.0,
+    };
+
+    // The side faces and the caps are found from the edges of the first profile, the same way
+    // they are for an extrusion.
+    let mut sketch_group = first.clone();
+    sketch_group.id = solid_id;
+    let mut extrude_group = do_post_extrude(Box::new(sketch_group), height, id, args).await?;
+
+    // The edges around the end cap are the paths of the last profile, so their tags can be
+    // filleted too.
+    extrude_group.sketch_group_values.extend(last.value.iter().cloned());
+
+    Ok(extrude_group)
+}
+
+/// Whether two profiles are sketched on the same plane or face.
+fn on_same_plane(a: &SketchSurface, b: &SketchSurface) -> bool {
+    match (a, b) {
+        (SketchSurface::Plane(a), SketchSurface::Plane(b)) => {
+            a.id == b.id || (a.origin == b.origin && a.z_axis == b.z_axis)
+        }
+        _ => a.id() == b.id(),
+    }
+}
@@ -12,7 +12,7 @@ use crate::{
 
 /// Computes the cosine of a number (in radians).
 pub async fn cos(args: Args) -> Result<MemoryItem, KclError> {
-    let num = args.get_number()?;
+    let num = args.get_angle_in_radians()?;
     let result = inner_cos(num)?;
 
     args.make_user_val_from_f64(result)
@@ -20,6 +20,8 @@ pub async fn cos(args: Args) -> Result<MemoryItem, KclError> {
 
 /// Computes the cosine of a number (in radians).
 ///
+/// An angle with a unit of measure, like `90deg`, is converted to radians first.
+///
 /// ```no_run
 /// const anotherVar = cos(2*pi())
 /// ```
@@ -33,7 +35,7 @@ fn inner_cos(num: f64) -> Result<f64, KclError> {
 
 /// Computes the sine of a number (in radians).
 pub async fn sin(args: Args) -> Result<MemoryItem, KclError> {
-    let num = args.get_number()?;
+    let num = args.get_angle_in_radians()?;
     let result = inner_sin(num)?;
 
     args.make_user_val_from_f64(result)
@@ -41,6 +43,8 @@ pub async fn sin(args: Args) -> Result<MemoryItem, KclError> {
 
 /// Computes the sine of a number (in radians).
 ///
+/// An angle with a unit of measure, like `90deg`, is converted to radians first.
+///
 /// ```no_run
 /// const myVar = sin(2*pi())
 /// ```
@@ -54,7 +58,7 @@ fn inner_sin(num: f64) -> Result<f64, KclError> {
 
 /// Computes the tangent of a number (in radians).
 pub async fn tan(args: Args) -> Result<MemoryItem, KclError> {
-    let num = args.get_number()?;
+    let num = args.get_angle_in_radians()?;
     let result = inner_tan(num)?;
 
     args.make_user_val_from_f64(result)
@@ -62,6 +66,8 @@ pub async fn tan(args: Args) -> Result<MemoryItem, KclError> {
 
 /// Computes the tangent of a number (in radians).
 ///
+/// An angle with a unit of measure, like `90deg`, is converted to radians first.
+///
 /// ```no_run
 /// const myVar = tan(2*pi())
 /// ```
@@ -240,6 +246,7 @@ pub async fn pow(args: Args) -> Result<MemoryItem, KclError> {
         return Err(KclError::Type(KclErrorDetails {
             message: format!("expected 2 arguments, got {}", nums.len()),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         }));
     }
 
@@ -247,6 +254,7 @@ pub async fn pow(args: Args) -> Result<MemoryItem, KclError> {
         return Err(KclError::Type(KclErrorDetails {
             message: format!("expected 2 arguments, got {}", nums.len()),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         }));
     }
 
@@ -342,6 +350,7 @@ pub async fn log(args: Args) -> Result<MemoryItem, KclError> {
         return Err(KclError::Type(KclErrorDetails {
             message: format!("expected 2 arguments, got {}", nums.len()),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         }));
     }
 
@@ -349,6 +358,7 @@ pub async fn log(args: Args) -> Result<MemoryItem, KclError> {
         return Err(KclError::Type(KclErrorDetails {
             message: format!("expected 2 arguments, got {}", nums.len()),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         }));
     }
     let result = inner_log(nums[0], nums[1])?;
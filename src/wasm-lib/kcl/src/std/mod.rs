@@ -1,16 +1,21 @@
 //! Functions implemented for language execution.
 
+pub mod array;
+pub mod boolean;
 pub mod extrude;
 pub mod fillet;
 pub mod helix;
 pub mod import;
 pub mod kcl_stdlib;
+pub mod loft;
 pub mod math;
 pub mod patterns;
 pub mod revolve;
 pub mod segment;
 pub mod shapes;
+pub mod shell;
 pub mod sketch;
+pub mod sweep;
 pub mod types;
 pub mod utils;
 
@@ -25,13 +30,18 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    ast::types::parse_json_number_as_f64,
+    ast::types::{parse_json_number_as_f64, Dimension},
     docs::StdLibFn,
     errors::{KclError, KclErrorDetails},
     executor::{
-        ExecutorContext, ExtrudeGroup, MemoryItem, Metadata, SketchGroup, SketchGroupSet, SketchSurface, SourceRange,
+        ExecutorContext, ExtrudeGroup, MemoryItem, Metadata, SketchGroup, SketchGroupSet, SketchSurface, Solid,
+        SourceRange, UserVal,
+    },
+    std::{
+        kcl_stdlib::KclStdLibFn,
+        sketch::SketchOnFaceTag,
+        types::{FnAsArg, KclValue},
     },
-    std::{kcl_stdlib::KclStdLibFn, sketch::SketchOnFaceTag},
 };
 
 pub type StdFn = fn(Args) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<MemoryItem, KclError>> + Send>>;
@@ -79,12 +89,19 @@ lazy_static! {
         Box::new(crate::std::patterns::PatternCircular2D),
         Box::new(crate::std::patterns::PatternCircular3D),
         Box::new(crate::std::fillet::Fillet),
+        Box::new(crate::std::fillet::Chamfer),
         Box::new(crate::std::fillet::GetOppositeEdge),
         Box::new(crate::std::fillet::GetNextAdjacentEdge),
         Box::new(crate::std::fillet::GetPreviousAdjacentEdge),
         Box::new(crate::std::helix::Helix),
         Box::new(crate::std::revolve::Revolve),
         Box::new(crate::std::revolve::GetEdge),
+        Box::new(crate::std::loft::Loft),
+        Box::new(crate::std::sweep::Sweep),
+        Box::new(crate::std::shell::Shell),
+        Box::new(crate::std::boolean::Union),
+        Box::new(crate::std::boolean::Subtract),
+        Box::new(crate::std::boolean::Intersection),
         Box::new(crate::std::import::Import),
         Box::new(crate::std::math::Cos),
         Box::new(crate::std::math::Sin),
@@ -108,6 +125,10 @@ lazy_static! {
         Box::new(crate::std::math::Ln),
         Box::new(crate::std::math::ToDegrees),
         Box::new(crate::std::math::ToRadians),
+        Box::new(crate::std::array::Map),
+        Box::new(crate::std::array::Filter),
+        Box::new(crate::std::array::Reduce),
+        Box::new(crate::std::array::Range),
     ];
 }
 
@@ -207,12 +228,39 @@ impl Args {
         }
     }
 
+    /// Report a problem with this call which doesn't stop the program.
+    pub fn warn(&self, message: impl Into<String>) {
+        self.ctx
+            .warnings
+            .add(crate::errors::KclWarning::new(self.source_range, message));
+    }
+
     pub async fn send_modeling_cmd(
         &self,
         id: uuid::Uuid,
         cmd: kittycad::types::ModelingCmd,
     ) -> Result<OkWebSocketResponseData, KclError> {
-        self.ctx.engine.send_modeling_cmd(id, self.source_range, cmd).await
+        self.ctx.budget.count_command();
+        let profiled = self.ctx.profiler.as_ref().map(|profiler| {
+            profiler.count_command(self.source_range);
+            // Commands with return values flush the batch, so this waits for the engine.
+            let flushed = crate::engine::is_cmd_with_return_values(&cmd)
+                .then(|| self.ctx.engine.batch().lock().unwrap().len() + 1);
+            (profiler, profiler.start(), flushed)
+        });
+        let response = self
+            .ctx
+            .engine
+            .send_modeling_cmd(id, self.source_range, cmd.clone())
+            .await;
+        if let Some((profiler, start, Some(commands))) = profiled {
+            profiler.end_flush(start, self.source_range, commands);
+        }
+        let response = response?;
+        self.ctx
+            .cache
+            .record_objects(crate::engine::created_object_ids(id, &cmd, &response));
+        Ok(response)
     }
 
     fn make_user_val_from_json(&self, j: serde_json::Value) -> Result<MemoryItem, KclError> {
@@ -221,6 +269,7 @@ impl Args {
             meta: vec![Metadata {
                 source_range: self.source_range,
             }],
+            ..Default::default()
         }))
     }
 
@@ -230,6 +279,7 @@ impl Args {
                 KclError::Type(KclErrorDetails {
                     message: format!("Failed to convert `{}` to a number", f),
                     source_ranges: vec![self.source_range],
+                    ..Default::default()
                 })
             },
         )?))
@@ -243,6 +293,7 @@ impl Args {
                 KclError::Type(KclErrorDetails {
                     message: format!("Expected a number as the first argument, found `{:?}`", self.args),
                     source_ranges: vec![self.source_range],
+                    ..Default::default()
                 })
             })?
             .get_json_value()?;
@@ -250,6 +301,21 @@ impl Args {
         parse_json_number_as_f64(&first_value, self.source_range)
     }
 
+    /// Get the first argument as an angle in radians. Angles with a unit of measure, like `90deg`,
+    /// are in degrees, and plain numbers are taken to be in radians already.
+    fn get_angle_in_radians(&self) -> Result<f64, KclError> {
+        let num = self.get_number()?;
+        match self.args.first().and_then(MemoryItem::dimension) {
+            Some(Dimension::Angle) => Ok(num.to_radians()),
+            Some(dimension) => Err(KclError::Type(KclErrorDetails {
+                message: format!("Expected an angle, but this is {}", dimension.describe()),
+                source_ranges: vec![self.source_range],
+                ..Default::default()
+            })),
+            None => Ok(num),
+        }
+    }
+
     fn get_number_array(&self) -> Result<Vec<f64>, KclError> {
         let mut numbers: Vec<f64> = Vec::new();
         for arg in &self.args {
@@ -266,6 +332,7 @@ impl Args {
             return Err(KclError::Type(KclErrorDetails {
                 message: format!("Expected a number array of length 2, found `{:?}`", numbers),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             }));
         }
 
@@ -285,6 +352,7 @@ impl Args {
                         self.args
                     ),
                     source_ranges: vec![self.source_range],
+                    ..Default::default()
                 })
             })?
             .get_json_value()?;
@@ -297,6 +365,7 @@ impl Args {
                         self.args
                     ),
                     source_ranges: vec![self.source_range],
+                    ..Default::default()
                 }));
             }
             let x = parse_json_number_as_f64(&arr[0], self.source_range)?;
@@ -309,6 +378,7 @@ impl Args {
                     self.args
                 ),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             }));
         };
 
@@ -319,6 +389,7 @@ impl Args {
                 KclError::Type(KclErrorDetails {
                     message: format!("Expected a number as the second argument, found `{:?}`", self.args),
                     source_ranges: vec![self.source_range],
+                    ..Default::default()
                 })
             })?
             .get_json_value()?;
@@ -327,6 +398,7 @@ impl Args {
             KclError::Type(KclErrorDetails {
                 message: format!("Failed to deserialize number from JSON: {}", e),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             })
         })?;
 
@@ -337,6 +409,7 @@ impl Args {
                     self.args
                 ),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             })
         })?;
 
@@ -353,6 +426,7 @@ impl Args {
                     self.args
                 ),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             }));
         };
 
@@ -361,6 +435,7 @@ impl Args {
                 KclError::Type(KclErrorDetails {
                     message: format!("Failed to deserialize String from JSON: {}", e),
                     source_ranges: vec![self.source_range],
+                    ..Default::default()
                 })
             })?;
             Ok((center, radius, sketch_group_or_surface, Some(tag)))
@@ -379,6 +454,7 @@ impl Args {
                 KclError::Type(KclErrorDetails {
                     message: format!("Expected a string as the first argument, found `{:?}`", self.args),
                     source_ranges: vec![self.source_range],
+                    ..Default::default()
                 })
             })?
             .get_json_value()?;
@@ -389,6 +465,7 @@ impl Args {
             return Err(KclError::Type(KclErrorDetails {
                 message: format!("Expected a string as the first argument, found `{:?}`", self.args),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             }));
         };
 
@@ -396,6 +473,7 @@ impl Args {
             KclError::Type(KclErrorDetails {
                 message: format!("Expected a SketchGroup as the second argument, found `{:?}`", self.args),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             })
         })?;
 
@@ -405,6 +483,7 @@ impl Args {
             return Err(KclError::Type(KclErrorDetails {
                 message: format!("Expected a SketchGroup as the second argument, found `{:?}`", self.args),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             }));
         };
 
@@ -416,6 +495,7 @@ impl Args {
             KclError::Type(KclErrorDetails {
                 message: format!("Expected a SketchGroup as the first argument, found `{:?}`", self.args),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             })
         })?;
 
@@ -430,6 +510,7 @@ impl Args {
                     self.args
                 ),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             }));
         };
 
@@ -437,6 +518,7 @@ impl Args {
             KclError::Type(KclErrorDetails {
                 message: format!("Expected a SketchGroup as the second argument, found `{:?}`", self.args),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             })
         })?;
 
@@ -446,17 +528,140 @@ impl Args {
             return Err(KclError::Type(KclErrorDetails {
                 message: format!("Expected a SketchGroup as the second argument, found `{:?}`", self.args),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             }));
         };
 
         Ok((sketch_set, sketch_group))
     }
 
+    fn get_sketch_group_array_and_optional_data<T: serde::de::DeserializeOwned>(
+        &self,
+    ) -> Result<(Vec<SketchGroup>, Option<T>), KclError> {
+        let wrong_type = || {
+            KclError::Type(KclErrorDetails {
+                message: format!(
+                    "Expected an array of SketchGroups as the first argument, found `{:?}`",
+                    self.args
+                ),
+                source_ranges: vec![self.source_range],
+                ..Default::default()
+            })
+        };
+        let first_value = self.args.first().ok_or_else(wrong_type)?;
+
+        // An array literal of sketch groups is a user value, e.g. `[profile0, profile1]`.
+        let sketch_groups = match first_value {
+            MemoryItem::SketchGroups { value } => value.iter().map(|sg| sg.as_ref().clone()).collect(),
+            MemoryItem::UserVal(UserVal {
+                value: serde_json::Value::Array(items),
+                ..
+            }) => items
+                .iter()
+                .map(
+                    |item| match crate::std::array::array_element_to_memory_item(item.clone(), self.source_range) {
+                        MemoryItem::SketchGroup(sg) => Ok(*sg),
+                        _ => Err(wrong_type()),
+                    },
+                )
+                .collect::<Result<Vec<_>, _>>()?,
+            _ => return Err(wrong_type()),
+        };
+
+        if let Some(second_value) = self.args.get(1) {
+            let data: T = serde_json::from_value(second_value.get_json_value()?).map_err(|e| {
+                KclError::Type(KclErrorDetails {
+                    message: format!("Failed to deserialize struct from JSON: {}", e),
+                    source_ranges: vec![self.source_range],
+                    ..Default::default()
+                })
+            })?;
+            Ok((sketch_groups, Some(data)))
+        } else {
+            Ok((sketch_groups, None))
+        }
+    }
+
+    /// Get the solids in the argument at the given index, which is either one solid, a group of
+    /// extrude groups, or an array of solids.
+    fn get_solid_set(&self, index: usize) -> Result<Vec<Solid>, KclError> {
+        let wrong_type = || {
+            KclError::Type(KclErrorDetails {
+                message: format!(
+                    "Expected an ExtrudeGroup, ImportedGeometry or an array of them as argument {}, found `{:?}`",
+                    index, self.args
+                ),
+                source_ranges: vec![self.source_range],
+                ..Default::default()
+            })
+        };
+        let to_solid = |item: &MemoryItem| match item {
+            MemoryItem::ExtrudeGroup(eg) => Ok(Solid::ExtrudeGroup(eg.clone())),
+            MemoryItem::ImportedGeometry(ig) => Ok(Solid::ImportedGeometry(Box::new(ig.clone()))),
+            _ => Err(wrong_type()),
+        };
+
+        match self.args.get(index).ok_or_else(wrong_type)? {
+            MemoryItem::ExtrudeGroups { value } => Ok(value.iter().map(|eg| Solid::ExtrudeGroup(eg.clone())).collect()),
+            // An array literal of solids is a user value, e.g. `[box, cylinder]`.
+            MemoryItem::UserVal(UserVal {
+                value: serde_json::Value::Array(items),
+                ..
+            }) => items
+                .iter()
+                .map(|item| {
+                    to_solid(&crate::std::array::array_element_to_memory_item(
+                        item.clone(),
+                        self.source_range,
+                    ))
+                })
+                .collect(),
+            item => Ok(vec![to_solid(item)?]),
+        }
+    }
+
+    fn get_solids(&self) -> Result<Vec<Solid>, KclError> {
+        self.get_solid_set(0)
+    }
+
+    fn get_solids_and_solid(&self) -> Result<(Vec<Solid>, Solid), KclError> {
+        let solids = self.get_solid_set(0)?;
+
+        let second_value = self.args.get(1).ok_or_else(|| {
+            KclError::Type(KclErrorDetails {
+                message: format!(
+                    "Expected an ExtrudeGroup or ImportedGeometry as the second argument, found `{:?}`",
+                    self.args
+                ),
+                source_ranges: vec![self.source_range],
+                ..Default::default()
+            })
+        })?;
+
+        let solid = match second_value {
+            MemoryItem::ExtrudeGroup(eg) => Solid::ExtrudeGroup(eg.clone()),
+            MemoryItem::ImportedGeometry(ig) => Solid::ImportedGeometry(Box::new(ig.clone())),
+            _ => {
+                return Err(KclError::Type(KclErrorDetails {
+                    message: format!(
+                        "Expected an ExtrudeGroup or ImportedGeometry as the second argument, found `{:?}`",
+                        self.args
+                    ),
+                    source_ranges: vec![self.source_range],
+                    ..Default::default()
+                }));
+            }
+        };
+
+        Ok((solids, solid))
+    }
+
     fn get_sketch_group(&self) -> Result<Box<SketchGroup>, KclError> {
         let first_value = self.args.first().ok_or_else(|| {
             KclError::Type(KclErrorDetails {
                 message: format!("Expected a SketchGroup as the first argument, found `{:?}`", self.args),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             })
         })?;
 
@@ -466,6 +671,7 @@ impl Args {
             return Err(KclError::Type(KclErrorDetails {
                 message: format!("Expected a SketchGroup as the first argument, found `{:?}`", self.args),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             }));
         };
 
@@ -480,6 +686,7 @@ impl Args {
                 KclError::Type(KclErrorDetails {
                     message: format!("Expected a struct as the first argument, found `{:?}`", self.args),
                     source_ranges: vec![self.source_range],
+                    ..Default::default()
                 })
             })?
             .get_json_value()?;
@@ -488,6 +695,7 @@ impl Args {
             KclError::Type(KclErrorDetails {
                 message: format!("Failed to deserialize struct from JSON: {}", e),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             })
         })?;
 
@@ -502,6 +710,7 @@ impl Args {
                 KclError::Type(KclErrorDetails {
                     message: format!("Expected a struct as the first argument, found `{:?}`", self.args),
                     source_ranges: vec![self.source_range],
+                    ..Default::default()
                 })
             })?
             .get_json_value()?;
@@ -509,6 +718,7 @@ impl Args {
             KclError::Type(KclErrorDetails {
                 message: format!("Expected a file path string: {}", e),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             })
         })?;
 
@@ -518,6 +728,7 @@ impl Args {
                     KclError::Type(KclErrorDetails {
                         message: format!("Expected input format data: {}", e),
                         source_ranges: vec![self.source_range],
+                        ..Default::default()
                     })
                 })?;
             Ok((data, Some(options)))
@@ -531,6 +742,7 @@ impl Args {
             KclError::Type(KclErrorDetails {
                 message: format!("Expected a SketchGroup as the first argument, found `{:?}`", self.args),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             })
         })?;
 
@@ -540,6 +752,7 @@ impl Args {
             return Err(KclError::Type(KclErrorDetails {
                 message: format!("Expected a SketchGroup as the first argument, found `{:?}`", self.args),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             }));
         };
 
@@ -548,6 +761,7 @@ impl Args {
                 KclError::Type(KclErrorDetails {
                     message: format!("Failed to deserialize String from JSON: {}", e),
                     source_ranges: vec![self.source_range],
+                    ..Default::default()
                 })
             })?;
             Ok((sketch_group, Some(tag)))
@@ -566,6 +780,7 @@ impl Args {
                 KclError::Type(KclErrorDetails {
                     message: format!("Expected a struct as the first argument, found `{:?}`", self.args),
                     source_ranges: vec![self.source_range],
+                    ..Default::default()
                 })
             })?
             .get_json_value()?;
@@ -574,6 +789,7 @@ impl Args {
             KclError::Type(KclErrorDetails {
                 message: format!("Failed to deserialize struct from JSON: {}", e),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             })
         })?;
 
@@ -582,6 +798,7 @@ impl Args {
                 KclError::Type(KclErrorDetails {
                     message: format!("Failed to deserialize SketchOnFaceTag from JSON: {}", e),
                     source_ranges: vec![self.source_range],
+                    ..Default::default()
                 })
             })?;
             Ok((data, Some(tag)))
@@ -598,6 +815,7 @@ impl Args {
                 KclError::Type(KclErrorDetails {
                     message: format!("Expected a struct as the first argument, found `{:?}`", self.args),
                     source_ranges: vec![self.source_range],
+                    ..Default::default()
                 })
             })?
             .get_json_value()?;
@@ -606,6 +824,7 @@ impl Args {
             KclError::Type(KclErrorDetails {
                 message: format!("Failed to deserialize struct from JSON: {}", e),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             })
         })?;
 
@@ -613,6 +832,7 @@ impl Args {
             KclError::Type(KclErrorDetails {
                 message: format!("Expected a SketchGroup as the second argument, found `{:?}`", self.args),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             })
         })?;
 
@@ -622,6 +842,7 @@ impl Args {
             return Err(KclError::Type(KclErrorDetails {
                 message: format!("Expected a SketchGroup as the second argument, found `{:?}`", self.args),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             }));
         };
 
@@ -636,6 +857,7 @@ impl Args {
                 KclError::Type(KclErrorDetails {
                     message: format!("Expected a struct as the first argument, found `{:?}`", self.args),
                     source_ranges: vec![self.source_range],
+                    ..Default::default()
                 })
             })?
             .get_json_value()?;
@@ -644,6 +866,7 @@ impl Args {
             KclError::Type(KclErrorDetails {
                 message: format!("Failed to deserialize struct from JSON: {}", e),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             })
         })?;
 
@@ -651,6 +874,7 @@ impl Args {
             KclError::Type(KclErrorDetails {
                 message: format!("Expected a SketchGroup as the second argument, found `{:?}`", self.args),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             })
         })?;
 
@@ -665,6 +889,7 @@ impl Args {
                     self.args
                 ),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             }));
         };
 
@@ -681,6 +906,7 @@ impl Args {
                 KclError::Type(KclErrorDetails {
                     message: format!("Expected a struct as the first argument, found `{:?}`", self.args),
                     source_ranges: vec![self.source_range],
+                    ..Default::default()
                 })
             })?
             .get_json_value()?;
@@ -689,6 +915,7 @@ impl Args {
             KclError::Type(KclErrorDetails {
                 message: format!("Failed to deserialize struct from JSON: {}", e),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             })
         })?;
 
@@ -696,6 +923,7 @@ impl Args {
             KclError::Type(KclErrorDetails {
                 message: format!("Expected a SketchGroup as the second argument, found `{:?}`", self.args),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             })
         })?;
 
@@ -705,6 +933,7 @@ impl Args {
             return Err(KclError::Type(KclErrorDetails {
                 message: format!("Expected a SketchGroup as the second argument, found `{:?}`", self.args),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             }));
         };
         let tag = if let Some(tag) = self.args.get(2) {
@@ -726,6 +955,7 @@ impl Args {
                 KclError::Type(KclErrorDetails {
                     message: format!("Expected a struct as the first argument, found `{:?}`", self.args),
                     source_ranges: vec![self.source_range],
+                    ..Default::default()
                 })
             })?
             .get_json_value()?;
@@ -734,6 +964,7 @@ impl Args {
             KclError::Type(KclErrorDetails {
                 message: format!("Failed to deserialize struct from JSON: {}", e),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             })
         })?;
 
@@ -741,6 +972,7 @@ impl Args {
             KclError::Type(KclErrorDetails {
                 message: format!("Expected a Plane as the second argument, found `{:?}`", self.args),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             })
         })?;
 
@@ -755,6 +987,7 @@ impl Args {
                     self.args
                 ),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             }));
         };
         let tag = if let Some(tag) = self.args.get(2) {
@@ -774,6 +1007,7 @@ impl Args {
                 KclError::Type(KclErrorDetails {
                     message: format!("Expected a struct as the first argument, found `{:?}`", self.args),
                     source_ranges: vec![self.source_range],
+                    ..Default::default()
                 })
             })?
             .get_json_value()?;
@@ -782,6 +1016,7 @@ impl Args {
             KclError::Type(KclErrorDetails {
                 message: format!("Failed to deserialize struct from JSON: {}", e),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             })
         })?;
 
@@ -792,6 +1027,7 @@ impl Args {
                     self.args
                 ),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             })
         })?;
 
@@ -804,6 +1040,7 @@ impl Args {
                     self.args
                 ),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             }));
         };
 
@@ -821,6 +1058,7 @@ impl Args {
                 KclError::Type(KclErrorDetails {
                     message: format!("Expected a string as the first argument, found `{:?}`", self.args),
                     source_ranges: vec![self.source_range],
+                    ..Default::default()
                 })
             })?
             .get_json_value()?;
@@ -831,6 +1069,7 @@ impl Args {
             return Err(KclError::Type(KclErrorDetails {
                 message: format!("Expected a string as the first argument, found `{:?}`", self.args),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             }));
         };
 
@@ -841,6 +1080,7 @@ impl Args {
                 KclError::Type(KclErrorDetails {
                     message: format!("Expected a number as the second argument, found `{:?}`", self.args),
                     source_ranges: vec![self.source_range],
+                    ..Default::default()
                 })
             })?
             .get_json_value()?;
@@ -851,6 +1091,7 @@ impl Args {
             KclError::Type(KclErrorDetails {
                 message: format!("Expected a SketchGroup as the third argument, found `{:?}`", self.args),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             })
         })?;
 
@@ -860,6 +1101,7 @@ impl Args {
             return Err(KclError::Type(KclErrorDetails {
                 message: format!("Expected a SketchGroup as the third argument, found `{:?}`", self.args),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             }));
         };
 
@@ -876,6 +1118,7 @@ impl Args {
                 KclError::Type(KclErrorDetails {
                     message: format!("Expected a number as the first argument, found `{:?}`", self.args),
                     source_ranges: vec![self.source_range],
+                    ..Default::default()
                 })
             })?
             .get_json_value()?;
@@ -886,6 +1129,7 @@ impl Args {
             KclError::Type(KclErrorDetails {
                 message: format!("Expected a SketchGroup as the second argument, found `{:?}`", self.args),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             })
         })?;
 
@@ -900,11 +1144,101 @@ impl Args {
                     self.args
                 ),
                 source_ranges: vec![self.source_range],
+                ..Default::default()
             }));
         };
 
         Ok((number, sketch_set))
     }
+
+    fn get_array(&self, index: usize) -> Result<Vec<KclValue>, KclError> {
+        let err = || {
+            KclError::Type(KclErrorDetails {
+                message: format!(
+                    "Expected an array as argument number {}, found `{:?}`",
+                    index + 1,
+                    self.args
+                ),
+                source_ranges: vec![self.source_range],
+                ..Default::default()
+            })
+        };
+        let items = match self.args.get(index).ok_or_else(err)? {
+            MemoryItem::SketchGroups { value } => value
+                .iter()
+                .cloned()
+                .map(|sketch_group| KclValue(MemoryItem::SketchGroup(sketch_group)))
+                .collect(),
+            MemoryItem::ExtrudeGroups { value } => value
+                .iter()
+                .cloned()
+                .map(|extrude_group| KclValue(MemoryItem::ExtrudeGroup(extrude_group)))
+                .collect(),
+            MemoryItem::UserVal(UserVal {
+                value: serde_json::Value::Array(items),
+                ..
+            }) => items
+                .iter()
+                .map(|item| {
+                    KclValue(crate::std::array::array_element_to_memory_item(
+                        item.clone(),
+                        self.source_range,
+                    ))
+                })
+                .collect(),
+            _ => return Err(err()),
+        };
+
+        Ok(items)
+    }
+
+    fn get_fn(&self, index: usize) -> Result<FnAsArg, KclError> {
+        let item = self.args.get(index).ok_or_else(|| {
+            KclError::Type(KclErrorDetails {
+                message: format!(
+                    "Expected a function as argument number {}, found `{:?}`",
+                    index + 1,
+                    self.args
+                ),
+                source_ranges: vec![self.source_range],
+                ..Default::default()
+            })
+        })?;
+
+        FnAsArg::new(item.clone(), self.source_range)
+    }
+
+    fn get_array_and_fn(&self) -> Result<(Vec<KclValue>, FnAsArg), KclError> {
+        Ok((self.get_array(0)?, self.get_fn(1)?))
+    }
+
+    fn get_array_start_and_fn(&self) -> Result<(Vec<KclValue>, KclValue, FnAsArg), KclError> {
+        let start = self.args.get(1).cloned().map(KclValue).ok_or_else(|| {
+            KclError::Type(KclErrorDetails {
+                message: format!(
+                    "Expected a starting value as the second argument, found `{:?}`",
+                    self.args
+                ),
+                source_ranges: vec![self.source_range],
+                ..Default::default()
+            })
+        })?;
+
+        Ok((self.get_array(0)?, start, self.get_fn(2)?))
+    }
+
+    fn get_range_args(&self) -> Result<(f64, f64, Option<f64>), KclError> {
+        let numbers = self.get_number_array()?;
+        match numbers[..] {
+            [start, end] => Ok((start, end, None)),
+            [start, end, step] => Ok((start, end, Some(step))),
+            _ => Err(KclError::Type(KclErrorDetails {
+                message: format!("Expected a start, an end and optionally a step, found `{:?}`", numbers),
+                source_ranges: vec![self.source_range],
+                ..Default::default()
+            })),
+        }
+    }
 }
 
 /// Returns the length of the given leg.
@@ -1077,10 +1411,11 @@ layout: manual
                     fn_docs.push_str(example);
                     fn_docs.push_str("\n```\n\n");
 
-                    // If this is not a "math" or "utilities" function,
+                    // If this is not a "math", "utilities" or "array" function,
                     // we should add the image to the docs.
                     if !internal_fn.tags().contains(&"math".to_string())
                         && !internal_fn.tags().contains(&"utilities".to_string())
+                        && !internal_fn.tags().contains(&"array".to_string())
                         && !internal_fn.tags().contains(&"norun".to_string())
                     {
                         // Get the path to this specific rust file.
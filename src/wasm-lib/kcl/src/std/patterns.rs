@@ -12,6 +12,23 @@ use crate::{
     std::{types::Uint, Args},
 };
 
+/// Warn about keys in a pattern's data which it doesn't use, e.g. `center` given to a linear
+/// pattern or a misspelled key, since they're ignored without a word otherwise.
+fn warn_unused_keys<T: JsonSchema>(args: &Args) {
+    let schema = schemars::gen::SchemaGenerator::default().into_root_schema_for::<T>();
+    let Some(known) = schema.schema.object.as_ref().map(|object| &object.properties) else {
+        return;
+    };
+    let Some(data) = args.args.first().and_then(|arg| arg.get_json_value().ok()) else {
+        return;
+    };
+    for key in data.as_object().into_iter().flat_map(|data| data.keys()) {
+        if !known.contains_key(key) {
+            args.warn(format!("`{key}` isn't used by this pattern, so it's ignored"));
+        }
+    }
+}
+
 /// Data for a linear pattern on a 2D sketch.
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema)]
 #[ts(export)]
@@ -73,6 +90,7 @@ impl LinearPattern {
 /// A linear pattern on a 2D sketch.
 pub async fn pattern_linear_2d(args: Args) -> Result<MemoryItem, KclError> {
     let (data, sketch_group_set): (LinearPattern2dData, SketchGroupSet) = args.get_data_and_sketch_group_set()?;
+    warn_unused_keys::<LinearPattern2dData>(&args);
 
     if data.axis == [0.0, 0.0] {
         return Err(KclError::Semantic(KclErrorDetails {
@@ -80,6 +98,7 @@ pub async fn pattern_linear_2d(args: Args) -> Result<MemoryItem, KclError> {
                 "The axis of the linear pattern cannot be the zero vector. Otherwise they will just duplicate in place."
                     .to_string(),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         }));
     }
 
@@ -121,6 +140,7 @@ async fn inner_pattern_linear_2d(
             return Err(KclError::Semantic(KclErrorDetails {
                 message: "Expected a vec of sketch groups".to_string(),
                 source_ranges: vec![args.source_range],
+                ..Default::default()
             }));
         };
 
@@ -133,6 +153,7 @@ async fn inner_pattern_linear_2d(
 /// A linear pattern on a 3D model.
 pub async fn pattern_linear_3d(args: Args) -> Result<MemoryItem, KclError> {
     let (data, extrude_group): (LinearPattern3dData, Box<ExtrudeGroup>) = args.get_data_and_extrude_group()?;
+    warn_unused_keys::<LinearPattern3dData>(&args);
 
     if data.axis == [0.0, 0.0, 0.0] {
         return Err(KclError::Semantic(KclErrorDetails {
@@ -140,6 +161,7 @@ pub async fn pattern_linear_3d(args: Args) -> Result<MemoryItem, KclError> {
                 "The axis of the linear pattern cannot be the zero vector. Otherwise they will just duplicate in place."
                     .to_string(),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         }));
     }
 
@@ -178,6 +200,7 @@ async fn inner_pattern_linear_3d(
         return Err(KclError::Semantic(KclErrorDetails {
             message: "Expected a vec of extrude groups".to_string(),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         }));
     };
 
@@ -185,7 +208,7 @@ async fn inner_pattern_linear_3d(
 }
 
 async fn pattern_linear(data: LinearPattern, geometry: Geometry, args: Args) -> Result<Geometries, KclError> {
-    let id = uuid::Uuid::new_v4();
+    let id = args.ctx.id_generator.next_uuid();
     println!(
         "id: {:#?}",
         ModelingCmd::EntityLinearPattern {
@@ -223,24 +246,35 @@ async fn pattern_linear(data: LinearPattern, geometry: Geometry, args: Args) ->
         return Err(KclError::Engine(KclErrorDetails {
             message: format!("EntityLinearPattern response was not as expected: {:?}", resp),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         }));
     };
 
     let geometries = match geometry {
         Geometry::SketchGroup(sketch_group) => {
             let mut geometries = vec![sketch_group.clone()];
-            for id in pattern_info.entity_ids.iter() {
+            for id in pattern_info
+                .entity_face_edge_ids
+                .iter()
+                .flatten()
+                .map(|info| info.object_id)
+            {
                 let mut new_sketch_group = sketch_group.clone();
-                new_sketch_group.id = *id;
+                new_sketch_group.id = id;
                 geometries.push(new_sketch_group);
             }
             Geometries::SketchGroups(geometries)
         }
         Geometry::ExtrudeGroup(extrude_group) => {
             let mut geometries = vec![extrude_group.clone()];
-            for id in pattern_info.entity_ids.iter() {
+            for id in pattern_info
+                .entity_face_edge_ids
+                .iter()
+                .flatten()
+                .map(|info| info.object_id)
+            {
                 let mut new_extrude_group = extrude_group.clone();
-                new_extrude_group.id = *id;
+                new_extrude_group.id = id;
                 geometries.push(new_extrude_group);
             }
             Geometries::ExtrudeGroups(geometries)
@@ -331,6 +365,7 @@ impl CircularPattern {
 /// A circular pattern on a 2D sketch.
 pub async fn pattern_circular_2d(args: Args) -> Result<MemoryItem, KclError> {
     let (data, sketch_group): (CircularPattern2dData, Box<SketchGroup>) = args.get_data_and_sketch_group()?;
+    warn_unused_keys::<CircularPattern2dData>(&args);
 
     let sketch_groups = inner_pattern_circular_2d(data, sketch_group, args).await?;
     Ok(MemoryItem::SketchGroups { value: sketch_groups })
@@ -363,6 +398,7 @@ async fn inner_pattern_circular_2d(
         return Err(KclError::Semantic(KclErrorDetails {
             message: "Expected a vec of sketch groups".to_string(),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         }));
     };
 
@@ -372,6 +408,7 @@ async fn inner_pattern_circular_2d(
 /// A circular pattern on a 3D model.
 pub async fn pattern_circular_3d(args: Args) -> Result<MemoryItem, KclError> {
     let (data, extrude_group): (CircularPattern3dData, Box<ExtrudeGroup>) = args.get_data_and_extrude_group()?;
+    warn_unused_keys::<CircularPattern3dData>(&args);
 
     let extrude_groups = inner_pattern_circular_3d(data, extrude_group, args).await?;
     Ok(MemoryItem::ExtrudeGroups { value: extrude_groups })
@@ -408,6 +445,7 @@ async fn inner_pattern_circular_3d(
         return Err(KclError::Semantic(KclErrorDetails {
             message: "Expected a vec of extrude groups".to_string(),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         }));
     };
 
@@ -415,7 +453,7 @@ async fn inner_pattern_circular_3d(
 }
 
 async fn pattern_circular(data: CircularPattern, geometry: Geometry, args: Args) -> Result<Geometries, KclError> {
-    let id = uuid::Uuid::new_v4();
+    let id = args.ctx.id_generator.next_uuid();
 
     let resp = args
         .send_modeling_cmd(
@@ -442,24 +480,35 @@ async fn pattern_circular(data: CircularPattern, geometry: Geometry, args: Args)
         return Err(KclError::Engine(KclErrorDetails {
             message: format!("EntityCircularPattern response was not as expected: {:?}", resp),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         }));
     };
 
     let geometries = match geometry {
         Geometry::SketchGroup(sketch_group) => {
             let mut geometries = vec![sketch_group.clone()];
-            for id in pattern_info.entity_ids.iter() {
+            for id in pattern_info
+                .entity_face_edge_ids
+                .iter()
+                .flatten()
+                .map(|info| info.object_id)
+            {
                 let mut new_sketch_group = sketch_group.clone();
-                new_sketch_group.id = *id;
+                new_sketch_group.id = id;
                 geometries.push(new_sketch_group);
             }
             Geometries::SketchGroups(geometries)
         }
         Geometry::ExtrudeGroup(extrude_group) => {
             let mut geometries = vec![extrude_group.clone()];
-            for id in pattern_info.entity_ids.iter() {
+            for id in pattern_info
+                .entity_face_edge_ids
+                .iter()
+                .flatten()
+                .map(|info| info.object_id)
+            {
                 let mut new_extrude_group = extrude_group.clone();
-                new_extrude_group.id = *id;
+                new_extrude_group.id = id;
                 geometries.push(new_extrude_group);
             }
             Geometries::ExtrudeGroups(geometries)
@@ -214,13 +214,14 @@ async fn inner_revolve(
             return Err(KclError::Semantic(KclErrorDetails {
                 message: format!("Expected angle to be between -360 and 360, found `{}`", angle),
                 source_ranges: vec![args.source_range],
+                ..Default::default()
             }));
         }
     }
 
     let angle = kittycad::types::Angle::from_degrees(data.angle.unwrap_or(360.0));
 
-    let id = uuid::Uuid::new_v4();
+    let id = args.ctx.id_generator.next_uuid();
     match data.axis {
         RevolveAxis::Axis(axis) => {
             let (axis, origin) = axis.axis_and_origin()?;
@@ -233,6 +234,7 @@ async fn inner_revolve(
                     origin,
                     tolerance: DEFAULT_TOLERANCE,
                     axis_is_2d: true,
+                    opposite: None,
                 },
             )
             .await?;
@@ -249,6 +251,7 @@ async fn inner_revolve(
                             KclError::Type(KclErrorDetails {
                                 message: format!("No edge found with tag: `{}`", tag),
                                 source_ranges: vec![args.source_range],
+                                ..Default::default()
                             })
                         })?
                         .get_base()
@@ -263,6 +266,7 @@ async fn inner_revolve(
                     target: sketch_group.id,
                     edge_id,
                     tolerance: DEFAULT_TOLERANCE,
+                    opposite: None,
                 },
             )
             .await?;
@@ -282,9 +286,11 @@ pub async fn get_edge(args: Args) -> Result<MemoryItem, KclError> {
             KclError::Type(KclErrorDetails {
                 message: format!("Failed to convert Uuid to json: {}", e),
                 source_ranges: vec![args.source_range],
+                ..Default::default()
             })
         })?,
         meta: vec![args.source_range.into()],
+        ..Default::default()
     }))
 }
 
@@ -319,6 +325,7 @@ async fn inner_get_edge(tag: String, extrude_group: Box<ExtrudeGroup>, args: Arg
             KclError::Type(KclErrorDetails {
                 message: format!("No edge found with tag: `{}`", tag),
                 source_ranges: vec![args.source_range],
+                ..Default::default()
             })
         })?
         .get_base();
@@ -41,6 +41,7 @@ fn inner_segment_end_x(segment_name: &str, sketch_group: Box<SketchGroup>, args:
                 segment_name
             ),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         })
     })?;
 
@@ -77,6 +78,7 @@ fn inner_segment_end_y(segment_name: &str, sketch_group: Box<SketchGroup>, args:
                 segment_name
             ),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         })
     })?;
 
@@ -116,6 +118,7 @@ fn inner_last_segment_x(sketch_group: Box<SketchGroup>, args: Args) -> Result<f6
                     sketch_group
                 ),
                 source_ranges: vec![args.source_range],
+                ..Default::default()
             })
         })?
         .get_base();
@@ -156,6 +159,7 @@ fn inner_last_segment_y(sketch_group: Box<SketchGroup>, args: Args) -> Result<f6
                     sketch_group
                 ),
                 source_ranges: vec![args.source_range],
+                ..Default::default()
             })
         })?
         .get_base();
@@ -192,6 +196,7 @@ fn inner_segment_length(segment_name: &str, sketch_group: Box<SketchGroup>, args
                 segment_name
             ),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         })
     })?;
     let line = path.get_base();
@@ -233,6 +238,7 @@ fn inner_segment_angle(segment_name: &str, sketch_group: Box<SketchGroup>, args:
                 segment_name
             ),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         })
     })?;
     let line = path.get_base();
@@ -278,6 +284,7 @@ fn inner_angle_to_match_length_x(
                 segment_name
             ),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         })
     })?;
     let line = path.get_base();
@@ -294,6 +301,7 @@ fn inner_angle_to_match_length_x(
                     sketch_group
                 ),
                 source_ranges: vec![args.source_range],
+                ..Default::default()
             })
         })?
         .get_base();
@@ -345,6 +353,7 @@ fn inner_angle_to_match_length_y(
                 segment_name
             ),
             source_ranges: vec![args.source_range],
+            ..Default::default()
         })
     })?;
     let line = path.get_base();
@@ -361,6 +370,7 @@ fn inner_angle_to_match_length_y(
                     sketch_group
                 ),
                 source_ranges: vec![args.source_range],
+                ..Default::default()
             })
         })?
         .get_base();
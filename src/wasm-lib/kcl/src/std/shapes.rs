@@ -25,7 +25,7 @@ pub async fn circle(args: Args) -> Result<MemoryItem, KclError> {
     let (center, radius, sketch_surface_or_group, tag): ([f64; 2], f64, SketchSurfaceOrGroup, Option<String>) =
         args.get_circle_args()?;
 
-    let sketch_group = inner_circle(center, radius, tag, sketch_surface_or_group, args).await?;
+    let sketch_group = inner_circle(center, radius, sketch_surface_or_group, tag, args).await?;
     Ok(MemoryItem::SketchGroup(sketch_group))
 }
 
@@ -50,8 +50,8 @@ pub async fn circle(args: Args) -> Result<MemoryItem, KclError> {
 async fn inner_circle(
     center: [f64; 2],
     radius: f64,
-    tag: Option<String>,
     sketch_surface_or_group: SketchSurfaceOrGroup,
+    tag: Option<String>,
     args: Args,
 ) -> Result<Box<SketchGroup>, KclError> {
     let sketch_surface = match sketch_surface_or_group {
@@ -0,0 +1,114 @@
+//! Standard library shells.
+
+use anyhow::Result;
+use derive_docs::stdlib;
+use kittycad::types::ModelingCmd;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    errors::{KclError, KclErrorDetails},
+    executor::{ExtrudeGroup, MemoryItem},
+    std::{
+        sketch::{SketchOnFaceTag, StartOrEnd},
+        Args,
+    },
+};
+
+/// Data for shells.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ShellData {
+    /// The thickness of the walls left behind.
+    pub thickness: f64,
+    /// The faces you want removed, so the inside of the solid is open there. Either the tag of
+    /// a face, or `start` or `end` for the caps.
+    pub faces: Vec<SketchOnFaceTag>,
+}
+
+/// Hollow out a solid, leaving walls of the given thickness.
+pub async fn shell(args: Args) -> Result<MemoryItem, KclError> {
+    let (data, extrude_group): (ShellData, Box<ExtrudeGroup>) = args.get_data_and_extrude_group()?;
+
+    let extrude_group = inner_shell(data, extrude_group, args).await?;
+    Ok(MemoryItem::ExtrudeGroup(extrude_group))
+}
+
+/// Hollow out a solid, leaving walls of the given thickness. The faces given are removed, so
+/// the solid is open there. The faces which are left can still be sketched on.
+///
+/// ```no_run
+/// const box = startSketchOn('XY')
+///     |> startProfileAt([-12, 12], %)
+///     |> line([24, 0], %)
+///     |> line([0, -24], %)
+///     |> line([-24, 0], %, "front")
+///     |> close(%)
+///     |> extrude(20, %)
+///     |> shell({thickness: 1, faces: ["end", "front"]}, %)
+/// ```
+#[stdlib {
+    name = "shell",
+}]
+async fn inner_shell(
+    data: ShellData,
+    extrude_group: Box<ExtrudeGroup>,
+    args: Args,
+) -> Result<Box<ExtrudeGroup>, KclError> {
+    if data.thickness <= 0.0 {
+        return Err(KclError::Semantic(KclErrorDetails {
+            message: format!(
+                "Expected the shell thickness to be positive, found `{}`",
+                data.thickness
+            ),
+            source_ranges: vec![args.source_range],
+            ..Default::default()
+        }));
+    }
+
+    let mut extrude_group = extrude_group;
+    let mut face_ids = Vec::new();
+    for face in &data.faces {
+        let face_id = match face {
+            SketchOnFaceTag::String(tag) => extrude_group.get_path_by_name(tag).map(|surface| surface.get_face_id()),
+            SketchOnFaceTag::StartOrEnd(StartOrEnd::Start) => extrude_group.start_cap_id,
+            SketchOnFaceTag::StartOrEnd(StartOrEnd::End) => extrude_group.end_cap_id,
+        }
+        .ok_or_else(|| {
+            KclError::Type(KclErrorDetails {
+                message: format!("Expected a face with the tag `{}`", face),
+                source_ranges: vec![args.source_range],
+                ..Default::default()
+            })
+        })?;
+        if face_ids.contains(&face_id) {
+            return Err(KclError::Type(KclErrorDetails {
+                message: "Duplicate faces are not allowed.".to_string(),
+                source_ranges: vec![args.source_range],
+                ..Default::default()
+            }));
+        }
+        face_ids.push(face_id);
+    }
+
+    args.send_modeling_cmd(
+        args.ctx.id_generator.next_uuid(),
+        ModelingCmd::Solid3DShellFace {
+            face_ids: face_ids.clone(),
+            hollow: false,
+            object_id: extrude_group.id,
+            shell_thickness: data.thickness,
+        },
+    )
+    .await?;
+
+    // The removed faces are gone, so nothing else can be sketched on them.
+    extrude_group
+        .value
+        .retain(|surface| !face_ids.contains(&surface.get_face_id()));
+    extrude_group.start_cap_id = extrude_group.start_cap_id.filter(|id| !face_ids.contains(id));
+    extrude_group.end_cap_id = extrude_group.end_cap_id.filter(|id| !face_ids.contains(id));
+
+    Ok(extrude_group)
+}
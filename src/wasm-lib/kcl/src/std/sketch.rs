@@ -23,6 +23,17 @@ use crate::{
     },
 };
 
+/// Segments shorter than this are almost certainly a mistake, e.g. a line to the point the
+/// sketch is already at.
+const MIN_SEGMENT_LENGTH: f64 = 1e-6;
+
+/// Warn about a straight segment which doesn't go anywhere.
+fn warn_if_zero_length(from: Point2d, to: [f64; 2], args: &Args) {
+    if (to[0] - from.x).hypot(to[1] - from.y) < MIN_SEGMENT_LENGTH {
+        args.warn("This segment has no length, so it doesn't add anything to the sketch");
+    }
+}
+
 /// Draw a line to a point.
 pub async fn line_to(args: Args) -> Result<MemoryItem, KclError> {
     let (to, sketch_group, tag): ([f64; 2], Box<SketchGroup>, Option<String>) =
@@ -59,11 +70,13 @@ async fn inner_line_to(
     args: Args,
 ) -> Result<Box<SketchGroup>, KclError> {
     let from = sketch_group.get_coords_from_paths()?;
-    let id = uuid::Uuid::new_v4();
+    warn_if_zero_length(from, to, &args);
+    let id = args.ctx.id_generator.next_uuid();
 
     args.send_modeling_cmd(
         id,
         ModelingCmd::ExtendPath {
+            label: None,
             path: sketch_group.id,
             segment: kittycad::types::PathSegment::Line {
                 end: Point3D {
@@ -193,12 +206,14 @@ async fn inner_line(
 ) -> Result<Box<SketchGroup>, KclError> {
     let from = sketch_group.get_coords_from_paths()?;
     let to = [from.x + delta[0], from.y + delta[1]];
+    warn_if_zero_length(from, to, &args);
 
-    let id = uuid::Uuid::new_v4();
+    let id = args.ctx.id_generator.next_uuid();
 
     args.send_modeling_cmd(
         id,
         ModelingCmd::ExtendPath {
+            label: None,
             path: sketch_group.id,
             segment: kittycad::types::PathSegment::Line {
                 end: Point3D {
@@ -354,8 +369,9 @@ async fn inner_angled_line(
     let relative = true;
 
     let to: [f64; 2] = [from.x + delta[0], from.y + delta[1]];
+    warn_if_zero_length(from, to, &args);
 
-    let id = uuid::Uuid::new_v4();
+    let id = args.ctx.id_generator.next_uuid();
 
     let current_path = Path::ToPoint {
         base: BasePath {
@@ -372,6 +388,7 @@ async fn inner_angled_line(
     args.send_modeling_cmd(
         id,
         ModelingCmd::ExtendPath {
+            label: None,
             path: sketch_group.id,
             segment: kittycad::types::PathSegment::Line {
                 end: Point3D {
@@ -634,6 +651,7 @@ async fn inner_angled_line_that_intersects(
                     data.intersect_tag
                 ),
                 source_ranges: vec![args.source_range],
+                ..Default::default()
             })
         })?
         .get_base();
@@ -861,6 +879,7 @@ async fn inner_start_sketch_on(
                 return Err(KclError::Type(KclErrorDetails {
                     message: "Expected a tag for the face to sketch on".to_string(),
                     source_ranges: vec![args.source_range],
+                    ..Default::default()
                 }));
             };
             let face = start_sketch_on_face(extrude_group, tag, args).await?;
@@ -914,6 +933,7 @@ async fn start_sketch_on_face(
                     Some(Err(KclError::Type(KclErrorDetails {
                         message: format!("Cannot sketch on a non-planar surface: `{}`", tag),
                         source_ranges: vec![args.source_range],
+                        ..Default::default()
                     })))
                 }
                 ExtrudeSurface::ExtrudePlane(_) | ExtrudeSurface::ExtrudeArc(_) => None,
@@ -922,24 +942,27 @@ async fn start_sketch_on_face(
                 KclError::Type(KclErrorDetails {
                     message: format!("Expected a face with the tag `{}`", tag),
                     source_ranges: vec![args.source_range],
+                    ..Default::default()
                 })
             })??,
         SketchOnFaceTag::StartOrEnd(StartOrEnd::Start) => extrude_group.start_cap_id.ok_or_else(|| {
             KclError::Type(KclErrorDetails {
                 message: "Expected a start face to sketch on".to_string(),
                 source_ranges: vec![args.source_range],
+                ..Default::default()
             })
         })?,
         SketchOnFaceTag::StartOrEnd(StartOrEnd::End) => extrude_group.end_cap_id.ok_or_else(|| {
             KclError::Type(KclErrorDetails {
                 message: "Expected an end face to sketch on".to_string(),
                 source_ranges: vec![args.source_range],
+                ..Default::default()
             })
         })?,
     };
 
     // Enter sketch mode on the face.
-    let id = uuid::Uuid::new_v4();
+    let id = args.ctx.id_generator.next_uuid();
     args.send_modeling_cmd(
         id,
         ModelingCmd::EnableSketchMode {
@@ -985,7 +1008,7 @@ async fn start_sketch_on_plane(data: PlaneData, args: Args) -> Result<Box<Plane>
             z_axis: _,
         } => {
             // Create the custom plane on the fly.
-            let id = uuid::Uuid::new_v4();
+            let id = args.ctx.id_generator.next_uuid();
             args.send_modeling_cmd(
                 id,
                 ModelingCmd::MakePlane {
@@ -1005,7 +1028,7 @@ async fn start_sketch_on_plane(data: PlaneData, args: Args) -> Result<Box<Plane>
 
     // Enter sketch mode on the plane.
     args.send_modeling_cmd(
-        uuid::Uuid::new_v4(),
+        args.ctx.id_generator.next_uuid(),
         ModelingCmd::EnableSketchMode {
             animated: false,
             ortho: false,
@@ -1047,8 +1070,8 @@ pub(crate) async fn inner_start_profile_at(
     tag: Option<String>,
     args: Args,
 ) -> Result<Box<SketchGroup>, KclError> {
-    let id = uuid::Uuid::new_v4();
-    let path_id = uuid::Uuid::new_v4();
+    let id = args.ctx.id_generator.next_uuid();
+    let path_id = args.ctx.id_generator.next_uuid();
 
     args.send_modeling_cmd(path_id, ModelingCmd::StartPath {}).await?;
     args.send_modeling_cmd(
@@ -1129,7 +1152,7 @@ pub(crate) async fn inner_close(
     let from = sketch_group.get_coords_from_paths()?;
     let to: Point2d = sketch_group.start.from.into();
 
-    let id = uuid::Uuid::new_v4();
+    let id = args.ctx.id_generator.next_uuid();
 
     args.send_modeling_cmd(
         id,
@@ -1142,8 +1165,11 @@ pub(crate) async fn inner_close(
     // If we are sketching on a plane we can close the sketch group now.
     if let SketchSurface::Plane(_) = sketch_group.on {
         // We were on a plane, disable the sketch mode.
-        args.send_modeling_cmd(uuid::Uuid::new_v4(), kittycad::types::ModelingCmd::SketchModeDisable {})
-            .await?;
+        args.send_modeling_cmd(
+            args.ctx.id_generator.next_uuid(),
+            kittycad::types::ModelingCmd::SketchModeDisable {},
+        )
+        .await?;
     }
 
     let mut new_sketch_group = sketch_group.clone();
@@ -1235,12 +1261,16 @@ pub(crate) async fn inner_arc(
             (center.into(), angle_start, angle_end, *radius, to.into())
         }
     };
+    if (radius * (angle_end.radians() - angle_start.radians())).abs() < MIN_SEGMENT_LENGTH {
+        args.warn("This arc is so short it's almost a point, check its radius and angles");
+    }
 
-    let id = uuid::Uuid::new_v4();
+    let id = args.ctx.id_generator.next_uuid();
 
     args.send_modeling_cmd(
         id,
         ModelingCmd::ExtendPath {
+            label: None,
             path: sketch_group.id,
             segment: kittycad::types::PathSegment::Arc {
                 start: angle_start,
@@ -1320,7 +1350,7 @@ async fn inner_tangential_arc(
 ) -> Result<Box<SketchGroup>, KclError> {
     let from: Point2d = sketch_group.get_coords_from_paths()?;
 
-    let id = uuid::Uuid::new_v4();
+    let id = args.ctx.id_generator.next_uuid();
 
     let to = match &data {
         TangentialArcData::RadiusAndOffset { radius, offset } => {
@@ -1332,6 +1362,7 @@ async fn inner_tangential_arc(
             args.send_modeling_cmd(
                 id,
                 ModelingCmd::ExtendPath {
+                    label: None,
                     path: sketch_group.id,
                     segment: kittycad::types::PathSegment::TangentialArc {
                         radius: *radius,
@@ -1374,6 +1405,7 @@ async fn inner_tangential_arc(
 
 fn tan_arc_to(sketch_group: &SketchGroup, to: &[f64; 2]) -> ModelingCmd {
     ModelingCmd::ExtendPath {
+        label: None,
         path: sketch_group.id,
         segment: kittycad::types::PathSegment::TangentialArcTo {
             angle_snap_increment: None,
@@ -1390,6 +1422,7 @@ fn too_few_args(source_range: SourceRange) -> KclError {
     KclError::Syntax(KclErrorDetails {
         source_ranges: vec![source_range],
         message: "too few arguments".to_owned(),
+        ..Default::default()
     })
 }
 
@@ -1450,7 +1483,7 @@ async fn inner_tangential_arc_to(
     });
 
     let delta = [to_x - from.x, to_y - from.y];
-    let id = uuid::Uuid::new_v4();
+    let id = args.ctx.id_generator.next_uuid();
     args.send_modeling_cmd(id, tan_arc_to(&sketch_group, &delta)).await?;
 
     let current_path = Path::TangentialArcTo {
@@ -1523,11 +1556,12 @@ async fn inner_bezier_curve(
     let delta = data.to;
     let to = [from.x + data.to[0], from.y + data.to[1]];
 
-    let id = uuid::Uuid::new_v4();
+    let id = args.ctx.id_generator.next_uuid();
 
     args.send_modeling_cmd(
         id,
         ModelingCmd::ExtendPath {
+            label: None,
             path: sketch_group.id,
             segment: kittycad::types::PathSegment::Bezier {
                 control_1: Point3D {
@@ -1603,7 +1637,7 @@ async fn inner_hole(
     match hole_sketch_group {
         SketchGroupSet::SketchGroup(hole_sketch_group) => {
             args.send_modeling_cmd(
-                uuid::Uuid::new_v4(),
+                args.ctx.id_generator.next_uuid(),
                 ModelingCmd::Solid2DAddHole {
                     object_id: sketch_group.id,
                     hole_id: hole_sketch_group.id,
@@ -1613,7 +1647,7 @@ async fn inner_hole(
             // suggestion (mike)
             // we also hide the source hole since its essentially "consumed" by this operation
             args.send_modeling_cmd(
-                uuid::Uuid::new_v4(),
+                args.ctx.id_generator.next_uuid(),
                 ModelingCmd::ObjectVisible {
                     object_id: hole_sketch_group.id,
                     hidden: true,
@@ -1624,7 +1658,7 @@ async fn inner_hole(
         SketchGroupSet::SketchGroups(hole_sketch_groups) => {
             for hole_sketch_group in hole_sketch_groups {
                 args.send_modeling_cmd(
-                    uuid::Uuid::new_v4(),
+                    args.ctx.id_generator.next_uuid(),
                     ModelingCmd::Solid2DAddHole {
                         object_id: sketch_group.id,
                         hole_id: hole_sketch_group.id,
@@ -1634,7 +1668,7 @@ async fn inner_hole(
                 // suggestion (mike)
                 // we also hide the source hole since its essentially "consumed" by this operation
                 args.send_modeling_cmd(
-                    uuid::Uuid::new_v4(),
+                    args.ctx.id_generator.next_uuid(),
                     ModelingCmd::ObjectVisible {
                         object_id: hole_sketch_group.id,
                         hidden: true,
@@ -0,0 +1,131 @@
+//! Standard library sweeps.
+
+use anyhow::Result;
+use derive_docs::stdlib;
+use kittycad::types::{ModelingCmd, RelativeTo};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    errors::{KclError, KclErrorDetails},
+    executor::{ExtrudeGroup, MemoryItem, SketchGroup},
+    std::{extrude::do_post_extrude, fillet::DEFAULT_TOLERANCE, Args},
+};
+
+/// Data for sweeps.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct SweepData {
+    /// The path to sweep the profile along.
+    pub path: SweepPath,
+    /// Turn the profile with the path as it goes, so it stays at the same angle to the path,
+    /// e.g. for a spring. Without twist, the profile keeps the orientation it was sketched in.
+    /// Default is true.
+    #[serde(default)]
+    pub twist: Option<bool>,
+}
+
+/// A path to sweep along.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema)]
+#[ts(export)]
+#[serde(untagged)]
+pub enum SweepPath {
+    /// An open sketch group.
+    SketchGroup(Box<SketchGroup>),
+    /// An extrude group with a helix made on it by `helix`. The newest helix is used.
+    Helix(Box<ExtrudeGroup>),
+}
+
+/// Sweep a closed profile along a path or a helix.
+pub async fn sweep(args: Args) -> Result<MemoryItem, KclError> {
+    let (data, sketch_group): (SweepData, Box<SketchGroup>) = args.get_data_and_sketch_group()?;
+
+    let extrude_group = inner_sweep(data, sketch_group, args).await?;
+    Ok(MemoryItem::ExtrudeGroup(extrude_group))
+}
+
+/// Sweep a closed profile along a path or a helix.
+///
+/// The path is either an open sketch group, or an extrude group with a helix made on it by
+/// `helix`. The profile should be sketched where the path starts.
+///
+/// ```no_run
+/// const path = startSketchOn('XZ')
+///     |> startProfileAt([0, 0], %)
+///     |> line([0, 20], %)
+///     |> tangentialArcTo([10, 30], %)
+///     |> line([20, 0], %)
+///
+/// const pipe = startSketchOn('XY')
+///     |> circle([0, 0], 2, %)
+///     |> hole(circle([0, 0], 1.5, %), %)
+///     |> sweep({path: path}, %)
+/// ```
+///
+/// ```no_run
+/// const coil = startSketchOn('XY')
+///     |> circle([0, 0], 10, %)
+///     |> extrude(40, %)
+///     |> helix({revolutions: 8, angle_start: 0}, %)
+///
+/// const spring = startSketchOn('XZ')
+///     |> circle([10, 0], 1, %)
+///     |> sweep({path: coil}, %)
+/// ```
+#[stdlib {
+    name = "sweep",
+}]
+async fn inner_sweep(
+    data: SweepData,
+    sketch_group: Box<SketchGroup>,
+    args: Args,
+) -> Result<Box<ExtrudeGroup>, KclError> {
+    if !sketch_group.is_closed() {
+        return Err(KclError::Semantic(KclErrorDetails {
+            message: "Expected the profile to be closed".to_string(),
+            source_ranges: vec![args.source_range],
+            ..Default::default()
+        }));
+    }
+
+    let trajectory = match &data.path {
+        SweepPath::SketchGroup(path) => {
+            if path.is_closed() {
+                return Err(KclError::Semantic(KclErrorDetails {
+                    message: "Expected the path to be open, but it is closed".to_string(),
+                    source_ranges: vec![args.source_range],
+                    ..Default::default()
+                }));
+            }
+            path.id
+        }
+        SweepPath::Helix(extrude_group) => *extrude_group.helix_ids.last().ok_or_else(|| {
+            KclError::Type(KclErrorDetails {
+                message: "Expected a helix to sweep along, but no helix was made on this extrude group".to_string(),
+                source_ranges: vec![args.source_range],
+                ..Default::default()
+            })
+        })?,
+    };
+
+    let id = args.ctx.id_generator.next_uuid();
+    args.send_modeling_cmd(
+        id,
+        ModelingCmd::Sweep {
+            target: sketch_group.id,
+            trajectory,
+            sectional: false,
+            tolerance: DEFAULT_TOLERANCE,
+            relative_to: if data.twist.unwrap_or(true) {
+                Some(RelativeTo::TrajectoryCurve)
+            } else {
+                Some(RelativeTo::SketchPlane)
+            },
+        },
+    )
+    .await?;
+
+    // A sweep has no single height, so helices on it need to be given a length.
+    do_post_extrude(sketch_group, 0.0, id, args).await
+}
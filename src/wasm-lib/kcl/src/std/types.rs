@@ -3,6 +3,12 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    errors::{KclError, KclErrorDetails},
+    executor::{MemoryItem, SourceRange},
+    std::Args,
+};
+
 /// A Uint that allows us to do math but rounds to a uint.
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize, ts_rs::TS)]
 #[ts(export)]
@@ -38,3 +44,85 @@ impl JsonSchema for Uint {
         gen.subschema_for::<u32>()
     }
 }
+
+/// Any KCL value passed to or returned from a stdlib function, e.g. an element of an array.
+#[derive(Debug, Clone)]
+pub struct KclValue(pub MemoryItem);
+
+impl JsonSchema for KclValue {
+    fn schema_name() -> String {
+        "KclValue".to_string()
+    }
+
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schemars::schema::SchemaObject {
+            metadata: Some(Box::new(schemars::schema::Metadata {
+                description: Some("Any KCL value, e.g. a number, an array or a sketch group.".to_string()),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// A KCL function passed as an argument to a stdlib function, e.g. the `f` in `map(array, f)`.
+#[derive(Debug, Clone)]
+pub struct FnAsArg(MemoryItem);
+
+impl FnAsArg {
+    /// Wrap a memory item, which must be a function.
+    pub fn new(item: MemoryItem, source_range: SourceRange) -> Result<Self, KclError> {
+        if !matches!(item, MemoryItem::Function { .. }) {
+            return Err(KclError::Type(KclErrorDetails {
+                message: format!("Expected a function, found `{:?}`", item),
+                source_ranges: vec![source_range],
+                ..Default::default()
+            }));
+        }
+        Ok(Self(item))
+    }
+
+    /// The name the function was declared with, for showing in call stacks.
+    fn name(&self) -> &str {
+        match &self.0 {
+            MemoryItem::Function { name: Some(name), .. } => name,
+            _ => "(anonymous function)",
+        }
+    }
+
+    /// Call the function with the given arguments, returning the value it returns.
+    pub async fn call(&self, fn_args: Vec<MemoryItem>, args: &Args) -> Result<MemoryItem, KclError> {
+        let result = self
+            .0
+            .call_fn(fn_args, args.ctx.clone())
+            .await
+            .map_err(|e| e.add_stack_frame(self.name(), args.source_range))?
+            .ok_or_else(|| {
+                KclError::UndefinedValue(KclErrorDetails {
+                    message: "The function passed as an argument must return a value".to_string(),
+                    source_ranges: vec![args.source_range],
+                    ..Default::default()
+                })
+            })?;
+
+        result.get_value()
+    }
+}
+
+impl JsonSchema for FnAsArg {
+    fn schema_name() -> String {
+        "FnAsArg".to_string()
+    }
+
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schemars::schema::SchemaObject {
+            metadata: Some(Box::new(schemars::schema::Metadata {
+                description: Some("A KCL function, declared with `fn`.".to_string()),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
@@ -237,6 +237,7 @@ pub fn arc_angles(
                 from, center, radius
             ),
             source_ranges: vec![source_range],
+            ..Default::default()
         }));
     }
 
@@ -247,6 +248,7 @@ pub fn arc_angles(
                 to, center, radius
             ),
             source_ranges: vec![source_range],
+            ..Default::default()
         }));
     }
 
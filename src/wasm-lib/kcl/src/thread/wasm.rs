@@ -2,6 +2,8 @@
 
 pub struct JoinHandle {
     inner: Option<crate::wasm::Promise>,
+    /// Closed when the future finishes or is dropped.
+    done: tokio::sync::watch::Receiver<()>,
 }
 
 impl JoinHandle {
@@ -9,16 +11,25 @@ impl JoinHandle {
     where
         F: std::future::Future<Output = ()> + Send + 'static,
     {
+        let (done_tx, done) = tokio::sync::watch::channel(());
         Self {
             inner: Some(
                 wasm_bindgen_futures::future_to_promise(async move {
+                    let _done_tx = done_tx;
                     future.await;
                     Ok(wasm_bindgen::JsValue::NULL)
                 })
                 .into(),
             ),
+            done,
         }
     }
+
+    /// Wait for the thread to finish.
+    pub async fn finished(&self) {
+        // Nothing is ever sent, so this only returns once the sender is dropped with the future.
+        let _ = self.done.clone().changed().await;
+    }
 }
 
 impl crate::thread::Thread for JoinHandle {
@@ -8,7 +8,10 @@ use winnow::{
     Located,
 };
 
-use crate::token::{Token, TokenType};
+use crate::{
+    ast::types::NumericSuffix,
+    token::{Token, TokenType},
+};
 
 pub fn lexer(i: &str) -> Result<Vec<Token>, ParseError<Located<&str>, ContextError>> {
     repeat(0.., token).parse(Located::new(i))
@@ -26,7 +29,7 @@ pub fn token(i: &mut Located<&str>) -> PResult<Token> {
         ':' => colon,
         '.' => alt((number, double_period, period)),
         '#' => hash,
-        '!' => bang,
+        '!' => alt((operator, bang)),
         ' ' | '\t' | '\n' => whitespace,
         _ => alt((operator, keyword,type_, word))
     }
@@ -67,7 +70,11 @@ fn number(i: &mut Located<&str>) -> PResult<Token> {
         // No digits before the decimal point.
         ('.', digit1).map(|_| ()),
     ));
-    let (value, range) = number_parser.recognize().with_span().parse_next(i)?;
+    // A unit of measure can be written straight after the number, e.g. `10mm` or `45deg`.
+    let suffix = inner_word
+        .recognize()
+        .verify(|suffix: &str| suffix.parse::<NumericSuffix>().is_ok());
+    let (value, range) = (number_parser, opt(suffix)).recognize().with_span().parse_next(i)?;
     Ok(Token::from_range(range, TokenType::Number, value.to_string()))
 }
 
@@ -89,7 +96,7 @@ fn word(i: &mut Located<&str>) -> PResult<Token> {
 
 fn operator(i: &mut Located<&str>) -> PResult<Token> {
     let (value, range) = alt((
-        ">=", "<=", "==", "=>", "!= ", "|>", "*", "+", "-", "/", "%", "=", "<", ">", r"\", "|", "^",
+        ">=", "<=", "==", "=>", "!=", "&&", "||", "|>", "*", "+", "-", "/", "%", "=", "<", ">", r"\", "|", "^",
     ))
     .with_span()
     .parse_next(i)?;
@@ -243,6 +250,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_number_with_units() {
+        for (input, expected) in [
+            ("10mm", "10mm"),
+            ("2.5in", "2.5in"),
+            ("45deg)", "45deg"),
+            ("1m + 2", "1m"),
+            // Other letters aren't part of the number.
+            ("10mmm", "10"),
+            ("3abc", "3"),
+            ("2min", "2"),
+        ] {
+            let mut input = Located::new(input);
+            assert_eq!(number.parse_next(&mut input).unwrap().value, expected);
+        }
+    }
+
     #[test]
     fn test_word() {
         for valid in ["a", "a ", "a5", "a5a"] {
@@ -257,7 +281,7 @@ mod tests {
     #[test]
     fn test_operator() {
         for valid in [
-            "+", "+ ", "-", "<=", "<= ", ">=", ">= ", "> ", "< ", "| ", "|> ", "^ ", "% ", "+* ",
+            "+", "+ ", "-", "<=", "<= ", ">=", ">= ", "> ", "< ", "| ", "|> ", "^ ", "% ", "+* ", "!=", "&&", "||",
         ] {
             assert_parse_ok(operator, valid);
         }
@@ -1523,6 +1547,38 @@ const things = "things"
         assert_tokens(expected, actual);
     }
 
+    #[test]
+    fn test_lexer_not_and_not_equal() {
+        let actual = lexer("!a!=b").unwrap();
+        let expected = vec![
+            Token {
+                token_type: TokenType::Bang,
+                value: "!".to_string(),
+                start: 0,
+                end: 1,
+            },
+            Token {
+                token_type: TokenType::Word,
+                value: "a".to_string(),
+                start: 1,
+                end: 2,
+            },
+            Token {
+                token_type: TokenType::Operator,
+                value: "!=".to_string(),
+                start: 2,
+                end: 4,
+            },
+            Token {
+                token_type: TokenType::Word,
+                value: "b".to_string(),
+                start: 4,
+                end: 5,
+            },
+        ];
+        assert_tokens(expected, actual);
+    }
+
     #[test]
     fn test_unrecognized_token() {
         let actual = lexer("12 ; 8").unwrap();
@@ -0,0 +1,1226 @@
+//! A static type checker for KCL programs.
+//!
+//! This runs over the AST before the program is executed, so that mistakes like passing a
+//! number where a sketch group is expected are reported before any commands are sent to the
+//! engine. Arguments to stdlib functions are checked against the JSON schemas of their
+//! parameters, and arguments to user-defined functions against their type annotations.
+//!
+//! The checker is deliberately conservative: when it can't work out the type of a value, it
+//! assumes the value is fine.
+
+use std::collections::{BTreeSet, HashMap};
+
+use schemars::{
+    schema::{InstanceType, Schema, SchemaObject, SingleOrVec},
+    JsonSchema,
+};
+use serde_json::Value as JValue;
+
+use crate::{
+    ast::types::{
+        BinaryOperator, BinaryPart, BindingPattern, BodyItem, CallExpression, Dimension, FnArgPrimitive, FnArgType,
+        ForIterable, FunctionExpression, LiteralIdentifier, LiteralValue, MemberExpression, MemberObject, Parameter,
+        Program, UnaryOperator, Value,
+    },
+    docs::StdLibFn,
+    errors::{KclError, KclErrorDetails},
+    executor::{ExtrudeGroup, Face, MemoryItem, Plane, ProgramMemory, SketchGroup, SourceRange},
+    std::StdLib,
+};
+
+/// Check a program for type errors, without executing it.
+/// Names already in `memory` (e.g. from a previous run) are in scope for the program.
+pub fn check_program(program: &Program, stdlib: &StdLib, memory: &ProgramMemory) -> Vec<KclError> {
+    let mut checker = TypeChecker::new(stdlib);
+    checker.scopes[0].extend(memory.root.iter().map(|(name, item)| (name.clone(), Type::of(item))));
+    checker.check_body(program);
+    checker.errors
+}
+
+/// What the checker knows about the type of a value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type {
+    /// The checker can't tell, so any use of the value is allowed.
+    Unknown,
+    Number,
+    /// A number with a unit of measure, e.g. `10mm`.
+    Quantity(Dimension),
+    String,
+    Bool,
+    /// An array of elements of the given type, and its length if known.
+    Array(Box<Type>, Option<usize>),
+    /// An object with the given properties.
+    Object(Vec<(String, Type)>),
+    SketchGroup,
+    /// A plane or a face.
+    SketchSurface,
+    ExtrudeGroup,
+    Function(Box<FnType>),
+}
+
+/// The signature of a user-defined function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FnType {
+    pub params: Vec<Parameter>,
+    pub return_type: Type,
+}
+
+impl Type {
+    /// The type of a value in memory.
+    pub fn of(item: &MemoryItem) -> Self {
+        match item {
+            MemoryItem::UserVal(user_val) => match user_val.dimension {
+                Some(dimension) => Type::Quantity(dimension),
+                None => Self::of_json(&user_val.value),
+            },
+            MemoryItem::Plane(_) | MemoryItem::Face(_) => Type::SketchSurface,
+            MemoryItem::SketchGroup(_) => Type::SketchGroup,
+            MemoryItem::SketchGroups { value } => Type::Array(Box::new(Type::SketchGroup), Some(value.len())),
+            MemoryItem::ExtrudeGroup(_) => Type::ExtrudeGroup,
+            MemoryItem::ExtrudeGroups { value } => Type::Array(Box::new(Type::ExtrudeGroup), Some(value.len())),
+            MemoryItem::ImportedGeometry(_) => Type::Unknown,
+            MemoryItem::Function { expression, .. } => Type::Function(Box::new(FnType {
+                params: expression.params.clone(),
+                return_type: expression.return_type.as_ref().map(Type::from).unwrap_or(Type::Unknown),
+            })),
+        }
+    }
+
+    fn of_json(value: &JValue) -> Self {
+        match value {
+            JValue::Number(_) => Type::Number,
+            JValue::String(_) => Type::String,
+            JValue::Bool(_) => Type::Bool,
+            JValue::Array(items) => Type::Array(
+                Box::new(Self::unify(items.iter().map(Self::of_json))),
+                Some(items.len()),
+            ),
+            JValue::Object(properties) => Type::Object(
+                properties
+                    .iter()
+                    .map(|(key, value)| (key.clone(), Self::of_json(value)))
+                    .collect(),
+            ),
+            JValue::Null => Type::Unknown,
+        }
+    }
+
+    /// The type of a stdlib function's return value, from the name of its Rust type.
+    fn of_stdlib_return(type_: &str) -> Self {
+        match type_ {
+            "number" => Type::Number,
+            "string" | "String" => Type::String,
+            "bool" => Type::Bool,
+            "[number]" => Type::Array(Box::new(Type::Number), None),
+            "SketchGroup" => Type::SketchGroup,
+            "[SketchGroup]" => Type::Array(Box::new(Type::SketchGroup), None),
+            "SketchSurface" => Type::SketchSurface,
+            "ExtrudeGroup" => Type::ExtrudeGroup,
+            "[ExtrudeGroup]" => Type::Array(Box::new(Type::ExtrudeGroup), None),
+            _ if type_.starts_with('[') => Type::Array(Box::new(Type::Unknown), None),
+            _ => Type::Unknown,
+        }
+    }
+
+    /// The type shared by all the given types, or unknown if they differ.
+    fn unify(mut types: impl Iterator<Item = Type>) -> Self {
+        let Some(first) = types.next() else {
+            return Type::Unknown;
+        };
+        let types: Vec<Type> = types.collect();
+        if types.iter().all(|ty| *ty == first) {
+            first
+        } else if first.is_number() && types.iter().all(Type::is_number) {
+            // A mix of numbers with and without units of measure.
+            Type::Number
+        } else {
+            Type::Unknown
+        }
+    }
+
+    /// Describe the type for an error message, e.g. "a number".
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Type::Unknown => "an unknown value",
+            Type::Number => "a number",
+            Type::Quantity(dimension) => dimension.describe(),
+            Type::String => "a string",
+            Type::Bool => "a boolean",
+            Type::Array(..) => "an array",
+            Type::Object(_) => "an object",
+            Type::SketchGroup => "a sketch group",
+            Type::SketchSurface => "a sketch surface",
+            Type::ExtrudeGroup => "an extrude group",
+            Type::Function(_) => "a function",
+        }
+    }
+
+    fn is_number(&self) -> bool {
+        matches!(self, Type::Number | Type::Quantity(_))
+    }
+
+    /// Could a value of this type be used where the given type annotation is expected?
+    fn matches_annotation(&self, expected: &FnArgType) -> bool {
+        match (expected, self) {
+            (_, Type::Unknown) => true,
+            (FnArgType::Primitive(FnArgPrimitive::Number), Type::Quantity(_)) => true,
+            (FnArgType::Primitive(primitive), _) => {
+                matches!(
+                    (primitive, self),
+                    (FnArgPrimitive::String, Type::String)
+                        | (FnArgPrimitive::Number, Type::Number)
+                        | (FnArgPrimitive::Boolean, Type::Bool)
+                        | (FnArgPrimitive::SketchGroup, Type::SketchGroup)
+                        | (FnArgPrimitive::SketchSurface, Type::SketchSurface)
+                        | (FnArgPrimitive::ExtrudeGroup, Type::ExtrudeGroup)
+                )
+            }
+            (FnArgType::Array(primitive), Type::Array(element, _)) => {
+                element.matches_annotation(&FnArgType::Primitive(primitive.clone()))
+            }
+            (FnArgType::Object { properties: expected }, Type::Object(properties)) => {
+                expected.iter().all(
+                    |param| match properties.iter().find(|(key, _)| *key == param.identifier.name) {
+                        Some((_, ty)) => param.type_.as_ref().map_or(true, |t| ty.matches_annotation(t)),
+                        None => param.optional,
+                    },
+                )
+            }
+            _ => false,
+        }
+    }
+}
+
+impl From<&FnArgType> for Type {
+    fn from(annotation: &FnArgType) -> Self {
+        match annotation {
+            FnArgType::Primitive(FnArgPrimitive::String) => Type::String,
+            FnArgType::Primitive(FnArgPrimitive::Number) => Type::Number,
+            FnArgType::Primitive(FnArgPrimitive::Boolean) => Type::Bool,
+            FnArgType::Primitive(FnArgPrimitive::SketchGroup) => Type::SketchGroup,
+            FnArgType::Primitive(FnArgPrimitive::SketchSurface) => Type::SketchSurface,
+            FnArgType::Primitive(FnArgPrimitive::ExtrudeGroup) => Type::ExtrudeGroup,
+            FnArgType::Array(primitive) => {
+                Type::Array(Box::new(Type::from(&FnArgType::Primitive(primitive.clone()))), None)
+            }
+            FnArgType::Object { properties } => Type::Object(
+                properties
+                    .iter()
+                    .map(|param| {
+                        (
+                            param.identifier.name.clone(),
+                            param.type_.as_ref().map(Type::from).unwrap_or(Type::Unknown),
+                        )
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Write a type annotation the way it appears in KCL source, e.g. `number[]`.
+fn annotation_to_string(annotation: &FnArgType) -> String {
+    match annotation {
+        FnArgType::Primitive(primitive) => primitive.to_string(),
+        FnArgType::Array(primitive) => format!("{}[]", primitive),
+        FnArgType::Object { properties } => format!(
+            "{{{}}}",
+            properties
+                .iter()
+                .map(|param| {
+                    let optional = if param.optional { "?" } else { "" };
+                    match &param.type_ {
+                        Some(type_) => {
+                            format!("{}{}: {}", param.identifier.name, optional, annotation_to_string(type_))
+                        }
+                        None => format!("{}{}", param.identifier.name, optional),
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+struct TypeChecker<'a> {
+    stdlib: &'a StdLib,
+    /// Names in scope, innermost scope last.
+    scopes: Vec<HashMap<String, Type>>,
+    /// The value of `%` in each pipe expression being checked, innermost last.
+    pipe_values: Vec<Type>,
+    /// For each function body being checked: its declared return type, and the type it was
+    /// first seen returning.
+    returns: Vec<(Option<FnArgType>, Option<Type>)>,
+    /// The properties of each kind of geometry, to match them against stdlib schemas.
+    sketch_group_properties: BTreeSet<String>,
+    extrude_group_properties: BTreeSet<String>,
+    plane_properties: BTreeSet<String>,
+    face_properties: BTreeSet<String>,
+    errors: Vec<KclError>,
+}
+
+impl<'a> TypeChecker<'a> {
+    fn new(stdlib: &'a StdLib) -> Self {
+        Self {
+            stdlib,
+            scopes: vec![HashMap::new()],
+            pipe_values: Vec::new(),
+            returns: Vec::new(),
+            sketch_group_properties: property_names::<SketchGroup>(),
+            extrude_group_properties: property_names::<ExtrudeGroup>(),
+            plane_properties: property_names::<Plane>(),
+            face_properties: property_names::<Face>(),
+            errors: Vec::new(),
+        }
+    }
+
+    fn error(&mut self, source_range: SourceRange, message: String) {
+        self.errors.push(KclError::Type(KclErrorDetails {
+            source_ranges: vec![source_range],
+            message,
+            ..Default::default()
+        }));
+    }
+
+    fn lookup(&self, name: &str) -> Type {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .cloned()
+            .unwrap_or(Type::Unknown)
+    }
+
+    fn bind(&mut self, name: &str, ty: Type) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), ty);
+        }
+    }
+
+    /// Check a block in its own scope, returning the type of the value it ends with, if any.
+    fn check_block(&mut self, program: &Program) -> Type {
+        self.scopes.push(HashMap::new());
+        let ty = self.check_body(program);
+        self.scopes.pop();
+        ty
+    }
+
+    fn check_body(&mut self, program: &Program) -> Type {
+        let mut last = Type::Unknown;
+        for item in &program.body {
+            last = Type::Unknown;
+            match item {
+                BodyItem::ExpressionStatement(expression_statement) => {
+                    last = self.infer(&expression_statement.expression);
+                }
+                BodyItem::VariableDeclaration(variable_declaration) => {
+                    for declaration in &variable_declaration.declarations {
+                        let ty = match (&declaration.init, &declaration.id) {
+                            (Value::FunctionExpression(function_expression), BindingPattern::Identifier(id)) => {
+                                self.check_function(function_expression, Some(&id.name))
+                            }
+                            (init, _) => self.infer(init),
+                        };
+                        self.bind_pattern(&declaration.id, ty);
+                    }
+                }
+                BodyItem::ReturnStatement(return_statement) => {
+                    let ty = self.infer(&return_statement.argument);
+                    let Some((expected, returned)) = self.returns.last_mut() else {
+                        continue;
+                    };
+                    if returned.is_none() {
+                        *returned = Some(ty.clone());
+                    }
+                    if let Some(expected) = expected.clone() {
+                        if !ty.matches_annotation(&expected) {
+                            self.error(
+                                (&return_statement.argument).into(),
+                                format!(
+                                    "This function should return `{}`, but this is {}",
+                                    annotation_to_string(&expected),
+                                    ty.describe()
+                                ),
+                            );
+                        }
+                    }
+                }
+                BodyItem::ForStatement(for_statement) => {
+                    let item_type = match &for_statement.iterable {
+                        ForIterable::Range(range) => {
+                            for bound in [&range.from, &range.to] {
+                                let ty = self.infer(bound);
+                                self.expect_number(&ty, bound.into());
+                            }
+                            Type::Number
+                        }
+                        ForIterable::Value(value) => match self.infer(value) {
+                            Type::Array(element, _) => *element,
+                            Type::Unknown => Type::Unknown,
+                            ty => {
+                                self.error(
+                                    value.into(),
+                                    format!(
+                                        "A for loop can only iterate over a range or an array, but this is {}",
+                                        ty.describe()
+                                    ),
+                                );
+                                Type::Unknown
+                            }
+                        },
+                    };
+                    self.scopes.push(HashMap::new());
+                    self.bind(&for_statement.variable.name, item_type);
+                    self.check_body(&for_statement.body);
+                    self.scopes.pop();
+                }
+                BodyItem::ImportStatement(import_statement) => {
+                    for import_item in &import_statement.items {
+                        self.bind(&import_item.local_name().name, Type::Unknown);
+                    }
+                }
+            }
+        }
+        last
+    }
+
+    /// Bind the names in a declaration, destructuring the value's type if need be.
+    fn bind_pattern(&mut self, pattern: &BindingPattern, ty: Type) {
+        let source_range = SourceRange::from(pattern);
+        match pattern {
+            BindingPattern::Identifier(id) => self.bind(&id.name, ty),
+            BindingPattern::ArrayPattern(array_pattern) => {
+                let item = match ty {
+                    Type::Array(item, len) => {
+                        if let Some(len) = len.filter(|len| *len < array_pattern.elements.len()) {
+                            self.error(
+                                source_range,
+                                format!(
+                                    "Expected an array of at least {} items to destructure, but it only has {}",
+                                    array_pattern.elements.len(),
+                                    len
+                                ),
+                            );
+                        }
+                        *item
+                    }
+                    Type::Unknown => Type::Unknown,
+                    ty => {
+                        self.error(
+                            source_range,
+                            format!(
+                                "Only an array can be destructured like this, but this is {}",
+                                ty.describe()
+                            ),
+                        );
+                        Type::Unknown
+                    }
+                };
+                for id in &array_pattern.elements {
+                    self.bind(&id.name, item.clone());
+                }
+            }
+            BindingPattern::ObjectPattern(object_pattern) => {
+                let properties = match ty {
+                    Type::Object(properties) => Some(properties),
+                    Type::Number
+                    | Type::Quantity(_)
+                    | Type::String
+                    | Type::Bool
+                    | Type::Array(..)
+                    | Type::Function(_) => {
+                        self.error(
+                            source_range,
+                            format!(
+                                "Only an object can be destructured like this, but this is {}",
+                                ty.describe()
+                            ),
+                        );
+                        None
+                    }
+                    _ => None,
+                };
+                for id in &object_pattern.properties {
+                    let ty = match &properties {
+                        Some(properties) => match properties.iter().find(|(name, _)| *name == id.name) {
+                            Some((_, ty)) => ty.clone(),
+                            None => {
+                                self.error(id.into(), format!("Property {} not found in object", id.name));
+                                Type::Unknown
+                            }
+                        },
+                        None => Type::Unknown,
+                    };
+                    self.bind(&id.name, ty);
+                }
+            }
+        }
+    }
+
+    /// Check a function's body, returning the function's type.
+    /// A declared function can call itself, so its own name is in scope in its body.
+    fn check_function(&mut self, function_expression: &FunctionExpression, name: Option<&str>) -> Type {
+        let declared_return = function_expression.return_type.as_ref().map(Type::from);
+        let fn_type = |return_type: Type| {
+            Type::Function(Box::new(FnType {
+                params: function_expression.params.clone(),
+                return_type,
+            }))
+        };
+
+        self.scopes.push(HashMap::new());
+        if let Some(name) = name {
+            self.bind(name, fn_type(declared_return.clone().unwrap_or(Type::Unknown)));
+        }
+        for param in &function_expression.params {
+            let default = param
+                .default_value
+                .as_ref()
+                .map(|default_value| (self.infer(default_value), SourceRange::from(default_value)));
+            let ty = match (&param.type_, default) {
+                (Some(expected), Some((default, source_range))) => {
+                    if !default.matches_annotation(expected) {
+                        self.error(
+                            source_range,
+                            format!(
+                                "The default value of `{}` should be `{}`, but it is {}",
+                                param.identifier.name,
+                                annotation_to_string(expected),
+                                default.describe()
+                            ),
+                        );
+                    }
+                    Type::from(expected)
+                }
+                (Some(expected), None) => Type::from(expected),
+                // Callers can pass anything for a parameter with no type, whatever its default is.
+                (None, _) => Type::Unknown,
+            };
+            self.bind(&param.identifier.name, ty);
+        }
+        self.returns.push((function_expression.return_type.clone(), None));
+        self.check_body(&function_expression.body);
+        let (_, returned) = self.returns.pop().unwrap_or_default();
+        self.scopes.pop();
+
+        fn_type(declared_return.or(returned).unwrap_or(Type::Unknown))
+    }
+
+    fn infer(&mut self, value: &Value) -> Type {
+        match value {
+            Value::Literal(literal) => match &literal.value {
+                LiteralValue::IInteger(_) | LiteralValue::Fractional(_) => match literal.suffix {
+                    Some(suffix) => Type::Quantity(suffix.dimension()),
+                    None => Type::Number,
+                },
+                LiteralValue::String(_) => Type::String,
+                LiteralValue::Bool(_) => Type::Bool,
+            },
+            Value::Identifier(identifier) => self.lookup(&identifier.name),
+            Value::BinaryExpression(binary_expression) => {
+                let left = self.infer_binary_part(&binary_expression.left);
+                let right = self.infer_binary_part(&binary_expression.right);
+                let operands = [
+                    (left, SourceRange::from(&binary_expression.left)),
+                    (right, SourceRange::from(&binary_expression.right)),
+                ];
+                match binary_expression.operator {
+                    BinaryOperator::Add => {
+                        if operands.iter().any(|(ty, _)| *ty == Type::String) {
+                            // Either both sides are strings, or this is an error at runtime.
+                            for (ty, source_range) in operands {
+                                if !matches!(ty, Type::String | Type::Unknown) {
+                                    self.error(
+                                        source_range,
+                                        format!(
+                                            "Only a string can be added to a string, but this is {}",
+                                            ty.describe()
+                                        ),
+                                    );
+                                }
+                            }
+                            Type::String
+                        } else {
+                            // Unknown values might be strings, so the sum is only known to be a
+                            // number if both sides are.
+                            let both_numbers = operands.iter().all(|(ty, _)| ty.is_number());
+                            match self.infer_arithmetic(BinaryOperator::Add, operands) {
+                                Type::Number if !both_numbers => Type::Unknown,
+                                ty => ty,
+                            }
+                        }
+                    }
+                    BinaryOperator::Sub
+                    | BinaryOperator::Mul
+                    | BinaryOperator::Div
+                    | BinaryOperator::Mod
+                    | BinaryOperator::Pow
+                    | BinaryOperator::Lt
+                    | BinaryOperator::Lte
+                    | BinaryOperator::Gt
+                    | BinaryOperator::Gte => self.infer_arithmetic(binary_expression.operator.clone(), operands),
+                    BinaryOperator::And | BinaryOperator::Or => {
+                        // The right-hand side is only evaluated if it's needed, so it's only an
+                        // error at runtime if the left-hand side doesn't short-circuit.
+                        let [(left, source_range), _] = operands;
+                        self.expect_bool(&left, source_range);
+                        Type::Bool
+                    }
+                    BinaryOperator::Eq | BinaryOperator::Neq => Type::Bool,
+                }
+            }
+            Value::FunctionExpression(function_expression) => self.check_function(function_expression, None),
+            Value::CallExpression(call_expression) => self.infer_call(call_expression),
+            Value::PipeExpression(pipe_expression) => {
+                let mut ty = Type::Unknown;
+                for (index, stage) in pipe_expression.body.iter().enumerate() {
+                    if index == 0 {
+                        ty = self.infer(stage);
+                        continue;
+                    }
+                    self.pipe_values.push(ty);
+                    ty = self.infer(stage);
+                    self.pipe_values.pop();
+                }
+                ty
+            }
+            Value::PipeSubstitution(_) => self.pipe_values.last().cloned().unwrap_or(Type::Unknown),
+            Value::ArrayExpression(array_expression) => {
+                let elements: Vec<Type> = array_expression
+                    .elements
+                    .iter()
+                    .map(|element| self.infer(element))
+                    .collect();
+                let len = elements.len();
+                Type::Array(Box::new(Type::unify(elements.into_iter())), Some(len))
+            }
+            Value::ObjectExpression(object_expression) => Type::Object(
+                object_expression
+                    .properties
+                    .iter()
+                    .map(|property| (property.key.name.clone(), self.infer(&property.value)))
+                    .collect(),
+            ),
+            Value::MemberExpression(member_expression) => self.infer_member(member_expression),
+            Value::UnaryExpression(unary_expression) => {
+                let ty = self.infer_binary_part(&unary_expression.argument);
+                let source_range = SourceRange::from(&unary_expression.argument);
+                match unary_expression.operator {
+                    UnaryOperator::Neg => {
+                        self.expect_number(&ty, source_range);
+                        match ty {
+                            Type::Quantity(dimension) => Type::Quantity(dimension),
+                            _ => Type::Number,
+                        }
+                    }
+                    UnaryOperator::Not => {
+                        self.expect_bool(&ty, source_range);
+                        Type::Bool
+                    }
+                }
+            }
+            Value::IfExpression(if_expression) => {
+                let conds = std::iter::once(if_expression.cond.as_ref())
+                    .chain(if_expression.else_ifs.iter().map(|else_if| &else_if.cond));
+                for cond in conds {
+                    let ty = self.infer(cond);
+                    self.expect_bool(&ty, cond.into());
+                }
+                let mut branches = vec![self.check_block(&if_expression.then_val)];
+                for else_if in &if_expression.else_ifs {
+                    branches.push(self.check_block(&else_if.then_val));
+                }
+                branches.push(self.check_block(&if_expression.final_else));
+                Type::unify(branches.into_iter())
+            }
+            Value::None(_) => Type::Unknown,
+        }
+    }
+
+    /// The type of arithmetic or a comparison on two numbers.
+    fn infer_arithmetic(&mut self, operator: BinaryOperator, operands: [(Type, SourceRange); 2]) -> Type {
+        for (ty, source_range) in &operands {
+            self.expect_number(ty, *source_range);
+        }
+        let dimension = |ty: &Type| match ty {
+            Type::Quantity(dimension) => Some(*dimension),
+            _ => None,
+        };
+        let [(left, _), (right, right_source_range)] = operands;
+        let dimension = match operator.apply_dimension(dimension(&left), dimension(&right), right_source_range) {
+            Ok(dimension) => dimension,
+            Err(err) => {
+                self.errors.push(err);
+                None
+            }
+        };
+        match operator {
+            BinaryOperator::Lt | BinaryOperator::Lte | BinaryOperator::Gt | BinaryOperator::Gte => Type::Bool,
+            _ => dimension.map(Type::Quantity).unwrap_or(Type::Number),
+        }
+    }
+
+    fn infer_binary_part(&mut self, part: &BinaryPart) -> Type {
+        match part {
+            BinaryPart::Literal(literal) => self.infer(&Value::Literal(literal.clone())),
+            BinaryPart::Identifier(identifier) => self.lookup(&identifier.name),
+            BinaryPart::BinaryExpression(binary_expression) => {
+                self.infer(&Value::BinaryExpression(binary_expression.clone()))
+            }
+            BinaryPart::CallExpression(call_expression) => self.infer_call(call_expression),
+            BinaryPart::UnaryExpression(unary_expression) => {
+                self.infer(&Value::UnaryExpression(unary_expression.clone()))
+            }
+            BinaryPart::MemberExpression(member_expression) => self.infer_member(member_expression),
+        }
+    }
+
+    fn infer_member(&mut self, member_expression: &MemberExpression) -> Type {
+        let object = match &member_expression.object {
+            MemberObject::MemberExpression(member_expression) => self.infer_member(member_expression),
+            MemberObject::Identifier(identifier) => self.lookup(&identifier.name),
+        };
+        let key = match &member_expression.property {
+            LiteralIdentifier::Identifier(identifier) if member_expression.computed => {
+                self.lookup(&identifier.name);
+                None
+            }
+            LiteralIdentifier::Identifier(identifier) => Some(identifier.name.clone()),
+            LiteralIdentifier::Literal(literal) => match &literal.value {
+                LiteralValue::String(key) => Some(key.clone()),
+                _ => None,
+            },
+        };
+        match object {
+            Type::Array(element, _) => *element,
+            Type::Object(properties) => key
+                .and_then(|key| properties.into_iter().find(|(name, _)| *name == key))
+                .map(|(_, ty)| ty)
+                .unwrap_or(Type::Unknown),
+            _ => Type::Unknown,
+        }
+    }
+
+    fn infer_call(&mut self, call_expression: &CallExpression) -> Type {
+        let fn_name = &call_expression.callee.name;
+
+        if let Some(std_fn) = self.stdlib.get(fn_name) {
+            self.check_stdlib_call(std_fn.as_ref(), call_expression);
+            return std_fn
+                .return_value()
+                .map(|return_value| Type::of_stdlib_return(&return_value.type_))
+                .unwrap_or(Type::Unknown);
+        }
+
+        let fn_type = match self.lookup(fn_name) {
+            Type::Function(fn_type) => Some(fn_type),
+            _ => None,
+        };
+        let names: Vec<&str> = fn_type
+            .iter()
+            .flat_map(|fn_type| &fn_type.params)
+            .map(|param| param.identifier.name.as_str())
+            .collect();
+        let args = self.infer_args(call_expression, &names);
+        let Some(fn_type) = fn_type else {
+            return Type::Unknown;
+        };
+        let required = fn_type.params.iter().filter(|param| !param.optional).count();
+        let max = fn_type.params.len();
+        let missing = fn_type
+            .params
+            .iter()
+            .enumerate()
+            .any(|(index, param)| !param.optional && !args.iter().any(|arg| arg.0 == Some(index)));
+        if missing || call_expression.arguments.len() > max {
+            let expected = if required == max {
+                format!("{required}")
+            } else {
+                format!("{required}-{max}")
+            };
+            self.error(
+                call_expression.into(),
+                format!("Expected {} arguments, got {}", expected, args.len()),
+            );
+        }
+        for (index, ty, source_range) in args {
+            let Some(index) = index else {
+                continue;
+            };
+            let param = &fn_type.params[index];
+            let Some(expected) = &param.type_ else {
+                continue;
+            };
+            if !ty.matches_annotation(expected) {
+                self.error(
+                    source_range,
+                    format!(
+                        "Argument {} of `{}` should be `{}: {}`, but it is {}",
+                        index + 1,
+                        fn_name,
+                        param.identifier.name,
+                        annotation_to_string(expected),
+                        ty.describe()
+                    ),
+                );
+            }
+        }
+        fn_type.return_type
+    }
+
+    /// Infer the type of every argument of a call, along with the index of the parameter it's for.
+    /// Positional arguments go to the parameter in their position, and named arguments to the parameter
+    /// with their name. Arguments with no such parameter get `None`; the executor reports those.
+    fn infer_args(
+        &mut self,
+        call_expression: &CallExpression,
+        names: &[&str],
+    ) -> Vec<(Option<usize>, Type, SourceRange)> {
+        let positional = call_expression
+            .arguments
+            .iter()
+            .enumerate()
+            .map(|(index, arg)| (Some(index).filter(|index| *index < names.len()), arg));
+        let named = call_expression
+            .named_arguments
+            .iter()
+            .map(|arg| (names.iter().position(|name| *name == arg.name.name), &arg.value));
+        positional
+            .chain(named)
+            .map(|(index, arg)| (index, self.infer(arg), arg.into()))
+            .collect()
+    }
+
+    fn check_stdlib_call(&mut self, std_fn: &dyn StdLibFn, call_expression: &CallExpression) {
+        let params = std_fn.args();
+
+        // Functions like `min` take any number of arguments, collected into a single `args` array.
+        if let [param] = &params[..] {
+            if let Schema::Object(SchemaObject { array: Some(array), .. }) = &param.schema {
+                if let (Some(SingleOrVec::Single(items)), "args") = (&array.items, param.name.as_str()) {
+                    for (_, ty, source_range) in self.infer_args(call_expression, &[]) {
+                        if !self.fits_schema(&ty, items) {
+                            self.error(
+                                source_range,
+                                format!(
+                                    "Every argument of `{}` should be a number, but this is {}",
+                                    std_fn.name(),
+                                    ty.describe()
+                                ),
+                            );
+                        }
+                    }
+                    return;
+                }
+            }
+        }
+
+        let names: Vec<&str> = params.iter().map(|param| param.name.as_str()).collect();
+        let args = self.infer_args(call_expression, &names);
+        let required = params.iter().filter(|param| param.required).count();
+        let missing = params
+            .iter()
+            .enumerate()
+            .any(|(index, param)| param.required && !args.iter().any(|arg| arg.0 == Some(index)));
+        if missing || call_expression.arguments.len() > params.len() {
+            let expected = if required == params.len() {
+                format!("{required}")
+            } else {
+                format!("{}-{}", required, params.len())
+            };
+            self.error(
+                call_expression.into(),
+                format!("`{}` expects {} arguments, got {}", std_fn.name(), expected, args.len()),
+            );
+        }
+        for (index, ty, source_range) in args {
+            let Some(index) = index else {
+                continue;
+            };
+            let param = &params[index];
+            if !self.fits_schema(&ty, &param.schema) {
+                self.error(
+                    source_range,
+                    format!(
+                        "Argument {} of `{}` should be `{}: {}`, but it is {}",
+                        index + 1,
+                        std_fn.name(),
+                        param.name,
+                        param.type_,
+                        ty.describe()
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Could a value of the given type be deserialized into the given schema?
+    /// When in doubt, this returns true.
+    fn fits_schema(&self, ty: &Type, schema: &Schema) -> bool {
+        let Schema::Object(schema) = schema else {
+            return true;
+        };
+        if *ty == Type::Unknown || schema.reference.is_some() {
+            return true;
+        }
+
+        if let Some(subschemas) = &schema.subschemas {
+            let alternatives = subschemas.one_of.iter().chain(subschemas.any_of.iter()).flatten();
+            let mut alternatives = alternatives.peekable();
+            if alternatives.peek().is_some() && !alternatives.any(|alternative| self.fits_schema(ty, alternative)) {
+                return false;
+            }
+            if let Some(all_of) = &subschemas.all_of {
+                if !all_of.iter().all(|subschema| self.fits_schema(ty, subschema)) {
+                    return false;
+                }
+            }
+        }
+
+        let Some(instance_types) = &schema.instance_type else {
+            return true;
+        };
+        let instance_types: Vec<InstanceType> = match instance_types {
+            SingleOrVec::Single(instance_type) => vec![**instance_type],
+            SingleOrVec::Vec(instance_types) => instance_types.clone(),
+        };
+        let allows = |instance_type: InstanceType| instance_types.contains(&instance_type);
+
+        match ty {
+            Type::Unknown | Type::Function(_) => true,
+            Type::Number | Type::Quantity(_) => allows(InstanceType::Number) || allows(InstanceType::Integer),
+            Type::String => allows(InstanceType::String),
+            Type::Bool => allows(InstanceType::Boolean),
+            Type::Array(element, len) => {
+                if !allows(InstanceType::Array) {
+                    // Structs can also be deserialized from an array of their fields, in order.
+                    return match (allows(InstanceType::Object), &schema.object, len) {
+                        (true, Some(object), Some(len)) => {
+                            (object.required.len()..=object.properties.len()).contains(len)
+                        }
+                        (true, _, _) => true,
+                        (false, _, _) => false,
+                    };
+                }
+                let Some(array) = &schema.array else {
+                    return true;
+                };
+                if let Some(len) = len {
+                    let len = *len as u32;
+                    if array.min_items.is_some_and(|min| len < min) || array.max_items.is_some_and(|max| len > max) {
+                        return false;
+                    }
+                }
+                match &array.items {
+                    Some(SingleOrVec::Single(items)) => self.fits_schema(element, items),
+                    _ => true,
+                }
+            }
+            Type::Object(properties) => {
+                if !allows(InstanceType::Object) {
+                    return false;
+                }
+                let Some(object) = &schema.object else {
+                    return true;
+                };
+                let has_required = object
+                    .required
+                    .iter()
+                    .all(|key| properties.iter().any(|(name, _)| name == key));
+                has_required
+                    && properties.iter().all(|(name, ty)| {
+                        object
+                            .properties
+                            .get(name)
+                            .map_or(true, |property| self.fits_schema(ty, property))
+                    })
+            }
+            Type::SketchGroup => self.fits_geometry(schema, &allows, &self.sketch_group_properties),
+            Type::ExtrudeGroup => self.fits_geometry(schema, &allows, &self.extrude_group_properties),
+            Type::SketchSurface => {
+                self.fits_geometry(schema, &allows, &self.plane_properties)
+                    || self.fits_geometry(schema, &allows, &self.face_properties)
+            }
+        }
+    }
+
+    /// Geometry is an object, so it fits an object schema if it has all the required properties.
+    fn fits_geometry(
+        &self,
+        schema: &SchemaObject,
+        allows: &dyn Fn(InstanceType) -> bool,
+        properties: &BTreeSet<String>,
+    ) -> bool {
+        // A tagged enum variant holding a list, e.g. `SketchGroupSet::SketchGroups`, is described
+        // as both an object and an array, but a single piece of geometry can't be deserialized into it.
+        if !allows(InstanceType::Object) || schema.array.is_some() {
+            return false;
+        }
+        let Some(object) = &schema.object else {
+            return true;
+        };
+        // Enums of geometry are tagged with a `type` property.
+        object
+            .required
+            .iter()
+            .all(|key| key == "type" || properties.contains(key))
+    }
+
+    fn expect_number(&mut self, ty: &Type, source_range: SourceRange) {
+        if !matches!(ty, Type::Number | Type::Quantity(_) | Type::Unknown) {
+            self.error(
+                source_range,
+                format!("Expected a number, but this is {}", ty.describe()),
+            );
+        }
+    }
+
+    fn expect_bool(&mut self, ty: &Type, source_range: SourceRange) {
+        if !matches!(ty, Type::Bool | Type::Unknown) {
+            self.error(
+                source_range,
+                format!("Expected a boolean, but this is {}", ty.describe()),
+            );
+        }
+    }
+}
+
+/// The names of the properties in the JSON schema of a type, as generated for the stdlib docs.
+fn property_names<T: JsonSchema>() -> BTreeSet<String> {
+    let mut settings = schemars::gen::SchemaSettings::openapi3();
+    settings.inline_subschemas = true;
+    let mut generator = schemars::gen::SchemaGenerator::new(settings);
+    match T::json_schema(&mut generator) {
+        Schema::Object(SchemaObject {
+            object: Some(object), ..
+        }) => object.properties.keys().cloned().collect(),
+        _ => BTreeSet::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn check(code: &str) -> Vec<String> {
+        let tokens = crate::token::lexer(code).unwrap();
+        let program = crate::parser::Parser::new(tokens).ast().unwrap();
+        check_program(&program, &StdLib::new(), &ProgramMemory::new())
+            .into_iter()
+            .map(|err| err.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_stdlib_examples_type_check() {
+        let stdlib = StdLib::new();
+        for std_fn in stdlib.fns.values() {
+            for example in std_fn.examples() {
+                assert_eq!(check(&example), Vec::<String>::new(), "example for `{}`", std_fn.name());
+            }
+        }
+    }
+
+    #[test]
+    fn test_wrong_stdlib_argument() {
+        let errors = check(
+            r#"const part = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line([0, 10], %)
+  |> close(%)
+const solid = extrude(part, 10)"#,
+        );
+        assert_eq!(
+            errors,
+            vec![
+                r#"type: KclErrorDetails { source_ranges: [SourceRange([122, 126])], message: "Argument 1 of `extrude` should be `length: number`, but it is a sketch group", call_stack: [] }"#,
+                r#"type: KclErrorDetails { source_ranges: [SourceRange([128, 130])], message: "Argument 2 of `extrude` should be `sketch_group_set: SketchGroupSet`, but it is a number", call_stack: [] }"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wrong_array_length() {
+        let errors = check(
+            r#"const part = startSketchOn('XY')
+  |> startProfileAt([0, 0, 0], %)"#,
+        );
+        assert_eq!(
+            errors,
+            vec![
+                r#"type: KclErrorDetails { source_ranges: [SourceRange([53, 62])], message: "Argument 1 of `startProfileAt` should be `to: [number]`, but it is an array", call_stack: [] }"#
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wrong_number_of_stdlib_arguments() {
+        let errors = check(r#"const solid = extrude(10)"#);
+        assert_eq!(
+            errors,
+            vec![
+                r#"type: KclErrorDetails { source_ranges: [SourceRange([14, 25])], message: "`extrude` expects 2 arguments, got 1", call_stack: [] }"#
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_object_property() {
+        let errors = check(
+            r#"const part = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> angledLine({ angle: 45 }, %)"#,
+        );
+        assert_eq!(
+            errors,
+            vec![
+                r#"type: KclErrorDetails { source_ranges: [SourceRange([80, 93])], message: "Argument 1 of `angledLine` should be `data: AngledLineData`, but it is an object", call_stack: [] }"#
+            ]
+        );
+    }
+
+    #[test]
+    fn test_annotated_parameters() {
+        let errors = check(
+            r#"fn grow = (part: sketch_group, size: number) => {
+  return extrude(size, part)
+}
+const solid = grow(5, 'big')"#,
+        );
+        assert_eq!(
+            errors,
+            vec![
+                r#"type: KclErrorDetails { source_ranges: [SourceRange([100, 101])], message: "Argument 1 of `grow` should be `part: sketch_group`, but it is a number", call_stack: [] }"#,
+                r#"type: KclErrorDetails { source_ranges: [SourceRange([103, 108])], message: "Argument 2 of `grow` should be `size: number`, but it is a string", call_stack: [] }"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_named_arguments() {
+        let errors = check(
+            r#"fn grow = (part, size: number = 'big', scale = 2) => {
+  return extrude(size * scale, part)
+}
+const a = grow(5, scale = 'x')
+const b = range(0, 10, step = 'two')"#,
+        );
+        assert_eq!(
+            errors,
+            vec![
+                r#"type: KclErrorDetails { source_ranges: [SourceRange([32, 37])], message: "The default value of `size` should be `number`, but it is a string", call_stack: [] }"#,
+                r#"type: KclErrorDetails { source_ranges: [SourceRange([155, 160])], message: "Argument 3 of `range` should be `step: number`, but it is a string", call_stack: [] }"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_units_of_measure() {
+        let errors = check(
+            r#"const width = 2in + 10mm
+const angle = 45deg
+const a = width - angle
+const b = width * 2 < 30deg
+const c = -angle + 1rad
+const d = width * width + angle
+const e2 = max(width, 3)"#,
+        );
+        assert_eq!(
+            errors,
+            vec![
+                r#"type: KclErrorDetails { source_ranges: [SourceRange([63, 68])], message: "Expected a length, but this is an angle", call_stack: [] }"#,
+                r#"type: KclErrorDetails { source_ranges: [SourceRange([91, 96])], message: "Expected a length, but this is an angle", call_stack: [] }"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_destructuring() {
+        let errors = check(
+            r#"const [x, y] = [1]
+const { a, b } = { a: 1 }
+const [c] = 2
+const d = a + 'text'"#,
+        );
+        assert_eq!(
+            errors,
+            vec![
+                r#"type: KclErrorDetails { source_ranges: [SourceRange([6, 12])], message: "Expected an array of at least 2 items to destructure, but it only has 1", call_stack: [] }"#,
+                r#"type: KclErrorDetails { source_ranges: [SourceRange([30, 31])], message: "Property b not found in object", call_stack: [] }"#,
+                r#"type: KclErrorDetails { source_ranges: [SourceRange([51, 54])], message: "Only an array can be destructured like this, but this is a number", call_stack: [] }"#,
+                r#"type: KclErrorDetails { source_ranges: [SourceRange([69, 70])], message: "Only a string can be added to a string, but this is a number", call_stack: [] }"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inferred_return_type() {
+        let errors = check(
+            r#"fn square = (size) => {
+  return startSketchOn('XY')
+    |> startProfileAt([0, 0], %)
+    |> line([0, size], %)
+    |> line([size, 0], %)
+    |> close(%)
+}
+const solid = extrude(square(2), square(3))"#,
+        );
+        assert_eq!(
+            errors,
+            vec![
+                r#"type: KclErrorDetails { source_ranges: [SourceRange([178, 187])], message: "Argument 1 of `extrude` should be `length: number`, but it is a sketch group", call_stack: [] }"#
+            ]
+        );
+    }
+
+    #[test]
+    fn test_declared_return_type() {
+        let errors = check(
+            r#"fn half = (x) => number {
+  return "half"
+}"#,
+        );
+        assert_eq!(
+            errors,
+            vec![
+                r#"type: KclErrorDetails { source_ranges: [SourceRange([35, 41])], message: "This function should return `number`, but this is a string", call_stack: [] }"#
+            ]
+        );
+    }
+
+    #[test]
+    fn test_operators() {
+        let errors = check(
+            r#"const a = 1 - "one"
+const b = "a" + "b"
+const c = !5
+const d = true || "never evaluated""#,
+        );
+        assert_eq!(
+            errors,
+            vec![
+                r#"type: KclErrorDetails { source_ranges: [SourceRange([14, 19])], message: "Expected a number, but this is a string", call_stack: [] }"#,
+                r#"type: KclErrorDetails { source_ranges: [SourceRange([51, 52])], message: "Expected a boolean, but this is a number", call_stack: [] }"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_values_are_allowed() {
+        let errors = check(
+            r#"fn double = (x) => {
+  return x * 2
+}
+const lengths = map([1, 2], double)
+const part = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line([0, lengths[0]], %)
+  |> line([lengths[1], 0], %)
+  |> close(%)
+  |> extrude(double(2), %)"#,
+        );
+        assert_eq!(errors, Vec::<String>::new());
+    }
+}
@@ -20,6 +20,8 @@ pub async fn execute_wasm(
     engine_manager: kcl_lib::engine::conn_wasm::EngineCommandManager,
     fs_manager: kcl_lib::fs::wasm::FileSystemManager,
     is_mock: bool,
+    project_directory: Option<String>,
+    signal: Option<web_sys::AbortSignal>,
 ) -> Result<JsValue, String> {
     console_error_panic_hook::set_once();
     // deserialize the ast from a stringified json
@@ -32,7 +34,7 @@ pub async fn execute_wasm(
         .await
         .map_err(|e| format!("{:?}", e))?;
     let fs = Arc::new(kcl_lib::fs::FileManager::new(fs_manager));
-    let ctx = kcl_lib::executor::ExecutorContext {
+    let mut ctx = kcl_lib::executor::ExecutorContext {
         engine: Arc::new(Box::new(engine)),
         fs,
         stdlib: std::sync::Arc::new(kcl_lib::std::StdLib::new()),
@@ -41,9 +43,38 @@ pub async fn execute_wasm(
             ..Default::default()
         },
         is_mock,
+        modules: Default::default(),
+        cache: Default::default(),
+        cancel: Default::default(),
+        budget: Default::default(),
+        profiler: None,
+        id_generator: Default::default(),
+        warnings: Default::default(),
     };
+    // Imports in the program are relative to the directory of the file being executed.
+    if let Some(project_directory) = project_directory {
+        ctx.modules.set_project_directory(project_directory);
+    }
+
+    // Stop executing when the signal is aborted. The handler has to live until the run is over.
+    let on_abort = signal.as_ref().map(|signal| {
+        let cancel = ctx.cancel.clone();
+        if signal.aborted() {
+            cancel.cancel();
+        }
+        let on_abort = Closure::<dyn FnMut()>::new(move || cancel.cancel());
+        // Add a listener rather than setting `onabort`, which would replace the caller's own handler.
+        let _ = signal.add_event_listener_with_callback("abort", on_abort.as_ref().unchecked_ref());
+        on_abort
+    });
+    let result = ctx.run(program, Some(memory)).await;
+    if let (Some(signal), Some(on_abort)) = (&signal, &on_abort) {
+        let _ = signal.remove_event_listener_with_callback("abort", on_abort.as_ref().unchecked_ref());
+    }
+    drop(on_abort);
 
-    let memory = ctx.run(program, Some(memory)).await.map_err(String::from)?;
+    // The memory includes the warnings found by the run.
+    let memory = result.map_err(String::from)?;
     // The serde-wasm-bindgen does not work here because of weird HashMap issues so we use the
     // gloo-serialize crate instead.
     JsValue::from_serde(&memory).map_err(|e| e.to_string())
@@ -208,7 +239,7 @@ pub async fn kcl_lsp_run(
         fs,
     } = config;
 
-    let stdlib = kcl_lib::std::StdLib::new();
+    let stdlib = Arc::new(kcl_lib::std::StdLib::new());
     let stdlib_completions = kcl_lib::lsp::kcl::get_completions_from_stdlib(&stdlib).map_err(|e| e.to_string())?;
     let stdlib_signatures = kcl_lib::lsp::kcl::get_signatures_from_stdlib(&stdlib).map_err(|e| e.to_string())?;
     // We can unwrap here because we know the tokeniser is valid, since
@@ -228,12 +259,19 @@ pub async fn kcl_lsp_run(
         Some(kcl_lib::executor::ExecutorContext {
             engine: Arc::new(Box::new(engine)),
             fs: file_manager.clone(),
-            stdlib: std::sync::Arc::new(stdlib),
+            stdlib: stdlib.clone(),
             settings: ExecutorSettings {
                 units,
                 ..Default::default()
             },
             is_mock: false,
+            modules: Default::default(),
+            cache: Default::default(),
+            cancel: Default::default(),
+            budget: Default::default(),
+            profiler: None,
+            id_generator: Default::default(),
+            warnings: Default::default(),
         })
     } else {
         None
@@ -261,6 +299,7 @@ pub async fn kcl_lsp_run(
         client,
         fs: file_manager,
         workspace_folders: Default::default(),
+        stdlib,
         stdlib_completions,
         stdlib_signatures,
         token_types,
@@ -271,6 +310,7 @@ pub async fn kcl_lsp_run(
         diagnostics_map: Default::default(),
         symbols_map: Default::default(),
         semantic_tokens_map: Default::default(),
+        profile_map: Default::default(),
         zoo_client,
         can_send_telemetry: privacy_settings.can_train_on_data,
         can_execute: Arc::new(tokio::sync::RwLock::new(executor_ctx.is_some())),
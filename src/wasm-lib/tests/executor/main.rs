@@ -1,9 +1,37 @@
+use std::sync::Arc;
+
 use anyhow::Result;
-use kcl_lib::executor::ExecutorSettings;
+use kcl_lib::{engine::EngineManager, executor::ExecutorSettings};
+
+/// Where the engine's responses for a test are recorded.
+fn recording_path(name: &str) -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/executor/recordings")
+        .join(format!("{name}.jsonl"))
+}
+
+/// Connect to the engine, for the test with the given name.
+///
+/// If the test has a recording, the engine's responses are replayed from it, so no network
+/// access is needed. Set `KCL_ENGINE_RECORD=1` to run against the real engine and save a new
+/// recording. Set `KCL_ENGINE_REPLAY=1`, as CI does, to never connect to the engine, so a test
+/// with no recording fails instead.
+async fn new_engine(name: &str, settings: &ExecutorSettings) -> Result<Arc<Box<dyn EngineManager>>> {
+    let recording = recording_path(name);
+    let record = std::env::var("KCL_ENGINE_RECORD").is_ok();
+    if !record && recording.exists() {
+        return Ok(Arc::new(Box::new(
+            kcl_lib::engine::conn_replay::EngineConnection::load(&recording)?,
+        )));
+    }
+    if !record && std::env::var("KCL_ENGINE_REPLAY").is_ok() {
+        anyhow::bail!(
+            "No engine recording for `{}` at {}, record one with KCL_ENGINE_RECORD=1",
+            name,
+            recording.display()
+        );
+    }
 
-/// Executes a kcl program and takes a snapshot of the result.
-/// This returns the bytes of the snapshot.
-async fn execute_and_snapshot(code: &str, units: kcl_lib::settings::types::UnitLength) -> Result<image::DynamicImage> {
     let user_agent = concat!(env!("CARGO_PKG_NAME"), ".rs/", env!("CARGO_PKG_VERSION"),);
     let http_client = reqwest::Client::builder()
         .user_agent(user_agent)
@@ -28,21 +56,35 @@ async fn execute_and_snapshot(code: &str, units: kcl_lib::settings::types::UnitL
         client.set_base_url(addr);
     }
 
+    let engine = kcl_lib::executor::ExecutorContext::connect_engine(&client, settings).await?;
+    if !record {
+        return Ok(engine);
+    }
+    Ok(Arc::new(Box::new(
+        kcl_lib::engine::conn_record::EngineConnection::new(engine, &recording).await?,
+    )))
+}
+
+/// Executes a kcl program and takes a snapshot of the result.
+/// This returns the bytes of the snapshot.
+async fn execute_and_snapshot(
+    name: &str,
+    code: &str,
+    units: kcl_lib::settings::types::UnitLength,
+) -> Result<image::DynamicImage> {
     // Create a temporary file to write the output to.
     let output_file = std::env::temp_dir().join(format!("kcl_output_{}.png", uuid::Uuid::new_v4()));
 
     let tokens = kcl_lib::token::lexer(code)?;
     let parser = kcl_lib::parser::Parser::new(tokens);
     let program = parser.ast()?;
-    let ctx = kcl_lib::executor::ExecutorContext::new(
-        &client,
-        ExecutorSettings {
-            units,
-            highlight_edges: true,
-            enable_ssao: false,
-        },
-    )
-    .await?;
+    let settings = ExecutorSettings {
+        units,
+        highlight_edges: true,
+        enable_ssao: false,
+    };
+    let engine = new_engine(name, &settings).await?;
+    let ctx = kcl_lib::executor::ExecutorContext::new_with_engine(engine, settings).await?;
 
     let _ = ctx.run(program, None).await?;
 
@@ -52,8 +94,9 @@ async fn execute_and_snapshot(code: &str, units: kcl_lib::settings::types::UnitL
             uuid::Uuid::new_v4(),
             kcl_lib::executor::SourceRange::default(),
             kittycad::types::ModelingCmd::ZoomToFit {
+                animated: false,
                 object_ids: Default::default(),
-                padding: 0.1,
+                padding: Some(0.1),
             },
         )
         .await?;
@@ -104,7 +147,7 @@ const part002 = startSketchOn(part001, "here")
   |> extrude(5, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("sketch_on_face", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/sketch_on_face.png", &result, 0.999);
@@ -113,7 +156,7 @@ const part002 = startSketchOn(part001, "here")
 #[tokio::test(flavor = "multi_thread")]
 async fn serial_test_riddle_small() {
     let code = include_str!("inputs/riddle_small.kcl");
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("riddle_small", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/riddle_small.png", &result, 0.999);
@@ -122,7 +165,7 @@ async fn serial_test_riddle_small() {
 #[tokio::test(flavor = "multi_thread")]
 async fn serial_test_lego() {
     let code = include_str!("inputs/lego.kcl");
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("lego", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/lego.png", &result, 0.999);
@@ -131,16 +174,20 @@ async fn serial_test_lego() {
 #[tokio::test(flavor = "multi_thread")]
 async fn serial_test_pentagon_fillet_desugar() {
     let code = include_str!("inputs/pentagon_fillet_desugar.kcl");
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Cm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "pentagon_fillet_desugar",
+        code,
+        kcl_lib::settings::types::UnitLength::Cm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/pentagon_fillet_desugar.png", &result, 0.999);
 }
 
 #[tokio::test(flavor = "multi_thread")]
 async fn serial_test_pentagon_fillet_sugar() {
     let code = include_str!("inputs/pentagon_fillet_sugar.kcl");
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Cm)
+    let result = execute_and_snapshot("pentagon_fillet_sugar", code, kcl_lib::settings::types::UnitLength::Cm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/pentagon_fillet_sugar.png", &result, 0.999);
@@ -170,7 +217,7 @@ const part002 = startSketchOn(part001, "start")
   |> extrude(5, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("sketch_on_face_start", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/sketch_on_face_start.png", &result, 0.999);
@@ -179,7 +226,7 @@ const part002 = startSketchOn(part001, "start")
 #[tokio::test(flavor = "multi_thread")]
 async fn serial_test_mike_stress_lines() {
     let code = include_str!("inputs/mike_stress_test.kcl");
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("mike_stress_lines", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/mike_stress_test.png", &result, 0.999);
@@ -209,7 +256,7 @@ const part002 = startSketchOn(part001, "END")
   |> extrude(5, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("sketch_on_face_end", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/sketch_on_face_end.png", &result, 0.999);
@@ -239,9 +286,13 @@ const part002 = startSketchOn(part001, "END")
   |> extrude(-5, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "sketch_on_face_end_negative_extrude",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image(
         "tests/executor/outputs/sketch_on_face_end_negative_extrude.png",
         &result,
@@ -261,16 +312,16 @@ async fn serial_test_fillet_duplicate_tags() {
     |> fillet({radius: 0.5, tags: ["thing", "thing"]}, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm).await;
+    let result = execute_and_snapshot("fillet_duplicate_tags", code, kcl_lib::settings::types::UnitLength::Mm).await;
     assert!(result.is_err());
     assert_eq!(
         result.err().unwrap().to_string(),
-        r#"type: KclErrorDetails { source_ranges: [SourceRange([205, 255])], message: "Duplicate tags are not allowed." }"#,
+        r#"type: KclErrorDetails { source_ranges: [SourceRange([205, 255])], message: "Duplicate tags are not allowed.", call_stack: [] }"#,
     );
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn serial_test_basic_fillet_cube_start() {
+async fn serial_test_basic_chamfer_cube() {
     let code = r#"const part001 = startSketchOn('XY')
     |> startProfileAt([0,0], %)
     |> line([0, 10], %, "thing")
@@ -278,12 +329,371 @@ async fn serial_test_basic_fillet_cube_start() {
     |> line([0, -10], %, "thing2")
     |> close(%)
     |> extrude(10, %)
-    |> fillet({radius: 2, tags: ["thing", "thing2"]}, %)
+    |> chamfer({length: 2, tags: ["thing", "thing2"]}, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("basic_chamfer_cube", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
+    twenty_twenty::assert_image("tests/executor/outputs/basic_chamfer_cube.png", &result, 0.999);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn serial_test_basic_chamfer_cube_adjacent() {
+    let code = r#"const part001 = startSketchOn('XY')
+    |> startProfileAt([0,0], %)
+    |> line([0, 10], %, "thing")
+    |> line([10, 0], %, "thing1")
+    |> line([0, -10], %, "thing2")
+    |> close(%, "thing3")
+    |> extrude(10, %)
+    |> chamfer({length: 2, tags: [getOppositeEdge("thing", %), getNextAdjacentEdge("thing3", %)]}, %)
+"#;
+
+    let result = execute_and_snapshot(
+        "basic_chamfer_cube_adjacent",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
+    twenty_twenty::assert_image("tests/executor/outputs/basic_chamfer_cube_adjacent.png", &result, 0.999);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn serial_test_chamfer_cube_two_lengths_and_angle() {
+    let code = r#"const part001 = startSketchOn('XY')
+    |> startProfileAt([0,0], %)
+    |> line([0, 10], %, "thing")
+    |> line([10, 0], %)
+    |> line([0, -10], %, "thing2")
+    |> close(%)
+    |> extrude(10, %)
+    |> chamfer({length: 1, otherLength: 3, tags: ["thing"]}, %)
+    |> chamfer({length: 2, angle: 30, tags: [getOppositeEdge("thing2", %)]}, %)
+"#;
+
+    let result = execute_and_snapshot(
+        "chamfer_cube_two_lengths_and_angle",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
+    twenty_twenty::assert_image(
+        "tests/executor/outputs/chamfer_cube_two_lengths_and_angle.png",
+        &result,
+        0.999,
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn serial_test_shell_then_sketch_on_face() {
+    let code = r#"const box = startSketchOn('XY')
+    |> startProfileAt([-12, 12], %)
+    |> line([24, 0], %)
+    |> line([0, -24], %)
+    |> line([-24, 0], %, "front")
+    |> close(%, "left")
+    |> extrude(20, %)
+    |> shell({thickness: 1, faces: ["end", "front"]}, %)
+
+const knob = startSketchOn(box, "left")
+    |> circle([0, 10], 3, %)
+    |> extrude(4, %)
+"#;
+
+    let result = execute_and_snapshot(
+        "shell_then_sketch_on_face",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
+    twenty_twenty::assert_image("tests/executor/outputs/shell_then_sketch_on_face.png", &result, 0.999);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn serial_test_shell_removed_face_cannot_be_sketched_on() {
+    let code = r#"const box = startSketchOn('XY')
+    |> startProfileAt([-12, 12], %)
+    |> line([24, 0], %)
+    |> line([0, -24], %)
+    |> line([-24, 0], %)
+    |> close(%)
+    |> extrude(20, %)
+    |> shell({thickness: 1, faces: ["end"]}, %)
+
+const lid = startSketchOn(box, "end")
+    |> circle([0, 0], 3, %)
+"#;
+
+    let result = execute_and_snapshot(
+        "shell_removed_face_cannot_be_sketched_on",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await;
+    assert!(result.is_err());
+    assert_eq!(
+        result.err().unwrap().to_string(),
+        r#"type: KclErrorDetails { source_ranges: [SourceRange([241, 266])], message: "Expected an end face to sketch on", call_stack: [] }"#,
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn serial_test_loft_square_to_circle() {
+    let code = r#"const squareSketch = startSketchOn('XY')
+    |> startProfileAt([-100, 200], %)
+    |> line([200, 0], %)
+    |> line([0, -200], %)
+    |> line([-200, 0], %)
+    |> close(%)
+
+const circleSketch = startSketchOn({
+       plane: {
+         origin: { x: 0, y: 100, z: 300 },
+         x_axis: { x: 1, y: 0, z: 0 },
+         y_axis: { x: 0, y: 1, z: 0 },
+         z_axis: { x: 0, y: 0, z: 1 }
+       }
+     })
+    |> circle([0, 100], 50, %)
+
+const duct = loft([squareSketch, circleSketch])
+"#;
+
+    let result = execute_and_snapshot("loft_square_to_circle", code, kcl_lib::settings::types::UnitLength::Mm)
+        .await
+        .unwrap();
+    twenty_twenty::assert_image("tests/executor/outputs/loft_square_to_circle.png", &result, 0.999);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn serial_test_loft_ruled_then_fillet_end_cap() {
+    let code = r#"const bottom = startSketchOn('XY')
+    |> startProfileAt([-10, -10], %)
+    |> line([20, 0], %)
+    |> line([0, 20], %)
+    |> line([-20, 0], %)
+    |> close(%)
+
+const middle = startSketchOn({
+       plane: {
+         origin: { x: 0, y: 0, z: 10 },
+         x_axis: { x: 1, y: 0, z: 0 },
+         y_axis: { x: 0, y: 1, z: 0 },
+         z_axis: { x: 0, y: 0, z: 1 }
+       }
+     })
+    |> circle([0, 0], 6, %)
+
+const top = startSketchOn({
+       plane: {
+         origin: { x: 0, y: 0, z: 20 },
+         x_axis: { x: 1, y: 0, z: 0 },
+         y_axis: { x: 0, y: 1, z: 0 },
+         z_axis: { x: 0, y: 0, z: 1 }
+       }
+     })
+    |> startProfileAt([-8, -8], %)
+    |> line([16, 0], %)
+    |> line([0, 16], %)
+    |> line([-16, 0], %, "topBack")
+    |> close(%)
+
+const handle = loft([bottom, middle, top], {ruled: true})
+    |> fillet({radius: 1, tags: ["topBack"]}, %)
+"#;
+
+    let result = execute_and_snapshot(
+        "loft_ruled_then_fillet_end_cap",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
+    twenty_twenty::assert_image(
+        "tests/executor/outputs/loft_ruled_then_fillet_end_cap.png",
+        &result,
+        0.999,
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn serial_test_sweep_pipe_along_path() {
+    let code = r#"const path = startSketchOn('XZ')
+    |> startProfileAt([0, 0], %)
+    |> line([0, 20], %)
+    |> tangentialArcTo([10, 30], %)
+    |> line([20, 0], %)
+
+const pipe = startSketchOn('XY')
+    |> circle([0, 0], 2, %)
+    |> hole(circle([0, 0], 1.5, %), %)
+    |> sweep({path: path}, %)
+"#;
+
+    let result = execute_and_snapshot("sweep_pipe_along_path", code, kcl_lib::settings::types::UnitLength::Mm)
+        .await
+        .unwrap();
+    twenty_twenty::assert_image("tests/executor/outputs/sweep_pipe_along_path.png", &result, 0.999);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn serial_test_sweep_spring_along_helix() {
+    let code = r#"const coil = startSketchOn('XY')
+    |> circle([0, 0], 10, %)
+    |> extrude(40, %)
+    |> helix({revolutions: 8, angle_start: 0}, %)
+
+const spring = startSketchOn('XZ')
+    |> circle([10, 0], 1, %)
+    |> sweep({path: coil}, %)
+"#;
+
+    let result = execute_and_snapshot(
+        "sweep_spring_along_helix",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
+    twenty_twenty::assert_image("tests/executor/outputs/sweep_spring_along_helix.png", &result, 0.999);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn serial_test_sweep_along_closed_path() {
+    let code = r#"const path = startSketchOn('XZ')
+    |> startProfileAt([0, 0], %)
+    |> line([0, 20], %)
+    |> line([20, 0], %)
+    |> close(%)
+
+const rod = startSketchOn('XY')
+    |> circle([0, 0], 1, %)
+    |> sweep({path: path}, %)
+"#;
+
+    let result = execute_and_snapshot(
+        "sweep_along_closed_path",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await;
+    assert!(result.is_err());
+    assert_eq!(
+        result.err().unwrap().to_string(),
+        r#"semantic: KclErrorDetails { source_ranges: [SourceRange([198, 220])], message: "Expected the path to be open, but it is closed", call_stack: [] }"#,
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn serial_test_union_then_fillet_by_tag() {
+    let code = r#"const base = startSketchOn('XY')
+    |> startProfileAt([-10, -10], %)
+    |> line([20, 0], %)
+    |> line([0, 20], %)
+    |> line([-20, 0], %, "back")
+    |> close(%)
+    |> extrude(5, %)
+
+const post = startSketchOn('XY')
+    |> circle([0, 0], 3, %)
+    |> extrude(15, %)
+
+const part = union([base, post])
+    |> fillet({radius: 1, tags: ["back"]}, %)
+"#;
+
+    let result = execute_and_snapshot(
+        "union_then_fillet_by_tag",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
+    twenty_twenty::assert_image("tests/executor/outputs/union_then_fillet_by_tag.png", &result, 0.999);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn serial_test_subtract_hole_from_plate() {
+    let code = r#"const plate = startSketchOn('XY')
+    |> startProfileAt([-20, -20], %)
+    |> line([40, 0], %)
+    |> line([0, 40], %)
+    |> line([-40, 0], %)
+    |> close(%)
+    |> extrude(4, %)
+
+const boltHole = startSketchOn('XY')
+    |> circle([0, 0], 5, %)
+    |> extrude(4, %)
+
+const washer = plate
+    |> subtract([boltHole], %)
+"#;
+
+    let result = execute_and_snapshot(
+        "subtract_hole_from_plate",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
+    twenty_twenty::assert_image("tests/executor/outputs/subtract_hole_from_plate.png", &result, 0.999);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn serial_test_intersection_of_cube_and_cylinder() {
+    let code = r#"const cube = startSketchOn('XY')
+    |> startProfileAt([-10, -10], %)
+    |> line([20, 0], %)
+    |> line([0, 20], %)
+    |> line([-20, 0], %)
+    |> close(%)
+    |> extrude(20, %)
+
+const rod = startSketchOn('XY')
+    |> circle([0, 0], 12, %)
+    |> extrude(20, %)
+
+const roundedCube = intersection([cube, rod])
+"#;
+
+    let result = execute_and_snapshot(
+        "intersection_of_cube_and_cylinder",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
+    twenty_twenty::assert_image(
+        "tests/executor/outputs/intersection_of_cube_and_cylinder.png",
+        &result,
+        0.999,
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn serial_test_basic_fillet_cube_start() {
+    let code = r#"const part001 = startSketchOn('XY')
+    |> startProfileAt([0,0], %)
+    |> line([0, 10], %, "thing")
+    |> line([10, 0], %)
+    |> line([0, -10], %, "thing2")
+    |> close(%)
+    |> extrude(10, %)
+    |> fillet({radius: 2, tags: ["thing", "thing2"]}, %)
+"#;
+
+    let result = execute_and_snapshot(
+        "basic_fillet_cube_start",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/basic_fillet_cube_start.png", &result, 0.999);
 }
 
@@ -300,7 +710,7 @@ async fn serial_test_basic_fillet_cube_end() {
 
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("basic_fillet_cube_end", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/basic_fillet_cube_end.png", &result, 0.999);
@@ -319,9 +729,13 @@ async fn serial_test_basic_fillet_cube_close_opposite() {
 
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "basic_fillet_cube_close_opposite",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image(
         "tests/executor/outputs/basic_fillet_cube_close_opposite.png",
         &result,
@@ -341,9 +755,13 @@ async fn serial_test_basic_fillet_cube_next_adjacent() {
     |> fillet({radius: 2, tags: [getNextAdjacentEdge("thing3", %)]}, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "basic_fillet_cube_next_adjacent",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image(
         "tests/executor/outputs/basic_fillet_cube_next_adjacent.png",
         &result,
@@ -363,9 +781,13 @@ async fn serial_test_basic_fillet_cube_previous_adjacent() {
     |> fillet({radius: 2, tags: [getPreviousAdjacentEdge("thing3", %)]}, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "basic_fillet_cube_previous_adjacent",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image(
         "tests/executor/outputs/basic_fillet_cube_previous_adjacent.png",
         &result,
@@ -390,9 +812,13 @@ async fn serial_test_execute_with_function_sketch() {
 const fnBox = box(3, 6, 10)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "execute_with_function_sketch",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/function_sketch.png", &result, 0.999);
 }
 
@@ -412,9 +838,13 @@ async fn serial_test_execute_with_function_sketch_with_position() {
 
 const thing = box([0,0], 3, 6, 10)"#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "execute_with_function_sketch_with_position",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image(
         "tests/executor/outputs/function_sketch_with_position.png",
         &result,
@@ -435,9 +865,13 @@ async fn serial_test_execute_with_angled_line() {
   |> extrude(4, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "execute_with_angled_line",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/angled_line.png", &result, 0.999);
 }
 
@@ -463,9 +897,13 @@ const bracket = startSketchOn('XY')
   |> extrude(width, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "execute_parametric_example",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/parametric.png", &result, 0.999);
 }
 
@@ -499,9 +937,13 @@ const bracket = startSketchAt([0, 0])
   |> extrude(width, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "execute_parametric_with_tan_arc_example",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/parametric_with_tan_arc.png", &result, 0.999);
 }
 
@@ -516,11 +958,16 @@ async fn serial_test_execute_engine_error_return() {
   |> extrude(4, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm).await;
+    let result = execute_and_snapshot(
+        "execute_engine_error_return",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await;
     assert!(result.is_err());
     assert_eq!(
         result.err().unwrap().to_string(),
-        r#"engine: KclErrorDetails { source_ranges: [SourceRange([222, 235])], message: "Modeling command failed: Some([ApiError { error_code: BadRequest, message: \"The path is not closed.  Solid2D construction requires a closed path!\" }])" }"#,
+        r#"engine: KclErrorDetails { source_ranges: [SourceRange([222, 235])], message: "Modeling command failed: Some([ApiError { error_code: BadRequest, message: \"The path is not closed.  Solid2D construction requires a closed path!\" }])", call_stack: [] }"#,
     );
 }
 
@@ -529,7 +976,7 @@ async fn serial_test_execute_i_shape() {
     // This is some code from lee that starts a pipe expression with a variable.
     let code = include_str!("inputs/i_shape.kcl");
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("execute_i_shape", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/i_shape.png", &result, 0.999);
@@ -540,7 +987,7 @@ async fn serial_test_execute_i_shape() {
 async fn serial_test_execute_pipes_on_pipes() {
     let code = include_str!("inputs/pipes_on_pipes.kcl");
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("execute_pipes_on_pipes", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/pipes_on_pipes.png", &result, 0.999);
@@ -550,7 +997,7 @@ async fn serial_test_execute_pipes_on_pipes() {
 async fn serial_test_execute_cylinder() {
     let code = include_str!("inputs/cylinder.kcl");
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("execute_cylinder", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/cylinder.png", &result, 0.999);
@@ -560,7 +1007,7 @@ async fn serial_test_execute_cylinder() {
 async fn serial_test_execute_kittycad_svg() {
     let code = include_str!("inputs/kittycad_svg.kcl");
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("execute_kittycad_svg", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/kittycad_svg.png", &result, 0.999);
@@ -587,9 +1034,13 @@ const pt1 = b1.value[0]
 const pt2 = b2.value[0]
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "member_expression_sketch_group",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image(
         "tests/executor/outputs/member_expression_sketch_group.png",
         &result,
@@ -605,7 +1056,7 @@ async fn serial_test_helix_defaults() {
      |> helix({revolutions: 16, angle_start: 0}, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("helix_defaults", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/helix_defaults.png", &result, 1.0);
@@ -619,9 +1070,13 @@ async fn serial_test_helix_defaults_negative_extrude() {
      |> helix({revolutions: 16, angle_start: 0}, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "helix_defaults_negative_extrude",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image(
         "tests/executor/outputs/helix_defaults_negative_extrude.png",
         &result,
@@ -637,7 +1092,7 @@ async fn serial_test_helix_ccw() {
      |> helix({revolutions: 16, angle_start: 0, ccw: true}, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("helix_ccw", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/helix_ccw.png", &result, 1.0);
@@ -651,7 +1106,7 @@ async fn serial_test_helix_with_length() {
      |> helix({revolutions: 16, angle_start: 0, length: 3}, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("helix_with_length", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/helix_with_length.png", &result, 1.0);
@@ -668,7 +1123,7 @@ async fn serial_test_dimensions_match() {
   |> extrude(10, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("dimensions_match", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/dimensions_match.png", &result, 1.0);
@@ -687,7 +1142,7 @@ const body = startSketchOn('XY')
       |> extrude(height, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("close_arc", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/close_arc.png", &result, 0.999);
@@ -715,7 +1170,7 @@ box(10, 23, 8)
 let thing = box(-12, -15, 10)
 box(-20, -5, 10)"#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("negative_args", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/negative_args.png", &result, 0.999);
@@ -730,7 +1185,7 @@ async fn serial_test_basic_tangential_arc() {
     |> extrude(10, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("basic_tangential_arc", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/tangential_arc.png", &result, 0.999);
@@ -745,9 +1200,13 @@ async fn serial_test_basic_tangential_arc_with_point() {
     |> extrude(10, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "basic_tangential_arc_with_point",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/tangential_arc_with_point.png", &result, 0.999);
 }
 
@@ -760,9 +1219,13 @@ async fn serial_test_basic_tangential_arc_to() {
     |> extrude(10, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "basic_tangential_arc_to",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/tangential_arc_to.png", &result, 0.999);
 }
 
@@ -789,9 +1252,13 @@ box(30, 43, 18, '-xy')
 let thing = box(-12, -15, 10, 'yz')
 box(-20, -5, 10, 'xy')"#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "different_planes_same_drawing",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image(
         "tests/executor/outputs/different_planes_same_drawing.png",
         &result,
@@ -853,7 +1320,7 @@ const part004 = startSketchOn('YZ')
   |> close(%)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("lots_of_planes", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/lots_of_planes.png", &result, 0.999);
@@ -872,7 +1339,7 @@ async fn serial_test_holes() {
   |> extrude(2, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("holes", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/holes.png", &result, 0.999);
@@ -893,7 +1360,7 @@ async fn optional_params() {
 
 const thing = other_circle([2, 2], 20)
 "#;
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("optional_params", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/optional_params.png", &result, 0.999);
@@ -931,7 +1398,7 @@ const part = roundedRectangle([0, 0], 20, 20, 4)
   |> extrude(2, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("rounded_with_holes", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/rounded_with_holes.png", &result, 0.999);
@@ -941,7 +1408,7 @@ const part = roundedRectangle([0, 0], 20, 20, 4)
 async fn serial_test_top_level_expression() {
     let code = r#"startSketchOn('XY') |> circle([0,0], 22, %) |> extrude(14, %)"#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("top_level_expression", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/top_level_expression.png", &result, 0.999);
@@ -957,9 +1424,13 @@ const part =  startSketchOn('XY')
     |> extrude(1, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "patterns_linear_basic_with_math",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image(
         "tests/executor/outputs/patterns_linear_basic_with_math.png",
         &result,
@@ -975,7 +1446,7 @@ async fn serial_test_patterns_linear_basic() {
     |> extrude(1, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("patterns_linear_basic", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/patterns_linear_basic.png", &result, 0.999);
@@ -993,9 +1464,13 @@ async fn serial_test_patterns_linear_basic_3d() {
     |> patternLinear3d({axis: [1, 0, 1], repetitions: 3, distance: 6}, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "patterns_linear_basic_3d",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/patterns_linear_basic_3d.png", &result, 0.999);
 }
 
@@ -1007,9 +1482,13 @@ async fn serial_test_patterns_linear_basic_negative_distance() {
     |> extrude(1, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "patterns_linear_basic_negative_distance",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image(
         "tests/executor/outputs/patterns_linear_basic_negative_distance.png",
         &result,
@@ -1025,9 +1504,13 @@ async fn serial_test_patterns_linear_basic_negative_axis() {
     |> extrude(1, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "patterns_linear_basic_negative_axis",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image(
         "tests/executor/outputs/patterns_linear_basic_negative_axis.png",
         &result,
@@ -1052,9 +1535,13 @@ const rectangle = startSketchOn('XY')
 
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "patterns_linear_basic_holes",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/patterns_linear_basic_holes.png", &result, 0.999);
 }
 
@@ -1066,9 +1553,13 @@ async fn serial_test_patterns_circular_basic_2d() {
     |> extrude(1, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "patterns_circular_basic_2d",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/patterns_circular_basic_2d.png", &result, 0.999);
 }
 
@@ -1084,9 +1575,13 @@ async fn serial_test_patterns_circular_basic_3d() {
     |> patternCircular3d({axis: [0,0, 1], center: [-20, -20, -20], repetitions: 40, arcDegrees: 360, rotateDuplicates: false}, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "patterns_circular_basic_3d",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/patterns_circular_basic_3d.png", &result, 0.999);
 }
 
@@ -1102,9 +1597,13 @@ async fn serial_test_patterns_circular_3d_tilted_axis() {
     |> patternCircular3d({axis: [1,1,0], center: [10, 0, 10], repetitions: 10, arcDegrees: 360, rotateDuplicates: true}, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "patterns_circular_3d_tilted_axis",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image(
         "tests/executor/outputs/patterns_circular_3d_tilted_axis.png",
         &result,
@@ -1116,11 +1615,16 @@ async fn serial_test_patterns_circular_3d_tilted_axis() {
 async fn serial_test_import_file_doesnt_exist() {
     let code = r#"const model = import("thing.obj")"#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm).await;
+    let result = execute_and_snapshot(
+        "import_file_doesnt_exist",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await;
     assert!(result.is_err());
     assert_eq!(
         result.err().unwrap().to_string(),
-        r#"semantic: KclErrorDetails { source_ranges: [SourceRange([14, 33])], message: "File `thing.obj` does not exist." }"#
+        r#"semantic: KclErrorDetails { source_ranges: [SourceRange([14, 33])], message: "File `thing.obj` does not exist.", call_stack: [] }"#
     );
 }
 
@@ -1128,7 +1632,7 @@ async fn serial_test_import_file_doesnt_exist() {
 async fn serial_test_import_obj_with_mtl() {
     let code = r#"const model = import("tests/executor/inputs/cube.obj")"#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("import_obj_with_mtl", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/import_obj_with_mtl.png", &result, 0.999);
@@ -1138,9 +1642,13 @@ async fn serial_test_import_obj_with_mtl() {
 async fn serial_test_import_obj_with_mtl_units() {
     let code = r#"const model = import("tests/executor/inputs/cube.obj", {type: "obj", units: "m"})"#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "import_obj_with_mtl_units",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/import_obj_with_mtl_units.png", &result, 0.999);
 }
 
@@ -1148,7 +1656,7 @@ async fn serial_test_import_obj_with_mtl_units() {
 async fn serial_test_import_gltf_with_bin() {
     let code = r#"const model = import("tests/executor/inputs/cube.gltf")"#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("import_gltf_with_bin", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/import_gltf_with_bin.png", &result, 0.999);
@@ -1158,7 +1666,7 @@ async fn serial_test_import_gltf_with_bin() {
 async fn serial_test_import_gltf_embedded() {
     let code = r#"const model = import("tests/executor/inputs/cube-embedded.gltf")"#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("import_gltf_embedded", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/import_gltf_embedded.png", &result, 0.999);
@@ -1168,7 +1676,7 @@ async fn serial_test_import_gltf_embedded() {
 async fn serial_test_import_glb() {
     let code = r#"const model = import("tests/executor/inputs/cube.glb")"#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("import_glb", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/import_glb.png", &result, 0.999);
@@ -1178,7 +1686,7 @@ async fn serial_test_import_glb() {
 async fn serial_test_import_glb_no_assign() {
     let code = r#"import("tests/executor/inputs/cube.glb")"#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("import_glb_no_assign", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/import_glb_no_assign.png", &result, 0.999);
@@ -1188,11 +1696,16 @@ async fn serial_test_import_glb_no_assign() {
 async fn serial_test_import_ext_doesnt_match() {
     let code = r#"const model = import("tests/executor/inputs/cube.gltf", {type: "obj", units: "m"})"#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm).await;
+    let result = execute_and_snapshot(
+        "import_ext_doesnt_match",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await;
     assert!(result.is_err());
     assert_eq!(
         result.err().unwrap().to_string(),
-        r#"semantic: KclErrorDetails { source_ranges: [SourceRange([14, 82])], message: "The given format does not match the file extension. Expected: `gltf`, Given: `obj`" }"#
+        r#"semantic: KclErrorDetails { source_ranges: [SourceRange([14, 82])], message: "The given format does not match the file extension. Expected: `gltf`, Given: `obj`", call_stack: [] }"#
     );
 }
 
@@ -1213,7 +1726,7 @@ async fn serial_test_cube_mm() {
 const myCube = cube([0,0], 10)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("cube_mm", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/cube_mm.png", &result, 1.0);
@@ -1236,7 +1749,7 @@ async fn serial_test_cube_cm() {
 const myCube = cube([0,0], 10)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Cm)
+    let result = execute_and_snapshot("cube_cm", code, kcl_lib::settings::types::UnitLength::Cm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/cube_cm.png", &result, 1.0);
@@ -1259,7 +1772,7 @@ async fn serial_test_cube_m() {
 const myCube = cube([0,0], 10)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::M)
+    let result = execute_and_snapshot("cube_m", code, kcl_lib::settings::types::UnitLength::M)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/cube_m.png", &result, 1.0);
@@ -1282,7 +1795,7 @@ async fn serial_test_cube_in() {
 const myCube = cube([0,0], 10)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::In)
+    let result = execute_and_snapshot("cube_in", code, kcl_lib::settings::types::UnitLength::In)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/cube_in.png", &result, 1.0);
@@ -1305,7 +1818,7 @@ async fn serial_test_cube_ft() {
 const myCube = cube([0,0], 10)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Ft)
+    let result = execute_and_snapshot("cube_ft", code, kcl_lib::settings::types::UnitLength::Ft)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/cube_ft.png", &result, 1.0);
@@ -1328,7 +1841,7 @@ async fn serial_test_cube_yd() {
 const myCube = cube([0,0], 10)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Yd)
+    let result = execute_and_snapshot("cube_yd", code, kcl_lib::settings::types::UnitLength::Yd)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/cube_yd.png", &result, 1.0);
@@ -1358,12 +1871,17 @@ const part002 = startSketchOn(part001, "here")
   |> extrude(1, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm).await;
+    let result = execute_and_snapshot(
+        "error_sketch_on_arc_face",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await;
 
     assert!(result.is_err());
     assert_eq!(
         result.err().unwrap().to_string(),
-        r#"type: KclErrorDetails { source_ranges: [SourceRange([281, 311])], message: "Cannot sketch on a non-planar surface: `here`" }"#
+        r#"type: KclErrorDetails { source_ranges: [SourceRange([281, 311])], message: "Cannot sketch on a non-planar surface: `here`", call_stack: [] }"#
     );
 }
 
@@ -1399,7 +1917,7 @@ const part003 = startSketchOn(part002, "end")
   |> extrude(5, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("sketch_on_face_of_face", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/sketch_on_face_of_face.png", &result, 1.0);
@@ -1418,11 +1936,16 @@ async fn serial_test_stdlib_kcl_error_right_code_path() {
   |> extrude(2, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm).await;
+    let result = execute_and_snapshot(
+        "stdlib_kcl_error_right_code_path",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await;
     assert!(result.is_err());
     assert_eq!(
         result.err().unwrap().to_string(),
-        r#"type: KclErrorDetails { source_ranges: [SourceRange([157, 175])], message: "Expected a SketchGroup or SketchSurface as the third argument, found `[UserVal(UserVal { value: Array [Number(2), Number(2)], meta: [Metadata { source_range: SourceRange([164, 170]) }] }), UserVal(UserVal { value: Number(0.5), meta: [Metadata { source_range: SourceRange([172, 174]) }] })]`" }"#
+        r#"type: KclErrorDetails { source_ranges: [SourceRange([157, 175])], message: "`circle` expects 3-4 arguments, got 2", call_stack: [] }"#
     );
 }
 
@@ -1446,7 +1969,7 @@ const part002 = startSketchOn(part001, "end")
   |> extrude(5, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("sketch_on_face_circle", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/sketch_on_face_circle.png", &result, 1.0);
@@ -1472,9 +1995,13 @@ const part002 = startSketchOn(part001, "end")
   |> extrude(5, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "sketch_on_face_circle_tagged",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/sketch_on_face_circle_tagged.png", &result, 1.0);
 }
 
@@ -1516,11 +2043,16 @@ const part = rectShape([0, 0], 20, 20)
      }, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm).await;
+    let result = execute_and_snapshot(
+        "stdlib_kcl_error_circle",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await;
     assert!(result.is_err());
     assert_eq!(
         result.err().unwrap().to_string(),
-        r#"type: KclErrorDetails { source_ranges: [SourceRange([891, 940])], message: "Expected a [number, number] as the first argument, found `[UserVal(UserVal { value: String(\"XY\"), meta: [Metadata { source_range: SourceRange([898, 902]) }] }), UserVal(UserVal { value: Array [Number(-6.0), Number(6)], meta: [Metadata { source_range: SourceRange([904, 927]) }] }), UserVal(UserVal { value: Number(1), meta: [Metadata { source_range: SourceRange([760, 761]) }] })]`" }"#
+        r#"type: KclErrorDetails { source_ranges: [SourceRange([898, 902])], message: "Argument 1 of `circle` should be `center: [number]`, but it is a string", call_stack: [] }"#
     );
 }
 
@@ -1537,9 +2069,13 @@ async fn serial_test_big_number_angle_to_match_length_x() {
   |> extrude(10, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "big_number_angle_to_match_length_x",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image(
         "tests/executor/outputs/big_number_angle_to_match_length_x.png",
         &result,
@@ -1560,9 +2096,13 @@ async fn serial_test_big_number_angle_to_match_length_y() {
   |> extrude(10, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "big_number_angle_to_match_length_y",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image(
         "tests/executor/outputs/big_number_angle_to_match_length_y.png",
         &result,
@@ -1586,7 +2126,7 @@ async fn serial_test_simple_revolve() {
 
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("simple_revolve", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/simple_revolve.png", &result, 1.0);
@@ -1608,9 +2148,13 @@ async fn serial_test_simple_revolve_uppercase() {
 
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "simple_revolve_uppercase",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/simple_revolve_uppercase.png", &result, 1.0);
 }
 
@@ -1630,9 +2174,13 @@ async fn serial_test_simple_revolve_negative() {
 
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "simple_revolve_negative",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/simple_revolve_negative.png", &result, 1.0);
 }
 
@@ -1652,12 +2200,12 @@ async fn serial_test_revolve_bad_angle_low() {
 
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm).await;
+    let result = execute_and_snapshot("revolve_bad_angle_low", code, kcl_lib::settings::types::UnitLength::Mm).await;
 
     assert!(result.is_err());
     assert_eq!(
         result.err().unwrap().to_string(),
-        r#"semantic: KclErrorDetails { source_ranges: [SourceRange([278, 314])], message: "Expected angle to be between -360 and 360, found `-455`" }"#
+        r#"semantic: KclErrorDetails { source_ranges: [SourceRange([278, 314])], message: "Expected angle to be between -360 and 360, found `-455`", call_stack: [] }"#
     );
 }
 
@@ -1677,12 +2225,12 @@ async fn serial_test_revolve_bad_angle_high() {
 
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm).await;
+    let result = execute_and_snapshot("revolve_bad_angle_high", code, kcl_lib::settings::types::UnitLength::Mm).await;
 
     assert!(result.is_err());
     assert_eq!(
         result.err().unwrap().to_string(),
-        r#"semantic: KclErrorDetails { source_ranges: [SourceRange([278, 313])], message: "Expected angle to be between -360 and 360, found `455`" }"#
+        r#"semantic: KclErrorDetails { source_ranges: [SourceRange([278, 313])], message: "Expected angle to be between -360 and 360, found `455`", call_stack: [] }"#
     );
 }
 
@@ -1702,9 +2250,13 @@ async fn serial_test_simple_revolve_custom_angle() {
 
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "simple_revolve_custom_angle",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/simple_revolve_custom_angle.png", &result, 1.0);
 }
 
@@ -1724,9 +2276,13 @@ async fn serial_test_simple_revolve_custom_axis() {
 
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "simple_revolve_custom_axis",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/simple_revolve_custom_axis.png", &result, 1.0);
 }
 
@@ -1750,7 +2306,7 @@ const sketch001 = startSketchOn(box, "end")
 
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("revolve_on_edge", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/revolve_on_edge.png", &result, 1.0);
@@ -1776,12 +2332,17 @@ const sketch001 = startSketchOn(box, "revolveAxis")
 
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm).await;
+    let result = execute_and_snapshot(
+        "revolve_on_edge_get_edge",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await;
 
     assert!(result.is_err());
     assert_eq!(
         result.err().unwrap().to_string(),
-        r#"engine: KclErrorDetails { source_ranges: [SourceRange([349, 409])], message: "Modeling command failed: Some([ApiError { error_code: InternalEngine, message: \"Solid3D revolve failed:  sketch profile must lie entirely on one side of the revolution axis\" }])" }"#
+        r#"engine: KclErrorDetails { source_ranges: [SourceRange([349, 409])], message: "Modeling command failed: Some([ApiError { error_code: InternalEngine, message: \"Solid3D revolve failed:  sketch profile must lie entirely on one side of the revolution axis\" }])", call_stack: [] }"#
     );
 }
 
@@ -1803,9 +2364,13 @@ const sketch001 = startSketchOn(box, "END")
     }, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "revolve_on_face_circle_edge",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/revolve_on_face_circle_edge.png", &result, 1.0);
 }
 
@@ -1827,7 +2392,7 @@ const sketch001 = startSketchOn(box, "END")
     }, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("revolve_on_face_circle", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/revolve_on_face_circle.png", &result, 1.0);
@@ -1855,7 +2420,7 @@ const sketch001 = startSketchOn(box, "end")
   }, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("revolve_on_face", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/revolve_on_face.png", &result, 1.0);
@@ -1871,7 +2436,7 @@ async fn serial_test_basic_revolve_circle() {
     }, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("basic_revolve_circle", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/basic_revolve_circle.png", &result, 1.0);
@@ -1900,9 +2465,13 @@ const part002 = startSketchOn(part001, 'end')
     |> extrude(5, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "simple_revolve_sketch_on_edge",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/simple_revolve_sketch_on_edge.png", &result, 1.0);
 }
 
@@ -1963,7 +2532,7 @@ const plumbus0 = make_circle(p, 'a', [0, 0], 2.5)
 //      }, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
+    let result = execute_and_snapshot("plumbus_fillets", code, kcl_lib::settings::types::UnitLength::Mm)
         .await
         .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/plumbus_fillets.png", &result, 1.0);
@@ -1973,7 +2542,7 @@ const plumbus0 = make_circle(p, 'a', [0, 0], 2.5)
 async fn serial_test_empty_file_is_ok() {
     let code = r#""#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm).await;
+    let result = execute_and_snapshot("empty_file_is_ok", code, kcl_lib::settings::types::UnitLength::Mm).await;
     assert!(result.is_ok());
 }
 
@@ -2003,9 +2572,13 @@ async fn serial_test_member_expression_in_params() {
 capScrew([0, 0.5, 0], 50, 37.5, 50, 25)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm)
-        .await
-        .unwrap();
+    let result = execute_and_snapshot(
+        "member_expression_in_params",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await
+    .unwrap();
     twenty_twenty::assert_image("tests/executor/outputs/member_expression_in_params.png", &result, 1.0);
 }
 
@@ -2050,10 +2623,15 @@ const bracket = startSketchOn('XY')
      }, %)
 "#;
 
-    let result = execute_and_snapshot(code, kcl_lib::settings::types::UnitLength::Mm).await;
+    let result = execute_and_snapshot(
+        "bracket_with_fillets_ensure_fail_on_flush_source_ranges",
+        code,
+        kcl_lib::settings::types::UnitLength::Mm,
+    )
+    .await;
     assert!(result.is_err());
     assert_eq!(
         result.err().unwrap().to_string(),
-        r#"engine: KclErrorDetails { source_ranges: [SourceRange([1443, 1443])], message: "Modeling command failed: Some([ApiError { error_code: BadRequest, message: \"Fillet failed\" }])" }"#
+        r#"engine: KclErrorDetails { source_ranges: [SourceRange([1443, 1443])], message: "Modeling command failed: Some([ApiError { error_code: BadRequest, message: \"Fillet failed\" }])", call_stack: [] }"#
     );
 }